@@ -0,0 +1,68 @@
+//! Reading the `modinfo.json` of every mod in a large mods directory, as `list`, `check`
+//! and `update` do on start.
+//!
+//! Compares the parallel scan of `FileManager::collect_mods` with reading the same zips
+//! one after another. Run with `cargo bench --bench scan_mods`.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use vintage_mod_manager::FileManager;
+use zip::write::SimpleFileOptions;
+
+/// Writes `count` mod zips with a `modinfo.json` and some compressed assets, like real mods.
+fn write_mods(dir: &Path, count: usize) {
+    let asset = "{\"code\": \"block\", \"variant\": \"oak\"}\n".repeat(2_000);
+    for index in 0..count {
+        let modid = format!("benchmod{index}");
+        let mut zip = zip::ZipWriter::new(File::create(dir.join(format!("{modid}.zip"))).unwrap());
+        zip.start_file("modinfo.json", SimpleFileOptions::default())
+            .unwrap();
+        write!(
+            zip,
+            r#"{{"type": "content", "modid": "{modid}", "name": "Bench mod {index}", "version": "1.0.{index}"}}"#
+        )
+        .unwrap();
+        zip.start_file("assets/blocktypes/block.json", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(asset.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    }
+}
+
+fn scan_mods(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("scan_mods");
+    group.sample_size(20);
+
+    for count in [50, 250] {
+        let dir = tempfile::tempdir().unwrap();
+        write_mods(dir.path(), count);
+        let file_manager = FileManager::new().with_mod_dirs(vec![dir.path().to_path_buf()]);
+
+        group.bench_with_input(BenchmarkId::new("parallel", count), &count, |b, _| {
+            b.to_async(&runtime).iter(|| async {
+                let mods = file_manager.collect_mods(&None).await.unwrap();
+                assert_eq!(mods.len(), count);
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("sequential", count), &count, |b, _| {
+            b.iter(|| {
+                let mut paths: Vec<_> = std::fs::read_dir(dir.path())
+                    .unwrap()
+                    .map(|entry| entry.unwrap().path())
+                    .collect();
+                paths.sort();
+                for path in &paths {
+                    file_manager.read_local_mod(path).unwrap();
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scan_mods);
+criterion_main!(benches);
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A ModDB API response kept on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// The requested URL, checked on load in case two URLs share a file name.
+    pub url: String,
+    pub body: String,
+    /// The `ETag` header of the response, sent back as `If-None-Match` once stale.
+    pub etag: Option<String>,
+    /// The `Last-Modified` header of the response, sent back as `If-Modified-Since`.
+    pub last_modified: Option<String>,
+    /// RFC 3339 timestamp of when the response was fetched or last revalidated.
+    pub fetched_at: String,
+}
+
+impl CachedResponse {
+    /// Whether the response is younger than `ttl` and can be used without asking ModDB.
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        let Ok(ttl) = chrono::Duration::from_std(ttl) else {
+            return true;
+        };
+        chrono::DateTime::parse_from_rfc3339(&self.fetched_at)
+            .is_ok_and(|fetched_at| chrono::Local::now().signed_duration_since(fetched_at) < ttl)
+    }
+}
+
+/// On-disk cache of ModDB API responses, one JSON file per URL.
+///
+/// Failing to read or write the cache never fails a request; the response is fetched
+/// from ModDB instead.
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    /// Creates a cache in `dir` whose responses are used for `ttl` before revalidating.
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// How long a response is used without asking ModDB.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let name: String = digest[..16].iter().map(|b| format!("{b:02x}")).collect();
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Returns the cached response for `url`, however old.
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let contents = fs::read_to_string(self.path(url)).ok()?;
+        serde_json::from_str::<CachedResponse>(&contents)
+            .ok()
+            .filter(|cached| cached.url == url)
+    }
+
+    /// Stores a response, stamped with the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The requested URL.
+    /// * `body` - The response body.
+    /// * `etag` - The `ETag` header, if ModDB sent one.
+    /// * `last_modified` - The `Last-Modified` header, if ModDB sent one.
+    pub fn store(
+        &self, url: &str, body: &str, etag: Option<String>, last_modified: Option<String>,
+    ) -> Result<(), std::io::Error> {
+        let cached = CachedResponse {
+            url: url.to_string(),
+            body: body.to_string(),
+            etag,
+            last_modified,
+            fetched_at: chrono::Local::now().to_rfc3339(),
+        };
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(url), serde_json::to_string(&cached)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn stores_responses_per_url() {
+        let dir = tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path().to_path_buf(), Duration::from_secs(60));
+        cache
+            .store(
+                "https://moddb/api/mod/carryon",
+                "{}",
+                Some("\"v1\"".into()),
+                None,
+            )
+            .unwrap();
+
+        let cached = cache.get("https://moddb/api/mod/carryon").unwrap();
+        assert_eq!(cached.body, "{}");
+        assert_eq!(cached.etag.as_deref(), Some("\"v1\""));
+        assert!(cached.is_fresh(cache.ttl()));
+        assert!(!cached.is_fresh(Duration::ZERO));
+        assert!(cache.get("https://moddb/api/mod/hudclock").is_none());
+    }
+}
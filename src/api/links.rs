@@ -0,0 +1,82 @@
+use reqwest::Url;
+
+/// Host of the ModDB website.
+const MODDB_HOST: &str = "mods.vintagestory.at";
+
+/// First path segments of ModDB pages that are not mod pages.
+const RESERVED_PATHS: &[&str] = &["api", "files", "list", "show", "login", "logout"];
+
+/// A mod referenced by the address of its ModDB page, as copied from the browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModPageLink {
+    /// `/show/mod/<id>`, the numeric ID of the mod.
+    Id(String),
+    /// `/<urlalias>`, the short address the author chose for the mod page.
+    Alias(String),
+}
+
+impl ModPageLink {
+    /// Parses a link to a mod page on ModDB. The scheme may be left out, and query
+    /// strings and fragments such as `#tab-files` are ignored.
+    ///
+    /// # Returns
+    ///
+    /// The mod the link points at, or `None` if `input` is not a link to a mod page.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let url = if input.contains("://") {
+            Url::parse(input).ok()?
+        } else {
+            Url::parse(&format!("https://{input}")).ok()?
+        };
+        let host = url.host_str()?;
+        if host != MODDB_HOST && host.strip_prefix("www.") != Some(MODDB_HOST) {
+            return None;
+        }
+
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            ["show", "mod", id] if id.bytes().all(|b| b.is_ascii_digit()) => {
+                Some(Self::Id(id.to_string()))
+            }
+            [alias] if !RESERVED_PATHS.contains(alias) => Some(Self::Alias(alias.to_string())),
+            _ => None,
+        }
+    }
+
+    /// The identifier to look the mod up with.
+    pub fn identifier(&self) -> &str {
+        match self {
+            Self::Id(id) => id,
+            Self::Alias(alias) => alias,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mod_page_links() {
+        assert_eq!(
+            ModPageLink::parse("https://mods.vintagestory.at/show/mod/1234#tab-files"),
+            Some(ModPageLink::Id("1234".to_string()))
+        );
+        assert_eq!(
+            ModPageLink::parse("mods.vintagestory.at/carryon/"),
+            Some(ModPageLink::Alias("carryon".to_string()))
+        );
+        assert_eq!(
+            ModPageLink::parse("http://www.mods.vintagestory.at/carryon?tab=files"),
+            Some(ModPageLink::Alias("carryon".to_string()))
+        );
+
+        assert_eq!(ModPageLink::parse("carryon"), None);
+        assert_eq!(
+            ModPageLink::parse("https://mods.vintagestory.at/list/mod"),
+            None
+        );
+        assert_eq!(ModPageLink::parse("https://example.com/carryon"), None);
+    }
+}
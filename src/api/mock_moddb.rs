@@ -0,0 +1,341 @@
+//! Local stand-ins for ModDB used by the end-to-end tests.
+
+use crate::api::{
+    ClientError, ModApiResponse, ModRepository, ModSearchResponse, Query, RetryPolicy, Tag,
+    VintageApiHandler,
+};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Serves canned ModDB API responses and release files from a local HTTP server.
+pub struct MockModDb {
+    server: MockServer,
+    /// Keeps partial downloads out of the user's cache directory.
+    download_dir: TempDir,
+}
+
+impl MockModDb {
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+            download_dir: tempfile::tempdir().unwrap(),
+        }
+    }
+
+    /// An API client pointed at this server, retrying failed requests without delay.
+    pub fn api(&self) -> VintageApiHandler {
+        VintageApiHandler::new()
+            .with_api_url(&self.server.uri())
+            .with_download_dir(self.download_dir.path().into())
+            .with_retry_policy(RetryPolicy::new(2, Duration::ZERO))
+    }
+
+    /// The download URL of a release file.
+    pub fn file_url(&self, modid: &str, version: &str) -> String {
+        format!(
+            "{}/files/{}",
+            self.server.uri(),
+            Self::filename(modid, version)
+        )
+    }
+
+    pub fn filename(modid: &str, version: &str) -> String {
+        format!("{modid}_v{version}.zip")
+    }
+
+    /// Publishes a mod with the given releases, newest first, and serves their files.
+    pub async fn add_mod(&self, modid: &str, versions: &[&str]) {
+        let body = mod_response(modid, versions, |version| self.file_url(modid, version));
+
+        Mock::given(method("GET"))
+            .and(path(format!("/api/mod/{modid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&self.server)
+            .await;
+
+        for version in versions {
+            Mock::given(method("GET"))
+                .and(path(format!("/files/{}", Self::filename(modid, version))))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(mod_zip(modid, version)))
+                .mount(&self.server)
+                .await;
+        }
+    }
+
+    /// How many requests were made to `path`.
+    pub async fn requests_to(&self, path: &str) -> usize {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|request| request.url.path() == path)
+            .count()
+    }
+
+    /// The paths of all requests made so far, in the order they arrived.
+    pub async fn requested_paths(&self) -> Vec<String> {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(|request| request.url.path().to_string())
+            .collect()
+    }
+
+    /// Makes every search return the given mods, in order.
+    pub async fn add_search_results(&self, modids: &[&str]) {
+        Mock::given(method("GET"))
+            .and(path("/api/mods"))
+            .respond_with(search_response(modids))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Makes searches filtered by `tag_id` return the given mods, in order.
+    pub async fn add_tagged_search_results(&self, tag_id: u16, modids: &[&str]) {
+        Mock::given(method("GET"))
+            .and(path("/api/mods"))
+            .and(query_param("tagids[]", tag_id.to_string()))
+            .respond_with(search_response(modids))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Publishes the mod tags, with IDs counting up from 1.
+    pub async fn add_tags(&self, names: &[&str]) {
+        let tags: Vec<Value> = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| json!({ "tagid": index + 1, "name": name, "color": "#C9C9C9" }))
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path("/api/tags"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "statuscode": "200",
+                "tags": tags,
+            })))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Answers lookups of `modid` the way ModDB does for unknown mods.
+    pub async fn add_missing_mod(&self, modid: &str) {
+        Mock::given(method("GET"))
+            .and(path(format!("/api/mod/{modid}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "statuscode": 404 })))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Serves `bytes` as the file of a release instead of the generated mod zip.
+    pub async fn serve_file(&self, modid: &str, version: &str, bytes: Vec<u8>) {
+        Mock::given(method("GET"))
+            .and(path(format!("/files/{}", Self::filename(modid, version))))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(bytes))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Makes downloads of a release file fail with a server error, even if it is served.
+    pub async fn fail_file(&self, modid: &str, version: &str) {
+        Mock::given(method("GET"))
+            .and(path(format!("/files/{}", Self::filename(modid, version))))
+            .respond_with(ResponseTemplate::new(500))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Makes lookups of `modid` fail with a server error.
+    pub async fn add_failing_mod(&self, modid: &str) {
+        Mock::given(method("GET"))
+            .and(path(format!("/api/mod/{modid}")))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&self.server)
+            .await;
+    }
+}
+
+/// A [`ModRepository`] serving mods from memory, for tests that don't need HTTP at all.
+#[derive(Default)]
+pub struct MemoryRepository {
+    /// `/api/mod/<modid>` responses by mod ID.
+    mods: BTreeMap<String, Value>,
+    /// Release files by download URL.
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryRepository {
+    /// Publishes a mod with the given releases, newest first.
+    pub fn with_mod(mut self, modid: &str, versions: &[&str]) -> Self {
+        let file_url = |version: &str| format!("memory://{}", MockModDb::filename(modid, version));
+        for version in versions {
+            self.files
+                .insert(file_url(version), mod_zip(modid, version));
+        }
+        self.mods
+            .insert(modid.to_string(), mod_response(modid, versions, file_url));
+        self
+    }
+}
+
+impl ModRepository for MemoryRepository {
+    async fn get_mod(&self, identifier: &str) -> Result<ModApiResponse, ClientError> {
+        let body = self
+            .mods
+            .get(identifier)
+            .ok_or_else(|| ClientError::ModNotFound(identifier.to_string()))?;
+        Ok(serde_json::from_value(body.clone())?)
+    }
+
+    async fn search_mods(&self, _query: String) -> Result<ModSearchResponse, ClientError> {
+        let modids: Vec<&str> = self.mods.keys().map(String::as_str).collect();
+        Ok(serde_json::from_value(search_json(&modids))?)
+    }
+
+    async fn resolve_query(&self, query: Query) -> Result<Query, ClientError> {
+        Ok(query)
+    }
+
+    async fn fetch_tags(&self) -> Result<&[Tag], ClientError> {
+        Ok(&[])
+    }
+
+    async fn fetch_file_size(&self, url: &str) -> Result<Option<u64>, ClientError> {
+        Ok(self.files.get(url).map(|bytes| bytes.len() as u64))
+    }
+
+    async fn fetch_file(&self, url: String) -> Result<Vec<u8>, ClientError> {
+        self.files
+            .get(&url)
+            .cloned()
+            .ok_or(ClientError::ModNotFound(url))
+    }
+}
+
+/// A `/api/mod/<modid>` response listing the given releases, newest first.
+///
+/// # Arguments
+///
+/// * `file_url` - The download URL of a release, by version.
+pub fn mod_response(modid: &str, versions: &[&str], file_url: impl Fn(&str) -> String) -> Value {
+    let releases: Vec<Value> = versions
+        .iter()
+        .enumerate()
+        .map(|(index, version)| {
+            json!({
+                "releaseid": 100 + index,
+                "mainfile": file_url(version),
+                "filename": MockModDb::filename(modid, version),
+                "fileid": 200 + index,
+                "downloads": 10,
+                "tags": ["v1.20.0"],
+                "modidstr": modid,
+                "modversion": version,
+                "created": "2025-01-01 12:00:00",
+                "changelog": format!("<p>Changes in {version}</p>"),
+            })
+        })
+        .collect();
+
+    json!({
+        "statuscode": "200",
+        "mod": {
+            "modid": 1,
+            "assetid": 1,
+            "name": modid,
+            "text": "",
+            "author": "tester",
+            "urlalias": null,
+            "logofilename": null,
+            "logofile": null,
+            "homepageurl": null,
+            "sourcecodeurl": null,
+            "trailervideourl": null,
+            "issuetrackerurl": null,
+            "wikiurl": null,
+            "downloads": 10,
+            "follows": 0,
+            "trendingpoints": 0,
+            "comments": 0,
+            "side": "both",
+            "type": "mod",
+            "created": "2025-01-01 12:00:00",
+            "lastmodified": "2025-01-01 12:00:00",
+            "tags": [],
+            "releases": releases,
+            "screenshots": [],
+        }
+    })
+}
+
+/// A search response listing the given mods, in order.
+fn search_response(modids: &[&str]) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(search_json(modids))
+}
+
+/// The body of a search response listing the given mods, in order.
+pub fn search_json(modids: &[&str]) -> Value {
+    let mods: Vec<Value> = modids
+        .iter()
+        .enumerate()
+        .map(|(index, modid)| {
+            json!({
+                "modid": index + 1,
+                "assetid": index + 1,
+                "downloads": 10,
+                "follows": 0,
+                "trendingpoints": 0,
+                "comments": 0,
+                "name": modid,
+                "summary": null,
+                "modidstrs": [modid],
+                "author": "tester",
+                "urlalias": null,
+                "side": "both",
+                "type": "mod",
+                "logo": null,
+                "tags": [],
+                "lastreleased": null,
+            })
+        })
+        .collect();
+
+    json!({
+        "statuscode": "200",
+        "mods": mods,
+    })
+}
+
+/// Builds a minimal mod zip containing a `modinfo.json`, byte-for-byte reproducible.
+pub fn mod_zip(modid: &str, version: &str) -> Vec<u8> {
+    modinfo_zip(&json!({ "modid": modid, "name": modid, "version": version }).to_string())
+}
+
+/// Builds a mod zip whose `modinfo.json` holds exactly `modinfo`, byte-for-byte
+/// reproducible.
+pub fn modinfo_zip(modinfo: &str) -> Vec<u8> {
+    zip_of(&[("modinfo.json", modinfo.as_bytes())])
+}
+
+/// Builds a zip holding `files` as `(name, contents)` pairs, byte-for-byte reproducible.
+/// Without a `modinfo.json` it isn't a mod.
+pub fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let options =
+        zip::write::SimpleFileOptions::default().last_modified_time(zip::DateTime::default());
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        zip.start_file(*name, options).unwrap();
+        std::io::Write::write_all(&mut zip, contents).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
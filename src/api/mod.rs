@@ -1,10 +1,23 @@
+mod cache;
 mod client;
+mod links;
+#[cfg(test)]
+pub mod mock_moddb;
 mod mod_api_response;
 mod mod_info;
 mod query;
 mod releases;
+mod repository;
+mod retry;
+mod throttle;
 
+pub use cache::ResponseCache;
 pub use client::*;
+pub use links::ModPageLink;
 pub use mod_api_response::*;
 pub use mod_info::*;
-pub use query::{OrderBy, Query};
+pub use query::{OrderBy, OrderDirection, Query};
+pub use releases::{Author, Tag};
+pub use repository::ModRepository;
+pub use retry::RetryPolicy;
+pub use throttle::SearchThrottle;
@@ -1,3 +1,4 @@
+use crate::utils::compare_versions;
 use serde::Serialize;
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, de};
@@ -26,6 +27,9 @@ pub struct Release {
     pub modversion: Option<String>,
     /// The creation date of the release.
     pub created: Option<String>,
+    /// The changelog of the release, as HTML.
+    #[serde(default)]
+    pub changelog: Option<String>,
 }
 
 impl Default for Release {
@@ -40,10 +44,35 @@ impl Default for Release {
             modidstr: Some("".to_string()),
             modversion: None,
             created: Some("".to_string()),
+            changelog: None,
         }
     }
 }
 
+impl Release {
+    /// Picks the release with the highest mod version, keeping the earliest listed one
+    /// (the most recent upload) on ties.
+    ///
+    /// # Arguments
+    ///
+    /// * `releases` - The releases to choose from.
+    ///
+    /// # Returns
+    ///
+    /// The highest release, or `None` if there are no releases.
+    pub fn highest<'a>(releases: impl IntoIterator<Item = &'a Release>) -> Option<&'a Release> {
+        releases.into_iter().reduce(|best, release| {
+            let best_version = best.modversion.as_deref().unwrap_or_default();
+            let version = release.modversion.as_deref().unwrap_or_default();
+            if compare_versions(version, best_version).is_gt() {
+                release
+            } else {
+                best
+            }
+        })
+    }
+}
+
 /// Struct representing a screenshot of a mod.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Screenshot {
@@ -174,7 +203,7 @@ where
     deserializer.deserialize_option(FilenameVisitor)
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModSearchResult {
     pub modid: u16,
     pub assetid: u32,
@@ -1,6 +1,29 @@
+use crate::api::{ClientError, ModApiResponse, Release};
+use crate::utils::is_newer;
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 
+/// The modinfo.json fields read into `ModInfo`, spelled the way its serde attributes
+/// expect them.
+const FIELDS: &[&str] = &[
+    "type",
+    "name",
+    "modid",
+    "version",
+    "networkVersion",
+    "textureSize",
+    "description",
+    "website",
+    "authors",
+    "author",
+    "contributors",
+    "side",
+    "requiredOnClient",
+    "requiredOnServer",
+    "dependencies",
+];
+
 /// Struct representing the information of a mod. that's given in the modinfo.json file.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase", default)]
@@ -31,10 +54,13 @@ pub struct ModInfo {
     #[serde(deserialize_with = "deserialize_optional", flatten)]
     pub website: Option<String>,
     /// The authors of the mod.
-    #[serde(deserialize_with = "deserialize_optional")]
+    #[serde(deserialize_with = "deserialize_people")]
     pub authors: Option<Vec<String>>,
+    /// The single `author` field used by older modinfo files.
+    #[serde(deserialize_with = "deserialize_people", skip_serializing)]
+    pub author: Option<Vec<String>>,
     /// The contributors to the mod.
-    #[serde(deserialize_with = "deserialize_optional")]
+    #[serde(deserialize_with = "deserialize_people")]
     pub contributors: Option<Vec<String>>,
     /// The side of the mod.
     #[serde(deserialize_with = "deserialize_optional")]
@@ -63,6 +89,7 @@ impl Default for ModInfo {
             description: None,
             website: None,
             authors: None,
+            author: None,
             contributors: None,
             side: None,
             required_on_client: None,
@@ -72,6 +99,114 @@ impl Default for ModInfo {
     }
 }
 
+impl ModInfo {
+    /// Parses a modinfo.json as leniently as the game reads it.
+    ///
+    /// The file may use JSON5 syntax (comments, trailing commas, single quotes, unquoted
+    /// keys) and spell field names in any case, e.g. `ModID` or `Version`. The mod ID and
+    /// the IDs of dependencies are lowercased, as mods are matched by them
+    /// case-insensitively; all other values keep their case.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `ModInfo`, or `None` if the text is not a JSON5 object.
+    pub fn parse(text: &str) -> Option<Self> {
+        let Value::Object(fields) =
+            json5::from_str::<Value>(text.trim_start_matches('\u{feff}')).ok()?
+        else {
+            return None;
+        };
+
+        let mut normalized = Map::new();
+        for (key, value) in fields {
+            let key = FIELDS
+                .iter()
+                .find(|field| field.eq_ignore_ascii_case(&key))
+                .map_or(key, |field| field.to_string());
+            normalized.insert(key, value);
+        }
+        if let Some(Value::String(modid)) = normalized.get_mut("modid") {
+            *modid = modid.to_lowercase();
+        }
+        if let Some(Value::Object(dependencies)) = normalized.remove("dependencies") {
+            let dependencies = dependencies
+                .into_iter()
+                .map(|(modid, version)| (modid.to_lowercase(), version))
+                .collect();
+            normalized.insert("dependencies".to_string(), Value::Object(dependencies));
+        }
+
+        serde_json::from_value(Value::Object(normalized)).ok()
+    }
+
+    /// Returns the mod's authors, from either the `authors` list or the older `author` field.
+    pub fn author_names(&self) -> Vec<String> {
+        let mut names = self.authors.clone().unwrap_or_default();
+        for name in self.author.iter().flatten() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+
+    /// Compares local Modinfo with the API Modinfo for updates.
+    ///
+    /// Releases are compared by semantic version rather than upload order, so an installed
+    /// pre-release or a newer locally built version is not reported as outdated.
+    ///
+    /// # Arguments
+    /// * `api_mod` - The mod's entry, as returned by `get_mod`.
+    ///
+    /// # Returns
+    /// A tuple containing a boolean indicating if an update is available and the newest release.
+    pub fn check_for_update(
+        &self, api_mod: &ModApiResponse,
+    ) -> Result<(bool, Release), ClientError> {
+        let mod_id = self
+            .modid
+            .clone()
+            .ok_or_else(|| ClientError::ModNotFound(self.name.clone().unwrap_or_default()))?;
+        let latest = Release::highest(&api_mod.mod_data.releases)
+            .ok_or_else(|| ClientError::ModNotFound(mod_id.clone()))?;
+
+        let is_update_available = match (&self.version, &latest.modversion) {
+            (Some(installed), Some(latest)) => is_newer(latest, installed),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+
+        Ok((is_update_available, latest.clone()))
+    }
+}
+
+/// A list of people, written either as a JSON array or as one comma-separated string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum People {
+    List(Vec<String>),
+    One(String),
+}
+
+/// Deserializes `authors`-style fields, accepting both a list and a single string.
+fn deserialize_people<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let names = match Option::<People>::deserialize(deserializer).ok().flatten() {
+        Some(People::List(names)) => names,
+        Some(People::One(names)) => names.split(',').map(str::to_string).collect(),
+        None => return Ok(None),
+    };
+    Ok(Some(
+        names
+            .into_iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect(),
+    ))
+}
+
 // Hacky way to deserialize optional fields.
 // This is needed because the modinfo.json file can have optional fields. and sometimes the fields have invalid types and Serde throws a hissy fit.
 fn deserialize_optional<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
@@ -81,3 +216,40 @@ where
 {
     Ok(Option::<T>::deserialize(deserializer).ok().flatten())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lenient_modinfo_without_changing_values() {
+        let modinfo = r#"{
+            // Written by hand
+            Type: 'code',
+            "ModID": "CarryOn",
+            "Name": "Carry On",
+            "Version": "1.8.0-RC.1",
+            "Description": "Carry Chests & Baskets",
+            "TextureSize": 32,
+            "RequiredOnClient": true,
+            "Dependencies": { "Game": "1.19.0", },
+            /* trailing comma */
+        }"#;
+
+        let info = ModInfo::parse(modinfo).unwrap();
+        assert_eq!(info.type_.as_deref(), Some("code"));
+        assert_eq!(info.modid.as_deref(), Some("carryon"));
+        assert_eq!(info.name.as_deref(), Some("Carry On"));
+        assert_eq!(info.version.as_deref(), Some("1.8.0-RC.1"));
+        assert_eq!(info.description.as_deref(), Some("Carry Chests & Baskets"));
+        assert_eq!(info.texture_size, Some(32));
+        assert_eq!(info.required_on_client, Some(true));
+        assert_eq!(
+            info.dependencies.unwrap().get("game").map(String::as_str),
+            Some("1.19.0")
+        );
+
+        assert!(ModInfo::parse("\u{feff}{\"modid\": \"hudclock\"}").is_some());
+        assert!(ModInfo::parse("[1, 2]").is_none());
+    }
+}
@@ -14,7 +14,7 @@
 ///
 /// * Example: Search Example: http://mods.vintagestory.at/api/mods?text=jack&tagids\[\]=7&tagids\[\]=8&orderby=Downloads
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum OrderBy {
     AssetCreated,
     LastReleased,
@@ -24,22 +24,26 @@ pub enum OrderBy {
     TrendingPoints,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum OrderDirection {
     Desc,
     Asc,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Query {
     /// Vector of tag IDs to filter by (AND).
     pub tag_ids: Vec<u16>,
+    /// Tag names still to be resolved to `tag_ids`.
+    pub tag_names: Vec<String>,
     /// Optional game version ID to filter by.
     pub game_version: Option<u16>,
     /// Vector of game version IDs to filter by (OR).
     pub game_versions: Vec<u16>,
     /// Optional author ID to filter by.
     pub author: Option<u16>,
+    /// Author name still to be resolved to `author`.
+    pub author_name: Option<String>,
     /// Optional text to search by mod text and title.
     pub text: Option<Vec<String>>,
     /// Optional order by field.
@@ -48,14 +52,22 @@ pub struct Query {
     pub order_direction: Option<OrderDirection>,
 }
 
+impl Default for Query {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Query {
     /// Creates a new `Query` instance with default values.
     pub fn new() -> Self {
         Self {
             tag_ids: Vec::new(),
+            tag_names: Vec::new(),
             game_version: None,
             game_versions: Vec::new(),
             author: None,
+            author_name: None,
             text: None,
             order_by: None,
             order_direction: None,
@@ -74,7 +86,9 @@ impl Query {
     ///
     /// # Example
     /// ```
-    /// let query = api::Query::new().with_tag_ids(vec![1, 2, 3]).build();
+    /// use vintage_mod_manager::api::Query;
+    ///
+    /// let query = Query::new().with_tag_ids(vec![1, 2, 3]).build();
     ///
     /// assert_eq!(query, "tagids[]=1&tagids[]=2&tagids[]=3");
     /// ```
@@ -83,6 +97,25 @@ impl Query {
         self
     }
 
+    /// Filters by tags given by name, such as `"QoL"`.
+    ///
+    /// The names are only sent once `VintageApiHandler::resolve_query` has looked up
+    /// their IDs; `build` ignores unresolved names.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_names` - The tag names, matched case-insensitively.
+    ///
+    /// # Returns
+    ///
+    /// The updated `Query` instance.
+    pub fn with_tag_names<S: Into<String>>(
+        mut self, tag_names: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.tag_names = tag_names.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Sets the game version for the query.
     ///
     /// # Arguments
@@ -95,7 +128,9 @@ impl Query {
     ///
     /// # Example
     /// ```
-    /// let query = api::Query::new().with_game_version(42).build();
+    /// use vintage_mod_manager::api::Query;
+    ///
+    /// let query = Query::new().with_game_version(42).build();
     ///
     /// assert_eq!(query, "gameversion=42");
     /// ```
@@ -118,7 +153,9 @@ impl Query {
     /// # Example
     ///
     /// ```
-    /// let query = api::Query::new()
+    /// use vintage_mod_manager::api::Query;
+    ///
+    /// let query = Query::new()
     ///     .with_game_versions(vec![1, 2])
     ///     .build();
     ///
@@ -142,7 +179,9 @@ impl Query {
     ///
     /// # Example
     /// ```
-    /// let query = api::Query::new()
+    /// use vintage_mod_manager::api::Query;
+    ///
+    /// let query = Query::new()
     ///     .with_author(7)
     ///     .build();
     ///
@@ -153,6 +192,23 @@ impl Query {
         self
     }
 
+    /// Filters by an author given by name, such as `"Tyron"`.
+    ///
+    /// Like [`Self::with_tag_names`], the name has to be resolved with
+    /// `VintageApiHandler::resolve_query` before the query is built.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_name` - The author's name, matched case-insensitively.
+    ///
+    /// # Returns
+    ///
+    /// The updated `Query` instance.
+    pub fn with_author_name(mut self, author_name: &str) -> Self {
+        self.author_name = Some(author_name.to_string());
+        self
+    }
+
     /// Sets the text for the query.
     ///
     /// # Arguments
@@ -166,8 +222,10 @@ impl Query {
     /// # Example
     ///
     /// ```
-    /// let query = api::Query::new()
-    ///     .with_text("example")
+    /// use vintage_mod_manager::api::Query;
+    ///
+    /// let query = Query::new()
+    ///     .with_text(&["example".to_string()])
     ///     .build();
     ///
     /// assert_eq!(query, "text=example");
@@ -190,11 +248,13 @@ impl Query {
     ///
     /// # Example
     /// ```
-    /// let query = api::Query::new()
-    ///     .with_order_by(api::OrderBy::Downloads)
+    /// use vintage_mod_manager::api::{OrderBy, Query};
+    ///
+    /// let query = Query::new()
+    ///     .with_order_by(OrderBy::Downloads)
     ///     .build();
     ///
-    /// assert_eq!(query, "orderby=Downloads");
+    /// assert_eq!(query, "orderby=downloads");
     /// ```
     pub fn with_order_by(mut self, order_by: OrderBy) -> Self {
         self.order_by = Some(order_by);
@@ -213,8 +273,10 @@ impl Query {
     ///
     /// # Example
     /// ```
-    /// let query = api::Query::new()
-    ///     .with_order_direction(api::OrderDirection::Asc)
+    /// use vintage_mod_manager::api::{OrderDirection, Query};
+    ///
+    /// let query = Query::new()
+    ///     .with_order_direction(OrderDirection::Asc)
     ///     .build();
     ///
     /// assert_eq!(query, "orderdirection=asc");
@@ -232,13 +294,15 @@ impl Query {
     ///
     /// # Example
     /// ```
-    /// let query = api::Query::new()
+    /// use vintage_mod_manager::api::{OrderBy, OrderDirection, Query};
+    ///
+    /// let query = Query::new()
     ///     .with_tag_ids(vec![1, 2])
     ///     .with_game_version(42)
     ///     .with_author(7)
-    ///     .with_text("example")
-    ///     .with_order_by(api::OrderBy::Downloads)
-    ///     .with_order_direction(api::OrderDirection::Desc)
+    ///     .with_text(&["example".to_string()])
+    ///     .with_order_by(OrderBy::Downloads)
+    ///     .with_order_direction(OrderDirection::Desc)
     ///     .build();
     ///
     /// assert_eq!(query, "tagids[]=1&tagids[]=2&gameversion=42&author=7&text=example&orderby=downloads&orderdirection=desc");
@@ -12,3 +12,30 @@ pub struct Version {
     pub name: String,
     pub color: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagsResponse {
+    pub statuscode: String,
+    pub tags: Vec<Tag>,
+}
+
+/// A mod category on ModDB, such as "Quality of Life" or "Storage".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub tagid: i64,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorsResponse {
+    pub statuscode: String,
+    pub authors: Vec<Author>,
+}
+
+/// A ModDB user that has published mods.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Author {
+    pub userid: i64,
+    pub name: String,
+}
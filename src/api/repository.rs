@@ -0,0 +1,47 @@
+use crate::api::releases::Tag;
+use crate::api::{ClientError, ModApiResponse, ModSearchResponse, Query};
+
+/// A source of mods: where they are looked up, searched for and downloaded from.
+///
+/// [`VintageApiHandler`](crate::api::VintageApiHandler) talks to ModDB. Other
+/// implementations can serve mods from elsewhere, such as a local directory or canned
+/// responses in tests.
+pub trait ModRepository {
+    /// Fetches a mod and all its releases.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The mod ID string (e.g. `carryon`) or the numeric ModDB ID.
+    fn get_mod(
+        &self, identifier: &str,
+    ) -> impl Future<Output = Result<ModApiResponse, ClientError>>;
+
+    /// Searches for mods.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The query string, as built by [`Query::build`].
+    fn search_mods(
+        &self, query: String,
+    ) -> impl Future<Output = Result<ModSearchResponse, ClientError>>;
+
+    /// Replaces the tag and author names of `query` with their IDs.
+    fn resolve_query(&self, query: Query) -> impl Future<Output = Result<Query, ClientError>>;
+
+    /// Fetches the tags mods can be browsed by.
+    fn fetch_tags(&self) -> impl Future<Output = Result<&[Tag], ClientError>>;
+
+    /// Asks for the size of a release file without downloading it.
+    ///
+    /// # Returns
+    ///
+    /// The size in bytes, or `None` if it isn't known up front.
+    fn fetch_file_size(&self, url: &str) -> impl Future<Output = Result<Option<u64>, ClientError>>;
+
+    /// Downloads a release file.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The `mainfile` URL of the release.
+    fn fetch_file(&self, url: String) -> impl Future<Output = Result<Vec<u8>, ClientError>>;
+}
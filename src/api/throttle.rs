@@ -0,0 +1,80 @@
+use crate::api::ModSearchResult;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Spaces out search requests sent to ModDB and remembers their results.
+///
+/// Used by the interactive server-side search, so refining a search or going back to an
+/// earlier one never sends the same query twice and new queries are at least
+/// `min_interval` apart.
+pub struct SearchThrottle {
+    min_interval: Duration,
+    last_request: Option<Instant>,
+    results: HashMap<String, Vec<ModSearchResult>>,
+}
+
+impl SearchThrottle {
+    /// Creates a new `SearchThrottle`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_interval` - The minimum time between two requests.
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_request: None,
+            results: HashMap::new(),
+        }
+    }
+
+    /// Returns the results of an earlier identical query, if any.
+    pub fn cached(&self, query: &str) -> Option<&Vec<ModSearchResult>> {
+        self.results.get(&Self::key(query))
+    }
+
+    /// Remembers the results of a query.
+    pub fn store(&mut self, query: &str, results: Vec<ModSearchResult>) {
+        self.results.insert(Self::key(query), results);
+    }
+
+    /// Waits until the next request may be sent and marks it as sent.
+    pub async fn wait_turn(&mut self) {
+        if let Some(last_request) = self.last_request {
+            let elapsed = last_request.elapsed();
+            if elapsed < self.min_interval {
+                tokio::time::sleep(self.min_interval - elapsed).await;
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    fn key(query: &str) -> String {
+        query.trim().to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_turn_spaces_out_requests() {
+        let mut throttle = SearchThrottle::new(Duration::from_millis(50));
+        let start = Instant::now();
+
+        throttle.wait_turn().await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        throttle.wait_turn().await;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn cached_ignores_case_and_whitespace() {
+        let mut throttle = SearchThrottle::new(Duration::ZERO);
+        throttle.store("Carry On", Vec::new());
+
+        assert!(throttle.cached("  carry on ").is_some());
+        assert!(throttle.cached("carry").is_none());
+    }
+}
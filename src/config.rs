@@ -1,4 +1,6 @@
+use crate::utils::{ModVersion, PreRelease};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -14,9 +16,310 @@ impl VersionMapping {
     }
 }
 
+/// How downloaded mod files are placed into the mods directory.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMode {
+    /// Write a regular copy of the file into the mods directory.
+    #[default]
+    Copy,
+    /// Keep the file in the shared mod store and symlink it into the mods directory.
+    Symlink,
+    /// Keep the file in the shared mod store and hardlink it into the mods directory.
+    Hardlink,
+}
+
+/// How unstable the releases `update` and `download` may pick are.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    /// Only stable releases.
+    #[default]
+    Stable,
+    /// Stable releases, release candidates (`-rc.N`) and previews (`-pre.N`).
+    Rc,
+    /// Every release, including dev builds (`-dev.N`) and other pre-releases.
+    Dev,
+}
+
+impl Channel {
+    /// Returns `true` if a release with this mod version may be picked on this channel.
+    /// Versions that can't be parsed are treated as stable.
+    pub fn allows(self, version: &str) -> bool {
+        let required = match ModVersion::parse(version)
+            .as_ref()
+            .and_then(ModVersion::pre_release)
+        {
+            None => Channel::Stable,
+            Some(PreRelease::Rc | PreRelease::Pre) => Channel::Rc,
+            Some(PreRelease::Dev | PreRelease::Other(_)) => Channel::Dev,
+        };
+        required <= self
+    }
+}
+
+/// A mod version pinned by a profile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProfileMod {
+    pub modid: String,
+    pub version: String,
+}
+
+/// A named set of mods that can be switched to as a whole.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Profile {
+    pub mods: Vec<ProfileMod>,
+}
+
+/// A game installation, selected with `--instance`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Instance {
+    /// The instance's `VintagestoryData` directory, which holds its `Mods` folder.
+    pub data_path: PathBuf,
+}
+
+/// A game server whose mods directory is synchronized over SFTP with `remote`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Remote {
+    /// Host name of the server, or a `Host` alias from `~/.ssh/config`.
+    pub host: String,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    /// Private key to log in with instead of the SSH agent or the default keys.
+    pub identity_file: Option<PathBuf>,
+    /// The server's `Mods` directory.
+    pub path: String,
+}
+
+impl Remote {
+    /// Parses an scp-style `[user@]host:path` target.
+    ///
+    /// # Returns
+    ///
+    /// The remote, or `None` if the host or path is missing.
+    pub fn from_target(target: &str) -> Option<Self> {
+        let (destination, path) = target.split_once(':')?;
+        let (user, host) = match destination.rsplit_once('@') {
+            Some((user, host)) => (Some(user.to_string()), host),
+            None => (None, destination),
+        };
+        if host.is_empty() || path.is_empty() || user.as_deref() == Some("") {
+            return None;
+        }
+        Some(Self {
+            host: host.to_string(),
+            user,
+            port: None,
+            identity_file: None,
+            path: path.to_string(),
+        })
+    }
+
+    /// The `[user@]host` destination passed to `sftp`.
+    pub fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{user}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+}
+
+/// Payload style of a webhook.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// Discord webhook, posts the summary as a chat message.
+    #[default]
+    Discord,
+    /// Slack incoming webhook, posts the summary as a chat message.
+    Slack,
+    /// Generic endpoint, receives the full run summary as JSON.
+    Json,
+}
+
+/// A webhook notified at the end of update runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Webhook {
+    pub url: String,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    /// Also post updates found by `check` and `watch` before they are installed.
+    #[serde(default)]
+    pub available: bool,
+}
+
+/// Retry, timeout and proxy settings for requests to ModDB.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Attempts per request, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry in milliseconds, doubled for every further retry.
+    pub retry_delay_ms: u64,
+    /// Seconds a connection may stay silent before the attempt fails.
+    pub timeout_secs: u64,
+    /// Seconds establishing a connection may take before the attempt fails.
+    pub connect_timeout_secs: u64,
+    /// Proxy for every request, e.g. `http://proxy:8080` or `socks5://127.0.0.1:1080`.
+    /// When unset, the `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` environment variables
+    /// are used.
+    pub proxy: Option<String>,
+    /// Base URL of the ModDB API, e.g. a self-hosted mirror. Defaults to
+    /// `https://mods.vintagestory.at`.
+    pub api_url: Option<String>,
+    /// Mirrors of the ModDB API tried in order when the API can't be reached.
+    pub mirrors: Vec<String>,
+    /// Seconds ModDB API responses are reused from the cache before being revalidated,
+    /// `0` disables the cache.
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            retry_delay_ms: 500,
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+            proxy: None,
+            api_url: None,
+            mirrors: Vec::new(),
+            cache_ttl_secs: 600,
+        }
+    }
+}
+
+/// Which commands an ignore list entry hides a mod from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum IgnoreScope {
+    /// Every command: installed copies aren't listed, updated or exported, and browse
+    /// results leave the mod out.
+    #[default]
+    All,
+    /// `update` and `check` leave the installed version alone.
+    Updates,
+    /// `browse` results leave the mod out, e.g. for NSFW or broken mods.
+    Browse,
+}
+
+/// Mods that commands skip, by lowercase mod ID.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct IgnoreLists {
+    /// Mods skipped by every command.
+    pub mods: Vec<String>,
+    /// Mods `update` and `check` skip.
+    pub updates: Vec<String>,
+    /// Mods hidden from `browse` results.
+    pub browse: Vec<String>,
+}
+
+impl IgnoreLists {
+    fn list_mut(&mut self, scope: IgnoreScope) -> &mut Vec<String> {
+        match scope {
+            IgnoreScope::All => &mut self.mods,
+            IgnoreScope::Updates => &mut self.updates,
+            IgnoreScope::Browse => &mut self.browse,
+        }
+    }
+
+    /// Adds a mod to the list for `scope`, returning `false` if it was already on it.
+    pub fn add(&mut self, scope: IgnoreScope, modid: &str) -> bool {
+        let modid = modid.to_lowercase();
+        let list = self.list_mut(scope);
+        if list.contains(&modid) {
+            return false;
+        }
+        list.push(modid);
+        true
+    }
+
+    /// Removes a mod from the list for `scope`, returning `false` if it was not on it.
+    pub fn remove(&mut self, scope: IgnoreScope, modid: &str) -> bool {
+        let list = self.list_mut(scope);
+        let len = list.len();
+        list.retain(|ignored| !ignored.eq_ignore_ascii_case(modid));
+        list.len() != len
+    }
+
+    /// Returns `true` if commands of `scope` skip the mod. Mods on the `mods` list are
+    /// skipped by every scope.
+    pub fn ignores(&self, scope: IgnoreScope, modid: &str) -> bool {
+        let scoped = match scope {
+            IgnoreScope::All => &[][..],
+            IgnoreScope::Updates => &self.updates,
+            IgnoreScope::Browse => &self.browse,
+        };
+        self.mods
+            .iter()
+            .chain(scoped)
+            .any(|ignored| ignored.eq_ignore_ascii_case(modid))
+    }
+
+    /// Returns `true` if no mod is ignored.
+    pub fn is_empty(&self) -> bool {
+        self.mods.is_empty() && self.updates.is_empty() && self.browse.is_empty()
+    }
+}
+
+/// Limits applied when pruning a directory of backups or cached files.
+///
+/// Every limit is optional; `None` disables that rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Remove entries older than this many days.
+    pub max_age_days: Option<u64>,
+    /// Remove the oldest entries until the directory is below this size.
+    pub max_size_mb: Option<u64>,
+    /// Keep at most this many entries for the same mod.
+    pub max_count_per_mod: Option<usize>,
+}
+
+impl RetentionPolicy {
+    /// Default policy for mod backups.
+    pub fn backups() -> Self {
+        Self {
+            max_age_days: Some(30),
+            max_size_mb: None,
+            max_count_per_mod: Some(5),
+        }
+    }
+
+    /// Default policy for the response cache.
+    pub fn cache() -> Self {
+        Self {
+            max_age_days: Some(7),
+            max_size_mb: Some(200),
+            max_count_per_mod: None,
+        }
+    }
+}
+
+/// The config layout written by this build, see `ConfigManager::load_config_from_file`
+/// for how older layouts are upgraded.
+pub const CONFIG_SCHEMA_VERSION: u32 = 1;
+
 /// Struct to represent the configuration settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
+    /// Layout version of the config file; files without one predate versioning
+    pub schema_version: u32,
+
     /// Path to the Vintage Story game installation directory
     pub game_path: Option<PathBuf>,
 
@@ -25,15 +328,96 @@ pub struct Config {
 
     /// Current detected game version (auto-detected from assets/{version}.txt)
     pub detected_game_version: Option<String>,
+
+    /// Game version set with `config set-game-version`, used instead of the detected one
+    pub preferred_game_version: Option<String>,
+
+    /// Main mods directory used instead of `VintagestoryData/Mods`, e.g. on servers
+    /// started with `--dataPath`
+    pub mods_dir: Option<PathBuf>,
+
+    /// Additional mod directories searched after the main mods directory, in order
+    /// (the equivalent of the game's `--addModPath` option)
+    pub additional_mod_paths: Vec<PathBuf>,
+
+    /// How downloaded mods are placed into the mods directory
+    pub install_mode: InstallMode,
+
+    /// Named mod profiles
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// The profile that was switched to last
+    pub active_profile: Option<String>,
+
+    /// Retention policy for mod backups
+    pub backup_retention: RetentionPolicy,
+
+    /// Retention policy for cached API responses
+    pub cache_retention: RetentionPolicy,
+
+    /// Webhooks notified with a summary after update runs
+    pub webhooks: Vec<Webhook>,
+
+    /// Retry and timeout settings for requests to ModDB
+    pub network: NetworkConfig,
+
+    /// Create the default mods directory when it doesn't exist yet instead of failing,
+    /// e.g. on servers provisioned before the game first ran
+    pub create_mods_dir: bool,
+
+    /// Template new mod files are named after (e.g. `{modid}_v{version}.zip`) instead of
+    /// the name they have on ModDB
+    pub filename_template: Option<String>,
+
+    /// The game version seen on the previous run, used to notice game updates
+    pub last_seen_game_version: Option<String>,
+
+    /// Mod IDs (lowercase) that `update` leaves at their installed version
+    pub pinned_mods: Vec<String>,
+
+    /// Mods hidden from commands, managed with `config ignore`
+    pub ignore: IgnoreLists,
+
+    /// Release channel used for mods without an override
+    pub channel: Channel,
+
+    /// Release channels of individual mods, by lowercase mod ID
+    pub mod_channels: BTreeMap<String, Channel>,
+
+    /// Named game installations that `--instance` selects between
+    pub instances: BTreeMap<String, Instance>,
+
+    /// Named game servers synchronized with `remote`
+    pub remotes: BTreeMap<String, Remote>,
 }
 
 impl Config {
     /// Creates a new `Config` instance with default values.
     pub fn new() -> Self {
         Self {
+            schema_version: CONFIG_SCHEMA_VERSION,
             game_path: None,
             version_mapping: Vec::new(),
             detected_game_version: None,
+            preferred_game_version: None,
+            mods_dir: None,
+            additional_mod_paths: Vec::new(),
+            install_mode: InstallMode::default(),
+            profiles: BTreeMap::new(),
+            active_profile: None,
+            backup_retention: RetentionPolicy::backups(),
+            cache_retention: RetentionPolicy::cache(),
+            webhooks: Vec::new(),
+            network: NetworkConfig::default(),
+            create_mods_dir: false,
+            filename_template: None,
+            last_seen_game_version: None,
+            pinned_mods: Vec::new(),
+            ignore: IgnoreLists::default(),
+            channel: Channel::default(),
+            mod_channels: BTreeMap::new(),
+            instances: BTreeMap::new(),
+            remotes: BTreeMap::new(),
         }
     }
 
@@ -122,6 +506,20 @@ impl Config {
         self.detected_game_version.as_ref()
     }
 
+    /// Records the detected game version as seen, noticing game updates.
+    ///
+    /// # Returns
+    ///
+    /// The previously seen and the newly detected version if the game version changed
+    /// since the last run, `None` on the first run or when nothing changed.
+    pub fn note_game_version(&mut self) -> Option<(String, String)> {
+        let detected = self.detected_game_version.clone()?;
+        match self.last_seen_game_version.replace(detected.clone()) {
+            Some(previous) if previous != detected => Some((previous, detected)),
+            _ => None,
+        }
+    }
+
     /// Get the tag ID for the detected game version
     pub fn get_detected_version_tag_id(&self) -> Option<i64> {
         if let Some(version) = &self.detected_game_version {
@@ -131,6 +529,20 @@ impl Config {
         }
     }
 
+    /// Gets the game version mods are matched against: the preferred version if one is
+    /// set, otherwise the detected one.
+    pub fn get_game_version(&self) -> Option<&String> {
+        self.preferred_game_version
+            .as_ref()
+            .or(self.detected_game_version.as_ref())
+    }
+
+    /// Gets the tag ID of the game version from [`Config::get_game_version`].
+    pub fn get_game_version_tag_id(&self) -> Option<i64> {
+        self.get_game_version()
+            .and_then(|version| self.get_tag_from_version(version))
+    }
+
     /// Check if the detected game version is compatible (has a mapping)
     pub fn is_detected_version_mapped(&self) -> bool {
         if let Some(version) = &self.detected_game_version {
@@ -164,6 +576,54 @@ impl Config {
         self.game_path = Some(path);
     }
 
+    /// Adds an additional mod directory, returning `false` if it was already present.
+    pub fn add_mod_path(&mut self, path: PathBuf) -> bool {
+        if self.additional_mod_paths.contains(&path) {
+            return false;
+        }
+        self.additional_mod_paths.push(path);
+        true
+    }
+
+    /// Removes an additional mod directory, returning `false` if it was not present.
+    pub fn remove_mod_path(&mut self, path: &PathBuf) -> bool {
+        let len = self.additional_mod_paths.len();
+        self.additional_mod_paths
+            .retain(|existing| existing != path);
+        self.additional_mod_paths.len() != len
+    }
+
+    /// Pins a mod so updates skip it, returning `false` if it was already pinned.
+    pub fn pin_mod(&mut self, modid: &str) -> bool {
+        let modid = modid.to_lowercase();
+        if self.pinned_mods.contains(&modid) {
+            return false;
+        }
+        self.pinned_mods.push(modid);
+        true
+    }
+
+    /// Unpins a mod, returning `false` if it was not pinned.
+    pub fn unpin_mod(&mut self, modid: &str) -> bool {
+        let modid = modid.to_lowercase();
+        let len = self.pinned_mods.len();
+        self.pinned_mods.retain(|pinned| *pinned != modid);
+        self.pinned_mods.len() != len
+    }
+
+    /// Adds or replaces the webhook for `webhook.url`.
+    pub fn add_webhook(&mut self, webhook: Webhook) {
+        self.webhooks.retain(|existing| existing.url != webhook.url);
+        self.webhooks.push(webhook);
+    }
+
+    /// Removes a webhook by URL, returning `false` if it was not configured.
+    pub fn remove_webhook(&mut self, url: &str) -> bool {
+        let len = self.webhooks.len();
+        self.webhooks.retain(|existing| existing.url != url);
+        self.webhooks.len() != len
+    }
+
     /// Gets a version string from a tag ID.
     pub fn get_version_from_tag(&self, tag_id: i64) -> Option<&String> {
         self.version_mapping
@@ -0,0 +1,49 @@
+//! Mod management for Vintage Story, usable without the command line interface.
+//!
+//! The `VintageModManager` binary is a thin wrapper around this crate. Launchers and
+//! other tools can embed the same functionality instead of shelling out to it:
+//!
+//! * [`VintageApiHandler`] searches ModDB, fetches mods and downloads release files.
+//! * [`FileManager`] finds installed mods and reads their `modinfo.json`.
+//! * [`Encoder`] turns mod lists into shareable strings and back.
+//! * [`LockFile`] records the exact releases of a mods directory.
+//! * [`ModVersion`] and [`compare_versions`] order mod and game versions.
+//!
+//! None of these prompt the user or print their results; they report what happened
+//! through their return values and log warnings, such as retried requests, with
+//! `tracing`. The only terminal output is the progress bar of a file download, drawn on
+//! stderr unless turned off with [`VintageApiHandler::with_progress`].
+//! [`ModManager`](utils::ModManager) runs the CLI commands and does both.
+//!
+//! ```no_run
+//! use vintage_mod_manager::{Query, VintageApiHandler, compare_versions};
+//!
+//! # async fn run() -> Result<(), vintage_mod_manager::ClientError> {
+//! let api = VintageApiHandler::new();
+//! let query = Query::new().with_text(&["carry".to_string()]);
+//! let results = api.search_mods(query.build()).await?;
+//! for result in results.mods {
+//!     println!("{} by {}", result.name, result.author);
+//! }
+//!
+//! let carryon = api.get_mod("carryon").await?;
+//! let newest = carryon
+//!     .mod_data
+//!     .releases
+//!     .iter()
+//!     .filter_map(|release| release.modversion.as_deref())
+//!     .max_by(|a, b| compare_versions(a, b));
+//! println!("Newest Carry On release: {newest:?}");
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod api;
+pub mod config;
+pub mod utils;
+
+pub use api::{ClientError, ModApiResponse, ModInfo, Query, Release, VintageApiHandler};
+pub use config::Config;
+pub use utils::{
+    Encoder, EncoderData, FileManager, LockFile, LockedMod, ModVersion, compare_versions, is_newer,
+};
@@ -1,10 +1,7 @@
-mod api;
-mod config;
-mod utils;
-
-use crate::utils::{ModManager, ModManagerError};
+use std::process::ExitCode;
+use vintage_mod_manager::utils::ModManager;
 
 #[tokio::main]
-async fn main() -> Result<(), ModManagerError> {
+async fn main() -> ExitCode {
     ModManager::run().await
 }
@@ -0,0 +1,99 @@
+use crate::say;
+use crate::utils::config_manager::ConfigManager;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Git commit the binary was built from, or `unknown` outside a git checkout.
+pub const GIT_COMMIT: &str = env!("VSMM_GIT_COMMIT");
+
+/// Target triple the binary was built for.
+pub const BUILD_TARGET: &str = env!("VSMM_TARGET");
+
+/// Text printed by `--version`: the version followed by the build details.
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "\ncommit: ",
+    env!("VSMM_GIT_COMMIT"),
+    "\ntarget: ",
+    env!("VSMM_TARGET"),
+    " (",
+    env!("VSMM_PROFILE"),
+    ")\nfeatures: ",
+    env!("VSMM_FEATURES_LABEL"),
+);
+
+/// Everything worth pasting into a bug report, as printed by the `about` command.
+#[derive(Debug, Serialize)]
+pub struct About {
+    pub version: &'static str,
+    pub commit: &'static str,
+    pub target: &'static str,
+    pub profile: &'static str,
+    pub features: Vec<&'static str>,
+    pub config_path: Option<PathBuf>,
+    pub game_path: Option<PathBuf>,
+    pub game_version: Option<String>,
+    pub mods_dirs: Vec<PathBuf>,
+    pub api_url: String,
+}
+
+impl About {
+    /// Collects the build details and the runtime setup.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_manager` - The loaded configuration, if there is one.
+    /// * `mods_dirs` - The mods directories in use.
+    /// * `api_url` - The ModDB endpoint requests are sent to.
+    pub fn collect(
+        config_manager: Option<&ConfigManager>, mods_dirs: Vec<PathBuf>, api_url: &str,
+    ) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            commit: GIT_COMMIT,
+            target: BUILD_TARGET,
+            profile: env!("VSMM_PROFILE"),
+            features: env!("VSMM_FEATURES")
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .collect(),
+            config_path: config_manager.map(|config_manager| config_manager.config_path().into()),
+            game_path: config_manager
+                .and_then(|config_manager| config_manager.config().game_path.clone()),
+            game_version: config_manager
+                .and_then(|config_manager| config_manager.get_detected_game_version())
+                .cloned(),
+            mods_dirs,
+            api_url: api_url.to_string(),
+        }
+    }
+
+    /// Prints the details as aligned `key: value` lines.
+    pub fn print(&self) {
+        let or_none = |value: Option<String>| value.unwrap_or_else(|| "not set".to_string());
+
+        say!("VintageModManager {}", self.version);
+        say!("{:<14} {}", "Commit:", self.commit);
+        say!("{:<14} {} ({})", "Target:", self.target, self.profile);
+        say!("{:<14} {}", "Features:", env!("VSMM_FEATURES_LABEL"));
+        say!(
+            "{:<14} {}",
+            "Config file:",
+            or_none(self.config_path.as_ref().map(|p| p.display().to_string()))
+        );
+        say!(
+            "{:<14} {}",
+            "Game path:",
+            or_none(self.game_path.as_ref().map(|p| p.display().to_string()))
+        );
+        say!(
+            "{:<14} {}",
+            "Game version:",
+            or_none(self.game_version.clone())
+        );
+        for dir in &self.mods_dirs {
+            say!("{:<14} {}", "Mods dir:", dir.display());
+        }
+        say!("{:<14} {}", "ModDB:", self.api_url);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::utils::encoding::ModListEntry;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use thiserror::Error;
+use zip::ZipArchive;
+use zip::write::SimpleFileOptions;
+
+/// File extension of mod pack bundles.
+pub const BUNDLE_EXTENSION: &str = "vsmodpack";
+
+/// Name of the manifest inside a bundle.
+const MANIFEST_NAME: &str = "modpack.json";
+
+/// Directory inside a bundle holding the included mod files.
+const MODS_DIR: &str = "mods";
+
+/// Current version of the bundle manifest format.
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Invalid manifest: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Not a mod pack bundle, {MANIFEST_NAME} is missing")]
+    MissingManifest,
+    #[error("Unsupported bundle format version {0}, try updating VintageModManager")]
+    UnsupportedVersion(u32),
+    #[error("{0} is not included in the bundle")]
+    MissingFile(String),
+}
+
+/// A mod listed in a bundle manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledMod {
+    #[serde(flatten)]
+    pub entry: ModListEntry,
+    /// Lowercase hex SHA-256 of the mod file, unless the mod is an unpacked folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Path of the mod file inside the bundle, if the bundle includes it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
+/// The `modpack.json` at the root of a bundle, describing the pack.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The game version the pack was exported from, if it was known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_version: Option<String>,
+    pub mods: Vec<BundledMod>,
+}
+
+impl BundleManifest {
+    /// Creates a manifest for a pack without any mods yet.
+    pub fn new(name: &str) -> Self {
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            name: name.to_string(),
+            author: None,
+            description: None,
+            game_version: None,
+            mods: Vec::new(),
+        }
+    }
+}
+
+/// The path of a mod file inside a bundle.
+pub fn bundled_file_path(filename: &str) -> String {
+    format!("{MODS_DIR}/{filename}")
+}
+
+/// Writes a bundle: the manifest, followed by the mod files it points at.
+///
+/// # Arguments
+///
+/// * `path` - The bundle file to create.
+/// * `manifest` - The manifest; every mod with a `file` must have a matching entry in `files`.
+/// * `files` - The path inside the bundle and the file on disk of each included mod.
+pub fn write_bundle(
+    path: &Path, manifest: &BundleManifest, files: &[(String, &Path)],
+) -> Result<(), BundleError> {
+    let mut zip = zip::ZipWriter::new(File::create(path)?);
+    zip.start_file(MANIFEST_NAME, SimpleFileOptions::default())?;
+    zip.write_all(serde_json::to_string_pretty(manifest)?.as_bytes())?;
+
+    // Mod archives are already compressed
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, source) in files {
+        zip.start_file(name.as_str(), stored)?;
+        std::io::copy(&mut File::open(source)?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// An open bundle to install a pack from.
+pub struct Bundle {
+    archive: ZipArchive<File>,
+    pub manifest: BundleManifest,
+}
+
+impl Bundle {
+    /// Opens a bundle and reads its manifest.
+    pub fn open(path: &Path) -> Result<Self, BundleError> {
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        let manifest: BundleManifest = match archive.by_name(MANIFEST_NAME) {
+            Ok(file) => serde_json::from_reader(file)?,
+            Err(zip::result::ZipError::FileNotFound) => return Err(BundleError::MissingManifest),
+            Err(e) => return Err(e.into()),
+        };
+        if manifest.format_version > BUNDLE_FORMAT_VERSION {
+            return Err(BundleError::UnsupportedVersion(manifest.format_version));
+        }
+        Ok(Self { archive, manifest })
+    }
+
+    /// Reads an included mod file.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The path of the file inside the bundle, from [`BundledMod::file`].
+    pub fn read_file(&mut self, name: &str) -> Result<Vec<u8>, BundleError> {
+        let mut file = match self.archive.by_name(name) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => {
+                return Err(BundleError::MissingFile(name.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut bytes = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock_moddb::mod_zip;
+    use tempfile::tempdir;
+
+    #[test]
+    fn bundles_round_trip() {
+        let dir = tempdir().unwrap();
+        let mod_file = dir.path().join("carryon_v1.8.0.zip");
+        std::fs::write(&mod_file, b"mod bytes").unwrap();
+        let mut manifest = BundleManifest::new("Friends server");
+        manifest.game_version = Some("1.20.0".to_string());
+        manifest.mods = vec![
+            BundledMod {
+                entry: ModListEntry {
+                    modid: "carryon".to_string(),
+                    version: "1.8.0".to_string(),
+                    ..ModListEntry::default()
+                },
+                sha256: Some("abc".to_string()),
+                file: Some(bundled_file_path("carryon_v1.8.0.zip")),
+            },
+            BundledMod {
+                entry: ModListEntry {
+                    modid: "hudclock".to_string(),
+                    version: "2.0.0".to_string(),
+                    ..ModListEntry::default()
+                },
+                sha256: None,
+                file: None,
+            },
+        ];
+        let path = dir.path().join("pack.vsmodpack");
+        let files = [(bundled_file_path("carryon_v1.8.0.zip"), mod_file.as_path())];
+
+        write_bundle(&path, &manifest, &files).unwrap();
+        let mut bundle = Bundle::open(&path).unwrap();
+
+        assert_eq!(bundle.manifest, manifest);
+        assert_eq!(
+            bundle.read_file("mods/carryon_v1.8.0.zip").unwrap(),
+            b"mod bytes"
+        );
+        assert!(matches!(
+            bundle.read_file("mods/hudclock_v2.0.0.zip"),
+            Err(BundleError::MissingFile(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_archives_without_manifest() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("not-a-pack.zip");
+        std::fs::write(&path, mod_zip("hudclock", "2.0.0")).unwrap();
+
+        assert!(matches!(
+            Bundle::open(&path),
+            Err(BundleError::MissingManifest)
+        ));
+    }
+}
@@ -1,16 +1,76 @@
-use clap::{ArgAction, Parser, Subcommand};
+use crate::config::{Channel, IgnoreScope, InstallMode, WebhookKind};
+use crate::utils::OutputFormat;
+use crate::utils::about::LONG_VERSION;
+use crate::utils::completions::complete_mod_ids;
+use crate::utils::encoding::ModListFormat;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use clap_complete::{ArgValueCompleter, Shell};
+use std::io::BufRead;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(
     author = "Mikkel M.H Pedersen",
-    version = "0.7.2",
+    version,
+    long_version = LONG_VERSION,
     long_about = "A mod manager for the game Vintage Story.\nCreated by Mikkel M.H Pedersen.\nThis CLI tool helps you manage Vintage Story mods through three main commands:\n- download: Get mods from the official repository\n- export: Create shareable mod collections\n- update: Keep your mods up to date"
 )]
 pub struct Cli {
-    #[clap(short, long, action=ArgAction::SetTrue)]
-    /// Enable detailed logging output for troubleshooting
-    pub verbose: Option<bool>,
+    #[clap(short, long, action = ArgAction::Count)]
+    /// Print more details: `-v` for progress, `-vv` for debugging, `-vvv` to include HTTP
+    /// traffic. Debug messages are always written to the log file in the data directory
+    pub verbose: u8,
+
+    #[clap(long = "dir", global = true)]
+    /// Use this mods directory instead of the configured ones (can be repeated)
+    pub dirs: Vec<PathBuf>,
+
+    #[clap(long, global = true, value_name = "PATH")]
+    /// Use this as the main mods directory instead of the configured or default one
+    ///
+    /// Unlike `--dir` the state file, lockfile and backups are kept next to it.
+    pub mods_dir: Option<PathBuf>,
+
+    #[clap(long, global = true, value_name = "NAME")]
+    /// Manage the game instance with this name (see `config add-instance`) instead of the
+    /// default `VintagestoryData` directory
+    pub instance: Option<String>,
+
+    #[clap(long, global = true, value_enum)]
+    /// Release channel for this run, overriding the configured channels: `stable`, `rc`
+    /// (also release candidates) or `dev` (every pre-release)
+    pub channel: Option<Channel>,
+
+    #[clap(long, global = true)]
+    /// Print what would be downloaded, deleted or overwritten without changing anything
+    pub dry_run: bool,
+
+    #[clap(short = 'y', long, global = true, visible_alias = "non-interactive")]
+    /// Accept all confirmations and never prompt, for CI and cron jobs
+    ///
+    /// Selections pick their default (e.g. the most downloaded search result); choices
+    /// without a sensible default fail with an error instead.
+    pub yes: bool,
+
+    #[clap(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    /// Print command results as JSON on stdout, with all other text on stderr
+    ///
+    /// Supported by `update` (updated, up-to-date, skipped and failed mods), `check`,
+    /// `export`, `list`, `info`, `about` and `config validate`.
+    pub output: OutputFormat,
+
+    #[clap(long, global = true, value_name = "SECS")]
+    /// Abort the whole command after this many seconds, removing partial downloads
+    pub timeout: Option<u64>,
+
+    #[clap(long, global = true)]
+    /// Always ask ModDB instead of reusing cached API responses
+    pub no_cache: bool,
+
+    #[clap(long, global = true, value_name = "URL")]
+    /// Send ModDB API requests to this server instead, e.g. a self-hosted mirror
+    pub api_url: Option<String>,
 
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -24,15 +84,82 @@ pub enum Commands {
         /// Import mods using an encoded mod string (obtained from the export command)
         mod_string: Option<String>,
 
+        #[clap(long, value_name = "LINK", conflicts_with = "mod_string")]
+        /// Import mods from a `vintagemods://` share link (obtained from `export --url`)
+        url: Option<String>,
+
+        #[clap(long, value_name = "FILE")]
+        /// Install a `.vsmodpack` bundle made by `export --bundle`, using the mod files in
+        /// it and downloading the rest
+        bundle: Option<PathBuf>,
+
         #[clap(long, value_delimiter = ',')]
-        /// Download multiple mods by their IDs or names (comma-separated)
+        /// Download multiple mods by their IDs or names (comma-separated, `-` to read from stdin)
         /// Example: --mods "worldedit,prospecting,bettertools"
         mods: Option<Vec<String>>,
 
         #[clap(long)]
-        /// Download a single mod by its ID or name
+        /// Download a single mod by its ID, name or ModDB page URL
         /// Example: --mod worldedit
         mod_: Option<String>,
+
+        #[clap(long, requires = "mod_")]
+        /// Install this release of the `--mod` mod instead of the newest compatible one
+        /// Example: --mod worldedit --version 1.2.3
+        version: Option<String>,
+
+        #[clap(long, conflicts_with = "version")]
+        /// Choose the release of the `--mod` mod, or of mods picked interactively, from a
+        /// list of all releases and the game versions they support
+        pick_release: bool,
+
+        #[clap(long)]
+        /// Read newline-separated `modid@version` pairs from stdin (as printed by `export --raw-ids`)
+        from_stdin: bool,
+
+        #[clap(long, value_name = "ZIP")]
+        /// Install a mod from a local zip file instead of the ModDB
+        /// Example: --file ~/Downloads/carryon_v1.8.0.zip
+        file: Option<PathBuf>,
+
+        #[clap(long, requires = "file")]
+        /// Replace an installed mod even if it is newer than the `--file` archive
+        force: bool,
+
+        #[clap(long, value_name = "FILE")]
+        /// Install the mods listed in a mod list file (`-` to read from stdin)
+        manifest: Option<PathBuf>,
+
+        #[clap(long, value_enum, requires = "manifest")]
+        /// Format of the `--manifest` file (detected from its contents by default)
+        manifest_format: Option<ModListFormat>,
+
+        #[clap(long)]
+        /// Install the exact versions listed in the mod string, `--from-stdin` input or
+        /// `--manifest` file instead of the latest releases compatible with the game
+        exact: bool,
+
+        #[clap(long, value_name = "LOCKFILE")]
+        /// Install exactly the releases pinned in a lockfile, verifying their checksums
+        /// (defaults to the `mods.lock` next to the mods directory)
+        from_lock: Option<Option<PathBuf>>,
+
+        #[clap(long)]
+        /// In interactive mode, search the whole ModDB instead of filtering the first page of results
+        search_online: bool,
+
+        #[clap(long)]
+        /// In interactive mode, show the changelog of a mod's release before downloading it
+        changelog: bool,
+
+        #[clap(long, value_name = "VERSION")]
+        /// Match releases against this game version instead of the configured one, e.g.
+        /// for a server running another version (see `config list-versions`)
+        game_version: Option<String>,
+
+        #[clap(long)]
+        /// Show example invocations of this command
+        examples: bool,
     },
 
     /// Create shareable mod collections as encoded strings
@@ -41,50 +168,622 @@ pub enum Commands {
     ///
     /// Default behavior is to export all mods.
     Export {
-        #[clap(short, long)]
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
         /// List of mod IDs to exclude from the export (comma-separated)
         /// Example: -e "worldedit,prospecting"
         exclude: Option<Vec<String>>,
 
-        #[clap(short, long)]
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
         /// List of specific mod IDs to include in the export (comma-separated)
         /// Example: -i "worldedit,prospecting"
         include: Option<Vec<String>>,
 
-        #[clap(short, long)]
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
         /// Export only one specific mod by its ID
         /// Example: -m worldedit
         mod_: Option<String>,
 
+        #[clap(long, value_enum)]
+        /// Only export mods that run on this side, e.g. `client` to leave out server-only
+        /// mods (universal mods run on both)
+        side: Option<Side>,
+
         #[clap(long, action=ArgAction::SetTrue)]
         /// Select mods to export through an interactive menu
         interactive: Option<bool>,
+
+        #[clap(long, value_enum, default_value_t = ModListFormat::String)]
+        /// Output format of the mod list
+        format: ModListFormat,
+
+        #[clap(long, conflicts_with = "format")]
+        /// Print newline-separated `modid@version` pairs instead of an encoded string
+        /// (same as `--format ids`)
+        raw_ids: bool,
+
+        #[clap(long)]
+        /// Record only the mod IDs, so importing installs the newest compatible releases
+        /// instead of the versions installed here
+        latest: bool,
+
+        #[clap(long, conflicts_with_all = ["format", "raw_ids"])]
+        /// Print a `vintagemods://` share link instead of the bare mod string
+        url: bool,
+
+        #[clap(long, conflicts_with_all = ["format", "raw_ids"])]
+        /// Also draw the mod string (or the link, with `--url`) as a QR code to scan
+        qr: bool,
+
+        #[clap(long, value_name = "FILE", conflicts_with_all = ["format", "raw_ids", "url", "qr", "out"])]
+        /// Write a `.vsmodpack` bundle: a manifest with the pack's details and the mods'
+        /// versions and checksums
+        bundle: Option<PathBuf>,
+
+        #[clap(long, requires = "bundle", conflicts_with = "latest")]
+        /// Put the mod files into the bundle too, so it installs without internet access
+        with_files: bool,
+
+        #[clap(long, requires = "bundle")]
+        /// Name of the pack (defaults to the bundle's file name)
+        name: Option<String>,
+
+        #[clap(long, requires = "bundle")]
+        /// Author of the pack
+        author: Option<String>,
+
+        #[clap(long, requires = "bundle")]
+        /// Description of the pack, shown when it is installed
+        description: Option<String>,
+
+        #[clap(long, value_name = "FILE")]
+        /// Write the mod list to this file instead of stdout
+        out: Option<PathBuf>,
+
+        #[clap(long)]
+        /// Show example invocations of this command
+        examples: bool,
+    },
+
+    /// Turn the mods of an existing installation into a mod list
+    ///
+    /// Reads a `Mods` directory copied from a server, or a server or client log such as
+    /// `server-main.txt`, and prints its mods as a mod string or another format, ready to
+    /// install with `download` or keep as a manifest.
+    ImportFrom {
+        /// The `Mods` directory or log file to read
+        source: PathBuf,
+
+        #[clap(long, value_enum, default_value_t = ModListFormat::String)]
+        /// Output format of the mod list
+        format: ModListFormat,
+
+        #[clap(long, value_name = "FILE")]
+        /// Write the mod list to this file instead of stdout
+        out: Option<PathBuf>,
     },
 
     /// Check for and install available mod updates
+    ///
+    /// Exits with 0 when every mod was already up to date, 2 when mods were updated and
+    /// 1 when any update failed. With `--auto`, it exits with 3 when updates were held back
+    /// and 75 when another `--auto` run still holds the mods directory.
     Update {
-        #[clap(short, long)]
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
         /// List of mod IDs to skip during update (comma-separated)
         ///
         /// Example: -e "worldedit,prospecting"
         exclude: Option<Vec<String>>,
 
-        #[clap(short, long)]
-        /// List of specific mod IDs to update (comma-separated)
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
+        /// List of specific mod IDs to update (comma-separated, `-` to read from stdin)
         ///
         /// Example: -i "worldedit,prospecting"
         include: Option<Vec<String>>,
 
-        #[clap(short, long)]
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
         /// Update only one specific mod by its ID
         ///
         /// Example: -m worldedit
         mod_: Option<String>,
+
+        #[clap(long, value_enum)]
+        /// Only update mods that run on this side (universal mods run on both)
+        side: Option<Side>,
+
+        #[clap(long)]
+        /// Show the changelogs of the releases since the installed version
+        changelog: bool,
+
+        #[clap(long, conflicts_with = "auto")]
+        /// Review the available updates with their changelogs and deselect the ones to
+        /// skip before anything is downloaded
+        review: bool,
+
+        #[clap(long)]
+        /// Run unattended, e.g. from cron or a systemd timer
+        ///
+        /// Never prompts, skips the run while another one is still going, holds back
+        /// releases not marked compatible with the game version and logs every action to
+        /// a file.
+        auto: bool,
+
+        #[clap(long, value_name = "FILE", requires = "auto")]
+        /// Log the actions of `--auto` as JSON lines to this file (defaults to
+        /// `auto-update.jsonl` in the log directory)
+        log_file: Option<PathBuf>,
+
+        #[clap(long, value_name = "VERSION")]
+        /// Match releases against this game version instead of the configured one, e.g.
+        /// for a server running another version (see `config list-versions`)
+        game_version: Option<String>,
+
+        #[clap(long)]
+        /// Show example invocations of this command
+        examples: bool,
+    },
+
+    /// Check whether the installed mods are ready for a game version before upgrading
+    ///
+    /// Reports which mods already support the version, which need an update first, and
+    /// which have no release for it yet. Exits with 0 when every mod supports the version,
+    /// after updates if needed, and 2 otherwise. Mods ModDB doesn't know, such as local
+    /// builds, are listed but can't be checked and don't count towards the exit code.
+    CompatCheck {
+        #[clap(long, value_name = "VERSION")]
+        /// The game version to upgrade to, e.g. 1.21.0
+        target: String,
+    },
+
+    /// Report available mod updates without installing them
+    ///
+    /// Exits with 0 when every mod is up to date and 2 when updates are available.
+    Check {
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
+        /// List of mod IDs to skip (comma-separated)
+        exclude: Option<Vec<String>>,
+
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
+        /// List of specific mod IDs to check (comma-separated, `-` to read from stdin)
+        include: Option<Vec<String>>,
+
+        #[clap(short, long, add = ArgValueCompleter::new(complete_mod_ids))]
+        /// Check only one specific mod by its ID
+        mod_: Option<String>,
+
+        #[clap(long, value_enum)]
+        /// Only check mods that run on this side (universal mods run on both)
+        side: Option<Side>,
+
+        #[clap(long)]
+        /// Show the changelogs of the releases since the installed version
+        changelog: bool,
+    },
+
+    /// Keep checking for updates on a schedule until interrupted
+    ///
+    /// Each newly published compatible release is announced once. With `--auto` the
+    /// updates are installed as they appear, as `update` would.
+    Watch {
+        #[clap(long, default_value = "6h", value_parser = parse_interval)]
+        /// Time between checks, e.g. `30m`, `6h` or `1d` (at least one minute)
+        interval: Duration,
+
+        #[clap(long)]
+        /// Install new updates as soon as they are found, holding back releases not
+        /// marked compatible with the game version like `update --auto`
+        auto: bool,
+
+        #[clap(long)]
+        /// Also show a desktop notification when updates are found
+        notify: bool,
+    },
+
+    /// Make the installed mods match a mod string exactly
+    ///
+    /// Installs the mods the string lists that are missing and replaces installed mods
+    /// that are at a different version with the listed release. The changes are shown and
+    /// confirmed first.
+    Sync {
+        /// The mod string to match, as produced by `export`
+        mod_string: String,
+
+        #[clap(long)]
+        /// Also remove installed mods that the mod string doesn't list
+        prune: bool,
+    },
+
+    /// Remove installed mods
+    #[command(alias = "uninstall")]
+    Remove {
+        /// Mod IDs to remove (`-` to read them from stdin)
+        #[clap(add = ArgValueCompleter::new(complete_mod_ids))]
+        mods: Vec<String>,
+
+        #[clap(long)]
+        /// Select mods to remove through an interactive menu
+        interactive: bool,
+    },
+
+    /// Check installed mod files for damage and tampering
+    ///
+    /// Reads every installed zip, checks that its modinfo.json parses, and compares the
+    /// version and checksum with the lockfile. Exits with status 1 if a problem remains.
+    Verify {
+        #[clap(long)]
+        /// Download damaged files again: the release pinned in the lockfile, or the one
+        /// recorded when the mod was installed
+        repair: bool,
+    },
+
+    /// Delete leftover files from the mods directories
+    ///
+    /// Finds older copies of installed mods (keeping the highest version), disabled copies
+    /// of mods that are also installed, unfinished downloads and zips without a
+    /// modinfo.json. The files are listed and confirmed before anything is deleted, and
+    /// mods are copied to the backup directory first.
+    Clean,
+
+    /// List installed mods
+    List {
+        #[clap(long, value_enum, default_value_t = ListFormat::Table)]
+        /// Output format
+        format: ListFormat,
+
+        #[clap(long, conflicts_with = "format")]
+        /// Print the list as JSON (same as `--format json`)
+        json: bool,
+
+        #[clap(long, value_enum, default_value_t = ListSort::Name)]
+        /// Column to sort by
+        sort: ListSort,
+
+        #[clap(long)]
+        /// Reverse the sort order
+        reverse: bool,
+
+        #[clap(short, long)]
+        /// Show authors, contributors and the ModDB release (id and publish date) each
+        /// file was installed from
+        verbose: bool,
+
+        #[clap(long, value_name = "NAME")]
+        /// Only list mods by this author or contributor (case-insensitive, partial match)
+        author: Option<String>,
+    },
+
+    /// Show the game version, installed mods, pending updates and the last update run
+    ///
+    /// This is also what runs when no command is given.
+    Status,
+
+    /// Browse ModDB and download mods through interactive menus
+    ///
+    /// This is the same browser `download` opens without arguments.
+    Browse {
+        #[clap(long)]
+        /// Pick categories first and only list mods tagged with them
+        tags: bool,
+
+        #[clap(long, value_delimiter = ',', value_name = "NAME")]
+        /// Only list mods with these tags, by name (comma-separated)
+        ///
+        /// Example: --tag "QoL,Storage"
+        tag: Option<Vec<String>>,
+
+        #[clap(long, value_name = "NAME")]
+        /// Only list mods published by this author
+        ///
+        /// Example: --author Tyron
+        author: Option<String>,
+
+        #[clap(long)]
+        /// Search the whole ModDB instead of filtering the first page of results
+        search_online: bool,
+
+        #[clap(long, value_name = "VERSION")]
+        /// Match releases against this game version instead of the configured one, e.g.
+        /// for a server running another version (see `config list-versions`)
+        game_version: Option<String>,
+    },
+
+    /// Browse installed mods, search ModDB and queue downloads in a full-screen UI
+    Tui,
+
+    /// Record the SHA-256 checksum of every installed mod file
+    ///
+    /// Files are hashed in parallel. The result is a JSON object mapping file names to
+    /// checksums, printed to stdout unless `--out` is given.
+    Snapshot {
+        #[clap(long)]
+        /// Write the snapshot to this file instead of stdout
+        out: Option<PathBuf>,
+    },
+
+    /// Explain why a mod is installed
+    ///
+    /// Reports whether the mod was installed explicitly, pulled in as a dependency, or adopted
+    /// from an existing file, and which installed mods depend on it.
+    Why {
+        /// The mod ID to explain
+        #[clap(value_name = "MOD", add = ArgValueCompleter::new(complete_mod_ids))]
+        mod_: String,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// Completes commands and flags, and the IDs of the installed mods for `--mod`,
+    /// `--include` and `--exclude`. For bash, add `source <(VintageModManager completions
+    /// bash)` to `~/.bashrc`; for PowerShell, add `VintageModManager completions powershell
+    /// | Out-String | Invoke-Expression` to your profile.
+    Completions {
+        #[clap(value_enum)]
+        /// The shell to complete in
+        shell: Shell,
+    },
+
+    /// Show the dependency graph of the installed mods
+    ///
+    /// Prints each mod with the mods it depends on beneath it, then lists missing
+    /// dependencies, dependencies older than required, and circular dependencies. Exits
+    /// with an error status when any are found.
+    Deps {
+        #[clap(long)]
+        /// Print the graph in Graphviz DOT format instead of as a tree
+        dot: bool,
+    },
+
+    /// Roll an installed mod back to an earlier release
+    ///
+    /// Without `--version` the mod's published releases are listed to pick from.
+    #[command(alias = "downgrade")]
+    Rollback {
+        /// The mod ID to roll back
+        #[clap(value_name = "MOD", add = ArgValueCompleter::new(complete_mod_ids))]
+        mod_: String,
+
+        #[clap(long)]
+        /// Install this release instead of picking one interactively
+        version: Option<String>,
+    },
+
+    /// Hold a mod at its installed version: `update` skips it until it is unpinned
+    Pin {
+        /// The mod ID to pin
+        #[clap(value_name = "MOD", add = ArgValueCompleter::new(complete_mod_ids))]
+        mod_: String,
+    },
+
+    /// Let `update` pick up new releases of a pinned mod again
+    Unpin {
+        /// The mod ID to unpin
+        #[clap(value_name = "MOD", add = ArgValueCompleter::new(complete_mod_ids))]
+        mod_: String,
+    },
+
+    /// Roll the mods folder back to a backup taken before an update
+    ///
+    /// Every `update` run backs up the files it replaces into a timestamped directory in
+    /// `VintagestoryData/ModBackups`. Without a backup name the backups are listed to pick
+    /// from.
+    Restore {
+        /// The backup to restore, e.g. `2025-01-31_18-04-12`
+        backup: Option<String>,
+
+        #[clap(long, conflicts_with = "backup")]
+        /// List the available backups instead of restoring one
+        list: bool,
+    },
+
+    /// Rename installed mod files to the filename template in one pass
+    ///
+    /// Uses the template set with `config set-filename-template`, or
+    /// `{modid}_v{version}.zip` if none is set. The state file and lockfile are updated
+    /// to the new names.
+    Normalize,
+
+    /// Show details of a mod on ModDB
+    ///
+    /// Prints the description, author, side, tags, popularity, project links and the
+    /// latest releases with the game versions they support.
+    Info {
+        /// The mod ID or ModDB id to look up
+        #[clap(value_name = "MOD")]
+        mod_: String,
+
+        #[clap(long, default_value_t = 10)]
+        /// Number of releases to show
+        releases: usize,
+    },
+
+    /// Print a single value for use in scripts
+    ///
+    /// Keys: `game.version`, `game.path`, `mods.dir`, `mods.count`, `install-mode`,
+    /// `profile`, and `mod.<modid>.<field>` with field `version`, `name`, `side` or `file`.
+    /// Exits with status 1 and prints nothing if the value is not set.
+    Get {
+        /// The key to look up, e.g. `mod.carryon.version`
+        key: String,
+    },
+
+    /// Show version, build and setup details for bug reports
+    About {
+        #[clap(long)]
+        /// Print the details as JSON
+        json: bool,
+    },
+
+    /// Show copy-pasteable example invocations for common workflows
+    Examples {
+        /// Only show examples for this command (e.g. "export")
+        command: Option<String>,
     },
 
     /// Manage configuration settigns
     #[command(subcommand)]
     Config(ConfigCommands),
+
+    /// Manage named mod profiles you can switch between
+    #[command(subcommand)]
+    Profile(ProfileCommands),
+
+    /// Manage the local API response cache
+    #[command(subcommand)]
+    Cache(CacheCommands),
+
+    /// Manage mod backups
+    #[command(subcommand)]
+    Backup(BackupCommands),
+
+    /// Synchronize mods with a game server over SFTP
+    #[command(subcommand)]
+    Remote(RemoteCommands),
+
+    /// List plugins found on PATH (`vsmm-<name>` executables, run as `VintageModManager <name>`)
+    Plugins,
+
+    /// Runs a `vsmm-<name>` plugin for any other subcommand
+    #[command(external_subcommand)]
+    Plugin(Vec<String>),
+}
+
+/// Output formats for `list`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ListFormat {
+    /// Human-readable table
+    Table,
+    /// JSON array of mods
+    Json,
+    /// One mod ID per line, for piping into other commands
+    Ids,
+}
+
+/// The side of the game a mod runs on, for `--side` filters.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    /// Universal and client-only mods
+    Client,
+    /// Universal and server-only mods
+    Server,
+}
+
+impl Side {
+    /// Returns `true` if a mod with the given side runs on this side.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The mod's side, as `modinfo.json` (`universal`, `client`, `server`) or
+    ///   the ModDB (`both`, `client`, `server`) spells it. The game treats mods without
+    ///   a side as universal.
+    pub fn runs(self, side: Option<&str>) -> bool {
+        match side.map(str::to_lowercase).as_deref() {
+            Some("client") => self == Side::Client,
+            Some("server") => self == Side::Server,
+            _ => true,
+        }
+    }
+}
+
+/// Sort keys for `list`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ListSort {
+    Name,
+    Modid,
+    Version,
+    Side,
+    Size,
+    /// ModDB download count, most downloaded first (cached for a day)
+    Popularity,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommands {
+    /// Remove cached files according to the configured retention policy
+    Prune {
+        #[clap(long)]
+        /// Only show what would be removed
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BackupCommands {
+    /// Remove old backups according to the configured retention policy
+    Prune {
+        #[clap(long)]
+        /// Only show what would be removed
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RemoteCommands {
+    /// Add a game server, logging in with your SSH keys or agent
+    Add {
+        /// Name to refer to the server by
+        name: String,
+        /// The server's mods directory as `[user@]host:path`, e.g. `vs@example.com:/srv/vs/Mods`
+        target: String,
+
+        #[clap(long)]
+        /// SSH port, if not 22 or set in ~/.ssh/config
+        port: Option<u16>,
+
+        #[clap(long)]
+        /// Private key to log in with
+        identity: Option<PathBuf>,
+    },
+
+    /// Forget a game server
+    Remove { name: String },
+
+    /// List the configured game servers
+    List,
+
+    /// Upload the mods that differ from the server's
+    Push {
+        name: String,
+
+        #[clap(long)]
+        /// Also delete mods on the server that aren't installed locally
+        prune: bool,
+    },
+
+    /// Download the server's mods that differ from the local ones
+    Pull {
+        name: String,
+
+        #[clap(long)]
+        /// Also delete local mods the server doesn't have
+        prune: bool,
+    },
+
+    /// Update the server's mods for your game version and upload the result
+    Update { name: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommands {
+    /// Create a profile from the currently enabled mods
+    Create {
+        name: String,
+
+        #[clap(long)]
+        /// Create the profile without any mods
+        empty: bool,
+
+        #[clap(long)]
+        /// Overwrite an existing profile with the same name
+        force: bool,
+    },
+
+    /// Switch to a profile, disabling mods that aren't in it and installing missing ones
+    Switch { name: String },
+
+    /// List all profiles
+    List,
+
+    /// Delete a profile (mods on disk are left untouched)
+    Delete { name: String },
 }
 
 #[derive(Subcommand, Debug)]
@@ -98,6 +797,26 @@ pub enum ConfigCommands {
     /// Show current configuration
     Show,
 
+    /// Print a setting, e.g. `network.timeout_secs`
+    ///
+    /// A single setting prints its bare value. A table such as `network`, or no key at all,
+    /// prints `key = value` lines.
+    Get {
+        /// The setting's dotted key, omit to list every setting
+        key: Option<String>,
+    },
+
+    /// Change a setting, e.g. `config set network.timeout_secs 60`
+    ///
+    /// The value is checked like the dedicated `config set-*` commands do. Lists take JSON
+    /// syntax, e.g. `config set pinned_mods '["carryon"]'`.
+    Set {
+        /// The setting's dotted key, see `config get`
+        key: String,
+        /// The new value, omit to go back to the default
+        value: Option<String>,
+    },
+
     /// Initialize configuration file with default values
     Init {
         #[clap(long)]
@@ -116,20 +835,506 @@ pub enum ConfigCommands {
     ListVersions,
 
     /// Reset configuration to defaults
-    Reset {
+    ///
+    /// Pass the global `--yes` to reset without prompting.
+    Reset,
+
+    /// Validate current configuration
+    ///
+    /// Exits with a non-zero status when a hard failure is found
+    /// (missing mods directory, invalid game path).
+    Validate {
+        #[clap(long)]
+        /// Print the validation report as JSON
+        json: bool,
+
         #[clap(long)]
-        /// Confirm reset without prompting
-        yes: bool,
+        /// Re-run game path and version detection before validating
+        fix: bool,
     },
 
-    /// Validate current configuration
-    Validate,
+    /// Use a custom main mods directory instead of `VintagestoryData/Mods`
+    ///
+    /// For servers and other setups that keep their data elsewhere. The state file,
+    /// lockfile and backups are kept next to it.
+    SetModsPath {
+        /// The mods directory, omit to go back to the default
+        path: Option<PathBuf>,
+    },
+
+    /// Add an additional mods directory that is searched after the main one
+    AddModPath {
+        /// Path to the additional mods directory
+        path: PathBuf,
+    },
+
+    /// Create the default mods directory if the game hasn't created it yet
+    CreateModsDir {
+        #[clap(long)]
+        /// Also create it automatically whenever it is missing, without asking
+        always: bool,
+    },
+
+    /// Remove an additional mods directory
+    RemoveModPath {
+        /// Path to the additional mods directory
+        path: PathBuf,
+    },
+
+    /// Register a game installation to manage with `--instance <name>`
+    AddInstance {
+        /// Name of the instance
+        name: String,
+        /// The instance's `VintagestoryData` directory (the one holding its `Mods` folder)
+        path: PathBuf,
+    },
+
+    /// Forget a game installation added with `add-instance`
+    RemoveInstance {
+        /// Name of the instance
+        name: String,
+    },
+
+    /// Post a summary to a webhook (Discord, Slack or generic JSON) after update runs
+    AddWebhook {
+        /// The webhook URL
+        url: String,
+
+        #[clap(long, value_enum, default_value_t = WebhookKind::Discord)]
+        /// Payload style expected by the webhook
+        kind: WebhookKind,
 
-    /// Set the current game version for compatibility filtering
+        #[clap(long)]
+        /// Also post the updates `check` and `watch` find, before they are installed
+        available: bool,
+    },
+
+    /// Stop posting to a webhook
+    RemoveWebhook {
+        /// The webhook URL
+        url: String,
+    },
+
+    /// Set how downloaded mods are placed into the mods directory
+    ///
+    /// `symlink` and `hardlink` keep one copy of each file in a shared store, which saves
+    /// disk space when the same mods are used by several mods directories.
+    SetInstallMode {
+        #[clap(value_enum)]
+        mode: InstallMode,
+    },
+
+    /// Name new mod files after a template instead of their ModDB filename
+    ///
+    /// `{modid}` and `{version}` are replaced, e.g. `{modid}_v{version}.zip`. Without a
+    /// template new mods keep their ModDB filenames. Run `normalize` to rename mods that
+    /// are already installed.
+    SetFilenameTemplate {
+        /// The template, omit to go back to ModDB filenames
+        template: Option<String>,
+    },
+
+    /// Limit how many mod backups are kept (applied after every update run)
+    ///
+    /// Only the given limits are changed; pass 0 to remove a limit.
+    SetBackupRetention {
+        #[clap(long, value_name = "DAYS")]
+        /// Remove backups older than this many days
+        max_age_days: Option<u64>,
+
+        #[clap(long, value_name = "MB")]
+        /// Remove the oldest backups until all of them fit in this many megabytes
+        max_size_mb: Option<u64>,
+
+        #[clap(long, value_name = "COUNT")]
+        /// Keep at most this many backups of each mod
+        max_count_per_mod: Option<usize>,
+    },
+
+    /// Choose which pre-releases `update` and `download` may install
+    ///
+    /// By default only stable releases are picked. `rc` also allows release candidates
+    /// and `dev` every pre-release. Use `--mod` to opt a single mod in or out.
+    SetChannel {
+        #[clap(value_enum)]
+        channel: Channel,
+
+        #[clap(long = "mod", value_name = "MOD")]
+        /// Only set the channel of this mod
+        mod_: Option<String>,
+    },
+
+    /// Match mods against this game version instead of the detected one
+    ///
+    /// The version must be one of the known version mappings (see `config list-versions`).
     SetGameVersion {
-        /// Game version string (e.g., "1.15.3")
-        version: String,
+        /// Game version string (e.g., "1.15.3"), omit to go back to the detected version
+        version: Option<String>,
+    },
+
+    /// Manage the mods commands skip, e.g. to keep `update` away from a mod or hide
+    /// broken mods from `browse`
+    #[command(subcommand)]
+    Ignore(IgnoreCommands),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum IgnoreCommands {
+    /// Ignore a mod
+    Add {
+        /// The mod ID to ignore
+        #[clap(value_name = "MOD", add = ArgValueCompleter::new(complete_mod_ids))]
+        mod_: String,
+
+        #[clap(long, value_enum, default_value_t = IgnoreScope::All)]
+        /// Which commands skip the mod
+        scope: IgnoreScope,
+    },
+
+    /// Stop ignoring a mod
+    Remove {
+        /// The mod ID to stop ignoring
+        #[clap(value_name = "MOD")]
+        mod_: String,
+
+        #[clap(long, value_enum, default_value_t = IgnoreScope::All)]
+        /// The list to remove the mod from
+        scope: IgnoreScope,
+    },
+
+    /// List the ignored mods
+    List,
+}
+
+/// A runnable example invocation shown by `examples` and `--examples`.
+pub struct Example {
+    /// The top-level command the example belongs to.
+    pub command: &'static str,
+    /// The workflow the example is part of.
+    pub workflow: &'static str,
+    /// What the invocation does.
+    pub description: &'static str,
+    /// The command line itself.
+    pub invocation: &'static str,
+}
+
+/// Example invocations, grouped by workflow in the order they are shown.
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Export every installed mod as a shareable string",
+        invocation: "VintageModManager export",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Export everything except a few client-side mods",
+        invocation: "VintageModManager export --exclude \"betterruins,hudclock\"",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Export only the mods players need, leaving out server-only mods",
+        invocation: "VintageModManager export --side client",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Pick the mods to export from a menu",
+        invocation: "VintageModManager export --interactive",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Share a pack that always installs the newest compatible releases",
+        invocation: "VintageModManager export --latest",
+    },
+    Example {
+        command: "download",
+        workflow: "Share a pack",
+        description: "Install a pack someone shared with you",
+        invocation: "VintageModManager download --mod-string \"<mod string>\"",
+    },
+    Example {
+        command: "download",
+        workflow: "Share a pack",
+        description: "Install the exact versions a shared pack was exported with",
+        invocation: "VintageModManager download --mod-string \"<mod string>\" --exact",
     },
+    Example {
+        command: "download",
+        workflow: "Share a pack",
+        description: "Install a mod from the address of its ModDB page",
+        invocation: "VintageModManager download --mod https://mods.vintagestory.at/carryon",
+    },
+    Example {
+        command: "download",
+        workflow: "Share a pack",
+        description: "Install the release of a mod the rest of the group plays with",
+        invocation: "VintageModManager download --mod carryon --version 1.7.0",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Show a link friends can scan off the screen",
+        invocation: "VintageModManager export --url --qr",
+    },
+    Example {
+        command: "download",
+        workflow: "Share a pack",
+        description: "Install a pack from a share link",
+        invocation: "VintageModManager download --url \"vintagemods://import?mods=...\"",
+    },
+    Example {
+        command: "import-from",
+        workflow: "Share a pack",
+        description: "Write a manifest of the mods an existing server loaded",
+        invocation: "VintageModManager import-from server-main.txt --format json --out mods.json",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Bundle the pack with its mod files for players without internet access",
+        invocation: "VintageModManager export --bundle friends.vsmodpack --with-files --name \"Friends server\"",
+    },
+    Example {
+        command: "download",
+        workflow: "Share a pack",
+        description: "Install a bundled pack",
+        invocation: "VintageModManager download --bundle friends.vsmodpack",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Copy a pack between machines with plain modid@version lines",
+        invocation: "VintageModManager export --raw-ids | ssh server VintageModManager download --from-stdin",
+    },
+    Example {
+        command: "export",
+        workflow: "Share a pack",
+        description: "Export a mod list for a 1-click launcher",
+        invocation: "VintageModManager export --format launcher > modlist.json",
+    },
+    Example {
+        command: "download",
+        workflow: "Share a pack",
+        description: "Install a mod list from another tool (format is detected automatically)",
+        invocation: "VintageModManager download --manifest modlist.json",
+    },
+    Example {
+        command: "download",
+        workflow: "Find and install mods",
+        description: "Search for a mod by name and download it",
+        invocation: "VintageModManager download --mod worldedit",
+    },
+    Example {
+        command: "download",
+        workflow: "Find and install mods",
+        description: "Install a mod zip you downloaded yourself",
+        invocation: "VintageModManager download --file ~/Downloads/carryon_v1.8.0.zip",
+    },
+    Example {
+        command: "download",
+        workflow: "Find and install mods",
+        description: "Search for several mods at once",
+        invocation: "VintageModManager download --mods \"prospecting,bettertools\"",
+    },
+    Example {
+        command: "download",
+        workflow: "Find and install mods",
+        description: "Browse the most downloaded mods for your game version",
+        invocation: "VintageModManager download",
+    },
+    Example {
+        command: "download",
+        workflow: "Find and install mods",
+        description: "Browse interactively, searching all of ModDB instead of the first page",
+        invocation: "VintageModManager download --search-online",
+    },
+    Example {
+        command: "download",
+        workflow: "Reproduce a setup",
+        description: "Install exactly the mods pinned in a lockfile copied from another machine",
+        invocation: "VintageModManager download --from-lock ./mods.lock",
+    },
+    Example {
+        command: "update",
+        workflow: "Update a server",
+        description: "Update every installed mod",
+        invocation: "VintageModManager update",
+    },
+    Example {
+        command: "update",
+        workflow: "Update a server",
+        description: "Update everything but hold back mods you have tested against",
+        invocation: "VintageModManager update --exclude \"carryon,primitivesurvival\"",
+    },
+    Example {
+        command: "update",
+        workflow: "Update a server",
+        description: "Update a single mod",
+        invocation: "VintageModManager update --mod worldedit",
+    },
+    Example {
+        command: "update",
+        workflow: "Update a server",
+        description: "Read the changelogs and untick the updates you don't want yet",
+        invocation: "VintageModManager update --review",
+    },
+    Example {
+        command: "update",
+        workflow: "Update a server",
+        description: "Prepare the mods for a server that runs another game version",
+        invocation: "VintageModManager update --dir ./server/Mods --game-version 1.20.4",
+    },
+    Example {
+        command: "update",
+        workflow: "Update a server",
+        description: "Update only the mods whose id matches a pattern",
+        invocation: "VintageModManager list --format ids | grep world | VintageModManager update --include -",
+    },
+    Example {
+        command: "update",
+        workflow: "Update a server",
+        description: "Update unattended every night from cron",
+        invocation: "0 4 * * * VintageModManager update --auto",
+    },
+    Example {
+        command: "check",
+        workflow: "Update a server",
+        description: "Get notified from cron when updates are available",
+        invocation: "VintageModManager check --output json > updates.json || notify-send \"Mod updates available\"",
+    },
+    Example {
+        command: "watch",
+        workflow: "Update a server",
+        description: "Install updates as they are published, checking twice a day",
+        invocation: "VintageModManager watch --interval 12h --auto",
+    },
+    Example {
+        command: "config",
+        workflow: "Update a server",
+        description: "Post found and installed updates to a Discord channel",
+        invocation: "VintageModManager config add-webhook \"https://discord.com/api/webhooks/<id>/<token>\" --available",
+    },
+    Example {
+        command: "get",
+        workflow: "Update a server",
+        description: "Check an installed version from a script",
+        invocation: "[ \"$(VintageModManager get mod.carryon.version)\" = 1.8.0 ] || VintageModManager update --mod carryon",
+    },
+    Example {
+        command: "config",
+        workflow: "Migrate game versions",
+        description: "Point the tool at the new game installation",
+        invocation: "VintageModManager config set-path \"/opt/vintagestory\"",
+    },
+    Example {
+        command: "config",
+        workflow: "Migrate game versions",
+        description: "Fetch version mappings that include the new release",
+        invocation: "VintageModManager config update-versions",
+    },
+    Example {
+        command: "compat-check",
+        workflow: "Migrate game versions",
+        description: "See which mods are ready for the new version before upgrading",
+        invocation: "VintageModManager compat-check --target 1.21.0",
+    },
+    Example {
+        command: "config",
+        workflow: "Migrate game versions",
+        description: "Check that everything was detected correctly",
+        invocation: "VintageModManager config validate --fix",
+    },
+    Example {
+        command: "update",
+        workflow: "Migrate game versions",
+        description: "Move your mods to releases compatible with the new version",
+        invocation: "VintageModManager update",
+    },
+    Example {
+        command: "remote",
+        workflow: "Update a server",
+        description: "Register a server reachable with your SSH key",
+        invocation: "VintageModManager remote add survival vs@example.com:/srv/vintagestory/Mods",
+    },
+    Example {
+        command: "remote",
+        workflow: "Update a server",
+        description: "Update the server's mods in place",
+        invocation: "VintageModManager remote update survival",
+    },
+    Example {
+        command: "remote",
+        workflow: "Update a server",
+        description: "Copy your local mods to the server, removing the ones you don't have",
+        invocation: "VintageModManager remote push survival --prune",
+    },
+];
+
+/// Returns the examples for the given command, or all examples if `command` is `None`.
+pub fn examples_for(command: Option<&str>) -> Vec<&'static Example> {
+    EXAMPLES
+        .iter()
+        .filter(|example| command.is_none_or(|command| example.command == command))
+        .collect()
+}
+
+/// Parses a `watch` interval: a number of seconds, or a number followed by `s`, `m`, `h`
+/// or `d`.
+///
+/// # Returns
+///
+/// The interval, or an error message if it is malformed or shorter than a minute.
+pub fn parse_interval(interval: &str) -> Result<Duration, String> {
+    let interval = interval.trim();
+    let (amount, unit) = match interval.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => interval.split_at(index),
+        None => (interval, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("unknown unit `{unit}`, use s, m, h or d")),
+    };
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("`{interval}` is not a duration like 30m or 6h"))?;
+    let seconds = amount.saturating_mul(multiplier);
+    if seconds < 60 {
+        return Err("the interval must be at least one minute".to_string());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Replaces a lone `-` argument with mod ids read from stdin, one per line.
+///
+/// Blank lines and `#` comments are skipped, and a `@version` suffix (as printed by
+/// `export --raw-ids`) is stripped so the output of other commands can be piped in directly.
+pub fn read_ids_from_stdin_if_dash(
+    ids: Option<Vec<String>>,
+) -> Result<Option<Vec<String>>, std::io::Error> {
+    match ids {
+        Some(ids) if ids.len() == 1 && ids[0] == "-" => {
+            let stdin = std::io::stdin();
+            let mut from_stdin = Vec::new();
+            for line in stdin.lock().lines() {
+                let line = line?;
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let id = line.split_once('@').map_or(line, |(id, _)| id);
+                from_stdin.push(id.to_string());
+            }
+            Ok(Some(from_stdin))
+        }
+        other => Ok(other),
+    }
 }
 
 #[derive(Default)]
@@ -137,13 +1342,36 @@ pub struct CliFlags {
     pub exclude: Option<Vec<String>>,
     pub include: Option<Vec<String>>,
     pub mod_: Option<String>,
+    /// Only mods that run on this side.
+    pub side: Option<Side>,
 }
 
 #[derive(Default)]
 pub struct DownloadFlags {
     pub mod_string: Option<String>,
+    pub bundle: Option<PathBuf>,
     pub mods: Option<Vec<String>>,
     pub mod_: Option<String>,
+    pub from_stdin: bool,
+    pub search_online: bool,
+    pub from_lock: Option<PathBuf>,
+    pub manifest: Option<PathBuf>,
+    pub manifest_format: Option<ModListFormat>,
+    pub exact: bool,
+    pub file: Option<PathBuf>,
+    pub force: bool,
+    /// The release of the `--mod` mod to install, instead of the newest compatible one.
+    pub version: Option<String>,
+}
+
+/// The pack details and contents of `export --bundle`.
+pub struct BundleFlags {
+    pub path: PathBuf,
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// Include the mod files, not just the manifest.
+    pub with_files: bool,
 }
 
 pub trait IsAllNone {
@@ -152,12 +1380,22 @@ pub trait IsAllNone {
 
 impl IsAllNone for DownloadFlags {
     fn is_all_none(&self) -> bool {
-        self.mod_string.is_none() && self.mods.is_none() && self.mod_.is_none()
+        self.mod_string.is_none()
+            && self.bundle.is_none()
+            && self.mods.is_none()
+            && self.mod_.is_none()
+            && !self.from_stdin
+            && self.from_lock.is_none()
+            && self.manifest.is_none()
+            && self.file.is_none()
     }
 }
 
 impl IsAllNone for CliFlags {
     fn is_all_none(&self) -> bool {
-        self.exclude.is_none() && self.include.is_none() && self.mod_.is_none()
+        self.exclude.is_none()
+            && self.include.is_none()
+            && self.mod_.is_none()
+            && self.side.is_none()
     }
 }
@@ -0,0 +1,461 @@
+//! Reads the mod info of code mods that come without a `modinfo.json`: standalone C#
+//! source files and compiled assemblies, which declare it with the `ModInfo` attribute.
+//!
+//! Both are translated into the `modinfo.json` the mod would otherwise ship, so they are
+//! parsed like every other mod.
+
+use serde_json::{Map, Value, json};
+
+/// Derives a mod ID from a mod name the way the game does for mods that don't declare
+/// one: lowercase letters and digits only.
+fn modid_from_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Builds the `modinfo.json` of a code mod from its `ModInfo` arguments.
+fn modinfo_json(
+    name: String, modid: Option<String>, named: Map<String, Value>,
+    dependencies: Map<String, Value>,
+) -> Value {
+    let modid = modid.unwrap_or_else(|| modid_from_name(&name));
+    let mut modinfo = json!({ "type": "code", "name": name, "modid": modid });
+    modinfo
+        .as_object_mut()
+        .expect("modinfo is an object")
+        .extend(named);
+    if !dependencies.is_empty() {
+        modinfo["dependencies"] = Value::Object(dependencies);
+    }
+    modinfo
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Str(String),
+    Ident(String),
+    Punct(char),
+}
+
+/// Splits C# source into string literals, identifiers and punctuation, dropping comments.
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '@' if chars.peek() == Some(&'"') => {
+                chars.next();
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '"' {
+                        // `""` is an escaped quote in verbatim strings
+                        if chars.peek() != Some(&'"') {
+                            break;
+                        }
+                        chars.next();
+                    }
+                    value.push(c);
+                }
+                tokens.push(Token::Str(value));
+            }
+            '"' => {
+                let mut value = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(escaped) => value.push(escaped),
+                            None => break,
+                        },
+                        c => value.push(c),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c if c.is_whitespace() => {}
+            c => tokens.push(Token::Punct(c)),
+        }
+    }
+    tokens
+}
+
+/// Returns the arguments of every `[assembly: <attribute>(...)]` in the tokens, each
+/// split at its top-level commas.
+fn assembly_attributes(tokens: &[Token], attribute: &str) -> Vec<Vec<Vec<Token>>> {
+    let matches_name = |ident: &str| {
+        let ident = ident.rsplit('.').next().unwrap_or(ident);
+        ident == attribute || ident.strip_suffix("Attribute") == Some(attribute)
+    };
+
+    let mut attributes = Vec::new();
+    for (start, window) in tokens.windows(5).enumerate() {
+        let [
+            Token::Punct('['),
+            Token::Ident(target),
+            Token::Punct(':'),
+            Token::Ident(name),
+            Token::Punct('('),
+        ] = window
+        else {
+            continue;
+        };
+        if target != "assembly" || !matches_name(name) {
+            continue;
+        }
+
+        let mut arguments = vec![Vec::new()];
+        let mut depth = 0;
+        for token in &tokens[start + 5..] {
+            match token {
+                Token::Punct(')') if depth == 0 => break,
+                Token::Punct(',') if depth == 0 => {
+                    arguments.push(Vec::new());
+                    continue;
+                }
+                Token::Punct('(' | '[' | '{') => depth += 1,
+                Token::Punct(')' | ']' | '}') => depth -= 1,
+                _ => {}
+            }
+            arguments
+                .last_mut()
+                .expect("arguments is not empty")
+                .push(token.clone());
+        }
+        arguments.retain(|argument| !argument.is_empty());
+        attributes.push(arguments);
+    }
+    attributes
+}
+
+/// Converts the value of an attribute argument: a string, a boolean or a list of strings.
+fn argument_value(tokens: &[Token]) -> Option<Value> {
+    match tokens {
+        [Token::Str(value)] => Some(Value::String(value.clone())),
+        [Token::Ident(value)] if value == "true" || value == "false" => {
+            Some(Value::Bool(value == "true"))
+        }
+        _ if tokens.contains(&Token::Punct('{')) => Some(Value::Array(
+            tokens
+                .iter()
+                .filter_map(|token| match token {
+                    Token::Str(value) => Some(Value::String(value.clone())),
+                    _ => None,
+                })
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+/// Reads the `ModInfo` and `ModDependency` assembly attributes of a C# source mod.
+///
+/// # Returns
+///
+/// The equivalent `modinfo.json`, or `None` if the source declares no `ModInfo`.
+pub(crate) fn modinfo_from_source(source: &str) -> Option<Value> {
+    let tokens = tokenize(source);
+    let arguments = assembly_attributes(&tokens, "ModInfo").into_iter().next()?;
+
+    let mut positional = Vec::new();
+    let mut named = Map::new();
+    for argument in arguments {
+        match argument.as_slice() {
+            [Token::Ident(name), Token::Punct('='), value @ ..] => {
+                if let Some(value) = argument_value(value) {
+                    named.insert(name.clone(), value);
+                }
+            }
+            [Token::Str(value)] => positional.push(value.clone()),
+            _ => {}
+        }
+    }
+
+    let mut dependencies = Map::new();
+    for arguments in assembly_attributes(&tokens, "ModDependency") {
+        if let Some([Token::Str(modid)]) = arguments.first().map(Vec::as_slice) {
+            let version = match arguments.get(1).map(Vec::as_slice) {
+                Some([Token::Str(version)]) => version.clone(),
+                _ => String::new(),
+            };
+            dependencies.insert(modid.clone(), Value::String(version));
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let name = positional.next()?;
+    Some(modinfo_json(name, positional.next(), named, dependencies))
+}
+
+/// Reads values of a custom attribute blob (ECMA-335 II.23.3).
+struct BlobReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl BlobReader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// A length-prefixed UTF-8 string; `Some(None)` is a null string.
+    fn string(&mut self) -> Option<Option<String>> {
+        let first = self.u8()?;
+        let len = match first {
+            0xFF => return Some(None),
+            _ if first & 0x80 == 0 => usize::from(first),
+            _ if first & 0xC0 == 0x80 => usize::from(first & 0x3F) << 8 | usize::from(self.u8()?),
+            _ if first & 0xE0 == 0xC0 => {
+                let rest = self.take(3)?;
+                usize::from(first & 0x1F) << 24
+                    | usize::from(rest[0]) << 16
+                    | usize::from(rest[1]) << 8
+                    | usize::from(rest[2])
+            }
+            _ => return None,
+        };
+        let text = std::str::from_utf8(self.take(len)?).ok()?;
+        Some(Some(text.to_string()))
+    }
+
+    /// A value of the given element type. Only the types `ModInfo` arguments use are
+    /// supported.
+    fn value(&mut self, element_type: u8) -> Option<Value> {
+        match element_type {
+            0x02 => Some(Value::Bool(self.u8()? != 0)),
+            0x08 | 0x55 => Some(json!(self.u32()? as i32)),
+            0x0E => Some(self.string()?.map_or(Value::Null, Value::String)),
+            _ => None,
+        }
+    }
+
+    /// A named argument: its name and value.
+    fn named_argument(&mut self) -> Option<(String, Value)> {
+        // 0x53 is a field, 0x54 a property
+        if !matches!(self.u8()?, 0x53 | 0x54) {
+            return None;
+        }
+        let value_type = self.u8()?;
+        let element_type = if value_type == 0x1D {
+            Some(self.u8()?)
+        } else {
+            None
+        };
+        if value_type == 0x55 {
+            // Enums name their type first
+            self.string()?;
+        }
+        let name = self.string()??;
+
+        let value = match element_type {
+            Some(element_type) => match self.u32()? {
+                u32::MAX => Value::Null,
+                count => Value::Array(
+                    (0..count)
+                        .map(|_| self.value(element_type))
+                        .collect::<Option<_>>()?,
+                ),
+            },
+            None => self.value(value_type)?,
+        };
+        Some((name, value))
+    }
+}
+
+/// Tries to read a `ModInfo` attribute blob at the start of `bytes`.
+///
+/// The blob holds the mod name, optionally the mod ID, and the named arguments, of which
+/// `Version` is required so unrelated attributes with string arguments aren't mistaken
+/// for mod info.
+fn modinfo_blob(bytes: &[u8]) -> Option<Value> {
+    let mut reader = BlobReader { bytes };
+    if reader.u16()? != 0x0001 {
+        return None;
+    }
+    let name = reader.string()??;
+    if name.is_empty() {
+        return None;
+    }
+
+    let named_arguments = |reader: &mut BlobReader| {
+        let count = reader.u16()?;
+        (0..count)
+            .map(|_| reader.named_argument())
+            .collect::<Option<Map<_, _>>>()
+    };
+    let mut with_modid = BlobReader {
+        bytes: reader.bytes,
+    };
+    let (modid, named) = match with_modid.string() {
+        Some(Some(modid))
+            if !modid.is_empty()
+                && modid
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()) =>
+        {
+            (Some(modid), named_arguments(&mut with_modid)?)
+        }
+        _ => (None, named_arguments(&mut reader)?),
+    };
+
+    if !named.get("Version").is_some_and(Value::is_string) {
+        return None;
+    }
+    let named = named
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .collect();
+    Some(modinfo_json(name, modid, named, Map::new()))
+}
+
+/// Finds the `ModInfo` attribute in a compiled mod assembly.
+///
+/// The attribute's arguments are stored as a blob in the assembly metadata, which is
+/// searched for directly rather than resolving the attribute through the metadata
+/// tables. Dependencies declared with `ModDependency` can't be told apart from other
+/// attributes this way and are not read.
+///
+/// # Returns
+///
+/// The equivalent `modinfo.json`, or `None` if no `ModInfo` attribute was found.
+pub(crate) fn modinfo_from_assembly(bytes: &[u8]) -> Option<Value> {
+    bytes
+        .windows(2)
+        .enumerate()
+        .filter(|(_, prolog)| *prolog == [0x01, 0x00])
+        .find_map(|(start, _)| modinfo_blob(&bytes[start..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_modinfo_from_source_mods() {
+        let source = r#"
+            using Vintagestory.API.Common;
+
+            // [assembly: ModInfo("Commented out")]
+            [assembly: ModInfo("Hud Clock", "hudclock",
+                Version = "1.2.0", Side = "Client",
+                Authors = new[] { "Melchior", @"Some ""one""" },
+                RequiredOnServer = false)]
+            [assembly: ModDependency("game", "1.19.0")]
+            [assembly: ModDependency("survival")]
+
+            public class HudClock : ModSystem {}
+        "#;
+
+        let modinfo = modinfo_from_source(source).unwrap();
+        assert_eq!(
+            modinfo,
+            json!({
+                "type": "code",
+                "name": "Hud Clock",
+                "modid": "hudclock",
+                "Version": "1.2.0",
+                "Side": "Client",
+                "Authors": ["Melchior", "Some \"one\""],
+                "RequiredOnServer": false,
+                "dependencies": { "game": "1.19.0", "survival": "" },
+            })
+        );
+
+        let unnamed = modinfo_from_source(r#"[assembly: ModInfo("Better Ruins")]"#).unwrap();
+        assert_eq!(unnamed["modid"], "betterruins");
+        assert!(modinfo_from_source("public class NotAMod {}").is_none());
+    }
+
+    #[test]
+    fn finds_modinfo_blobs_in_assemblies() {
+        let string = |text: &str| {
+            let mut bytes = vec![text.len() as u8];
+            bytes.extend_from_slice(text.as_bytes());
+            bytes
+        };
+        let property = |name: &str, value_type: &[u8], value: &[u8]| {
+            let mut bytes = vec![0x54];
+            bytes.extend_from_slice(value_type);
+            bytes.extend(string(name));
+            bytes.extend_from_slice(value);
+            bytes
+        };
+
+        // Another attribute with string arguments, which must not be taken for mod info
+        let mut assembly = b"MZ\x90\x00\x01\x00".to_vec();
+        assembly.extend(string("Company"));
+        assembly.extend([0x00, 0x00]);
+        assembly.extend([0x01, 0x00]);
+        assembly.extend(string("Carry On"));
+        assembly.extend(string("carryon"));
+        assembly.extend([0x03, 0x00]);
+        assembly.extend(property("Version", &[0x0E], &string("1.8.0")));
+        assembly.extend(property("Authors", &[0x1D, 0x0E], &{
+            let mut authors = vec![0x02, 0x00, 0x00, 0x00];
+            authors.extend(string("copygirl"));
+            authors.extend(string("Nikeyu"));
+            authors
+        }));
+        assembly.extend(property("RequiredOnClient", &[0x02], &[0x01]));
+        assembly.extend(b"\x00\x00 trailing metadata");
+
+        let modinfo = modinfo_from_assembly(&assembly).unwrap();
+        assert_eq!(
+            modinfo,
+            json!({
+                "type": "code",
+                "name": "Carry On",
+                "modid": "carryon",
+                "Version": "1.8.0",
+                "Authors": ["copygirl", "Nikeyu"],
+                "RequiredOnClient": true,
+            })
+        );
+        assert!(modinfo_from_assembly(b"MZ\x90\x00\x01\x00\x03abc").is_none());
+    }
+}
@@ -0,0 +1,338 @@
+use crate::api::Release;
+use crate::utils::compare_versions;
+use colored::Colorize;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// How well a release's game-version tags match a given game version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// The release is tagged with the game version, or a wildcard (`1.20.x`) or range
+    /// (`1.19.0 - 1.20.4`, `>=1.20.0`) covering it.
+    Compatible,
+    /// The release is tagged with a version sharing the game's major.minor version.
+    SameMinor,
+    /// None of the release's tags match the game version.
+    Incompatible,
+    /// The game version or the release's tags are not known.
+    Unknown,
+}
+
+impl Compatibility {
+    /// Classifies a set of release tags against a game version.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - The game-version tags of the release (e.g. `["v1.20.3", "v1.20.4"]`).
+    /// * `game_version` - The game version to compare against, if known.
+    ///
+    /// # Returns
+    ///
+    /// The `Compatibility` of the release.
+    pub fn of_tags(tags: &[String], game_version: Option<&str>) -> Self {
+        let Some(game_version) = game_version else {
+            return Compatibility::Unknown;
+        };
+        if tags.is_empty() {
+            return Compatibility::Unknown;
+        }
+
+        let game_version = normalize(game_version);
+        if tags.iter().any(|tag| covers(tag, game_version)) {
+            return Compatibility::Compatible;
+        }
+
+        let game_minor = major_minor(game_version);
+        if tags
+            .iter()
+            .any(|tag| major_minor(normalize(tag)) == game_minor)
+        {
+            return Compatibility::SameMinor;
+        }
+
+        Compatibility::Incompatible
+    }
+
+    /// Returns the single-character symbol for this compatibility level.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Compatibility::Compatible => "✓",
+            Compatibility::SameMinor => "~",
+            Compatibility::Incompatible => "✗",
+            Compatibility::Unknown => "?",
+        }
+    }
+
+    /// Returns a short label for this compatibility level.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Compatibility::Compatible => "compatible",
+            Compatibility::SameMinor => "same minor",
+            Compatibility::Incompatible => "incompatible",
+            Compatibility::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Compatibility {
+    /// Renders the compatibility as a colored badge such as `✓ compatible`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let badge = format!("{} {}", self.symbol(), self.label());
+        let badge = match self {
+            Compatibility::Compatible => badge.green(),
+            Compatibility::SameMinor => badge.yellow(),
+            Compatibility::Incompatible => badge.red(),
+            Compatibility::Unknown => badge.dimmed(),
+        };
+        write!(f, "{badge}")
+    }
+}
+
+/// Serializes as the plain label, e.g. `"same minor"`.
+impl Serialize for Compatibility {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.label())
+    }
+}
+
+/// How ready an installed mod is for a game version, as reported by `compat-check`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Readiness {
+    /// The installed release supports the game version.
+    Ready,
+    /// Another release supports the game version: the newest such one.
+    NeedsUpdate(String),
+    /// No release supports the exact game version, but this one was made for its
+    /// major.minor version.
+    Untested(String),
+    /// No release was made for the game version.
+    Unsupported,
+}
+
+impl Readiness {
+    /// Decides how ready a mod is for a game version.
+    ///
+    /// # Arguments
+    ///
+    /// * `installed` - The installed version of the mod.
+    /// * `releases` - The releases of the mod to consider.
+    /// * `game_version` - The game version to check against.
+    ///
+    /// # Returns
+    ///
+    /// The `Readiness` of the mod.
+    pub fn of_releases(installed: &str, releases: &[&Release], game_version: &str) -> Self {
+        let compatibility =
+            |release: &Release| Compatibility::of_tags(&release.tags, Some(game_version));
+        let newest_with = |level: Compatibility| {
+            Release::highest(
+                releases
+                    .iter()
+                    .copied()
+                    .filter(|release| compatibility(release) == level),
+            )
+            .map(|release| release.modversion.clone().unwrap_or_default())
+        };
+
+        let installed_release = releases.iter().find(|release| {
+            release
+                .modversion
+                .as_deref()
+                .is_some_and(|version| compare_versions(version, installed).is_eq())
+        });
+        if installed_release
+            .is_some_and(|release| compatibility(release) == Compatibility::Compatible)
+        {
+            return Readiness::Ready;
+        }
+        if let Some(version) = newest_with(Compatibility::Compatible) {
+            return Readiness::NeedsUpdate(version);
+        }
+        if let Some(version) = newest_with(Compatibility::SameMinor) {
+            return Readiness::Untested(version);
+        }
+        Readiness::Unsupported
+    }
+
+    /// Returns `true` if the mod runs on the game version, possibly after an update.
+    pub fn is_supported(&self) -> bool {
+        matches!(self, Readiness::Ready | Readiness::NeedsUpdate(_))
+    }
+
+    /// Returns a short label for this readiness level.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Readiness::Ready => "ready",
+            Readiness::NeedsUpdate(_) => "needs update",
+            Readiness::Untested(_) => "untested",
+            Readiness::Unsupported => "unsupported",
+        }
+    }
+
+    /// The release to switch to, if another release than the installed one is needed.
+    pub fn release(&self) -> Option<&str> {
+        match self {
+            Readiness::NeedsUpdate(version) | Readiness::Untested(version) => Some(version),
+            Readiness::Ready | Readiness::Unsupported => None,
+        }
+    }
+}
+
+impl fmt::Display for Readiness {
+    /// Renders the readiness as a colored badge such as `↑ needs update (1.8.0)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let badge = match self {
+            Readiness::Ready => format!("✓ {}", self.label()).green(),
+            Readiness::NeedsUpdate(version) => format!("↑ {} ({version})", self.label()).cyan(),
+            Readiness::Untested(version) => format!("~ {} ({version})", self.label()).yellow(),
+            Readiness::Unsupported => format!("✗ {}", self.label()).red(),
+        };
+        write!(f, "{badge}")
+    }
+}
+
+/// Strips the `v` prefix the ModDB uses on game-version tags.
+fn normalize(version: &str) -> &str {
+    version.trim().trim_start_matches('v')
+}
+
+/// Returns `true` if a game-version tag covers `game_version`.
+///
+/// Besides exact versions, tags may use `x` or `*` wildcards (`1.20.x`), inclusive ranges
+/// (`1.19.0 - 1.20.4`), comparisons (`>=1.20.0`, `<1.21`) or a trailing `+` for "this
+/// version or newer" (`1.20.0+`).
+fn covers(tag: &str, game_version: &str) -> bool {
+    let tag = normalize(tag);
+    let compare = |version: &str| compare_versions(game_version, normalize(version));
+
+    if let Some((low, high)) = tag.split_once(" - ") {
+        compare(low).is_ge() && compare(high).is_le()
+    } else if let Some(version) = tag.strip_prefix(">=") {
+        compare(version).is_ge()
+    } else if let Some(version) = tag.strip_prefix('>') {
+        compare(version).is_gt()
+    } else if let Some(version) = tag.strip_prefix("<=") {
+        compare(version).is_le()
+    } else if let Some(version) = tag.strip_prefix('<') {
+        compare(version).is_lt()
+    } else if let Some(version) = tag.strip_suffix('+') {
+        compare(version).is_ge()
+    } else if tag.contains(['x', 'X', '*']) {
+        let mut version_parts = game_version.split(['.', '-']);
+        tag.split('.')
+            .take_while(|part| !matches!(*part, "x" | "X" | "*"))
+            .all(|part| version_parts.next() == Some(part))
+    } else {
+        compare(tag).is_eq()
+    }
+}
+
+/// Returns the `major.minor` prefix of a version string.
+fn major_minor(version: &str) -> Option<(&str, &str)> {
+    let mut parts = version.split(['.', '-']);
+    Some((parts.next()?, parts.next()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn exact_tag_is_compatible() {
+        let compat = Compatibility::of_tags(&tags(&["v1.20.2", "v1.20.3"]), Some("1.20.3"));
+        assert_eq!(compat, Compatibility::Compatible);
+    }
+
+    #[test]
+    fn wildcards_and_ranges_are_compatible() {
+        for tag in [
+            "v1.20.x",
+            "1.20.*",
+            "1.x",
+            "1.19.0 - 1.20.4",
+            ">=1.20.0",
+            "<1.21",
+            "v1.20.0+",
+        ] {
+            assert_eq!(
+                Compatibility::of_tags(&tags(&[tag]), Some("1.20.3")),
+                Compatibility::Compatible,
+                "{tag}"
+            );
+        }
+
+        for tag in [
+            "1.21.x",
+            "1.20.4 - 1.21.0",
+            ">1.20.3",
+            "<=1.20.2",
+            "1.21.0+",
+        ] {
+            assert_ne!(
+                Compatibility::of_tags(&tags(&[tag]), Some("1.20.3")),
+                Compatibility::Compatible,
+                "{tag}"
+            );
+        }
+    }
+
+    #[test]
+    fn same_minor_tag_is_same_minor() {
+        let compat = Compatibility::of_tags(&tags(&["v1.20.1"]), Some("1.20.3"));
+        assert_eq!(compat, Compatibility::SameMinor);
+    }
+
+    #[test]
+    fn other_minor_is_incompatible() {
+        let compat = Compatibility::of_tags(&tags(&["v1.19.8"]), Some("1.20.3"));
+        assert_eq!(compat, Compatibility::Incompatible);
+    }
+
+    #[test]
+    fn readiness_prefers_the_installed_release() {
+        let release = |version: &str, tag: &str| Release {
+            modversion: Some(version.to_string()),
+            tags: tags(&[tag]),
+            ..Release::default()
+        };
+        let releases = [
+            release("2.0.0", "v1.21.0"),
+            release("1.9.0", "v1.20.3"),
+            release("1.8.0", "v1.20.0"),
+        ];
+        let releases: Vec<&Release> = releases.iter().collect();
+
+        assert_eq!(
+            Readiness::of_releases("1.9.0", &releases, "1.20.3"),
+            Readiness::Ready
+        );
+        assert_eq!(
+            Readiness::of_releases("1.8.0", &releases, "1.21.0"),
+            Readiness::NeedsUpdate("2.0.0".to_string())
+        );
+        assert_eq!(
+            Readiness::of_releases("1.8.0", &releases, "1.20.5"),
+            Readiness::Untested("1.9.0".to_string())
+        );
+        assert_eq!(
+            Readiness::of_releases("1.8.0", &releases, "1.22.0"),
+            Readiness::Unsupported
+        );
+    }
+
+    #[test]
+    fn missing_information_is_unknown() {
+        assert_eq!(
+            Compatibility::of_tags(&tags(&["v1.20.3"]), None),
+            Compatibility::Unknown
+        );
+        assert_eq!(
+            Compatibility::of_tags(&[], Some("1.20.3")),
+            Compatibility::Unknown
+        );
+    }
+}
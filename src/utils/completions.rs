@@ -0,0 +1,180 @@
+use crate::utils::config_manager::ConfigManager;
+use crate::utils::{Cli, FileManager, default_vintage_mods_dir, existing_mods_dir};
+use clap::CommandFactory;
+use clap_complete::env::Shells;
+use clap_complete::{CompleteEnv, CompletionCandidate, Shell};
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Environment variable the completion scripts set when asking for completions.
+const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Answers a completion request from one of the scripts written by
+/// [`write_completions`] and exits. Does nothing on a regular run.
+pub fn complete_if_requested() {
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_VAR)
+        .complete();
+}
+
+/// Writes the completion script for a shell.
+///
+/// The script calls back into this program for every completion, so the installed mod
+/// IDs offered for `--mod`, `--include` and `--exclude` are always current.
+///
+/// # Arguments
+///
+/// * `shell` - The shell to write the script for.
+/// * `out` - Where to write the script, usually stdout.
+pub fn write_completions(shell: Shell, out: &mut dyn Write) -> Result<(), std::io::Error> {
+    let name = shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells.completer(&name).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("Completions are not available for {name}"),
+        )
+    })?;
+
+    let command = Cli::command();
+    let bin = command.get_bin_name().unwrap_or(command.get_name());
+    completer.write_registration(COMPLETE_VAR, command.get_name(), bin, bin, out)
+}
+
+/// Completes the mod IDs of the installed mods. In a comma-separated list only the ID
+/// after the last comma is completed.
+pub fn complete_mod_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let (listed, partial) = match current.rsplit_once(',') {
+        Some((listed, partial)) => (format!("{listed},"), partial),
+        None => (String::new(), current.as_ref()),
+    };
+
+    installed_mod_ids(&LineDirs::parse(std::env::args_os()))
+        .into_iter()
+        .filter(|modid| modid.starts_with(partial) && !listed.split(',').any(|id| id == modid))
+        .map(|modid| CompletionCandidate::new(format!("{listed}{modid}")))
+        .collect()
+}
+
+/// The options selecting mods directories on the command line being completed.
+#[derive(Debug, Default, PartialEq)]
+struct LineDirs {
+    dirs: Vec<PathBuf>,
+    mods_dir: Option<PathBuf>,
+    instance: Option<String>,
+}
+
+impl LineDirs {
+    /// Reads `--dir`, `--mods-dir` and `--instance` from the arguments of a completion
+    /// request, where the line being completed follows `--`. The line may be unfinished,
+    /// so it is scanned rather than parsed.
+    fn parse(args: impl IntoIterator<Item = OsString>) -> Self {
+        let words: Vec<String> = args
+            .into_iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .skip_while(|arg| arg != "--")
+            .collect();
+
+        let mut line = Self::default();
+        let mut words = words.iter().skip(1);
+        while let Some(word) = words.next() {
+            let (option, value) = match word.split_once('=') {
+                Some((option, value)) => (option, Some(value.to_string())),
+                None => (word.as_str(), None),
+            };
+            if !matches!(option, "--dir" | "--mods-dir" | "--instance") {
+                continue;
+            }
+            let Some(value) = value.or_else(|| words.next().cloned()) else {
+                break;
+            };
+            match option {
+                "--dir" => line.dirs.push(PathBuf::from(value)),
+                "--mods-dir" => line.mods_dir = Some(PathBuf::from(value)),
+                _ => line.instance = Some(value),
+            }
+        }
+        line
+    }
+}
+
+/// The mod IDs in the mods directories `run` would select for the line being completed.
+fn installed_mod_ids(line: &LineDirs) -> Vec<String> {
+    let mod_dirs = if !line.dirs.is_empty() {
+        line.dirs.clone()
+    } else {
+        match ConfigManager::new() {
+            Ok(config_manager) => {
+                let mods_dir = line.mods_dir.clone().or_else(|| {
+                    let instance = config_manager.get_instance(line.instance.as_ref()?).ok()?;
+                    Some(instance.data_path.join("Mods"))
+                });
+                config_manager.with_mods_dir(mods_dir).get_mod_dirs()
+            }
+            Err(_) => existing_mods_dir(
+                line.mods_dir
+                    .clone()
+                    .unwrap_or_else(default_vintage_mods_dir),
+            )
+            .into_iter()
+            .collect(),
+        }
+    };
+    FileManager::new()
+        .with_mod_dirs(mod_dirs)
+        .installed_mod_ids()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_call_back_for_completions() {
+        let mut script = Vec::new();
+        write_completions(Shell::Bash, &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("COMPLETE=\"bash\""));
+        assert!(script.contains("VintageModManager"));
+    }
+
+    #[test]
+    fn directory_options_are_read_from_the_completed_line() {
+        let args = [
+            "vintage-mod-manager",
+            "--",
+            "vintage-mod-manager",
+            "--instance",
+            "server",
+            "update",
+            "--dir=/srv/mods",
+            "--mods-dir",
+            "/srv/main",
+            "--include",
+            "car",
+        ];
+        let line = LineDirs::parse(args.map(OsString::from));
+        assert_eq!(
+            line,
+            LineDirs {
+                dirs: vec![PathBuf::from("/srv/mods")],
+                mods_dir: Some(PathBuf::from("/srv/main")),
+                instance: Some("server".to_string()),
+            }
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("carryon.zip"),
+            crate::api::mock_moddb::mod_zip("carryon", "1.8.0"),
+        )
+        .unwrap();
+        let line = LineDirs {
+            dirs: vec![dir.path().to_path_buf()],
+            ..LineDirs::default()
+        };
+        assert_eq!(installed_mod_ids(&line), vec!["carryon".to_string()]);
+    }
+}
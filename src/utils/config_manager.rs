@@ -1,11 +1,30 @@
-use crate::api::VintageApiHandler;
-use crate::config::{Config, VersionMapping};
+use crate::api::{ClientError, VintageApiHandler};
+use crate::config::{
+    CONFIG_SCHEMA_VERSION, Channel, Config, IgnoreScope, InstallMode, Instance, Profile, Remote,
+    VersionMapping, Webhook, WebhookKind,
+};
+use crate::say;
 use crate::utils::terminal::Terminal;
-use crate::utils::{LogLevel, Logger};
+use crate::utils::{compare_versions, default_vintage_mods_dir, existing_mods_dir, json_output};
 use directories::ProjectDirs;
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
+use tracing::{info, warn};
+
+/// Where the Vintage Story Flatpak keeps the game inside a Flatpak installation.
+const FLATPAK_GAME_DIR: &str =
+    "app/at.vintagestory.VintageStory/current/active/files/extra/vintagestory";
+
+/// Reads the library folders out of a Steam `libraryfolders.vdf` file.
+fn steam_library_paths(vdf: &str) -> Vec<PathBuf> {
+    let path_line = regex::Regex::new(r#""path"\s+"((?:[^"\\]|\\.)*)""#).unwrap();
+    path_line
+        .captures_iter(vdf)
+        .map(|captures| PathBuf::from(captures[1].replace(r"\\", r"\")))
+        .collect()
+}
 
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -21,43 +40,208 @@ pub enum ConfigError {
     InvalidGamePath(String),
     #[error("API error: {0}")]
     Api(#[from] reqwest::Error),
+    #[error("API error: {0}")]
+    Client(#[from] ClientError),
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
     #[error("Regex error: {0}")]
     Regex(#[from] regex::Error),
+    #[error("Instance not found: {0} (add it with `config add-instance`)")]
+    InstanceNotFound(String),
+    #[error("Remote not found: {0} (add it with `remote add`)")]
+    RemoteNotFound(String),
+    #[error("Invalid remote target {0}, expected [user@]host:path")]
+    InvalidRemote(String),
+    #[error("Unknown game version {version}: {hint}")]
+    UnknownGameVersion { version: String, hint: String },
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("Profile already exists: {0} (use --force to overwrite)")]
+    ProfileExists(String),
+    #[error("Invalid webhook URL: {0}")]
+    InvalidWebhook(String),
+    #[error(
+        "Invalid filename template '{0}': it must contain {{modid}}, end in .zip and not contain path separators"
+    )]
+    InvalidFilenameTemplate(String),
+    #[error("Unknown config key: {0} (run `config get` to list them)")]
+    UnknownKey(String),
+    #[error("Invalid value for {key}: {reason}")]
+    InvalidValue { key: String, reason: String },
+}
+
+/// Settings the tool keeps up to date itself, which `config set` refuses to change.
+const READ_ONLY_KEYS: [&str; 4] = [
+    "schema_version",
+    "detected_game_version",
+    "last_seen_game_version",
+    "version_mapping",
+];
+
+/// Severity of a single validation check.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Outcome of a single configuration check.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationCheck {
+    /// Short machine-friendly name of the check (e.g. `game_path`).
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    /// Command or action that would resolve the problem, if any.
+    pub suggestion: Option<String>,
+}
+
+/// Machine-readable result of `config validate`.
+#[derive(Debug, Serialize)]
+pub struct ValidationReport {
+    pub config_path: PathBuf,
+    /// Actions taken by `--fix` before the checks ran.
+    pub fixes_applied: Vec<String>,
+    pub checks: Vec<ValidationCheck>,
+}
+
+impl ValidationReport {
+    fn new(config_path: PathBuf) -> Self {
+        Self {
+            config_path,
+            fixes_applied: Vec::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, name: &str, status: CheckStatus, message: String, suggestion: Option<&str>) {
+        self.checks.push(ValidationCheck {
+            name: name.to_string(),
+            status,
+            message,
+            suggestion: suggestion.map(str::to_string),
+        });
+    }
+
+    /// Returns `true` if any check is a hard failure.
+    pub fn has_errors(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Error)
+    }
+
+    /// Prints the report in human-readable form.
+    pub fn print(&self) {
+        say!("Validating configuration: {}", self.config_path.display());
+
+        for fix in &self.fixes_applied {
+            say!("  [fixed] {fix}");
+        }
+
+        for check in &self.checks {
+            let label = match check.status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Warning => "warning",
+                CheckStatus::Error => "error",
+            };
+            say!("  [{label}] {}", check.message);
+            if let Some(suggestion) = &check.suggestion {
+                say!("      hint: {suggestion}");
+            }
+        }
+    }
 }
 
 pub struct ConfigManager {
     config_path: PathBuf,
     config: Config,
-    logger: Logger,
+    /// Set by `--mods-dir` or `--instance`: the main mods directory, taking precedence over
+    /// the `mods_dir` setting.
+    mods_dir: Option<PathBuf>,
 }
 
 impl ConfigManager {
     /// Create a new ConfigManager
-    pub fn new(verbose: bool) -> Result<Self, ConfigError> {
-        let config_path = Self::get_config_path()?;
-        let logger = Logger::new("ConfigManager".to_string(), LogLevel::Info, None, verbose);
+    pub fn new() -> Result<Self, ConfigError> {
+        Self::load(Self::get_config_path()?)
+    }
 
-        let mut config = if config_path.exists() {
-            Self::load_config_from_file(&config_path)?
+    /// Creates a ConfigManager for the config file at `config_path`, starting from the
+    /// defaults if there is none yet.
+    pub fn load(config_path: PathBuf) -> Result<Self, ConfigError> {
+        let (config, parse_error) = if config_path.exists() {
+            match Self::load_config_from_file(&config_path) {
+                Ok(config) => (config, None),
+                Err(ConfigError::Toml(e)) => (Config::new(), Some(e)),
+                Err(e) => return Err(e),
+            }
         } else {
-            Config::new()
+            (Config::new(), None)
         };
 
+        let mut manager = Self {
+            config_path,
+            config,
+            mods_dir: None,
+        };
+
+        if let Some(e) = parse_error {
+            manager.recover_corrupt_config(&e)?;
+        }
+
         // Auto-detect game version if path is set but version isn't detected yet
-        if config.get_game_path().is_some() && config.get_detected_game_version().is_none() {
-            if let Err(e) = config.detect_game_version() {
-                // Log but don't fail - version detection is optional
-                eprintln!("Warning: Could not detect game version: {e}");
+        if manager.config.get_game_path().is_some()
+            && manager.config.get_detected_game_version().is_none()
+            && let Err(e) = manager.config.detect_game_version()
+        {
+            // Log but don't fail - version detection is optional
+            eprintln!("Warning: Could not detect game version: {e}");
+        }
+
+        Ok(manager)
+    }
+
+    /// Recover from a config file that failed to parse.
+    ///
+    /// The broken file is copied to `config.toml.bak` and the user is asked whether to
+    /// salvage the readable settings or re-initialize from scratch. Without a terminal to
+    /// prompt on, the readable settings are salvaged.
+    fn recover_corrupt_config(&mut self, error: &toml::de::Error) -> Result<(), ConfigError> {
+        let backup_path = self.config_path.with_extension("toml.bak");
+        fs::copy(&self.config_path, &backup_path)?;
+
+        eprintln!(
+            "Warning: Could not parse config file {}: {}",
+            self.config_path.display(),
+            error.message()
+        );
+        eprintln!(
+            "A copy of the broken file was saved to {}",
+            backup_path.display()
+        );
+
+        let options = [
+            "Salvage readable settings",
+            "Re-initialize with auto-detected defaults",
+        ];
+        let choice = Terminal::select("How do you want to recover the configuration?", &options);
+
+        if choice == Some(1) {
+            self.config = Config::new();
+            if let Some(game_path) = self.try_detect_game_path() {
+                self.config.set_game_path(game_path);
+                let _ = self.config.detect_game_version();
             }
+            eprintln!("Configuration re-initialized");
+        } else {
+            let contents = fs::read_to_string(&self.config_path)?;
+            self.config = salvage_config(&contents);
+            eprintln!("Salvaged readable settings from the broken config file");
         }
 
-        Ok(Self {
-            config_path,
-            config,
-            logger,
-        })
+        self.save()
     }
 
     /// Get the standard config file path
@@ -73,18 +257,66 @@ impl ConfigManager {
         }
     }
 
-    /// Load config from file
+    /// Load config from file, upgrading files written with an older schema version.
+    ///
+    /// Migrated files are rewritten in the current layout, with the original kept as
+    /// `config.toml.v<version>.bak`. If either can't be written, e.g. on a read-only
+    /// config, the migrated config is used for this run only. Files from a newer build are
+    /// read as far as they can be and left untouched.
     fn load_config_from_file(path: &Path) -> Result<Config, ConfigError> {
         let contents = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&contents)?;
+        let mut table: toml::Table = toml::from_str(&contents)?;
+
+        let version = schema_version(&table);
+        if version > CONFIG_SCHEMA_VERSION {
+            warn!(
+                "Config file {} was written by a newer version (schema {version}, this build \
+                 reads up to {CONFIG_SCHEMA_VERSION}); unknown settings are ignored",
+                path.display()
+            );
+            return Ok(toml::Value::Table(table).try_into()?);
+        }
+        if version == CONFIG_SCHEMA_VERSION {
+            return Ok(toml::Value::Table(table).try_into()?);
+        }
+
+        for migration in &MIGRATIONS[version as usize..] {
+            migration(&mut table);
+        }
+        table.insert(
+            "schema_version".to_string(),
+            toml::Value::Integer(CONFIG_SCHEMA_VERSION.into()),
+        );
+        let config: Config = toml::Value::Table(table).try_into()?;
+
+        let backup_path = path.with_extension(format!("toml.v{version}.bak"));
+        match Self::save_migrated(path, &backup_path, &config) {
+            Ok(()) => info!(
+                "Migrated config file from schema {version} to {CONFIG_SCHEMA_VERSION}, the \
+                 original was saved to {}",
+                backup_path.display()
+            ),
+            Err(e) => warn!(
+                "Failed to save the config file {} migrated to schema {CONFIG_SCHEMA_VERSION}, \
+                 it is migrated again on the next run: {e}",
+                path.display()
+            ),
+        }
         Ok(config)
     }
 
+    /// Backs up a config file to `backup_path` and replaces it with the migrated `config`.
+    fn save_migrated(path: &Path, backup_path: &Path, config: &Config) -> Result<(), ConfigError> {
+        fs::copy(path, backup_path)?;
+        fs::write(path, toml::to_string_pretty(config)?)?;
+        Ok(())
+    }
+
     /// Save current config to file
     pub fn save(&self) -> Result<(), ConfigError> {
         let toml_string = toml::to_string_pretty(&self.config)?;
         fs::write(&self.config_path, toml_string)?;
-        self.logger.log_default("Configuration saved");
+        info!("Configuration saved");
         Ok(())
     }
 
@@ -105,13 +337,13 @@ impl ConfigManager {
 
             // Try to detect game version
             if let Ok(Some(version)) = self.config.detect_game_version() {
-                println!(
+                say!(
                     "Auto-detected game path: {} (version: {})",
                     self.config.get_game_path().unwrap().display(),
                     version
                 );
             } else {
-                println!(
+                say!(
                     "Auto-detected game path: {}",
                     self.config.get_game_path().unwrap().display()
                 );
@@ -119,7 +351,7 @@ impl ConfigManager {
         }
 
         self.save()?;
-        println!(
+        say!(
             "Configuration initialized at: {}",
             self.config_path.display()
         );
@@ -127,24 +359,90 @@ impl ConfigManager {
     }
 
     /// Try to auto-detect Vintage Story installation
+    ///
+    /// When more than one installation is found the user picks one, the first candidate
+    /// is used when nobody can be asked.
     fn try_detect_game_path(&self) -> Option<PathBuf> {
-        let possible_paths = vec![
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        for path in self.game_path_candidates() {
+            if path.exists() && self.validate_game_path(&path) && !candidates.contains(&path) {
+                candidates.push(path);
+            }
+        }
+
+        if candidates.len() <= 1 {
+            return candidates.pop();
+        }
+
+        let labels: Vec<_> = candidates.iter().map(|path| path.display()).collect();
+        let choice = Terminal::select(
+            "Found several Vintage Story installations, which one should be managed?",
+            &labels,
+        )
+        .unwrap_or(0);
+        candidates.into_iter().nth(choice)
+    }
+
+    /// Places Vintage Story is commonly installed to, most specific first: the
+    /// `VINTAGE_STORY` environment variable, the default install locations, Flatpak
+    /// installs and every Steam library.
+    fn game_path_candidates(&self) -> Vec<PathBuf> {
+        let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+        let mut paths: Vec<PathBuf> = std::env::var_os("VINTAGE_STORY")
+            .map(PathBuf::from)
+            .into_iter()
+            .collect();
+
+        // Portable installs keep this tool next to the game
+        if let Ok(exe) = std::env::current_exe()
+            && let Some(dir) = exe.parent()
+        {
+            paths.push(dir.to_path_buf());
+        }
+
+        if let Some(appdata) = std::env::var_os("APPDATA") {
+            paths.push(PathBuf::from(appdata).join("Vintagestory"));
+        }
+        paths.extend([
             // Windows
             PathBuf::from(r"C:\Program Files\Vintage Story"),
             PathBuf::from(r"C:\Program Files (x86)\Vintage Story"),
             // Linux
             PathBuf::from("/opt/vintagestory"),
-            PathBuf::from(format!(
-                "{}/.local/share/VintageStory",
-                std::env::var("HOME").unwrap_or_default()
-            )),
+            home.join(".local/share/VintageStory"),
+            home.join(".local/share/vintagestory"),
             // macOS
             PathBuf::from("/Applications/Vintage Story.app"),
+        ]);
+
+        // Flatpak, installed system-wide or per user
+        for flatpak_root in [
+            PathBuf::from("/var/lib/flatpak"),
+            home.join(".local/share/flatpak"),
+        ] {
+            paths.push(flatpak_root.join(FLATPAK_GAME_DIR));
+        }
+
+        let steam_roots = [
+            home.join(".steam/steam"),
+            home.join(".local/share/Steam"),
+            home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+            home.join("Library/Application Support/Steam"),
+            PathBuf::from(r"C:\Program Files (x86)\Steam"),
         ];
+        for root in steam_roots {
+            let vdf = root.join("steamapps").join("libraryfolders.vdf");
+            let Ok(contents) = fs::read_to_string(&vdf) else {
+                continue;
+            };
+            for library in steam_library_paths(&contents) {
+                let common = library.join("steamapps").join("common");
+                paths.push(common.join("Vintage Story"));
+                paths.push(common.join("VintageStory"));
+            }
+        }
 
-        possible_paths
-            .into_iter()
-            .find(|path| path.exists() && self.validate_game_path(path))
+        paths
     }
 
     /// Set game installation path and auto-detect version
@@ -168,32 +466,457 @@ impl ConfigManager {
         // Auto-detect game version
         match self.config.detect_game_version() {
             Ok(Some(version)) => {
-                println!("Game path set to: {}", path.display());
-                println!("Detected game version: {version}");
+                say!("Game path set to: {}", path.display());
+                say!("Detected game version: {version}");
 
                 // Check if we have a mapping for this version
                 if self.config.is_detected_version_mapped() {
-                    println!("Version mapping available");
+                    say!("Version mapping available");
                 } else {
-                    println!(
+                    say!(
                         "No version mapping for {version}. Run 'config update-versions' to fetch mappings."
                     );
                 }
             }
             Ok(None) => {
-                println!("Game path set to: {}", path.display());
-                println!("Could not auto-detect game version from assets directory");
+                say!("Game path set to: {}", path.display());
+                say!("Could not auto-detect game version from assets directory");
             }
             Err(e) => {
-                println!("Game path set to: {}", path.display());
-                println!("Error detecting game version: {e}");
+                say!("Game path set to: {}", path.display());
+                say!("Error detecting game version: {e}");
+            }
+        }
+
+        self.save()?;
+        Ok(())
+    }
+
+    /// Add an additional mods directory
+    pub fn add_mod_path(&mut self, path: PathBuf) -> Result<(), ConfigError> {
+        if !path.is_dir() {
+            return Err(ConfigError::InvalidGamePath(format!(
+                "Mods directory does not exist: {}",
+                path.display()
+            )));
+        }
+
+        if self.config.add_mod_path(path.clone()) {
+            self.save()?;
+            say!("Added mods directory: {}", path.display());
+        } else {
+            say!("Mods directory already configured: {}", path.display());
+        }
+        Ok(())
+    }
+
+    /// Add or replace a named game instance
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name to select the instance with `--instance`.
+    /// * `data_path` - The instance's `VintagestoryData` directory.
+    pub fn add_instance(&mut self, name: &str, data_path: PathBuf) -> Result<(), ConfigError> {
+        if !data_path.is_dir() {
+            return Err(ConfigError::InvalidGamePath(format!(
+                "Data directory does not exist: {}",
+                data_path.display()
+            )));
+        }
+
+        say!("Added instance '{name}': {}", data_path.display());
+        self.config
+            .instances
+            .insert(name.to_string(), Instance { data_path });
+        self.save()
+    }
+
+    /// Remove a named game instance
+    pub fn remove_instance(&mut self, name: &str) -> Result<(), ConfigError> {
+        if self.config.instances.remove(name).is_none() {
+            return Err(ConfigError::InstanceNotFound(name.to_string()));
+        }
+        self.save()?;
+        say!("Removed instance '{name}'");
+        Ok(())
+    }
+
+    /// Get a game instance by name
+    pub fn get_instance(&self, name: &str) -> Result<&Instance, ConfigError> {
+        self.config
+            .instances
+            .get(name)
+            .ok_or_else(|| ConfigError::InstanceNotFound(name.to_string()))
+    }
+
+    /// Add or replace a game server to synchronize mods with
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name to refer to the server by in `remote` commands.
+    /// * `remote` - How to reach the server and where its mods directory is.
+    pub fn add_remote(&mut self, name: &str, remote: Remote) -> Result<(), ConfigError> {
+        say!(
+            "Added remote '{name}': {}:{}",
+            remote.destination(),
+            remote.path
+        );
+        self.config.remotes.insert(name.to_string(), remote);
+        self.save()
+    }
+
+    /// Remove a game server added with `remote add`
+    pub fn remove_remote(&mut self, name: &str) -> Result<(), ConfigError> {
+        if self.config.remotes.remove(name).is_none() {
+            return Err(ConfigError::RemoteNotFound(name.to_string()));
+        }
+        self.save()?;
+        say!("Removed remote '{name}'");
+        Ok(())
+    }
+
+    /// Get a game server by name
+    pub fn get_remote(&self, name: &str) -> Result<&Remote, ConfigError> {
+        self.config
+            .remotes
+            .get(name)
+            .ok_or_else(|| ConfigError::RemoteNotFound(name.to_string()))
+    }
+
+    /// Print the game servers added with `remote add`
+    pub fn list_remotes(&self) {
+        if self.config.remotes.is_empty() {
+            say!("No remotes configured, add one with `remote add`");
+            return;
+        }
+        for (name, remote) in &self.config.remotes {
+            say!("{name}: {}:{}", remote.destination(), remote.path);
+        }
+    }
+
+    /// Pin a mod so `update` leaves it at its installed version
+    pub fn pin_mod(&mut self, modid: &str) -> Result<(), ConfigError> {
+        if self.config.pin_mod(modid) {
+            self.save()?;
+            say!("Pinned {modid}, updates will skip it");
+        } else {
+            say!("{modid} is already pinned");
+        }
+        Ok(())
+    }
+
+    /// Unpin a mod so `update` picks up new releases again
+    pub fn unpin_mod(&mut self, modid: &str) -> Result<(), ConfigError> {
+        if self.config.unpin_mod(modid) {
+            self.save()?;
+            say!("Unpinned {modid}");
+        } else {
+            say!("{modid} is not pinned");
+        }
+        Ok(())
+    }
+
+    /// Add a mod to the ignore list of `scope`
+    pub fn ignore_mod(&mut self, modid: &str, scope: IgnoreScope) -> Result<(), ConfigError> {
+        if self.config.ignore.add(scope, modid) {
+            self.save()?;
+            say!("Ignoring {modid} ({scope:?})");
+        } else {
+            say!("{modid} is already ignored ({scope:?})");
+        }
+        Ok(())
+    }
+
+    /// Remove a mod from the ignore list of `scope`
+    pub fn unignore_mod(&mut self, modid: &str, scope: IgnoreScope) -> Result<(), ConfigError> {
+        if self.config.ignore.remove(scope, modid) {
+            self.save()?;
+            say!("No longer ignoring {modid} ({scope:?})");
+        } else {
+            say!("{modid} is not ignored ({scope:?})");
+        }
+        Ok(())
+    }
+
+    /// Print the ignore lists
+    pub fn list_ignored(&self) {
+        if self.config.ignore.is_empty() {
+            say!("No mods are ignored, add one with `config ignore add`");
+            return;
+        }
+        self.print_ignored();
+    }
+
+    fn print_ignored(&self) {
+        let ignore = &self.config.ignore;
+        for (scope, mods) in [
+            (IgnoreScope::All, &ignore.mods),
+            (IgnoreScope::Updates, &ignore.updates),
+            (IgnoreScope::Browse, &ignore.browse),
+        ] {
+            if !mods.is_empty() {
+                say!("Ignored mods ({scope:?}): {}", mods.join(", "));
             }
         }
+    }
 
+    /// Remove an additional mods directory
+    pub fn remove_mod_path(&mut self, path: PathBuf) -> Result<(), ConfigError> {
+        if self.config.remove_mod_path(&path) {
+            self.save()?;
+            say!("Removed mods directory: {}", path.display());
+        } else {
+            say!("Mods directory was not configured: {}", path.display());
+        }
+        Ok(())
+    }
+
+    /// Create or overwrite a profile
+    pub fn create_profile(
+        &mut self, name: &str, profile: Profile, force: bool,
+    ) -> Result<(), ConfigError> {
+        if !force && self.config.profiles.contains_key(name) {
+            return Err(ConfigError::ProfileExists(name.to_string()));
+        }
+
+        let count = profile.mods.len();
+        self.config.profiles.insert(name.to_string(), profile);
         self.save()?;
+        say!("Created profile '{name}' with {count} mods");
         Ok(())
     }
 
+    /// Delete a profile
+    pub fn delete_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        if self.config.profiles.remove(name).is_none() {
+            return Err(ConfigError::ProfileNotFound(name.to_string()));
+        }
+        if self.config.active_profile.as_deref() == Some(name) {
+            self.config.active_profile = None;
+        }
+
+        self.save()?;
+        say!("Deleted profile '{name}'");
+        Ok(())
+    }
+
+    /// Get a profile by name
+    pub fn get_profile(&self, name: &str) -> Result<&Profile, ConfigError> {
+        self.config
+            .profiles
+            .get(name)
+            .ok_or_else(|| ConfigError::ProfileNotFound(name.to_string()))
+    }
+
+    /// Mark a profile as the active one
+    pub fn set_active_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        self.get_profile(name)?;
+        self.config.active_profile = Some(name.to_string());
+        self.save()
+    }
+
+    /// Print all profiles, marking the active one
+    pub fn list_profiles(&self) {
+        if self.config.profiles.is_empty() {
+            say!("No profiles yet. Create one with `profile create <name>`.");
+            return;
+        }
+
+        for (name, profile) in &self.config.profiles {
+            let marker = if self.config.active_profile.as_deref() == Some(name.as_str()) {
+                "*"
+            } else {
+                " "
+            };
+            say!("{marker} {name} ({} mods)", profile.mods.len());
+        }
+    }
+
+    /// Add a webhook notified after update runs, and with `available` also of the updates
+    /// found by `check` and `watch`
+    pub fn add_webhook(
+        &mut self, url: String, kind: WebhookKind, available: bool,
+    ) -> Result<(), ConfigError> {
+        if !is_http_url(&url) {
+            return Err(ConfigError::InvalidWebhook(url));
+        }
+
+        self.config.add_webhook(Webhook {
+            url: url.clone(),
+            kind,
+            available,
+        });
+        self.save()?;
+        say!("Added {kind:?} webhook: {url}");
+        Ok(())
+    }
+
+    /// Remove a webhook
+    pub fn remove_webhook(&mut self, url: &str) -> Result<(), ConfigError> {
+        if self.config.remove_webhook(url) {
+            self.save()?;
+            say!("Removed webhook: {url}");
+        } else {
+            say!("Webhook was not configured: {url}");
+        }
+        Ok(())
+    }
+
+    /// Create the main mods directory if it doesn't exist yet
+    ///
+    /// # Arguments
+    ///
+    /// * `always` - Also create it automatically whenever it goes missing in the future.
+    pub fn create_mods_dir(&mut self, always: bool) -> Result<(), ConfigError> {
+        let mods_dir = self.main_mods_dir();
+        if mods_dir.is_dir() {
+            say!("Mods directory already exists: {}", mods_dir.display());
+        } else {
+            fs::create_dir_all(&mods_dir)?;
+            say!("Created mods directory: {}", mods_dir.display());
+        }
+
+        if always && !self.config.create_mods_dir {
+            self.config.create_mods_dir = true;
+            self.save()?;
+            say!("The mods directory will be created automatically when missing");
+        }
+        Ok(())
+    }
+
+    /// Set the template new mod files are named after, or go back to ModDB's filenames
+    pub fn set_filename_template(&mut self, template: Option<String>) -> Result<(), ConfigError> {
+        if let Some(template) = &template {
+            let valid = template.contains("{modid}")
+                && template.ends_with(".zip")
+                && !template.contains(['/', '\\']);
+            if !valid {
+                return Err(ConfigError::InvalidFilenameTemplate(template.clone()));
+            }
+        }
+
+        match &template {
+            Some(template) => say!("Filename template set to: {template}"),
+            None => say!("New mods keep their ModDB filenames"),
+        }
+        self.config.filename_template = template;
+        self.save()?;
+        Ok(())
+    }
+
+    /// Set the main mods directory, or go back to `VintagestoryData/Mods`
+    pub fn set_mods_path(&mut self, path: Option<PathBuf>) -> Result<(), ConfigError> {
+        match &path {
+            Some(path) if !path.is_dir() => {
+                return Err(ConfigError::InvalidGamePath(format!(
+                    "Mods directory does not exist: {}",
+                    path.display()
+                )));
+            }
+            Some(path) => say!("Mods directory set to: {}", path.display()),
+            None => say!("Using the default mods directory"),
+        }
+        self.config.mods_dir = path;
+        self.save()
+    }
+
+    /// Set the release channel, for all mods or for a single one
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The channel to use.
+    /// * `modid` - The mod to set the channel of, `None` for the default channel.
+    pub fn set_channel(
+        &mut self, channel: Channel, modid: Option<&str>,
+    ) -> Result<(), ConfigError> {
+        match modid {
+            Some(modid) => {
+                let modid = modid.to_lowercase();
+                // Kept even if it matches the default, so changing the default leaves it be
+                self.config.mod_channels.insert(modid.clone(), channel);
+                say!("Release channel of {modid} set to: {channel:?}");
+            }
+            None => {
+                self.config.channel = channel;
+                say!("Release channel set to: {channel:?}");
+            }
+        }
+        self.save()
+    }
+
+    /// Set how downloaded mods are placed into the mods directory
+    pub fn set_install_mode(&mut self, mode: InstallMode) -> Result<(), ConfigError> {
+        self.config.install_mode = mode;
+        self.save()?;
+        say!("Install mode set to: {mode:?}");
+        Ok(())
+    }
+
+    /// Changes the limits of the backup retention policy. Limits that are `None` are kept
+    /// and limits of 0 are removed.
+    pub fn set_backup_retention(
+        &mut self, max_age_days: Option<u64>, max_size_mb: Option<u64>,
+        max_count_per_mod: Option<usize>,
+    ) -> Result<(), ConfigError> {
+        let retention = &mut self.config.backup_retention;
+        if let Some(days) = max_age_days {
+            retention.max_age_days = (days > 0).then_some(days);
+        }
+        if let Some(size) = max_size_mb {
+            retention.max_size_mb = (size > 0).then_some(size);
+        }
+        if let Some(count) = max_count_per_mod {
+            retention.max_count_per_mod = (count > 0).then_some(count);
+        }
+        self.save()?;
+
+        let retention = &self.config.backup_retention;
+        let limit = |value: Option<String>| value.unwrap_or_else(|| "no limit".to_string());
+        say!("Backup retention:");
+        say!(
+            "  Max age: {}",
+            limit(retention.max_age_days.map(|d| format!("{d} days")))
+        );
+        say!(
+            "  Max size: {}",
+            limit(retention.max_size_mb.map(|mb| format!("{mb} MB")))
+        );
+        say!(
+            "  Max backups per mod: {}",
+            limit(retention.max_count_per_mod.map(|count| count.to_string()))
+        );
+        Ok(())
+    }
+
+    /// Overrides the main mods directory for this run, e.g. with `--mods-dir` or the `Mods`
+    /// directory of an `--instance`. The config file is left unchanged.
+    pub fn with_mods_dir(mut self, mods_dir: Option<PathBuf>) -> Self {
+        self.mods_dir = mods_dir;
+        self
+    }
+
+    /// Get the main mods directory: the override set with [`Self::with_mods_dir`], the
+    /// `mods_dir` setting, or else the default one. Does not check if the directory exists.
+    pub fn main_mods_dir(&self) -> PathBuf {
+        self.mods_dir
+            .clone()
+            .or_else(|| self.config.mods_dir.clone())
+            .unwrap_or_else(default_vintage_mods_dir)
+    }
+
+    /// Get the mods directories to operate on: the main one followed by the additional ones
+    pub fn get_mod_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = existing_mods_dir(self.main_mods_dir())
+            .into_iter()
+            .collect();
+        for path in &self.config.additional_mod_paths {
+            if !dirs.contains(path) {
+                dirs.push(path.clone());
+            }
+        }
+        dirs
+    }
+
     /// Validate that a path contains a Vintage Story installation
     fn validate_game_path(&self, path: &Path) -> bool {
         // Look for key Vintage Story files/directories
@@ -211,11 +934,10 @@ impl ConfigManager {
     }
 
     /// Update version mappings from API
-    pub async fn update_version_mappings(&mut self, verbose: bool) -> Result<(), ConfigError> {
-        self.logger
-            .log_default("Fetching version mappings from API...");
+    pub async fn update_version_mappings(&mut self) -> Result<(), ConfigError> {
+        info!("Fetching version mappings from API...");
 
-        let api = VintageApiHandler::new(verbose);
+        let api = VintageApiHandler::new();
 
         // Fetch version mappings using the new method
         let version_mappings = api.fetch_game_versions().await?;
@@ -223,7 +945,7 @@ impl ConfigManager {
         self.config.update_version_mapping(version_mappings);
         self.save()?;
 
-        println!(
+        say!(
             "Version mappings updated successfully ({} versions)",
             self.config.get_all_mappings().len()
         );
@@ -231,9 +953,9 @@ impl ConfigManager {
         // Check if our detected version now has a mapping
         if let Some(version) = self.config.get_detected_game_version() {
             if self.config.is_detected_version_mapped() {
-                println!("Mapping found for detected version: {version}");
+                say!("Mapping found for detected version: {version}");
             } else {
-                println!("No mapping found for detected version: {version}");
+                say!("No mapping found for detected version: {version}");
             }
         }
 
@@ -270,8 +992,7 @@ impl ConfigManager {
 
         if version_mappings.is_empty() {
             // Fallback to some default mappings if parsing fails
-            self.logger
-                .log_default("Warning: Could not parse version mappings from API, using defaults");
+            info!("Warning: Could not parse version mappings from API, using defaults");
             version_mappings = vec![
                 VersionMapping::new(-281539401465857, "1.15.3-rc.1".to_string()),
                 VersionMapping::new(-281539401285631, "1.15.0".to_string()),
@@ -284,34 +1005,74 @@ impl ConfigManager {
 
     /// Show current configuration with detected version
     pub fn show(&self) {
-        println!("Configuration file: {}", self.config_path.display());
-        println!();
+        say!("Configuration file: {}", self.config_path.display());
+        say!();
 
         if let Some(game_path) = self.config.get_game_path() {
-            println!("Game path: {}", game_path.display());
+            say!("Game path: {}", game_path.display());
 
             if let Some(version) = self.config.get_detected_game_version() {
-                println!("Detected version: {version}");
+                say!("Detected version: {version}");
 
                 if let Some(tag_id) = self.config.get_detected_version_tag_id() {
-                    println!("Version tag ID: {tag_id}");
+                    say!("Version tag ID: {tag_id}");
                 } else {
-                    println!("Version tag ID: No mapping found");
+                    say!("Version tag ID: No mapping found");
                 }
             } else {
-                println!("Detected version: Could not detect");
+                say!("Detected version: Could not detect");
             }
         } else {
-            println!("Game path: Not set");
+            say!("Game path: Not set");
         }
 
-        println!(
+        if let Some(version) = &self.config.preferred_game_version {
+            say!("Preferred game version: {version}");
+        }
+
+        if let Some(mods_dir) = &self.config.mods_dir {
+            say!("Mods directory: {}", mods_dir.display());
+        }
+        for path in &self.config.additional_mod_paths {
+            say!("Additional mods directory: {}", path.display());
+        }
+        for (name, instance) in &self.config.instances {
+            say!("Instance '{name}': {}", instance.data_path.display());
+        }
+        for (name, remote) in &self.config.remotes {
+            say!("Remote '{name}': {}:{}", remote.destination(), remote.path);
+        }
+        say!("Install mode: {:?}", self.config.install_mode);
+        say!("Release channel: {:?}", self.config.channel);
+        for (modid, channel) in &self.config.mod_channels {
+            say!("  {modid}: {channel:?}");
+        }
+        if self.config.create_mods_dir {
+            say!("Create missing mods directory: yes");
+        }
+        if let Some(template) = &self.config.filename_template {
+            say!("Filename template: {template}");
+        }
+        if !self.config.pinned_mods.is_empty() {
+            say!("Pinned mods: {}", self.config.pinned_mods.join(", "));
+        }
+        self.print_ignored();
+        for webhook in &self.config.webhooks {
+            let available = if webhook.available {
+                ", with available updates"
+            } else {
+                ""
+            };
+            say!("Webhook ({:?}{available}): {}", webhook.kind, webhook.url);
+        }
+
+        say!(
             "Version mappings: {} entries",
             self.config.get_all_mappings().len()
         );
 
         if !self.config.get_all_mappings().is_empty() {
-            println!("\nAvailable game versions:");
+            say!("\nAvailable game versions:");
             let versions = self.config.get_all_versions();
             for version in versions.iter().take(10) {
                 // Show first 10
@@ -322,10 +1083,10 @@ impl ConfigManager {
                 } else {
                     ""
                 };
-                println!("  - {version}{indicator}");
+                say!("  - {version}{indicator}");
             }
             if versions.len() > 10 {
-                println!("  ... and {} more", versions.len() - 10);
+                say!("  ... and {} more", versions.len() - 10);
             }
         }
     }
@@ -335,11 +1096,11 @@ impl ConfigManager {
         let versions = self.config.get_all_versions();
 
         if versions.is_empty() {
-            println!("No version mappings available. Run 'config update-versions' to fetch them.");
+            say!("No version mappings available. Run 'config update-versions' to fetch them.");
             return;
         }
 
-        println!("Available game versions ({} total):", versions.len());
+        say!("Available game versions ({} total):", versions.len());
         for version in versions {
             if let Some(tag_id) = self.config.get_tag_from_version(version) {
                 let indicator = if Some(version.as_str())
@@ -349,7 +1110,7 @@ impl ConfigManager {
                 } else {
                     ""
                 };
-                println!("  {version} (tag: {tag_id}){indicator}");
+                say!("  {version} (tag: {tag_id}){indicator}");
             }
         }
     }
@@ -358,17 +1119,17 @@ impl ConfigManager {
     pub fn refresh_detected_version(&mut self) -> Result<(), ConfigError> {
         match self.config.detect_game_version() {
             Ok(Some(version)) => {
-                println!("Detected game version: {version}");
+                say!("Detected game version: {version}");
                 if self.config.is_detected_version_mapped() {
-                    println!("Version mapping available");
+                    say!("Version mapping available");
                 } else {
-                    println!("No version mapping available for this version");
+                    say!("No version mapping available for this version");
                 }
                 self.save()?;
                 Ok(())
             }
             Ok(None) => {
-                println!("Could not detect game version from assets directory");
+                say!("Could not detect game version from assets directory");
                 Ok(())
             }
             Err(e) => Err(ConfigError::NotFound(format!(
@@ -377,62 +1138,324 @@ impl ConfigManager {
         }
     }
 
+    /// Re-detects the game version and checks it against the one seen on the last run.
+    ///
+    /// The detected version is saved, so a change is only reported once.
+    ///
+    /// # Returns
+    ///
+    /// The previous and the new game version if the game was updated since the last run.
+    pub fn check_game_version_change(&mut self) -> Result<Option<(String, String)>, ConfigError> {
+        if self.config.get_game_path().is_none() {
+            return Ok(None);
+        }
+        let previous = (
+            self.config.detected_game_version.clone(),
+            self.config.last_seen_game_version.clone(),
+        );
+        if let Err(e) = self.config.detect_game_version() {
+            info!("Could not detect game version: {e}");
+            return Ok(None);
+        }
+
+        let change = self.config.note_game_version();
+        if previous.0 != self.config.detected_game_version
+            || previous.1 != self.config.last_seen_game_version
+        {
+            self.save()?;
+        }
+        Ok(change)
+    }
+
     /// Reset configuration
     pub fn reset(&mut self, confirmed: bool) -> Result<(), ConfigError> {
         if !confirmed && !Terminal::confirm("This will reset all configuration. Continue?") {
-            println!("Reset cancelled.");
+            say!("Reset cancelled.");
             return Ok(());
         }
 
         self.config = Config::new();
         self.save()?;
-        println!("Configuration reset to defaults.");
+        say!("Configuration reset to defaults.");
         Ok(())
     }
 
     /// Validate current configuration including version detection
-    pub fn validate(&self) -> Result<(), ConfigError> {
-        println!("Validating configuration...");
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::new(self.config_path.clone());
 
-        // Check game path
-        if let Some(game_path) = self.config.get_game_path() {
-            if !game_path.exists() {
-                println!("Game path does not exist: {}", game_path.display());
-            } else if !self.validate_game_path(game_path) {
-                println!("Game path is not a valid Vintage Story installation");
-            } else {
-                println!("Game path is valid");
+        if self.config_path.exists() {
+            report.push(
+                "config_file",
+                CheckStatus::Ok,
+                "Config file exists".to_string(),
+                None,
+            );
+        } else {
+            report.push(
+                "config_file",
+                CheckStatus::Warning,
+                "Config file has not been created yet".to_string(),
+                Some("run `config init`"),
+            );
+        }
 
-                // Check version detection
-                if let Some(version) = self.config.get_detected_game_version() {
-                    println!("Game version detected: {version}");
+        match self.config.get_game_path() {
+            Some(game_path) if !game_path.exists() => report.push(
+                "game_path",
+                CheckStatus::Error,
+                format!("Game path does not exist: {}", game_path.display()),
+                Some("run `config validate --fix` or `config set-path <path>`"),
+            ),
+            Some(game_path) if !self.validate_game_path(game_path) => report.push(
+                "game_path",
+                CheckStatus::Error,
+                format!(
+                    "Game path is not a valid Vintage Story installation: {}",
+                    game_path.display()
+                ),
+                Some("run `config validate --fix` or `config set-path <path>`"),
+            ),
+            Some(game_path) => report.push(
+                "game_path",
+                CheckStatus::Ok,
+                format!("Game path is valid: {}", game_path.display()),
+                None,
+            ),
+            None => report.push(
+                "game_path",
+                CheckStatus::Warning,
+                "Game path not set".to_string(),
+                Some("run `config validate --fix` or `config set-path <path>`"),
+            ),
+        }
 
-                    if self.config.is_detected_version_mapped() {
-                        println!("Version mapping available");
-                    } else {
-                        println!("No version mapping available for detected version");
-                    }
-                } else {
-                    println!("Could not detect game version from assets directory");
-                }
-            }
-        } else {
-            println!("Game path not set");
+        match self.config.get_detected_game_version() {
+            Some(version) => report.push(
+                "game_version",
+                CheckStatus::Ok,
+                format!("Game version detected: {version}"),
+                None,
+            ),
+            None => report.push(
+                "game_version",
+                CheckStatus::Warning,
+                "Could not detect game version from assets directory".to_string(),
+                Some("run `config validate --fix` after setting a valid game path"),
+            ),
         }
 
-        // Check version mappings
-        if self.config.has_version_mapping() {
-            println!(
-                "Version mappings available ({} entries)",
-                self.config.get_all_mappings().len()
+        if !self.config.has_version_mapping() {
+            report.push(
+                "version_mappings",
+                CheckStatus::Warning,
+                "No version mappings available".to_string(),
+                Some("run `config update-versions`"),
+            );
+        } else if self.config.get_detected_game_version().is_some()
+            && !self.config.is_detected_version_mapped()
+        {
+            report.push(
+                "version_mappings",
+                CheckStatus::Warning,
+                format!(
+                    "No version mapping for detected version ({} entries available)",
+                    self.config.get_all_mappings().len()
+                ),
+                Some("run `config update-versions`"),
             );
         } else {
-            println!("No version mappings available");
+            report.push(
+                "version_mappings",
+                CheckStatus::Ok,
+                format!(
+                    "Version mappings available ({} entries)",
+                    self.config.get_all_mappings().len()
+                ),
+                None,
+            );
+        }
+
+        match existing_mods_dir(self.main_mods_dir()) {
+            Ok(mods_dir) => report.push(
+                "mods_dir",
+                CheckStatus::Ok,
+                format!("Mods directory found: {}", mods_dir.display()),
+                None,
+            ),
+            Err(e) => report.push(
+                "mods_dir",
+                CheckStatus::Error,
+                format!("Mods directory unavailable: {e}"),
+                Some("run `config validate --fix` or `config create-mods-dir`, or start the game once"),
+            ),
         }
 
+        report
+    }
+
+    /// Re-run game path and version detection, saving the result.
+    ///
+    /// # Returns
+    ///
+    /// A list of human-readable descriptions of the changes that were made.
+    pub fn fix(&mut self) -> Result<Vec<String>, ConfigError> {
+        let mut fixes = Vec::new();
+
+        let game_path_valid = self
+            .config
+            .get_game_path()
+            .is_some_and(|path| path.exists() && self.validate_game_path(path));
+
+        if !game_path_valid && let Some(game_path) = self.try_detect_game_path() {
+            fixes.push(format!("Detected game path: {}", game_path.display()));
+            self.config.set_game_path(game_path);
+        }
+
+        let previous_version = self.config.get_detected_game_version().cloned();
+        if let Ok(Some(version)) = self.config.detect_game_version()
+            && previous_version.as_ref() != Some(&version)
+        {
+            fixes.push(format!("Detected game version: {version}"));
+        }
+
+        let mods_dir = self.main_mods_dir();
+        if !mods_dir.exists() {
+            fs::create_dir_all(&mods_dir)?;
+            fixes.push(format!("Created mods directory: {}", mods_dir.display()));
+        }
+
+        if !fixes.is_empty() {
+            self.save()?;
+        }
+
+        Ok(fixes)
+    }
+
+    /// Lists every setting by its dotted key (e.g. `network.timeout_secs`).
+    ///
+    /// # Returns
+    ///
+    /// The keys and their values, sorted by key; unset settings are `null`.
+    pub fn settings(&self) -> Result<Vec<(String, serde_json::Value)>, ConfigError> {
+        let mut settings = Vec::new();
+        flatten_settings("", serde_json::to_value(&self.config)?, &mut settings);
+        Ok(settings)
+    }
+
+    /// Prints a setting, the settings of a table such as `network`, or all settings.
+    ///
+    /// A single setting is printed as its bare value for use in scripts, several settings
+    /// as `key = value` lines.
+    pub fn print_settings(&self, key: Option<&str>) -> Result<(), ConfigError> {
+        let settings: Vec<(String, serde_json::Value)> = self
+            .settings()?
+            .into_iter()
+            .filter(|(name, _)| match key {
+                Some(key) => name == key || name.starts_with(&format!("{key}.")),
+                None => true,
+            })
+            .collect();
+
+        match settings.as_slice() {
+            [] => return Err(ConfigError::UnknownKey(key.unwrap_or_default().to_string())),
+            _ if json_output() => {
+                let settings: serde_json::Map<String, serde_json::Value> =
+                    settings.into_iter().collect();
+                println!("{}", serde_json::to_string_pretty(&settings)?);
+            }
+            [(name, value)] if Some(name.as_str()) == key => println!("{}", display_value(value)),
+            settings => {
+                for (name, value) in settings {
+                    println!("{name} = {}", display_value(value));
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Reads a single setting.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The dotted key, as listed by [`ConfigManager::settings`].
+    pub fn get_value(&self, key: &str) -> Result<serde_json::Value, ConfigError> {
+        self.settings()?
+            .into_iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+            .ok_or_else(|| ConfigError::UnknownKey(key.to_string()))
+    }
+
+    /// Changes a single setting and saves the config.
+    ///
+    /// The value is read as JSON (numbers, `true`, lists like `["a", "b"]`) and otherwise
+    /// taken as a string, then checked against the setting's type. Settings with a
+    /// dedicated command go through the same validation as that command.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The dotted key, as listed by [`ConfigManager::settings`].
+    /// * `value` - The new value, or `None` to go back to the default.
+    pub fn set_value(&mut self, key: &str, value: Option<&str>) -> Result<(), ConfigError> {
+        self.get_value(key)?;
+        if READ_ONLY_KEYS.contains(&key) {
+            return Err(ConfigError::InvalidValue {
+                key: key.to_string(),
+                reason: "this setting is managed automatically".to_string(),
+            });
+        }
+
+        match key {
+            "game_path" => {
+                let Some(path) = value else {
+                    self.config.game_path = None;
+                    return self.save();
+                };
+                return self.set_game_path(PathBuf::from(path));
+            }
+            "mods_dir" => return self.set_mods_path(value.map(PathBuf::from)),
+            "filename_template" => return self.set_filename_template(value.map(str::to_string)),
+            "preferred_game_version" => return self.set_game_version(value.map(str::to_string)),
+            _ => {}
+        }
+
+        let candidates = match value {
+            None => vec![lookup(&serde_json::to_value(Config::new())?, key)],
+            Some(value) => {
+                let mut candidates: Vec<serde_json::Value> =
+                    serde_json::from_str(value).into_iter().collect();
+                candidates.push(serde_json::Value::String(value.to_string()));
+                candidates
+            }
+        };
+
+        let mut error = None;
+        for candidate in candidates {
+            let mut settings = serde_json::to_value(&self.config)?;
+            replace(&mut settings, key, candidate);
+            match serde_json::from_value::<Config>(settings) {
+                Ok(config) => {
+                    validate_setting(key, &config)?;
+                    self.config = config;
+                    self.save()?;
+                    say!("{key} = {}", display_value(&self.get_value(key)?));
+                    return Ok(());
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+        Err(ConfigError::InvalidValue {
+            key: key.to_string(),
+            reason: error.map(|e| e.to_string()).unwrap_or_default(),
+        })
+    }
+
+    /// Path of the configuration file
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
     /// Get current config (read-only access)
     pub fn config(&self) -> &Config {
         &self.config
@@ -448,9 +1471,76 @@ impl ConfigManager {
         self.config.get_detected_game_version()
     }
 
-    /// Get the tag ID for the detected game version
-    pub fn get_detected_version_tag_id(&self) -> Option<i64> {
-        self.config.get_detected_version_tag_id()
+    /// Get the game version mods are matched against, preferring the one set with
+    /// `config set-game-version` over the detected one
+    pub fn get_game_version(&self) -> Option<&String> {
+        self.config.get_game_version()
+    }
+
+    /// Get the tag ID for the game version from [`ConfigManager::get_game_version`]
+    pub fn get_game_version_tag_id(&self) -> Option<i64> {
+        self.config.get_game_version_tag_id()
+    }
+
+    /// Set the game version mods are matched against instead of the detected one
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The game version, or `None` to go back to the detected version. It
+    ///   must be one of the known version mappings.
+    pub fn set_game_version(&mut self, version: Option<String>) -> Result<(), ConfigError> {
+        let version = version
+            .map(|version| self.check_game_version(&version))
+            .transpose()?;
+
+        match (&version, self.config.get_detected_game_version()) {
+            (Some(version), Some(detected)) if version != detected => {
+                say!("Game version set to {version} (detected: {detected})")
+            }
+            (Some(version), _) => say!("Game version set to {version}"),
+            (None, Some(detected)) => say!("Using the detected game version {detected}"),
+            (None, None) => say!("Using the detected game version"),
+        }
+        self.config.preferred_game_version = version;
+        self.save()
+    }
+
+    /// Checks that a game version given by the user is one of the known version mappings.
+    ///
+    /// # Returns
+    ///
+    /// The version without a leading `v`, or an error suggesting similar known versions.
+    pub fn check_game_version(&self, version: &str) -> Result<String, ConfigError> {
+        let version = version.trim_start_matches('v');
+        if self.config.get_tag_from_version(version).is_none() {
+            return Err(ConfigError::UnknownGameVersion {
+                version: version.to_string(),
+                hint: self.game_version_hint(version),
+            });
+        }
+        Ok(version.to_string())
+    }
+
+    /// Suggests known game versions close to an unknown one.
+    fn game_version_hint(&self, version: &str) -> String {
+        let mappings = self.config.get_all_mappings();
+        if mappings.is_empty() {
+            return "no version mappings are known yet, run `config update-versions`".to_string();
+        }
+
+        let minor = version.split('.').take(2).collect::<Vec<_>>().join(".");
+        let mut similar: Vec<&str> = mappings
+            .iter()
+            .map(|mapping| mapping.version.as_str())
+            .filter(|known| known.starts_with(&format!("{minor}.")))
+            .collect();
+        similar.sort_by(|a, b| compare_versions(b, a));
+
+        if similar.is_empty() {
+            "see `config list-versions` for the known versions".to_string()
+        } else {
+            format!("did you mean {}?", similar.join(", "))
+        }
     }
 
     /// Check if auto-detected version filtering should be used
@@ -459,3 +1549,410 @@ impl ConfigManager {
             && self.config.is_detected_version_mapped()
     }
 }
+
+/// Collects the settings below `prefix`. Tables of named settings (like `network`) are
+/// descended into, while maps keyed by user data (like `profiles`) are single settings.
+fn flatten_settings(
+    prefix: &str, value: serde_json::Value, settings: &mut Vec<(String, serde_json::Value)>,
+) {
+    match value {
+        serde_json::Value::Object(table) if is_settings_table(prefix) => {
+            for (key, value) in table {
+                let key = match prefix {
+                    "" => key,
+                    prefix => format!("{prefix}.{key}"),
+                };
+                flatten_settings(&key, value, settings);
+            }
+        }
+        value => settings.push((prefix.to_string(), value)),
+    }
+}
+
+fn is_settings_table(key: &str) -> bool {
+    matches!(key, "" | "network" | "backup_retention" | "cache_retention")
+}
+
+/// The value at a dotted key, `null` if there is none.
+fn lookup(settings: &serde_json::Value, key: &str) -> serde_json::Value {
+    settings
+        .pointer(&format!("/{}", key.replace('.', "/")))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Replaces the value at a dotted key that is known to exist.
+fn replace(settings: &mut serde_json::Value, key: &str, value: serde_json::Value) {
+    if let Some(slot) = settings.pointer_mut(&format!("/{}", key.replace('.', "/"))) {
+        *slot = value;
+    }
+}
+
+/// Checks the meaning of a setting whose type is already known to be right.
+fn validate_setting(key: &str, config: &Config) -> Result<(), ConfigError> {
+    let invalid = |reason: &str| ConfigError::InvalidValue {
+        key: key.to_string(),
+        reason: reason.to_string(),
+    };
+    match key {
+        "network.max_attempts" if config.network.max_attempts == 0 => {
+            Err(invalid("at least one attempt is needed"))
+        }
+        "network.timeout_secs" if config.network.timeout_secs == 0 => {
+            Err(invalid("the timeout must be at least one second"))
+        }
+        "network.connect_timeout_secs" if config.network.connect_timeout_secs == 0 => {
+            Err(invalid("the timeout must be at least one second"))
+        }
+        "network.api_url" | "network.mirrors" => {
+            let urls = config.network.api_url.iter().chain(&config.network.mirrors);
+            match urls.into_iter().find(|url| !is_http_url(url)) {
+                Some(url) => Err(invalid(&format!("{url} is not an http(s) URL"))),
+                None => Ok(()),
+            }
+        }
+        "webhooks" => match config
+            .webhooks
+            .iter()
+            .find(|webhook| !is_http_url(&webhook.url))
+        {
+            Some(webhook) => Err(ConfigError::InvalidWebhook(webhook.url.clone())),
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Formats a setting for display: strings without quotes, unset settings as empty.
+fn display_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// An upgrade of a config file's raw TOML from one schema version to the next.
+type Migration = fn(&mut toml::Table);
+
+/// The upgrades from each schema version to the next, indexed by the version they start from.
+const MIGRATIONS: [Migration; CONFIG_SCHEMA_VERSION as usize] = [lowercase_mod_ids];
+
+/// The schema version of a raw config file; files without one are version 0.
+fn schema_version(table: &toml::Table) -> u32 {
+    table
+        .get("schema_version")
+        .and_then(toml::Value::as_integer)
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or(0)
+}
+
+/// Schema 0 to 1: keys pinned mods and per-mod channels by lowercase mod ID. `pin` and
+/// `config set-channel` always stored them lowercase, but hand-edited files may not.
+fn lowercase_mod_ids(table: &mut toml::Table) {
+    if let Some(toml::Value::Array(pinned)) = table.get_mut("pinned_mods") {
+        for modid in pinned.iter_mut() {
+            if let toml::Value::String(modid) = modid {
+                *modid = modid.to_lowercase();
+            }
+        }
+    }
+
+    if let Some(toml::Value::Table(channels)) = table.get_mut("mod_channels") {
+        *channels = std::mem::take(channels)
+            .into_iter()
+            .map(|(modid, channel)| (modid.to_lowercase(), channel))
+            .collect();
+    }
+}
+
+/// Builds a `Config` from whatever parts of a broken config file are still readable.
+///
+/// The file is split into its top-level and `[table]` sections so that a syntax error only
+/// discards the section it occurs in. Each remaining key is then checked on its own, so a
+/// value with the wrong type only discards that key.
+fn salvage_config(contents: &str) -> Config {
+    let mut sections: Vec<String> = vec![String::new()];
+    for line in contents.lines() {
+        if line.trim_start().starts_with('[') {
+            sections.push(String::new());
+        }
+        let section = sections.last_mut().expect("sections is never empty");
+        section.push_str(line);
+        section.push('\n');
+    }
+
+    let readable: String = sections
+        .into_iter()
+        .filter(|section| toml::from_str::<toml::Table>(section).is_ok())
+        .collect();
+
+    let table = toml::from_str::<toml::Table>(&readable).unwrap_or_default();
+    let salvaged: toml::Table = table
+        .into_iter()
+        .filter(|(key, value)| {
+            let mut single = toml::Table::new();
+            single.insert(key.clone(), value.clone());
+            toml::Value::Table(single).try_into::<Config>().is_ok()
+        })
+        .collect();
+
+    toml::Value::Table(salvaged)
+        .try_into::<Config>()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_version_change_is_reported_once() {
+        let mut config = Config::new();
+        config.detected_game_version = Some("1.20.3".to_string());
+        assert_eq!(config.note_game_version(), None);
+        assert_eq!(config.note_game_version(), None);
+
+        config.detected_game_version = Some("1.21.0".to_string());
+        assert_eq!(
+            config.note_game_version(),
+            Some(("1.20.3".to_string(), "1.21.0".to_string()))
+        );
+        assert_eq!(config.note_game_version(), None);
+    }
+
+    #[test]
+    fn preferred_game_version_overrides_detected_one() {
+        let mut config = Config::new()
+            .add_version_mapping(1, "1.20.3".to_string())
+            .add_version_mapping(2, "1.19.8".to_string());
+        config.detected_game_version = Some("1.20.3".to_string());
+        assert_eq!(config.get_game_version_tag_id(), Some(1));
+
+        config.preferred_game_version = Some("1.19.8".to_string());
+        assert_eq!(config.get_game_version().unwrap(), "1.19.8");
+        assert_eq!(config.get_game_version_tag_id(), Some(2));
+    }
+
+    #[test]
+    fn steam_library_paths_are_read_from_libraryfolders() {
+        let vdf = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"apps"
+		{
+			"228980"		"184938"
+		}
+	}
+	"1"
+	{
+		"path"		"/mnt/games/SteamLibrary"
+	}
+}
+"#;
+        assert_eq!(
+            steam_library_paths(vdf),
+            vec![
+                PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                PathBuf::from("/mnt/games/SteamLibrary"),
+            ]
+        );
+    }
+
+    #[test]
+    fn salvage_keeps_valid_keys_with_wrong_typed_neighbours() {
+        let contents = r#"
+game_path = "/opt/vintagestory"
+detected_game_version = 42
+
+[[version_mapping]]
+tag_id = 1
+version = "1.20.3"
+"#;
+        let config = salvage_config(contents);
+
+        assert_eq!(
+            config.get_game_path(),
+            Some(&PathBuf::from("/opt/vintagestory"))
+        );
+        assert_eq!(config.get_detected_game_version(), None);
+        assert_eq!(config.get_tag_from_version("1.20.3"), Some(1));
+    }
+
+    #[test]
+    fn salvage_drops_only_the_broken_section() {
+        let contents = r#"
+game_path = "/opt/vintagestory"
+
+[[version_mapping]]
+tag_id = = 1
+version = "1.20.3"
+
+[[version_mapping]]
+tag_id = 2
+version = "1.20.4"
+"#;
+        let config = salvage_config(contents);
+
+        assert!(config.get_game_path().is_some());
+        assert_eq!(config.get_all_mappings().len(), 1);
+        assert_eq!(config.get_tag_from_version("1.20.4"), Some(2));
+    }
+
+    #[test]
+    fn unversioned_configs_are_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let original = r#"
+            pinned_mods = ["CarryOn"]
+
+            [mod_channels]
+            PrimitiveSurvival = "rc"
+
+            [[webhooks]]
+            url = "https://discord.com/api/webhooks/1/token"
+        "#;
+        fs::write(&path, original).unwrap();
+
+        let config = ConfigManager::load_config_from_file(&path).unwrap();
+        assert_eq!(config.schema_version, CONFIG_SCHEMA_VERSION);
+        assert_eq!(config.pinned_mods, ["carryon"]);
+        assert_eq!(
+            config.mod_channels.get("primitivesurvival"),
+            Some(&Channel::Rc)
+        );
+        assert_eq!(
+            config.webhooks[0].url,
+            "https://discord.com/api/webhooks/1/token"
+        );
+
+        assert_eq!(
+            fs::read_to_string(path.with_extension("toml.v0.bak")).unwrap(),
+            original
+        );
+        let rewritten = ConfigManager::load_config_from_file(&path).unwrap();
+        assert_eq!(rewritten.pinned_mods, config.pinned_mods);
+        assert_eq!(rewritten.webhooks, config.webhooks);
+        assert!(!dir.path().join("config.toml.v1.bak").exists());
+    }
+
+    #[test]
+    fn migrated_config_is_used_when_it_cannot_be_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let original = r#"pinned_mods = ["CarryOn"]"#;
+        fs::write(&path, original).unwrap();
+        // The backup can't be written where a directory is in the way
+        fs::create_dir(path.with_extension("toml.v0.bak")).unwrap();
+
+        let config = ConfigManager::load_config_from_file(&path).unwrap();
+        assert_eq!(config.pinned_mods, ["carryon"]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn mods_dir_override_takes_precedence_over_the_setting() {
+        let dir = tempfile::tempdir().unwrap();
+        let configured = dir.path().join("Configured");
+        let instance = dir.path().join("Instance").join("Mods");
+        std::fs::create_dir(&configured).unwrap();
+        std::fs::create_dir_all(&instance).unwrap();
+        let mut manager = ConfigManager {
+            config_path: dir.path().join("config.toml"),
+            config: Config::new(),
+            mods_dir: None,
+        };
+        manager.config.mods_dir = Some(configured.clone());
+        assert_eq!(manager.get_mod_dirs(), vec![configured]);
+
+        let manager = manager.with_mods_dir(Some(instance.clone()));
+        assert_eq!(manager.main_mods_dir(), instance);
+        assert_eq!(manager.get_mod_dirs(), vec![instance]);
+    }
+
+    #[test]
+    fn mod_channels_are_kept_when_they_match_the_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = ConfigManager {
+            config_path: dir.path().join("config.toml"),
+            config: Config::new(),
+            mods_dir: None,
+        };
+
+        manager
+            .set_channel(Channel::Stable, Some("CarryOn"))
+            .unwrap();
+        manager.set_channel(Channel::Dev, None).unwrap();
+
+        assert_eq!(
+            manager.config().mod_channels.get("carryon"),
+            Some(&Channel::Stable)
+        );
+    }
+
+    #[test]
+    fn settings_are_set_by_key_with_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = ConfigManager {
+            config_path: dir.path().join("config.toml"),
+            config: Config::new(),
+            mods_dir: None,
+        };
+
+        manager
+            .set_value("network.timeout_secs", Some("60"))
+            .unwrap();
+        manager.set_value("channel", Some("rc")).unwrap();
+        manager
+            .set_value("network.proxy", Some("socks5://127.0.0.1:1080"))
+            .unwrap();
+        manager
+            .set_value("pinned_mods", Some(r#"["carryon"]"#))
+            .unwrap();
+        assert_eq!(manager.config.network.timeout_secs, 60);
+        assert_eq!(manager.config.channel, Channel::Rc);
+        assert_eq!(
+            manager.get_value("network.proxy").unwrap(),
+            "socks5://127.0.0.1:1080"
+        );
+        assert_eq!(manager.config.pinned_mods, ["carryon"]);
+
+        manager.set_value("network.timeout_secs", None).unwrap();
+        assert_eq!(manager.config.network.timeout_secs, 30);
+
+        assert!(matches!(
+            manager.set_value("network.timeout_secs", Some("soon")),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            manager.set_value("network.max_attempts", Some("0")),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            manager.set_value("schema_version", Some("7")),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            manager.set_value("network.timeout", Some("5")),
+            Err(ConfigError::UnknownKey(_))
+        ));
+
+        let saved = ConfigManager::load_config_from_file(&manager.config_path).unwrap();
+        assert_eq!(saved.channel, Channel::Rc);
+    }
+
+    #[test]
+    fn salvage_of_garbage_is_default() {
+        let config = salvage_config("this is not toml at all {{{");
+
+        assert!(config.get_game_path().is_none());
+        assert!(!config.has_version_mapping());
+    }
+}
@@ -0,0 +1,442 @@
+use crate::api::ModInfo;
+use crate::utils::is_newer;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Mod IDs provided by the game itself rather than by an installed mod.
+pub(crate) const GAME_MODS: [&str; 3] = ["game", "survival", "creative"];
+
+/// A problem found in the dependency graph of the installed mods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyProblem {
+    /// A mod depends on a mod that is not installed.
+    Missing { modid: String, dependency: String },
+    /// A mod needs a newer version of a dependency than the one installed.
+    Conflict {
+        modid: String,
+        dependency: String,
+        required: String,
+        installed: String,
+    },
+    /// Mods that depend on each other in a loop, starting and ending with the same mod.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for DependencyProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { modid, dependency } => {
+                write!(f, "{modid} depends on {dependency}, which is not installed")
+            }
+            Self::Conflict {
+                modid,
+                dependency,
+                required,
+                installed,
+            } => write!(
+                f,
+                "{modid} needs {dependency} >= {required}, but {installed} is installed"
+            ),
+            Self::Cycle(path) => write!(f, "circular dependency: {}", path.join(" -> ")),
+        }
+    }
+}
+
+/// The dependencies between installed mods, keyed by lowercase mod ID.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    /// The installed version of every known mod; `None` when it doesn't declare one.
+    versions: BTreeMap<String, Option<String>>,
+    /// Each mod's dependencies and their minimum versions (empty or `*` for any).
+    edges: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl DependencyGraph {
+    /// Builds the graph from the installed mods' `dependencies` maps.
+    ///
+    /// # Arguments
+    ///
+    /// * `mods` - The installed mods. Mods without a mod ID are skipped.
+    /// * `game_version` - The game version, which `game`, `survival` and `creative`
+    ///   dependencies are checked against. When unknown they are taken as satisfied.
+    pub fn new<'a>(
+        mods: impl IntoIterator<Item = &'a ModInfo>, game_version: Option<&str>,
+    ) -> Self {
+        let mut graph = Self::default();
+        for modid in GAME_MODS {
+            graph
+                .versions
+                .insert(modid.to_string(), game_version.map(str::to_string));
+        }
+
+        for info in mods {
+            let Some(modid) = info.modid.as_deref().map(str::to_lowercase) else {
+                continue;
+            };
+            graph.versions.insert(modid.clone(), info.version.clone());
+            let dependencies = info
+                .dependencies
+                .iter()
+                .flatten()
+                .map(|(id, constraint)| (id.to_lowercase(), constraint.trim().to_string()))
+                .collect();
+            graph.edges.insert(modid, dependencies);
+        }
+
+        graph
+    }
+
+    /// Records a new installed version of a mod, such as one just updated.
+    pub fn set_version(&mut self, modid: &str, version: &str) {
+        self.versions
+            .insert(modid.to_lowercase(), Some(version.to_string()));
+    }
+
+    /// Checks a set of dependencies against the installed mods.
+    ///
+    /// # Arguments
+    ///
+    /// * `modid` - The mod declaring the dependencies, named in the problems.
+    /// * `dependencies` - Mod IDs mapped to their minimum versions (empty or `*` for any).
+    ///
+    /// # Returns
+    ///
+    /// The dependencies that are not installed or installed in too old a version.
+    pub fn unmet<'a>(
+        &self, modid: &str, dependencies: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> Vec<DependencyProblem> {
+        let mut problems = Vec::new();
+        for (dependency, required) in dependencies {
+            let dependency = dependency.to_lowercase();
+            let required = required.trim();
+            let Some(installed) = self.versions.get(&dependency) else {
+                problems.push(DependencyProblem::Missing {
+                    modid: modid.to_string(),
+                    dependency,
+                });
+                continue;
+            };
+            if matches!(required, "" | "*") {
+                continue;
+            }
+            if let Some(installed) = installed
+                && is_newer(required, installed)
+            {
+                problems.push(DependencyProblem::Conflict {
+                    modid: modid.to_string(),
+                    dependency,
+                    required: required.to_string(),
+                    installed: installed.clone(),
+                });
+            }
+        }
+        problems
+    }
+
+    /// Orders mod IDs so every mod comes after the mods it depends on, which is the order
+    /// to update them in. Mods in a cycle keep their mod ID order among each other.
+    pub fn update_order(&self) -> Vec<String> {
+        let mut order = Vec::new();
+        let mut visited = BTreeSet::new();
+        for modid in self.edges.keys() {
+            self.visit_dependencies_first(modid, &mut visited, &mut order);
+        }
+        order
+    }
+
+    fn visit_dependencies_first<'a>(
+        &'a self, modid: &'a str, visited: &mut BTreeSet<&'a str>, order: &mut Vec<String>,
+    ) {
+        if !visited.insert(modid) {
+            return;
+        }
+        let Some(dependencies) = self.edges.get(modid) else {
+            return;
+        };
+        for dependency in dependencies.keys() {
+            self.visit_dependencies_first(dependency, visited, order);
+        }
+        order.push(modid.to_string());
+    }
+
+    fn is_game_mod(modid: &str) -> bool {
+        GAME_MODS.contains(&modid)
+    }
+
+    /// Finds missing dependencies, version conflicts and circular dependencies.
+    ///
+    /// # Returns
+    ///
+    /// The problems found, missing dependencies and conflicts first, in mod ID order.
+    pub fn problems(&self) -> Vec<DependencyProblem> {
+        let mut problems: Vec<DependencyProblem> = self
+            .edges
+            .iter()
+            .flat_map(|(modid, dependencies)| self.unmet(modid, dependencies))
+            .collect();
+        problems.extend(self.cycles().into_iter().map(DependencyProblem::Cycle));
+        problems
+    }
+
+    /// Finds every distinct loop of dependencies, each reported once starting from its
+    /// smallest mod ID.
+    fn cycles(&self) -> Vec<Vec<String>> {
+        let mut cycles = BTreeSet::new();
+        for start in self.edges.keys() {
+            let mut path = vec![start.as_str()];
+            self.find_cycles(&mut path, &mut cycles);
+        }
+        cycles.into_iter().collect()
+    }
+
+    fn find_cycles<'a>(&'a self, path: &mut Vec<&'a str>, cycles: &mut BTreeSet<Vec<String>>) {
+        let current = path[path.len() - 1];
+        for dependency in self
+            .edges
+            .get(current)
+            .into_iter()
+            .flat_map(|edges| edges.keys())
+        {
+            if let Some(position) = path.iter().position(|id| id == dependency) {
+                let mut cycle: Vec<String> =
+                    path[position..].iter().map(|id| id.to_string()).collect();
+                let smallest = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap_or(0);
+                cycle.rotate_left(smallest);
+                cycle.push(cycle[0].clone());
+                cycles.insert(cycle);
+            } else {
+                path.push(dependency);
+                self.find_cycles(path, cycles);
+                path.pop();
+            }
+        }
+    }
+
+    fn label(&self, modid: &str) -> String {
+        match self.versions.get(modid) {
+            Some(Some(version)) => format!("{modid} {version}"),
+            Some(None) => modid.to_string(),
+            None => format!("{modid} (missing)"),
+        }
+    }
+
+    /// Renders the graph as a tree, with the mods nothing depends on at the top.
+    ///
+    /// Game dependencies are left out, and a mod depending on one of its own ancestors is
+    /// marked as a cycle instead of being expanded again.
+    pub fn render_tree(&self) -> String {
+        let depended_on: BTreeSet<&str> = self
+            .edges
+            .values()
+            .flat_map(|edges| edges.keys().map(String::as_str))
+            .collect();
+        let mut roots: Vec<&str> = self
+            .edges
+            .keys()
+            .map(String::as_str)
+            .filter(|modid| !depended_on.contains(modid))
+            .collect();
+
+        let mut output = String::new();
+        let mut visited = BTreeSet::new();
+        let mut index = 0;
+        loop {
+            while index < roots.len() {
+                let root = roots[index];
+                output.push_str(&self.label(root));
+                output.push('\n');
+                let mut path = vec![root];
+                self.render_children(&mut path, "", &mut visited, &mut output);
+                visited.insert(root);
+                index += 1;
+            }
+            // Mods that only appear inside cycles have no root above them.
+            match self
+                .edges
+                .keys()
+                .find(|modid| !visited.contains(modid.as_str()))
+            {
+                Some(modid) => roots.push(modid),
+                None => break,
+            }
+        }
+        output
+    }
+
+    fn render_children<'a>(
+        &'a self, path: &mut Vec<&'a str>, prefix: &str, visited: &mut BTreeSet<&'a str>,
+        output: &mut String,
+    ) {
+        let current = path[path.len() - 1];
+        let children: Vec<(&String, &String)> = self
+            .edges
+            .get(current)
+            .into_iter()
+            .flatten()
+            .filter(|(modid, _)| !Self::is_game_mod(modid))
+            .collect();
+
+        for (i, (child, constraint)) in children.iter().enumerate() {
+            let last = i == children.len() - 1;
+            let branch = if last { "└── " } else { "├── " };
+            let requirement = match constraint.as_str() {
+                "" | "*" => String::new(),
+                constraint => format!(" (needs >= {constraint})"),
+            };
+            let cycle = path.contains(&child.as_str());
+            output.push_str(&format!(
+                "{prefix}{branch}{}{requirement}{}\n",
+                self.label(child),
+                if cycle { " (cycle)" } else { "" }
+            ));
+            visited.insert(child);
+
+            if !cycle {
+                path.push(child);
+                let prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+                self.render_children(path, &prefix, visited, output);
+                path.pop();
+            }
+        }
+    }
+
+    /// Renders the graph in Graphviz DOT format, with missing mods drawn dashed and red.
+    pub fn render_dot(&self) -> String {
+        let mut output = String::from("digraph dependencies {\n    rankdir=LR;\n");
+        let mut missing = BTreeSet::new();
+
+        for modid in self.edges.keys() {
+            output.push_str(&format!(
+                "    {:?} [label={:?}];\n",
+                modid,
+                self.label(modid)
+            ));
+        }
+        for (modid, dependencies) in &self.edges {
+            for (dependency, constraint) in dependencies {
+                if Self::is_game_mod(dependency) {
+                    continue;
+                }
+                if !self.versions.contains_key(dependency) {
+                    missing.insert(dependency);
+                }
+                match constraint.as_str() {
+                    "" | "*" => output.push_str(&format!("    {modid:?} -> {dependency:?};\n")),
+                    constraint => output.push_str(&format!(
+                        "    {modid:?} -> {dependency:?} [label={:?}];\n",
+                        format!(">= {constraint}")
+                    )),
+                }
+            }
+        }
+        for modid in missing {
+            output.push_str(&format!(
+                "    {modid:?} [label={:?}, style=dashed, color=red];\n",
+                self.label(modid)
+            ));
+        }
+
+        output.push_str("}\n");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn mod_info(modid: &str, version: &str, dependencies: &[(&str, &str)]) -> ModInfo {
+        ModInfo {
+            modid: Some(modid.to_string()),
+            version: Some(version.to_string()),
+            dependencies: Some(
+                dependencies
+                    .iter()
+                    .map(|(id, version)| (id.to_string(), version.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn problems_cover_missing_conflicts_and_cycles() {
+        let mods = [
+            mod_info("expanded", "1.0.0", &[("game", "1.19.0"), ("lib", "2.0.0")]),
+            mod_info("lib", "1.5.0", &[("helper", "*")]),
+            mod_info("a", "1.0.0", &[("b", "")]),
+            mod_info("b", "1.0.0", &[("a", "")]),
+        ];
+        let graph = DependencyGraph::new(&mods, Some("1.20.4"));
+
+        assert_eq!(
+            graph.problems(),
+            vec![
+                DependencyProblem::Conflict {
+                    modid: "expanded".into(),
+                    dependency: "lib".into(),
+                    required: "2.0.0".into(),
+                    installed: "1.5.0".into(),
+                },
+                DependencyProblem::Missing {
+                    modid: "lib".into(),
+                    dependency: "helper".into(),
+                },
+                DependencyProblem::Cycle(vec!["a".into(), "b".into(), "a".into()]),
+            ]
+        );
+
+        let old_game = DependencyGraph::new(&mods[..1], Some("1.18.0"));
+        assert!(old_game.problems().iter().any(|problem| matches!(
+            problem,
+            DependencyProblem::Conflict { dependency, .. } if dependency == "game"
+        )));
+    }
+
+    #[test]
+    fn dependencies_are_updated_first() {
+        let mods = [
+            mod_info("expanded", "1.0.0", &[("game", ""), ("lib", "2.0.0")]),
+            mod_info("addon", "1.0.0", &[("expanded", "")]),
+            mod_info("lib", "1.0.0", &[]),
+        ];
+        let mut graph = DependencyGraph::new(&mods, None);
+        assert_eq!(graph.update_order(), ["lib", "expanded", "addon"]);
+
+        let requires = HashMap::from([("lib".to_string(), "2.0.0".to_string())]);
+        assert_eq!(graph.unmet("expanded", &requires).len(), 1);
+        graph.set_version("lib", "2.0.1");
+        assert!(graph.unmet("expanded", &requires).is_empty());
+    }
+
+    #[test]
+    fn tree_and_dot_show_every_mod() {
+        let mods = [
+            mod_info("expanded", "1.0.0", &[("game", ""), ("lib", "2.0.0")]),
+            mod_info("lib", "2.1.0", &[("missing", "")]),
+            mod_info("a", "1.0.0", &[("b", "")]),
+            mod_info("b", "1.0.0", &[("a", "")]),
+        ];
+        let graph = DependencyGraph::new(&mods, None);
+
+        assert_eq!(
+            graph.render_tree().lines().collect::<Vec<_>>(),
+            [
+                "expanded 1.0.0",
+                "└── lib 2.1.0 (needs >= 2.0.0)",
+                "    └── missing (missing)",
+                "a 1.0.0",
+                "└── b 1.0.0",
+                "    └── a 1.0.0 (cycle)",
+            ]
+        );
+
+        let dot = graph.render_dot();
+        assert!(dot.contains("\"expanded\" -> \"lib\" [label=\">= 2.0.0\"];"));
+        assert!(
+            dot.contains("\"missing\" [label=\"missing (missing)\", style=dashed, color=red];")
+        );
+        assert!(!dot.contains("\"game\""));
+    }
+}
@@ -1,11 +1,13 @@
-use crate::utils::{LogLevel, Logger};
+use crate::utils::deps::GAME_MODS;
 use base85::{decode, encode};
 use brotli::{CompressorWriter, Decompressor};
+use clap::ValueEnum;
 use std::io::{Read, Write};
 use std::{io, str};
 use thiserror::Error;
 
 use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct EncoderData {
@@ -13,14 +15,62 @@ pub struct EncoderData {
     pub mod_version: String,
 }
 
+/// A mod together with the details shown in human-readable mod lists.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModListEntry {
+    pub modid: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Download URL of the release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+}
+
+impl From<&EncoderData> for ModListEntry {
+    fn from(data: &EncoderData) -> Self {
+        Self {
+            modid: data.mod_id.clone(),
+            version: data.mod_version.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<ModListEntry> for EncoderData {
+    fn from(entry: ModListEntry) -> Self {
+        Self {
+            mod_id: entry.modid,
+            mod_version: entry.version,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum EncodingError {
     #[error("Decoding error: {0}")]
     Decode(String),
+    #[error("Encoding error: {0}")]
+    Encode(String),
     #[error("Decompression error: {0}")]
     Decompress(String),
     #[error("UTF-8 error: {0}")]
     Utf8(#[from] std::string::FromUtf8Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Mod string is corrupted: checksum {found:08x} does not match {expected:08x}")]
+    Checksum { expected: u32, found: u32 },
+    #[error(
+        "Mod string format version {found} is not supported (this build reads versions up to {supported}), try updating VintageModManager"
+    )]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("Not a share link: {0}")]
+    ShareLink(String),
+    #[error("The mod list is too long for a QR code: {0}")]
+    QrCode(String),
 }
 
 impl From<io::Error> for EncodingError {
@@ -29,10 +79,62 @@ impl From<io::Error> for EncodingError {
     }
 }
 
+/// Marks a versioned mod string, followed by the format version and a `:`.
+///
+/// `:` is not part of the base85 alphabet, so strings without the header are legacy
+/// version 1 strings.
+const MOD_STRING_MAGIC: &str = "VMM";
+/// The mod string format version written by [`Encoder::encode_mod_string`].
+///
+/// * `1` - Unprefixed base85 of the brotli-compressed `mod_id|mod_version;...` list.
+/// * `2` - The same payload behind a `VMM2:` header.
+/// * `3` - A `VMM3:` header, then base85 of a big-endian CRC-32 of the compressed bytes
+///   followed by the brotli-compressed `game_version\nmod_id|mod_version;...` text. The game
+///   version is empty when the exporter's was not known.
+const MOD_STRING_VERSION: u32 = 3;
+
+/// Marks the line of a server or client log listing the loaded mods.
+const LOG_MOD_LIST_MARKER: &str = "Mods, sorted by dependency:";
+
+/// Reads the mods a Vintage Story server or client loaded from its log, e.g.
+/// `server-main.txt`.
+///
+/// The log lists the loaded mods on start-up as `modid` or, in newer game versions,
+/// `modid@version`. If the log covers several start-ups the last one is used, and the
+/// mods built into the game are left out.
+///
+/// # Returns
+///
+/// The mods, with empty versions where the log doesn't name them, or an error if the log
+/// doesn't list any mods.
+pub fn parse_server_log(log: &str) -> Result<Vec<EncoderData>, EncodingError> {
+    let list = log
+        .lines()
+        .rev()
+        .find_map(|line| line.split_once(LOG_MOD_LIST_MARKER).map(|(_, list)| list))
+        .ok_or_else(|| {
+            EncodingError::Decode(format!("The log has no \"{LOG_MOD_LIST_MARKER}\" line"))
+        })?;
+
+    Ok(list
+        .split(',')
+        .map(str::trim)
+        .filter(|mod_| !mod_.is_empty())
+        .map(|mod_| mod_.split_once('@').unwrap_or((mod_, "")))
+        .filter(|(mod_id, _)| !GAME_MODS.contains(mod_id))
+        .map(|(mod_id, mod_version)| EncoderData {
+            mod_id: mod_id.to_string(),
+            mod_version: mod_version.to_string(),
+        })
+        .collect())
+}
+
 /// Struct to handle encoding and decoding operations.
+#[derive(Default)]
 pub struct Encoder {
-    /// Logger instance for logging encoding operations.
-    logger: Logger,
+    /// The detected game version, embedded in exported mod strings and compared against
+    /// the one embedded in imported strings.
+    game_version: Option<String>,
 }
 
 impl Encoder {
@@ -40,11 +142,15 @@ impl Encoder {
     ///
     /// # Returns
     ///
-    /// A new `Encoder` instance with a default logger.
-    pub fn new(verbose: bool) -> Self {
-        Self {
-            logger: Logger::new("Encoder".to_string(), LogLevel::Info, None, verbose),
-        }
+    /// A new `Encoder` instance without a game version.
+    pub fn new() -> Self {
+        Self { game_version: None }
+    }
+
+    /// Sets the game version embedded in mod strings and checked when importing them.
+    pub fn with_game_version(mut self, game_version: Option<String>) -> Self {
+        self.game_version = game_version;
+        self
     }
 
     /// Encodes the given data to a base85 string.
@@ -58,8 +164,7 @@ impl Encoder {
     /// A `String` containing the base85 encoded data.
     pub fn encode(&self, data: &[u8]) -> String {
         let encoded = encode(data);
-        self.logger
-            .log_default(&format!("Encoding using `encode` function: {encoded}"));
+        debug!("Encoding using `encode` function: {encoded}");
         encoded
     }
 
@@ -73,8 +178,7 @@ impl Encoder {
     ///
     /// A `Result` containing a vector of bytes or a `EncodingError`.
     pub fn decode(&self, data: &str) -> Result<Vec<u8>, EncodingError> {
-        self.logger
-            .log_default(&format!("Decoding using `decode` function: {data}"));
+        debug!("Decoding using `decode` function: {data}");
 
         decode(data).map_err(|e| EncodingError::Decode(e.to_string()))
     }
@@ -88,19 +192,60 @@ impl Encoder {
     /// # Returns
     ///
     /// A `String` containing the compact encoded data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vintage_mod_manager::utils::{Encoder, EncoderData};
+    ///
+    /// let encoder = Encoder::new();
+    ///
+    /// let mod_: EncoderData = EncoderData {
+    ///     mod_id: "foo".to_string(),
+    ///     mod_version: "1.10".to_string(),
+    /// };
+    ///
+    /// let encoded = encoder.encode_mod_string(&[mod_]);
+    /// let decoded = encoder.decode_mod_string(encoded).unwrap();
+    /// assert_eq!(decoded[0].mod_id, "foo");
+    /// assert_eq!(decoded[0].mod_version, "1.10");
+    /// ```
     pub fn encode_mod_string(&self, mods: &[EncoderData]) -> String {
-        let mod_string = self.format_encoder_data(mods);
-        self.logger
-            .log_default(&format!("Mod string before encoding: {mod_string}"));
+        let mod_string = format!(
+            "{}\n{}",
+            self.game_version.as_deref().unwrap_or_default(),
+            self.format_encoder_data(mods)
+        );
+        debug!("Mod string before encoding: {mod_string}");
 
-        // Compress
+        // Compress, then prefix the checksum
         let compressed = self.compress(&mod_string).unwrap();
-        let encoded = self.encode(&compressed);
-        self.logger
-            .log_default(&format!("Encoded mod string: {encoded}"));
+        let mut payload = crc32(&compressed).to_be_bytes().to_vec();
+        payload.extend_from_slice(&compressed);
+        let encoded = format!(
+            "{MOD_STRING_MAGIC}{MOD_STRING_VERSION}:{}",
+            self.encode(&payload)
+        );
+        debug!("Encoded mod string: {encoded}");
         encoded
     }
 
+    /// Splits the format version off a mod string.
+    ///
+    /// # Returns
+    ///
+    /// The format version and the payload after the header, version 1 with the whole string
+    /// for unprefixed strings, or `None` if the header's version is not a number.
+    fn split_mod_string_header(data: &str) -> Option<(u32, &str)> {
+        match data
+            .strip_prefix(MOD_STRING_MAGIC)
+            .and_then(|rest| rest.split_once(':'))
+        {
+            Some((version, payload)) => Some((version.parse().ok()?, payload)),
+            None => Some((1, data)),
+        }
+    }
+
     /// Formats a list of `EncoderData` to a compact string.
     ///
     /// The format is `mod_id|mod_version;mod_id|mod_version;...`.
@@ -114,24 +259,6 @@ impl Encoder {
     /// # Returns
     ///
     /// A `String` containing the formatted data.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use utils::encoding::Encoder;
-    /// use utils::encoding::EncoderData;
-    ///
-    /// let encoder = Encoder::new(false);
-    ///
-    /// let mod_: EncoderData = EncoderData {
-    ///     mod_id: "foo".to_string(),
-    ///     mod_version: "1.10".to_string(),
-    ///    };
-    ///
-    /// let formatted = encoder.format_encoder_data(&[mod_]);
-    /// assert_eq!(formatted, "foo|1.10");
-    ///```
-    ///
     fn format_encoder_data(&self, mods: &[EncoderData]) -> String {
         mods.iter()
             .map(|mod_info| format!("{}|{}", mod_info.mod_id, mod_info.mod_version))
@@ -141,6 +268,9 @@ impl Encoder {
 
     /// Decodes a compact string to a list of `EncoderData`.
     ///
+    /// Prints a warning when the string was exported for a different game version than the
+    /// one set with [`Encoder::with_game_version`].
+    ///
     /// # Arguments
     ///
     /// * `data` - A `String` representing the compact encoded data.
@@ -149,10 +279,61 @@ impl Encoder {
     ///
     /// A `Result` containing a vector of `EncoderData` or an EncodingError.
     pub fn decode_mod_string(&self, data: String) -> Result<Vec<EncoderData>, EncodingError> {
-        let binary_data = self.decode(&data)?;
-        let decompressed = self.decompress(&binary_data)?;
+        let (version, payload) = Self::split_mod_string_header(data.trim()).ok_or_else(|| {
+            EncodingError::Decode("Invalid mod string format version".to_string())
+        })?;
+        let mod_list = match version {
+            1 | 2 => self.decompress(&self.decode(payload)?)?,
+            3 => {
+                let (game_version, mod_list) = self.decode_mod_string_v3(payload)?;
+                if let (Some(exported), Some(current)) = (&game_version, &self.game_version)
+                    && exported != current
+                {
+                    warn!(
+                        "This mod string was exported for game version {exported}, you are running {current}"
+                    );
+                }
+                mod_list
+            }
+            found => {
+                return Err(EncodingError::UnsupportedVersion {
+                    found,
+                    supported: MOD_STRING_VERSION,
+                });
+            }
+        };
+        self.parse_mod_list(&mod_list)
+    }
 
-        let mods: Result<Vec<EncoderData>, EncodingError> = decompressed
+    /// Verifies and decompresses a version 3 payload.
+    ///
+    /// # Returns
+    ///
+    /// The exporter's game version, if it was known, and the `mod_id|mod_version;...` list.
+    fn decode_mod_string_v3(
+        &self, payload: &str,
+    ) -> Result<(Option<String>, String), EncodingError> {
+        let binary_data = self.decode(payload)?;
+        let Some((checksum, compressed)) = binary_data.split_first_chunk::<4>() else {
+            return Err(EncodingError::Decode("Mod string is too short".to_string()));
+        };
+        let expected = u32::from_be_bytes(*checksum);
+        let found = crc32(compressed);
+        if expected != found {
+            return Err(EncodingError::Checksum { expected, found });
+        }
+
+        let decompressed = self.decompress(compressed)?;
+        let (game_version, mod_list) = decompressed.split_once('\n').ok_or_else(|| {
+            EncodingError::Decode("Missing game version in mod string".to_string())
+        })?;
+        let game_version = Some(game_version.to_string()).filter(|version| !version.is_empty());
+        Ok((game_version, mod_list.to_string()))
+    }
+
+    /// Parses a `mod_id|mod_version;...` list.
+    fn parse_mod_list(&self, mod_list: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        let mods: Result<Vec<EncoderData>, EncodingError> = mod_list
             .split(';')
             .map(|mod_info| {
                 let parts: Vec<&str> = mod_info.split('|').collect();
@@ -168,17 +349,65 @@ impl Encoder {
             })
             .collect();
 
-        self.logger
-            .log_default(&format!("Decoded mod string: {mods:?}"));
+        debug!("Decoded mod string: {mods:?}");
         mods
     }
 
+    /// Formats a list of `EncoderData` as newline-separated `mod_id@mod_version` pairs.
+    ///
+    /// Unlike [`Encoder::encode_mod_string`] the output is not compressed, so it can be
+    /// piped into other tools.
+    ///
+    /// Example: `foo@1.10\nbar@4.0`. Mods without a version are written as a bare `mod_id`.
+    pub fn format_raw_ids(&self, mods: &[EncoderData]) -> String {
+        mods.iter()
+            .map(|mod_info| match mod_info.mod_version.as_str() {
+                "" => mod_info.mod_id.clone(),
+                version => format!("{}@{version}", mod_info.mod_id),
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Parses newline-separated `mod_id@mod_version` pairs as produced by
+    /// [`Encoder::format_raw_ids`].
+    ///
+    /// Blank lines and lines starting with `#` are skipped. The version is optional, a bare
+    /// `mod_id` yields an empty `mod_version`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The raw id list to parse.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a vector of `EncoderData` or an EncodingError.
+    pub fn parse_raw_ids(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        data.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (mod_id, mod_version) = line.split_once('@').unwrap_or((line, ""));
+                if mod_id.is_empty() {
+                    return Err(EncodingError::Decode(format!(
+                        "Invalid mod id line: {line}"
+                    )));
+                }
+                Ok(EncoderData {
+                    mod_id: mod_id.to_string(),
+                    mod_version: mod_version.to_string(),
+                })
+            })
+            .collect()
+    }
+
     /// Decompresses the data using Brotli decompression.
     pub fn decompress(&self, data: &[u8]) -> io::Result<String> {
         let mut decoder = Decompressor::new(data, 4096);
         let mut decompressed_data = Vec::new();
         decoder.read_to_end(&mut decompressed_data)?;
-        let decompressed_string = String::from_utf8(decompressed_data).unwrap();
+        let decompressed_string = String::from_utf8(decompressed_data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
         Ok(decompressed_string)
     }
 
@@ -187,19 +416,460 @@ impl Encoder {
         let mut encoder = CompressorWriter::new(Vec::new(), 4096, 11, 22);
         encoder.write_all(data.as_bytes())?;
         let compressed_data = encoder.into_inner();
-        self.logger
-            .log_default(&format!("Compressed data: {compressed_data:?}"));
+        debug!("Compressed data: {compressed_data:?}");
         Ok(compressed_data)
     }
 }
 
+/// Computes the CRC-32 (IEEE) checksum of `data`.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Mod list formats understood by `export --format` and `download --manifest`.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ModListFormat {
+    /// Compressed, shareable mod string
+    #[default]
+    String,
+    /// Newline-separated `modid@version` pairs
+    Ids,
+    /// Plain list of mod IDs, one per line (versions are dropped)
+    Modids,
+    /// JSON mod list used by the community 1-click launchers
+    Launcher,
+    /// JSON manifest with names, authors and download URLs
+    Json,
+    /// TOML manifest with names, authors and download URLs
+    Toml,
+    /// CSV table with names, authors and download URLs
+    Csv,
+    /// Markdown table for posting modpack contents on forums
+    Markdown,
+}
+
+impl ModListFormat {
+    /// Guesses the format of a mod list from its contents.
+    ///
+    /// Valid JSON is treated as a launcher list (which also reads JSON manifests), TOML
+    /// manifests, CSV and Markdown tables are recognized by their structure, and anything
+    /// with a mod string header or that decodes as a mod string as a mod string. Everything
+    /// else is parsed as `modid@version` pairs, which also covers plain mod ID lists.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The mod list to inspect.
+    /// * `encoder` - The encoder used to probe for a mod string.
+    pub fn detect(data: &str, encoder: &Encoder) -> Self {
+        let trimmed = data.trim();
+        let first_line = trimmed.lines().next().unwrap_or_default().trim();
+        if serde_json::from_str::<serde_json::Value>(trimmed).is_ok_and(|v| !v.is_string()) {
+            ModListFormat::Launcher
+        } else if trimmed.lines().any(|line| line.trim() == "[[mods]]") {
+            ModListFormat::Toml
+        } else if first_line.starts_with(CSV_HEADER) {
+            ModListFormat::Csv
+        } else if trimmed
+            .lines()
+            .any(|line| line.trim_start().starts_with('|'))
+        {
+            ModListFormat::Markdown
+        } else if Encoder::split_mod_string_header(trimmed).is_some_and(|(version, _)| version > 1)
+            || encoder.decode_mod_string(trimmed.to_string()).is_ok()
+        {
+            ModListFormat::String
+        } else {
+            ModListFormat::Ids
+        }
+    }
+
+    /// Returns the codec implementing this format.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoder` - The encoder used by formats that build on the mod string.
+    pub fn codec(self, encoder: &Encoder) -> Box<dyn ModListCodec + '_> {
+        match self {
+            ModListFormat::String => Box::new(ModStringCodec(encoder)),
+            ModListFormat::Ids => Box::new(RawIdsCodec(encoder)),
+            ModListFormat::Modids => Box::new(ModIdsCodec),
+            ModListFormat::Launcher => Box::new(LauncherCodec),
+            ModListFormat::Json => Box::new(JsonManifestCodec),
+            ModListFormat::Toml => Box::new(TomlManifestCodec),
+            ModListFormat::Csv => Box::new(CsvCodec),
+            ModListFormat::Markdown => Box::new(MarkdownCodec),
+        }
+    }
+
+    /// Returns `true` if the format shows names, authors and download URLs, so those are
+    /// worth looking up before exporting.
+    pub fn shows_details(self) -> bool {
+        matches!(
+            self,
+            ModListFormat::Json
+                | ModListFormat::Toml
+                | ModListFormat::Csv
+                | ModListFormat::Markdown
+        )
+    }
+}
+
+/// Converts between a list of `EncoderData` and one textual mod list format.
+///
+/// New formats are added by implementing this trait and registering the codec in
+/// [`ModListFormat::codec`].
+pub trait ModListCodec {
+    /// Serializes the mods into this format.
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError>;
+
+    /// Parses a mod list in this format.
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError>;
+
+    /// Serializes the mods with their details. Formats that only store mod IDs and
+    /// versions drop the details.
+    fn encode_entries(&self, mods: &[ModListEntry]) -> Result<String, EncodingError> {
+        let mods: Vec<EncoderData> = mods.iter().cloned().map(EncoderData::from).collect();
+        self.encode(&mods)
+    }
+}
+
+/// The compressed mod string produced by [`Encoder::encode_mod_string`].
+struct ModStringCodec<'a>(&'a Encoder);
+
+impl ModListCodec for ModStringCodec<'_> {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        Ok(self.0.encode_mod_string(mods))
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        self.0.decode_mod_string(data.trim().to_string())
+    }
+}
+
+/// Newline-separated `modid@version` pairs, see [`Encoder::format_raw_ids`].
+struct RawIdsCodec<'a>(&'a Encoder);
+
+impl ModListCodec for RawIdsCodec<'_> {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        Ok(self.0.format_raw_ids(mods))
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        self.0.parse_raw_ids(data)
+    }
+}
+
+/// Plain mod ID lists as shared on forums and Discord. Entries may be separated by
+/// newlines or commas, and any version suffix is ignored.
+struct ModIdsCodec;
+
+impl ModListCodec for ModIdsCodec {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        Ok(mods
+            .iter()
+            .map(|mod_info| mod_info.mod_id.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        Ok(data
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(|line| line.split(','))
+            .map(|entry| entry.trim().split('@').next().unwrap_or_default())
+            .filter(|mod_id| !mod_id.is_empty())
+            .map(|mod_id| EncoderData {
+                mod_id: mod_id.to_string(),
+                mod_version: String::new(),
+            })
+            .collect())
+    }
+}
+
+/// A single entry of a launcher mod list.
+#[derive(Serialize, Deserialize)]
+struct LauncherMod {
+    #[serde(alias = "modid", alias = "modId")]
+    id: String,
+    #[serde(default)]
+    version: String,
+}
+
+/// Launcher mod lists are either a bare array of mods or an object with a `mods` array.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum LauncherList {
+    Wrapped { mods: Vec<LauncherMod> },
+    Bare(Vec<LauncherMod>),
+}
+
+/// The JSON mod list format used by the community 1-click launchers.
+struct LauncherCodec;
+
+impl ModListCodec for LauncherCodec {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        let list = LauncherList::Wrapped {
+            mods: mods
+                .iter()
+                .map(|mod_info| LauncherMod {
+                    id: mod_info.mod_id.clone(),
+                    version: mod_info.mod_version.clone(),
+                })
+                .collect(),
+        };
+        Ok(serde_json::to_string_pretty(&list)?)
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        let mods = match serde_json::from_str(data)? {
+            LauncherList::Wrapped { mods } | LauncherList::Bare(mods) => mods,
+        };
+        Ok(mods
+            .into_iter()
+            .map(|entry| EncoderData {
+                mod_id: entry.id,
+                mod_version: entry.version,
+            })
+            .collect())
+    }
+}
+
+/// Header row of CSV mod lists.
+const CSV_HEADER: &str = "modid,version,name,authors,url";
+
+/// Manifests list their mods in a `mods` array (`[[mods]]` tables in TOML).
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    mods: Vec<ModListEntry>,
+}
+
+/// Converts plain mod entries for formats that store details.
+fn entries(mods: &[EncoderData]) -> Vec<ModListEntry> {
+    mods.iter().map(ModListEntry::from).collect()
+}
+
+/// Converts parsed entries back into the data mods are installed from.
+fn encoder_data(mods: Vec<ModListEntry>) -> Vec<EncoderData> {
+    mods.into_iter().map(EncoderData::from).collect()
+}
+
+/// A JSON manifest, e.g. `{"mods": [{"modid": "carryon", "version": "1.8.0", ...}]}`.
+struct JsonManifestCodec;
+
+impl ModListCodec for JsonManifestCodec {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        self.encode_entries(&entries(mods))
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        let manifest: Manifest = serde_json::from_str(data)?;
+        Ok(encoder_data(manifest.mods))
+    }
+
+    fn encode_entries(&self, mods: &[ModListEntry]) -> Result<String, EncodingError> {
+        Ok(serde_json::to_string_pretty(&Manifest {
+            mods: mods.to_vec(),
+        })?)
+    }
+}
+
+/// A TOML manifest with one `[[mods]]` table per mod.
+struct TomlManifestCodec;
+
+impl ModListCodec for TomlManifestCodec {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        self.encode_entries(&entries(mods))
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        let manifest: Manifest =
+            toml::from_str(data).map_err(|e| EncodingError::Decode(e.to_string()))?;
+        Ok(encoder_data(manifest.mods))
+    }
+
+    fn encode_entries(&self, mods: &[ModListEntry]) -> Result<String, EncodingError> {
+        toml::to_string_pretty(&Manifest {
+            mods: mods.to_vec(),
+        })
+        .map_err(|e| EncodingError::Encode(e.to_string()))
+    }
+}
+
+/// A CSV table with a [`CSV_HEADER`] row. Authors are separated by `; `.
+struct CsvCodec;
+
+impl CsvCodec {
+    /// Quotes a field if it contains a separator, quote or line break.
+    fn field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Splits a CSV line into fields, unquoting quoted ones.
+    fn split(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, quoted) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    fields.last_mut().unwrap().push('"');
+                }
+                ('"', _) => quoted = !quoted,
+                (',', false) => fields.push(String::new()),
+                (c, _) => fields.last_mut().unwrap().push(c),
+            }
+        }
+        fields
+    }
+}
+
+impl ModListCodec for CsvCodec {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        self.encode_entries(&entries(mods))
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        data.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with(CSV_HEADER))
+            .map(|line| {
+                let fields = Self::split(line);
+                match fields.first() {
+                    Some(modid) if !modid.trim().is_empty() => Ok(EncoderData {
+                        mod_id: modid.trim().to_string(),
+                        mod_version: fields
+                            .get(1)
+                            .map(|v| v.trim())
+                            .unwrap_or_default()
+                            .to_string(),
+                    }),
+                    _ => Err(EncodingError::Decode(format!("Invalid CSV line: {line}"))),
+                }
+            })
+            .collect()
+    }
+
+    fn encode_entries(&self, mods: &[ModListEntry]) -> Result<String, EncodingError> {
+        let mut lines = vec![CSV_HEADER.to_string()];
+        for entry in mods {
+            let fields = [
+                entry.modid.as_str(),
+                entry.version.as_str(),
+                entry.name.as_deref().unwrap_or_default(),
+                &entry.authors.join("; "),
+                entry.url.as_deref().unwrap_or_default(),
+            ];
+            lines.push(
+                fields
+                    .iter()
+                    .map(|field| Self::field(field))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+/// A Markdown table for forum posts. The `Mod ID` and `Version` columns are read back.
+struct MarkdownCodec;
+
+impl MarkdownCodec {
+    /// Escapes characters that would break out of a table cell.
+    fn cell(value: &str) -> String {
+        value.replace('|', "\\|").replace('\n', " ")
+    }
+}
+
+impl ModListCodec for MarkdownCodec {
+    fn encode(&self, mods: &[EncoderData]) -> Result<String, EncodingError> {
+        self.encode_entries(&entries(mods))
+    }
+
+    fn decode(&self, data: &str) -> Result<Vec<EncoderData>, EncodingError> {
+        Ok(data
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('|'))
+            .map(|line| {
+                line.trim_matches('|')
+                    .split(" | ")
+                    .map(str::trim)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| cells.len() >= 3)
+            .filter(|cells| cells[1] != "Mod ID" && !cells[1].starts_with("---"))
+            .map(|cells| EncoderData {
+                mod_id: cells[1].trim_matches('`').to_string(),
+                mod_version: cells[2].to_string(),
+            })
+            .collect())
+    }
+
+    fn encode_entries(&self, mods: &[ModListEntry]) -> Result<String, EncodingError> {
+        let mut lines = vec![
+            "| Name | Mod ID | Version | Authors | Download |".to_string(),
+            "| --- | --- | --- | --- | --- |".to_string(),
+        ];
+        for entry in mods {
+            let download = entry
+                .url
+                .as_deref()
+                .map(|url| format!("[download]({url})"))
+                .unwrap_or_default();
+            lines.push(format!(
+                "| {} | `{}` | {} | {} | {download} |",
+                Self::cell(entry.name.as_deref().unwrap_or(&entry.modid)),
+                entry.modid,
+                Self::cell(&entry.version),
+                Self::cell(&entry.authors.join(", ")),
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn server_logs_list_the_last_loaded_mods() {
+        let log = "\
+18.5.2025 10:00:00 [Server Notification] Mods, sorted by dependency: game, carryon, survival
+18.5.2025 12:00:00 [Server Notification] Found 3 mods (0 disabled)
+18.5.2025 12:00:00 [Server Notification] Mods, sorted by dependency: game@1.20.10, carryon@1.8.0, hudclock, survival@1.20.10
+18.5.2025 12:00:01 [Server Event] Server started";
+
+        assert_eq!(
+            parse_server_log(log).unwrap(),
+            vec![
+                EncoderData {
+                    mod_id: "carryon".to_string(),
+                    mod_version: "1.8.0".to_string(),
+                },
+                EncoderData {
+                    mod_id: "hudclock".to_string(),
+                    mod_version: String::new(),
+                },
+            ]
+        );
+        assert!(parse_server_log("[Server Event] Server started").is_err());
+    }
+
     #[test]
     fn encode_base85() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let data = b"hello";
         let encoded = encoder.encode(data);
         assert_eq!(encoded, "Xk~0{Zv");
@@ -207,7 +877,7 @@ mod tests {
 
     #[test]
     fn decode_base85() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let data = "Xk~0{Zv";
         let decoded = encoder.decode(data);
         assert!(decoded.is_ok(), "Decoding failed: {:?}", decoded.err());
@@ -216,7 +886,7 @@ mod tests {
 
     #[test]
     fn encode_mod_string() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let mods = vec![
             EncoderData {
                 mod_id: "foo".to_string(),
@@ -233,7 +903,7 @@ mod tests {
 
     #[test]
     fn decode_mod_string() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let data = encoder.encode_mod_string(&[
             EncoderData {
                 mod_id: "foo".to_string(),
@@ -252,9 +922,64 @@ mod tests {
         assert_eq!(decoded[1].mod_version, "2.0");
     }
 
+    #[test]
+    fn mod_string_versions() {
+        let encoder = Encoder::new();
+        let mods = [EncoderData {
+            mod_id: "foo".to_string(),
+            mod_version: "1.10".to_string(),
+        }];
+
+        let encoded = encoder.encode_mod_string(&mods);
+        assert!(encoded.starts_with("VMM3:"));
+        assert_eq!(encoder.decode_mod_string(encoded.clone()).unwrap(), mods);
+
+        let legacy = encoder.encode(&encoder.compress("foo|1.10").unwrap());
+        assert_eq!(encoder.decode_mod_string(legacy.clone()).unwrap(), mods);
+        assert_eq!(
+            encoder.decode_mod_string(format!("VMM2:{legacy}")).unwrap(),
+            mods
+        );
+
+        let future = encoded.replace("VMM3:", "VMM9:");
+        assert!(matches!(
+            encoder.decode_mod_string(future.clone()),
+            Err(EncodingError::UnsupportedVersion { found: 9, .. })
+        ));
+        assert_eq!(
+            ModListFormat::detect(&future, &encoder),
+            ModListFormat::String
+        );
+    }
+
+    #[test]
+    fn mod_string_carries_game_version_and_checksum() {
+        let encoder = Encoder::new().with_game_version(Some("1.20.3".to_string()));
+        let mods = [EncoderData {
+            mod_id: "foo".to_string(),
+            mod_version: "1.10".to_string(),
+        }];
+        let encoded = encoder.encode_mod_string(&mods);
+
+        let payload = encoder.decode(&encoded["VMM3:".len()..]).unwrap();
+        let (game_version, _) = encoder
+            .decode_mod_string_v3(&encoder.encode(&payload))
+            .unwrap();
+        assert_eq!(game_version.as_deref(), Some("1.20.3"));
+
+        let mut corrupted = payload;
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        let corrupted = format!("VMM3:{}", encoder.encode(&corrupted));
+        assert!(matches!(
+            encoder.decode_mod_string(corrupted),
+            Err(EncodingError::Checksum { .. })
+        ));
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
     #[test]
     fn format_encoder_data() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let mods = vec![
             EncoderData {
                 mod_id: "foo".to_string(),
@@ -271,7 +996,7 @@ mod tests {
 
     #[test]
     fn decompress_data() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let data = encoder.compress("hello").unwrap();
         let decompressed = encoder.decompress(&data).unwrap();
         assert_eq!(decompressed, "hello");
@@ -279,7 +1004,7 @@ mod tests {
 
     #[test]
     fn compress_data() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let data = "hello";
         let compressed = encoder.compress(data).unwrap();
         assert!(!compressed.is_empty());
@@ -287,17 +1012,125 @@ mod tests {
 
     #[test]
     fn decode_mod_string_with_invalid_data() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let data = "invalid_data";
         let result = encoder.decode_mod_string(data.to_string());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn raw_ids_round_trip() {
+        let encoder = Encoder::new();
+        let mods = vec![
+            EncoderData {
+                mod_id: "foo".to_string(),
+                mod_version: "1.10".to_string(),
+            },
+            EncoderData {
+                mod_id: "bar".to_string(),
+                mod_version: "2.0.0-rc.1".to_string(),
+            },
+        ];
+        let formatted = encoder.format_raw_ids(&mods);
+        assert_eq!(formatted, "foo@1.10\nbar@2.0.0-rc.1");
+        assert_eq!(encoder.parse_raw_ids(&formatted).unwrap(), mods);
+    }
+
+    #[test]
+    fn parse_raw_ids_skips_comments_and_allows_missing_version() {
+        let encoder = Encoder::new();
+        let parsed = encoder
+            .parse_raw_ids("# my pack\n\nfoo@1.0\n  bar  \n")
+            .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].mod_id, "bar");
+        assert_eq!(parsed[1].mod_version, "");
+        assert!(encoder.parse_raw_ids("@1.0").is_err());
+    }
+
     #[test]
     fn format_empty_encoder_data() {
-        let encoder = Encoder::new(false);
+        let encoder = Encoder::new();
         let mods: Vec<EncoderData> = vec![];
         let formatted = encoder.format_encoder_data(&mods);
         assert_eq!(formatted, "");
     }
+
+    fn sample_mods() -> Vec<EncoderData> {
+        vec![
+            EncoderData {
+                mod_id: "foo".to_string(),
+                mod_version: "1.10".to_string(),
+            },
+            EncoderData {
+                mod_id: "bar".to_string(),
+                mod_version: "2.0".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn codecs_round_trip() {
+        let encoder = Encoder::new();
+        for format in [
+            ModListFormat::String,
+            ModListFormat::Ids,
+            ModListFormat::Launcher,
+        ] {
+            let codec = format.codec(&encoder);
+            let encoded = codec.encode(&sample_mods()).unwrap();
+            assert_eq!(ModListFormat::detect(&encoded, &encoder), format);
+            assert_eq!(codec.decode(&encoded).unwrap(), sample_mods());
+        }
+    }
+
+    #[test]
+    fn community_formats_decode() {
+        let encoder = Encoder::new();
+
+        let ids = ModListFormat::Modids
+            .codec(&encoder)
+            .decode("# server mods\nfoo, bar@2.0\n\n")
+            .unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[1].mod_id, "bar");
+        assert!(ids[1].mod_version.is_empty());
+
+        let launcher = ModListFormat::Launcher
+            .codec(&encoder)
+            .decode(r#"[{"modid": "foo", "version": "1.10"}, {"modId": "bar"}]"#)
+            .unwrap();
+        assert_eq!(launcher[0], sample_mods()[0]);
+        assert_eq!(launcher[1].mod_id, "bar");
+    }
+
+    #[test]
+    fn detailed_formats_round_trip() {
+        let encoder = Encoder::new();
+        let entries = vec![ModListEntry {
+            name: Some("Foo, the | mod".to_string()),
+            authors: vec!["Ann".to_string(), "Bob \"B\"".to_string()],
+            url: Some("https://example.com/foo_v1.10.zip".to_string()),
+            ..ModListEntry::from(&sample_mods()[0])
+        }];
+
+        for format in [
+            ModListFormat::Json,
+            ModListFormat::Toml,
+            ModListFormat::Csv,
+            ModListFormat::Markdown,
+        ] {
+            let codec = format.codec(&encoder);
+            let encoded = codec.encode_entries(&entries).unwrap();
+            assert!(encoded.contains("example.com"), "{format:?}");
+
+            let detected = ModListFormat::detect(&encoded, &encoder);
+            // JSON manifests are read by the launcher codec
+            if format != ModListFormat::Json {
+                assert_eq!(detected, format);
+            }
+            let decoded = detected.codec(&encoder).decode(&encoded).unwrap();
+            assert_eq!(decoded, sample_mods()[..1], "{format:?}");
+        }
+    }
 }
@@ -1,15 +1,25 @@
 use crate::api::ModInfo;
-use crate::utils::{CliFlags, LogLevel, Logger, get_vintage_mods_dir};
+use crate::config::InstallMode;
+use crate::utils::code_mods::{modinfo_from_assembly, modinfo_from_source};
+use crate::utils::{
+    CliFlags, ModIndex, ProgressBarWrapper, compare_versions, get_store_dir, get_vintage_mods_dir,
+};
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
-
-use std::io::Read;
+use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
 use thiserror::Error;
 use tokio::fs;
 use tokio::io::AsyncReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::ReadDirStream;
+use tracing::{debug, warn};
 use zip::ZipArchive;
 
 #[derive(Error, Debug)]
@@ -24,13 +34,71 @@ pub enum FileError {
     Utf8(#[from] std::str::Utf8Error),
     #[error("File not found: {0}")]
     FileNotFound(String),
+    #[error("Task error: {0}")]
+    Task(#[from] tokio::task::JoinError),
+    #[error(
+        "No mods directory available (start the game once, run `config create-mods-dir`, or pass --dir)"
+    )]
+    NoModsDir,
+    #[error("Not a valid mod archive: {0}")]
+    InvalidMod(String),
+    #[error("Checksum mismatch for {path}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Why `clean` deletes a file from a mods directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clutter {
+    /// An older copy of a mod that is installed in a newer version at `kept`.
+    Duplicate { modid: String, kept: PathBuf },
+    /// A disabled copy of a mod that is also installed and enabled.
+    DisabledDuplicate { modid: String },
+    /// An unfinished download or install.
+    Partial,
+    /// A zip archive without a `modinfo.json`, which the game can't load.
+    NotAMod,
+}
+
+impl fmt::Display for Clutter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Clutter::Duplicate { modid, kept } => {
+                let kept = kept.file_name().unwrap_or_default().to_string_lossy();
+                write!(f, "older copy of {modid}, keeping {kept}")
+            }
+            Clutter::DisabledDuplicate { modid } => {
+                write!(f, "disabled copy of {modid}, which is installed")
+            }
+            Clutter::Partial => write!(f, "unfinished download"),
+            Clutter::NotAMod => write!(f, "zip without modinfo.json"),
+        }
+    }
 }
 
 /// Struct to manage file operations with logging.
 pub struct FileManager {
-    /// Logger instance for logging file operations.
-    logger: Logger,
-    base_path: PathBuf,
+    /// Mods directories to operate on, in order. New mods are installed into the first one.
+    mod_dirs: Vec<PathBuf>,
+    /// How mod files are placed into the mods directory.
+    install_mode: InstallMode,
+    /// Content-addressed store used by linked install modes.
+    store_dir: Option<PathBuf>,
+    /// `(uid, gid)` that newly installed mod files are handed over to.
+    owner: Option<(u32, u32)>,
+    /// Template new mod files are named after, instead of the name they have on ModDB.
+    filename_template: Option<String>,
+    /// Where the [`ModIndex`] of installed mods is kept, if anywhere.
+    mod_index: Option<PathBuf>,
+}
+
+impl Default for FileManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FileManager {
@@ -38,14 +106,78 @@ impl FileManager {
     ///
     /// # Returns
     ///
-    /// A new `FileManager` instance with a default logger.
-    pub fn new(verbose: bool) -> Self {
+    /// A new `FileManager` instance for the default mods directory.
+    pub fn new() -> Self {
         Self {
-            logger: Logger::new("FileManager".to_string(), LogLevel::Info, None, verbose),
-            base_path: get_vintage_mods_dir().unwrap_or_default(),
+            mod_dirs: get_vintage_mods_dir().into_iter().collect(),
+            install_mode: InstallMode::Copy,
+            store_dir: get_store_dir(),
+            owner: None,
+            filename_template: None,
+            mod_index: None,
         }
     }
 
+    /// Sets how mod files are placed into the mods directory.
+    pub fn with_install_mode(mut self, install_mode: InstallMode) -> Self {
+        self.install_mode = install_mode;
+        self
+    }
+
+    /// Sets the `(uid, gid)` that newly installed mod files are handed over to.
+    pub fn with_owner(mut self, owner: Option<(u32, u32)>) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Sets the template new mod files are named after (see [`render_filename`]).
+    pub fn with_filename_template(mut self, filename_template: Option<String>) -> Self {
+        self.filename_template = filename_template;
+        self
+    }
+
+    /// Keeps the `modinfo.json` of installed mods in an index at `path`, so unchanged
+    /// zips aren't opened again when mods are listed. `None` reads every zip.
+    pub fn with_mod_index(mut self, path: Option<PathBuf>) -> Self {
+        self.mod_index = path;
+        self
+    }
+
+    /// Returns the configured filename template, if any.
+    pub fn filename_template(&self) -> Option<&str> {
+        self.filename_template.as_deref()
+    }
+
+    /// Sets the content-addressed store used by linked install modes.
+    pub fn with_store_dir(mut self, store_dir: PathBuf) -> Self {
+        self.store_dir = Some(store_dir);
+        self
+    }
+
+    /// Sets the mods directories to operate on, replacing the default mods directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `mod_dirs` - The directories in priority order. New mods are installed into the first.
+    ///
+    /// # Returns
+    ///
+    /// The updated `FileManager` instance.
+    pub fn with_mod_dirs(mut self, mod_dirs: Vec<PathBuf>) -> Self {
+        self.mod_dirs = mod_dirs;
+        self
+    }
+
+    /// Returns the mods directories in priority order.
+    pub fn mod_dirs(&self) -> &[PathBuf] {
+        &self.mod_dirs
+    }
+
+    /// Returns the directory new mods are installed into.
+    pub fn install_dir(&self) -> Result<PathBuf, FileError> {
+        self.mod_dirs.first().cloned().ok_or(FileError::NoModsDir)
+    }
+
     /// Saves a file asynchronously.
     ///
     /// # Arguments
@@ -57,15 +189,150 @@ impl FileManager {
     ///
     /// A `Result` indicating success or failure.
     pub async fn save_file(&self, file_name: &PathBuf, bytes: &[u8]) -> Result<(), std::io::Error> {
-        self.logger
-            .log_default(&format!("Saving file: {}", file_name.display()));
-        let mut file = fs::File::create(file_name).await?;
+        debug!("Saving file: {}", file_name.display());
+        // Write next to the destination and rename, so an interrupted write never leaves a
+        // truncated mod behind
+        let part = part_path(file_name);
+        let mut file = fs::File::create(&part).await?;
         file.write_all(bytes).await?;
+        file.flush().await?;
+        drop(file);
+        fs::rename(&part, file_name).await?;
+        Ok(())
+    }
+
+    /// Installs a downloaded mod file according to the configured install mode.
+    ///
+    /// In the linked modes the file is stored once in the content-addressed store (named by
+    /// its SHA-256) and linked into place. If the platform or filesystem doesn't allow the
+    /// requested link type, the next option is tried: symlink, then hardlink, then a copy.
+    ///
+    /// The installed file is read back and compared against the SHA-256 of `bytes`; on a
+    /// mismatch it is removed and `FileError::ChecksumMismatch` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The destination path in the mods directory.
+    /// * `bytes` - The content of the mod file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure.
+    pub async fn install_mod_file(
+        &self, file_name: &PathBuf, bytes: &[u8],
+    ) -> Result<(), FileError> {
+        self.place_mod_file(file_name, bytes).await?;
+
+        let expected = Self::hash_bytes(bytes);
+        let actual = Self::hash_file(file_name)?;
+        if actual != expected {
+            let _ = fs::remove_file(file_name).await;
+            return Err(FileError::ChecksumMismatch {
+                path: file_name.clone(),
+                expected,
+                actual,
+            });
+        }
+
+        #[cfg(unix)]
+        if let Some((uid, gid)) = self.owner {
+            debug!("Changing owner of {} to {uid}:{gid}", file_name.display());
+            std::os::unix::fs::lchown(file_name, Some(uid), Some(gid))?;
+        }
+
+        Ok(())
+    }
+
+    /// Gives a replacement mod file the permissions and (on Unix) ownership of the file it
+    /// replaces, so servers running under a different account can still read it.
+    ///
+    /// Symlinked installs are left alone, as their target is shared through the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The newly installed file.
+    /// * `original` - Metadata of the file that was replaced.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure.
+    pub fn preserve_metadata(
+        &self, path: &Path, original: &std::fs::Metadata,
+    ) -> Result<(), FileError> {
+        if std::fs::symlink_metadata(path)?.file_type().is_symlink() {
+            return Ok(());
+        }
+
+        std::fs::set_permissions(path, original.permissions())?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            let current = std::fs::metadata(path)?;
+            if (current.uid(), current.gid()) != (original.uid(), original.gid())
+                && let Err(e) =
+                    std::os::unix::fs::chown(path, Some(original.uid()), Some(original.gid()))
+            {
+                warn!(
+                    "Could not restore owner {}:{} of {}: {e}",
+                    original.uid(),
+                    original.gid(),
+                    path.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn place_mod_file(&self, file_name: &PathBuf, bytes: &[u8]) -> Result<(), FileError> {
+        let store_dir = match (&self.install_mode, &self.store_dir) {
+            (InstallMode::Copy, _) | (_, None) => {
+                self.save_file(file_name, bytes).await?;
+                return Ok(());
+            }
+            (_, Some(store_dir)) => store_dir,
+        };
+
+        let hash = Self::hash_bytes(bytes);
+        let extension = file_name
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        let stored = store_dir.join(format!("{hash}{extension}"));
+
+        if !stored.exists() {
+            fs::create_dir_all(store_dir).await?;
+            self.save_file(&stored, bytes).await?;
+        }
+        if fs::symlink_metadata(file_name).await.is_ok() {
+            fs::remove_file(file_name).await?;
+        }
+
+        if self.install_mode == InstallMode::Symlink && symlinks_supported(store_dir) {
+            debug!("Symlinking {} -> {}", file_name.display(), stored.display());
+            if create_symlink(&stored, file_name).is_ok() {
+                return Ok(());
+            }
+        }
+
+        debug!(
+            "Hardlinking {} -> {}",
+            file_name.display(),
+            stored.display()
+        );
+        if std::fs::hard_link(&stored, file_name).is_ok() {
+            return Ok(());
+        }
+
+        warn!("Linking is not supported here, falling back to copying the file");
+        fs::copy(&stored, file_name).await?;
         Ok(())
     }
 
     async fn validate_path(&self, path: &PathBuf) -> Result<(), FileError> {
-        if !path.starts_with(&self.base_path) {
+        if !self.mod_dirs.iter().any(|dir| path.starts_with(dir)) {
             return Err(FileError::InvalidPath(path.to_owned()));
         }
         Ok(())
@@ -86,8 +353,7 @@ impl FileManager {
     ///
     /// A `Result` indicating success or failure.
     pub fn save_file_sync(&self, file_name: &str, bytes: &[u8]) -> Result<(), std::io::Error> {
-        self.logger
-            .log_default(&format!("Saving file: {file_name}"));
+        debug!("Saving file: {file_name}");
         let mut file = File::create(file_name)?;
         std::io::Write::write_all(&mut file, bytes)?;
         Ok(())
@@ -129,8 +395,7 @@ impl FileManager {
     ///
     /// A `Result` containing the file content as `Bytes` or an error.
     pub fn read_file_sync(&self, file_name: &str) -> Result<Vec<u8>, std::io::Error> {
-        self.logger
-            .log_default(&format!("Reading file: {file_name}"));
+        debug!("Reading file: {file_name}");
         let mut file = File::open(file_name)?;
         let mut contents = Vec::new();
         Read::read_to_end(&mut file, &mut contents)?;
@@ -147,18 +412,129 @@ impl FileManager {
     ///
     /// A `Result` containing the content of `mod_info.json` as a `Vec<u8>` or an error.
     pub fn read_mod_info_from_zip(&self, path: &PathBuf) -> Result<Vec<u8>, FileError> {
-        self.logger
-            .log_default(&format!("Reading zip file: {}", path.display()));
+        debug!("Reading zip file: {}", path.display());
         if !self.is_valid_mod_file(path) {
             return Err(FileError::InvalidPath(path.to_owned()));
         }
 
-        let file = File::open(path)?;
-        let mut archive = ZipArchive::new(file)?;
-        let mut mod_info = archive.by_name("modinfo.json")?;
-        let mut contents = Vec::new();
-        mod_info.read_to_end(&mut contents)?;
-        Ok(contents)
+        read_zip_modinfo(path)
+    }
+
+    /// Reads and validates the `modinfo.json` of a mod zip that is not installed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The mod zip to inspect.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `ModInfo`, or `FileError::InvalidMod` if the archive has no readable
+    /// `modinfo.json` declaring both a modid and a version.
+    pub fn read_local_mod(&self, path: &Path) -> Result<ModInfo, FileError> {
+        debug!("Inspecting mod archive: {}", path.display());
+        if !path.is_file() {
+            return Err(FileError::FileNotFound(path.display().to_string()));
+        }
+
+        let invalid = |reason: &str| FileError::InvalidMod(format!("{}: {reason}", path.display()));
+        let bytes = match read_zip_modinfo(path) {
+            Ok(bytes) => bytes,
+            Err(FileError::Zip(zip::result::ZipError::FileNotFound)) => {
+                return Err(invalid("missing modinfo.json"));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let info = parse_mod_info(&bytes).ok_or_else(|| invalid("unreadable modinfo.json"))?;
+        if info.modid.is_none() || info.version.is_none() {
+            return Err(invalid("modinfo.json has no modid or version"));
+        }
+        Ok(info)
+    }
+
+    /// Checks that a mod zip can be loaded: every file in it is read, so damaged entries
+    /// fail their CRC check, and its `modinfo.json` is validated like
+    /// [`FileManager::read_local_mod`] does.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `ModInfo`, or `FileError::InvalidMod` describing what is broken.
+    pub fn verify_archive(&self, path: &Path) -> Result<ModInfo, FileError> {
+        debug!("Verifying mod archive: {}", path.display());
+        let invalid =
+            |reason: String| FileError::InvalidMod(format!("{}: {reason}", path.display()));
+        let mut archive = ZipArchive::new(File::open(path)?).map_err(|e| invalid(e.to_string()))?;
+        for index in 0..archive.len() {
+            let mut entry = archive
+                .by_index(index)
+                .map_err(|e| invalid(e.to_string()))?;
+            std::io::copy(&mut entry, &mut std::io::sink())
+                .map_err(|e| invalid(format!("{}: {e}", entry.name())))?;
+        }
+        self.read_local_mod(path)
+    }
+
+    /// Lists the mod zips in the mods directories, whether or not they can be read.
+    pub fn mod_archives(&self) -> Result<Vec<PathBuf>, FileError> {
+        let mut archives = Vec::new();
+        for dir in self.mod_dirs.iter().filter(|dir| dir.is_dir()) {
+            for entry in std::fs::read_dir(dir)? {
+                let path = entry?.path();
+                if path.is_file() && self.is_valid_mod_file(&path) {
+                    archives.push(path);
+                }
+            }
+        }
+        archives.sort();
+        Ok(archives)
+    }
+
+    /// Removes `.part` files modified since `since` from `dirs`, e.g. after a command was
+    /// cancelled mid-download, along with the `.validator` files of resumable downloads.
+    ///
+    /// # Arguments
+    ///
+    /// * `dirs` - The directories to clean, missing ones are skipped.
+    /// * `since` - Only files written after this time are removed.
+    ///
+    /// # Returns
+    ///
+    /// The removed files.
+    pub fn remove_partial_files(dirs: &[PathBuf], since: SystemTime) -> Vec<PathBuf> {
+        let mut removed = Vec::new();
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_part = path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().ends_with(PART_SUFFIX));
+                let recent = entry
+                    .metadata()
+                    .and_then(|meta| meta.modified())
+                    .is_ok_and(|modified| modified >= since);
+                if is_part && recent && std::fs::remove_file(&path).is_ok() {
+                    // Downloads keep the ETag of the file they resume next to the part
+                    let _ = std::fs::remove_file(path.with_extension("validator"));
+                    removed.push(path);
+                }
+            }
+        }
+        removed
+    }
+
+    /// Returns the filename a mod is stored under, rendered from the configured filename
+    /// template or [`DEFAULT_FILENAME_TEMPLATE`].
+    pub fn mod_filename(&self, modid: &str, version: &str) -> String {
+        render_filename(
+            self.filename_template
+                .as_deref()
+                .unwrap_or(DEFAULT_FILENAME_TEMPLATE),
+            modid,
+            version,
+        )
     }
 
     /// Deletes a file asynchronously.
@@ -171,9 +547,13 @@ impl FileManager {
     ///
     /// A `Result` indicating success or failure.
     pub async fn delete_file(&self, path_buf: &PathBuf) -> Result<(), FileError> {
-        self.logger
-            .log_default(&format!("Deleting file: {}", path_buf.display()));
-        fs::remove_file(path_buf).await?;
+        debug!("Deleting file: {}", path_buf.display());
+        if path_buf.is_dir() {
+            // Unpacked mods are folders
+            fs::remove_dir_all(path_buf).await?;
+        } else {
+            fs::remove_file(path_buf).await?;
+        }
         Ok(())
     }
 
@@ -187,8 +567,7 @@ impl FileManager {
     ///
     /// A `Result` indicating success or failure.
     pub fn delete_file_sync(&self, file_name: &str) -> Result<(), std::io::Error> {
-        self.logger
-            .log_default(&format!("Deleting file: {file_name}"));
+        debug!("Deleting file: {file_name}");
         std::fs::remove_file(file_name)?;
         Ok(())
     }
@@ -203,8 +582,7 @@ impl FileManager {
     ///
     /// A `Result` containing `true` if the file exists, `false` if it does not, or an error.
     pub async fn file_exists(&self, file_name: &str) -> Result<bool, std::io::Error> {
-        self.logger
-            .log_default(&format!("Checking if file exists: {file_name}"));
+        debug!("Checking if file exists: {file_name}");
         match fs::metadata(file_name).await {
             Ok(_) => Ok(true),
             Err(e) => {
@@ -227,10 +605,7 @@ impl FileManager {
     pub async fn get_files_in_directory(
         &self, directory: &PathBuf,
     ) -> Result<Vec<String>, std::io::Error> {
-        self.logger.log_default(&format!(
-            "Getting files in directory: {}",
-            directory.display()
-        ));
+        debug!("Getting files in directory: {}", directory.display());
         let mut files = vec![];
         let entries = fs::read_dir(directory).await?;
         let mut entries = ReadDirStream::new(entries);
@@ -243,6 +618,72 @@ impl FileManager {
         Ok(files)
     }
 
+    /// Computes the SHA-256 checksum of in-memory data, as a lowercase hex digest.
+    pub fn hash_bytes(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    /// Computes the SHA-256 checksum of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to hash.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the lowercase hex digest or an error.
+    pub fn hash_file(path: &Path) -> Result<String, FileError> {
+        let mut file = File::open(path)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Computes the SHA-256 checksums of many files in parallel.
+    ///
+    /// Hashing runs on rayon's worker threads inside a blocking task so large installs
+    /// don't stall the async runtime, and progress is shown as files complete.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The files to hash.
+    ///
+    /// # Returns
+    ///
+    /// Each path paired with its hex digest or the error that occurred, in input order.
+    pub async fn hash_files(
+        &self, paths: Vec<PathBuf>,
+    ) -> Result<Vec<(PathBuf, Result<String, FileError>)>, FileError> {
+        debug!("Hashing {} files", paths.len());
+
+        let hashes = tokio::task::spawn_blocking(move || {
+            let progress_bar = ProgressBarWrapper::new(paths.len() as u64);
+            progress_bar.set_message("Hashing mod files");
+
+            let hashes: Vec<_> = paths
+                .into_par_iter()
+                .map(|path| {
+                    let hash = Self::hash_file(&path);
+                    progress_bar.inc(1);
+                    (path, hash)
+                })
+                .collect();
+
+            progress_bar.finish_with_message("Finished hashing mod files");
+            hashes
+        })
+        .await?;
+
+        Ok(hashes)
+    }
+
     pub async fn read_mod_info_from_zips(
         &self, paths: Vec<PathBuf>,
     ) -> Result<Vec<Vec<u8>>, FileError> {
@@ -255,20 +696,200 @@ impl FileManager {
         Ok(zips)
     }
 
+    /// Reads the raw `modinfo.json` of every mod in the mods directories: zips, unpacked
+    /// mod folders, and C# source files and assemblies declaring a `ModInfo` attribute.
+    ///
+    /// Mods in the [`ModIndex`] that haven't changed since are not opened. The others are
+    /// split between [`zip_scan_concurrency`] tasks on the blocking thread pool, so large
+    /// mods directories are read in parallel. Mods are returned in directory order.
     async fn get_mod_info_with_paths(&self) -> Result<Vec<(Vec<u8>, PathBuf)>, FileError> {
-        let mut mod_info = Vec::new();
-        let entries = fs::read_dir(&self.base_path).await?;
-        let mut entries = ReadDirStream::new(entries);
+        if self.mod_dirs.is_empty() {
+            return Err(FileError::NoModsDir);
+        }
 
-        while let Some(entry) = entries.next().await {
-            let entry = entry?;
-            let path = entry.path();
-            if self.is_valid_mod_file(&path) {
-                let zip = self.read_mod_info_from_zip(&path)?;
-                mod_info.push((zip, path));
+        let mut paths = Vec::new();
+        for dir in &self.mod_dirs {
+            if !dir.is_dir() {
+                warn!("Skipping missing mods directory: {}", dir.display());
+                continue;
+            }
+
+            let entries = fs::read_dir(dir).await?;
+            let mut entries = ReadDirStream::new(entries);
+
+            while let Some(entry) = entries.next().await {
+                let path = entry?.path();
+                if self.is_valid_mod_file(&path) || is_unpacked_mod(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+
+        let mut index = self.mod_index.as_deref().map(ModIndex::load);
+        let mut mod_info: Vec<Option<Vec<u8>>> = vec![None; paths.len()];
+        let mut unindexed = Vec::new();
+        for (slot, path) in mod_info.iter_mut().zip(&paths) {
+            let indexed = index.as_ref().and_then(|index| {
+                let metadata = std::fs::metadata(modinfo_source(path)).ok()?;
+                index.get(path, &metadata).map(<[u8]>::to_vec)
+            });
+            match indexed {
+                Some(zip) => *slot = Some(zip),
+                None => unindexed.push(path.clone()),
+            }
+        }
+
+        debug!("Reading {} of {} mod zips", unindexed.len(), paths.len());
+        // One task per chunk rather than per zip, as most modinfo.json files take less time
+        // to read than handing a task to the blocking pool
+        let chunk_size = unindexed.len().div_ceil(zip_scan_concurrency()).max(1);
+        let tasks: Vec<_> = unindexed
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let chunk = chunk.to_vec();
+                tokio::task::spawn_blocking(move || {
+                    chunk
+                        .into_iter()
+                        .filter_map(|path| {
+                            read_modinfo(&path)
+                                .transpose()
+                                .map(|zip| zip.map(|zip| (zip, path)))
+                        })
+                        .collect::<Result<Vec<_>, FileError>>()
+                })
+            })
+            .collect();
+
+        let mut read = HashMap::new();
+        for task in tasks {
+            for (zip, path) in task.await?? {
+                let metadata = std::fs::metadata(modinfo_source(&path));
+                if let (Some(index), Ok(metadata)) = (index.as_mut(), metadata) {
+                    index.insert(&path, &metadata, &zip);
+                }
+                read.insert(path, zip);
+            }
+        }
+
+        if let Some(index) = index.as_mut() {
+            index.retain_present(&self.mod_dirs, &paths);
+            if let Err(e) = index.save() {
+                warn!("Failed to save the mod index: {e}");
             }
         }
-        Ok(mod_info)
+
+        Ok(mod_info
+            .into_iter()
+            .zip(paths)
+            .filter_map(|(indexed, path)| {
+                let zip = indexed.or_else(|| read.remove(&path))?;
+                Some((zip, path))
+            })
+            .collect())
+    }
+
+    /// Collects mods that were disabled by appending `.disabled` to their name, whether
+    /// zips, mod folders or code mods.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing each disabled mod's info and path, or an error.
+    pub async fn collect_disabled_mods(&self) -> Result<Vec<(ModInfo, PathBuf)>, FileError> {
+        let mut disabled = Vec::new();
+        for dir in self.mod_dirs.iter().filter(|dir| dir.is_dir()) {
+            let mut entries = ReadDirStream::new(fs::read_dir(dir).await?);
+            while let Some(entry) = entries.next().await {
+                let path = entry?.path();
+                let is_disabled_mod = enabled_path(&path).is_some_and(|enabled| {
+                    path.join("modinfo.json").is_file()
+                        || (path.is_file() && is_mod_extension(&enabled))
+                });
+                if !is_disabled_mod {
+                    continue;
+                }
+
+                match read_modinfo(&path).map(|bytes| parse_mod_info(&bytes?)) {
+                    Ok(Some(mod_info)) => disabled.push((mod_info, path)),
+                    _ => warn!("Skipping unreadable disabled mod: {}", path.display()),
+                }
+            }
+        }
+        Ok(disabled)
+    }
+
+    /// Disables a mod by appending `.disabled` to its file name, so the game skips it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new path of the mod or an error.
+    pub async fn disable_mod(&self, path: &Path) -> Result<PathBuf, FileError> {
+        let mut disabled = path.as_os_str().to_owned();
+        disabled.push(DISABLED_SUFFIX);
+        let disabled = PathBuf::from(disabled);
+
+        debug!("Disabling {}", path.display());
+        fs::rename(path, &disabled).await?;
+        Ok(disabled)
+    }
+
+    /// Re-enables a mod disabled with [`FileManager::disable_mod`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new path of the mod or an error.
+    pub async fn enable_mod(&self, path: &Path) -> Result<PathBuf, FileError> {
+        let enabled =
+            enabled_path(path).ok_or_else(|| FileError::InvalidPath(path.to_path_buf()))?;
+
+        debug!("Enabling {}", enabled.display());
+        fs::rename(path, &enabled).await?;
+        Ok(enabled)
+    }
+
+    /// Finds files in the mods directories that can be deleted: older copies of mods,
+    /// disabled copies of installed mods, unfinished downloads and zips that aren't mods.
+    ///
+    /// Each directory is checked on its own, so a mod installed in two directories, such
+    /// as the game's and a server's, isn't a duplicate. Of several copies of a mod in a
+    /// directory the highest version is kept, and of equal versions the most recently
+    /// modified one, whether zip, mod folder or code mod. Disabled mods that aren't
+    /// installed otherwise were disabled on purpose and are left alone, as are mods whose
+    /// `modinfo.json` can't be parsed.
+    ///
+    /// # Returns
+    ///
+    /// The files and folders to delete and why, in directory order.
+    pub fn find_clutter(&self) -> Result<Vec<(PathBuf, Clutter)>, FileError> {
+        let mut clutter = Vec::new();
+        for dir in self.mod_dirs.iter().filter(|dir| dir.is_dir()) {
+            clutter.extend(dir_clutter(dir)?);
+        }
+        Ok(clutter)
+    }
+
+    /// Lists the mod IDs of the installed mods, skipping anything unreadable.
+    ///
+    /// Unlike [`FileManager::collect_mods`] this runs synchronously and doesn't touch the
+    /// mod index, so it is cheap enough for shell completion.
+    ///
+    /// # Returns
+    ///
+    /// The mod IDs, sorted and without duplicates.
+    pub fn installed_mod_ids(&self) -> Vec<String> {
+        let mut modids: Vec<String> = self
+            .mod_dirs
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                (path.is_file() && self.is_valid_mod_file(path)) || is_unpacked_mod(path)
+            })
+            .filter_map(|path| parse_mod_info(&read_modinfo(&path).ok()??)?.modid)
+            .collect();
+        modids.sort();
+        modids.dedup();
+        modids
     }
 
     pub async fn collect_mods(
@@ -280,12 +901,7 @@ impl FileManager {
 
         let mods = mod_vec
             .into_iter()
-            .filter_map(|(mod_slice, path)| {
-                let mod_string = std::str::from_utf8(&mod_slice).ok()?;
-                let mod_string = remove_trailing_comma(mod_string);
-                let mod_info: ModInfo = serde_json::from_str(&mod_string.to_lowercase()).ok()?;
-                Some((mod_info, path))
-            })
+            .filter_map(|(mod_slice, path)| Some((parse_mod_info(&mod_slice)?, path)))
             .filter(|(mod_info, _)| {
                 if let Some(mod_) = &option.mod_ {
                     return mod_info
@@ -310,58 +926,263 @@ impl FileManager {
                 }
                 true
             })
+            .filter(|(mod_info, _)| {
+                option
+                    .side
+                    .is_none_or(|side| side.runs(mod_info.side.as_deref()))
+            })
             .collect();
 
         Ok(mods)
     }
 }
 
-fn remove_trailing_comma(json: &str) -> String {
-    let mut result = String::new();
-    let mut in_string = false;
-    let mut in_escape = false;
-
-    for (i, c) in json.chars().enumerate() {
-        if in_escape {
-            in_escape = false;
-        } else if c == '\\' {
-            in_escape = true;
-        } else if c == '"' {
-            in_string = !in_string;
-        }
-
-        if !in_string && c == ',' {
-            if let Some(next_char) = json.chars().nth(i + 1) {
-                if next_char.is_whitespace() {
-                    if let Some(non_whitespace_char) =
-                        json.chars().skip(i + 1).find(|&ch| !ch.is_whitespace())
-                    {
-                        if !"{[\"'\\w".contains(non_whitespace_char) {
-                            continue;
-                        }
-                    }
-                } else if !"{[\"'\\w".contains(next_char) {
-                    continue;
-                }
+/// Suffix appended to mod files to hide them from the game.
+pub const DISABLED_SUFFIX: &str = ".disabled";
+
+/// Filename template used when none is configured.
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{modid}_v{version}.zip";
+
+/// Renders a filename template, replacing `{modid}` and `{version}`.
+///
+/// Path separators in the values are replaced so a malformed version can't move the file
+/// out of the mods directory.
+pub fn render_filename(template: &str, modid: &str, version: &str) -> String {
+    let sanitize = |value: &str| value.replace(['/', '\\'], "_");
+    template
+        .replace("{modid}", &sanitize(modid))
+        .replace("{version}", &sanitize(version))
+}
+
+/// Suffix of files that are still being written.
+pub const PART_SUFFIX: &str = ".part";
+
+/// Returns the temporary path a file is written to before being renamed into place.
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(PART_SUFFIX);
+    path.with_file_name(name)
+}
+
+/// How many threads read mod zips at once: one per CPU core, as reading is mostly
+/// decompression.
+fn zip_scan_concurrency() -> usize {
+    std::thread::available_parallelism().map_or(4, usize::from)
+}
+
+/// Returns when a file was last modified, or `None` if that isn't known.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Returns `true` for mod zips, as opposed to mod folders and code mods.
+pub fn is_mod_zip(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext == "zip")
+}
+
+/// Returns `true` for mods the game loads besides zips: folders with a `modinfo.json`,
+/// and standalone C# source files and assemblies. Disabled folders are not loaded.
+fn is_unpacked_mod(path: &Path) -> bool {
+    if path.is_dir() {
+        return enabled_path(path).is_none() && path.join("modinfo.json").is_file();
+    }
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext == "cs" || ext == "dll")
+}
+
+/// Returns `true` if the file name has the extension of a mod the game loads.
+fn is_mod_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "zip" || ext == "cs" || ext == "dll")
+}
+
+/// Finds the clutter in one mods directory, as described for [`FileManager::find_clutter`].
+fn dir_clutter(dir: &Path) -> Result<Vec<(PathBuf, Clutter)>, FileError> {
+    let mut clutter = Vec::new();
+    let mut installed: HashMap<String, Vec<(ModInfo, PathBuf)>> = HashMap::new();
+    let mut disabled = Vec::new();
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.sort();
+
+    for path in paths {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_file() && name.ends_with(PART_SUFFIX) {
+            clutter.push((path, Clutter::Partial));
+            continue;
+        }
+        let enabled = enabled_path(&path);
+        let is_disabled = enabled.as_deref().is_some_and(|enabled| {
+            path.join("modinfo.json").is_file() || (path.is_file() && is_mod_extension(enabled))
+        });
+        if !is_disabled && !is_mod_zip(&path) && !is_unpacked_mod(&path) {
+            continue;
+        }
+
+        let mod_info = match read_modinfo(&path) {
+            Ok(Some(bytes)) => parse_mod_info(&bytes),
+            // Code mods without mod info are libraries other mods load
+            Ok(None) => continue,
+            Err(FileError::Io(e)) => return Err(FileError::Io(e)),
+            Err(_) => {
+                clutter.push((path, Clutter::NotAMod));
+                continue;
             }
+        };
+        let Some(modid) = mod_info.as_ref().and_then(|info| info.modid.clone()) else {
+            warn!("Skipping unreadable mod: {}", path.display());
+            continue;
+        };
+        if is_disabled {
+            disabled.push((modid.to_lowercase(), path));
+        } else {
+            installed
+                .entry(modid.to_lowercase())
+                .or_default()
+                .push((mod_info.unwrap_or_default(), path));
         }
+    }
+
+    let mut modids: Vec<_> = installed.keys().cloned().collect();
+    modids.sort();
+    for modid in modids {
+        let Some(copies) = installed.get_mut(&modid).filter(|copies| copies.len() > 1) else {
+            continue;
+        };
+        copies.sort_by(|(a, a_path), (b, b_path)| {
+            let version = |info: &ModInfo| info.version.clone().unwrap_or_default();
+            compare_versions(&version(b), &version(a)).then_with(|| {
+                modified(&modinfo_source(b_path)).cmp(&modified(&modinfo_source(a_path)))
+            })
+        });
+        let kept = copies[0].1.clone();
+        for (_, path) in copies.drain(1..) {
+            let reason = Clutter::Duplicate {
+                modid: modid.clone(),
+                kept: kept.clone(),
+            };
+            clutter.push((path, reason));
+        }
+    }
+    for (modid, path) in disabled {
+        if installed.contains_key(&modid) {
+            clutter.push((path, Clutter::DisabledDuplicate { modid }));
+        }
+    }
 
-        result.push(c);
+    Ok(clutter)
+}
+
+/// The path a disabled mod is enabled at, or `None` if it isn't disabled.
+fn enabled_path(path: &Path) -> Option<PathBuf> {
+    path.to_str()?
+        .strip_suffix(DISABLED_SUFFIX)
+        .map(PathBuf::from)
+}
+
+/// The file whose changes mean a mod has to be read again: the `modinfo.json` of a mod
+/// folder, or the mod file itself.
+fn modinfo_source(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join("modinfo.json")
+    } else {
+        path.to_path_buf()
     }
+}
+
+/// Reads the raw `modinfo.json` of a mod, whatever form it comes in. The mod info of
+/// code mods is converted to `modinfo.json`.
+///
+/// # Returns
+///
+/// The `modinfo.json`, or `None` for C# files and assemblies that declare no mod info,
+/// such as libraries mods depend on.
+fn read_modinfo(path: &Path) -> Result<Option<Vec<u8>>, FileError> {
+    // Disabled mods are read as the kind of mod they are once enabled
+    let kind = enabled_path(path).unwrap_or_else(|| path.to_path_buf());
+    let modinfo = match kind.extension().and_then(|ext| ext.to_str()) {
+        _ if path.is_dir() => return Ok(Some(std::fs::read(path.join("modinfo.json"))?)),
+        Some("cs") => modinfo_from_source(&String::from_utf8_lossy(&std::fs::read(path)?)),
+        Some("dll") => modinfo_from_assembly(&std::fs::read(path)?),
+        _ => return read_zip_modinfo(path).map(Some),
+    };
+    if modinfo.is_none() {
+        debug!("No mod info declared in {}", path.display());
+    }
+    Ok(modinfo.map(|modinfo| modinfo.to_string().into_bytes()))
+}
+
+/// Reads the mod info of a mod zip that is still in memory, such as a download.
+///
+/// # Returns
+///
+/// The parsed `ModInfo`, or `None` if the archive has no readable `modinfo.json`.
+pub fn mod_info_from_zip(bytes: &[u8]) -> Option<ModInfo> {
+    parse_mod_info(&zip_modinfo(std::io::Cursor::new(bytes)).ok()?)
+}
+
+/// Reads the raw `modinfo.json` from a mod zip.
+fn read_zip_modinfo(path: &Path) -> Result<Vec<u8>, FileError> {
+    zip_modinfo(File::open(path)?)
+}
+
+/// Reads the raw `modinfo.json` from a mod zip archive.
+fn zip_modinfo(reader: impl Read + Seek) -> Result<Vec<u8>, FileError> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut mod_info = archive.by_name("modinfo.json")?;
+    let mut contents = Vec::new();
+    mod_info.read_to_end(&mut contents)?;
+    Ok(contents)
+}
 
-    result
+/// Parses a raw `modinfo.json` with [`ModInfo::parse`].
+fn parse_mod_info(bytes: &[u8]) -> Option<ModInfo> {
+    ModInfo::parse(std::str::from_utf8(bytes).ok()?)
+}
+
+/// Checks once per run whether symlinks can be created, which on Windows requires
+/// developer mode or administrator rights.
+fn symlinks_supported(dir: &Path) -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        let target = dir.join(".symlink-probe-target");
+        let link = dir.join(".symlink-probe-link");
+        let supported =
+            std::fs::write(&target, b"").is_ok() && create_symlink(&target, &link).is_ok();
+        let _ = std::fs::remove_file(&link);
+        let _ = std::fs::remove_file(&target);
+        supported
+    })
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> Result<(), std::io::Error> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> Result<(), std::io::Error> {
+    std::os::windows::fs::symlink_file(target, link)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::mock_moddb::{mod_zip, modinfo_zip, zip_of};
+    use crate::utils::cli::Side;
     use std::io::Write;
     use std::path::PathBuf;
     use tempfile::tempdir;
 
     #[tokio::test]
     async fn read_file_returns_correct_content() {
-        let file_manager = FileManager::new(false);
+        let file_manager = FileManager::new();
         let test_content = b"test content";
         let temp_dir = tempdir().unwrap();
         let test_file_path = temp_dir.path().join("test_read_file.txt");
@@ -371,6 +1192,8 @@ mod tests {
         println!("test_file_path: {test_file_path:?}");
 
         file.write_all(test_content).await.unwrap();
+        // tokio writes in the background, the content may not be on disk until flushed
+        file.flush().await.unwrap();
 
         let read_content = file_manager.read_file(&test_file_path).await.unwrap();
         assert_eq!(read_content, test_content);
@@ -384,7 +1207,7 @@ mod tests {
         let test_content = b"test content";
 
         // Create a test file manager
-        let file_manager = FileManager::new(false);
+        let file_manager = FileManager::new();
 
         // Save the test content
         file_manager
@@ -401,7 +1224,7 @@ mod tests {
 
     #[tokio::test]
     async fn delete_file_removes_file() {
-        let file_manager = FileManager::new(false);
+        let file_manager = FileManager::new();
         let file_name = &PathBuf::from("test_delete_file.txt");
         let content = "Hello, World!".as_bytes();
 
@@ -415,7 +1238,7 @@ mod tests {
 
     #[tokio::test]
     async fn file_exists_returns_true_for_existing_file() {
-        let file_manager = FileManager::new(false);
+        let file_manager = FileManager::new();
         let file_name = "test_file_exists.txt";
         let content = "Hello, World!".as_bytes();
 
@@ -426,12 +1249,358 @@ mod tests {
         std::fs::remove_file(file_name).unwrap();
     }
 
+    #[test]
+    fn find_clutter_keeps_the_newest_copy() {
+        let mods_dir = tempdir().unwrap();
+        let write = |name: &str, bytes: Vec<u8>| {
+            std::fs::write(mods_dir.path().join(name), bytes).unwrap();
+        };
+        write("foo_v1.10.0.zip", mod_zip("foo", "1.10.0"));
+        write("foo_v1.9.0.zip", mod_zip("foo", "1.9.0"));
+        write("foo_v1.0.0.zip.disabled", mod_zip("foo", "1.0.0"));
+        write("bar.zip.disabled", mod_zip("bar", "1.0.0"));
+        write("textures.zip", zip_of(&[("readme.txt", b"")]));
+        write("hudclock_v1.0.0.zip", mod_zip("hudclock", "1.0.0"));
+        let folder = mods_dir.path().join("hudclock");
+        std::fs::create_dir(&folder).unwrap();
+        std::fs::write(
+            folder.join("modinfo.json"),
+            r#"{"modid": "hudclock", "version": "2.0.0"}"#,
+        )
+        .unwrap();
+        std::fs::write(mods_dir.path().join("baz.zip.part"), b"partial").unwrap();
+        std::fs::write(mods_dir.path().join("notes.txt"), b"keep me").unwrap();
+        // A copy in another directory, such as a server's, isn't a duplicate
+        let server_dir = tempdir().unwrap();
+        std::fs::write(
+            server_dir.path().join("foo_v1.0.0.zip"),
+            mod_zip("foo", "1.0.0"),
+        )
+        .unwrap();
+
+        let file_manager = FileManager::new().with_mod_dirs(vec![
+            mods_dir.path().to_path_buf(),
+            server_dir.path().to_path_buf(),
+        ]);
+        let clutter = file_manager.find_clutter().unwrap();
+        let names: Vec<(String, &Clutter)> = clutter
+            .iter()
+            .map(|(path, reason)| {
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                (name, reason)
+            })
+            .collect();
+
+        let kept = mods_dir.path().join("foo_v1.10.0.zip");
+        assert_eq!(
+            names,
+            vec![
+                ("baz.zip.part".to_string(), &Clutter::Partial),
+                ("textures.zip".to_string(), &Clutter::NotAMod),
+                (
+                    "foo_v1.9.0.zip".to_string(),
+                    &Clutter::Duplicate {
+                        modid: "foo".to_string(),
+                        kept
+                    }
+                ),
+                (
+                    "hudclock_v1.0.0.zip".to_string(),
+                    &Clutter::Duplicate {
+                        modid: "hudclock".to_string(),
+                        kept: folder.clone()
+                    }
+                ),
+                (
+                    "foo_v1.0.0.zip.disabled".to_string(),
+                    &Clutter::DisabledDuplicate {
+                        modid: "foo".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn collect_mods_aggregates_all_mod_dirs() {
+        let main_dir = tempdir().unwrap();
+        let extra_dir = tempdir().unwrap();
+        std::fs::write(main_dir.path().join("foo.zip"), mod_zip("foo", "1.0.0")).unwrap();
+        std::fs::write(extra_dir.path().join("bar.zip"), mod_zip("bar", "1.0.0")).unwrap();
+
+        let file_manager = FileManager::new().with_mod_dirs(vec![
+            main_dir.path().to_path_buf(),
+            extra_dir.path().to_path_buf(),
+            PathBuf::from("/does/not/exist"),
+        ]);
+
+        let mut modids: Vec<String> = file_manager
+            .collect_mods(&None)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|(info, _)| info.modid)
+            .collect();
+        modids.sort();
+
+        assert_eq!(modids, vec!["bar", "foo"]);
+        assert_eq!(file_manager.install_dir().unwrap(), main_dir.path());
+    }
+
+    #[tokio::test]
+    async fn collect_mods_rereads_changed_zips() {
+        let mods_dir = tempdir().unwrap();
+        let cache_dir = tempdir().unwrap();
+        let index_path = cache_dir.path().join("mod_index.json");
+        std::fs::write(mods_dir.path().join("foo.zip"), mod_zip("foo", "1.0.0")).unwrap();
+        let file_manager = FileManager::new()
+            .with_mod_dirs(vec![mods_dir.path().to_path_buf()])
+            .with_mod_index(Some(index_path.clone()));
+
+        let mods = file_manager.collect_mods(&None).await.unwrap();
+        assert_eq!(mods[0].0.version.as_deref(), Some("1.0.0"));
+        assert!(
+            std::fs::read_to_string(&index_path)
+                .unwrap()
+                .contains("foo.zip")
+        );
+
+        std::fs::write(mods_dir.path().join("foo.zip"), mod_zip("foo", "1.10.0")).unwrap();
+
+        let mods = file_manager.collect_mods(&None).await.unwrap();
+        assert_eq!(mods[0].0.version.as_deref(), Some("1.10.0"));
+
+        std::fs::remove_file(mods_dir.path().join("foo.zip")).unwrap();
+        assert!(file_manager.collect_mods(&None).await.unwrap().is_empty());
+        assert!(
+            !std::fs::read_to_string(&index_path)
+                .unwrap()
+                .contains("foo.zip")
+        );
+    }
+
+    #[tokio::test]
+    async fn collect_mods_reads_folders_and_code_mods() {
+        let mods_dir = tempdir().unwrap();
+        let folder = mods_dir.path().join("hudclock");
+        std::fs::create_dir(&folder).unwrap();
+        std::fs::write(
+            folder.join("modinfo.json"),
+            r#"{"modid": "hudclock", "version": "1.2.0"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            mods_dir.path().join("CarryOn.cs"),
+            r#"[assembly: ModInfo("Carry On", "carryon", Version = "1.8.0")]"#,
+        )
+        .unwrap();
+        // A library without mod info, and a folder that isn't a mod
+        std::fs::write(mods_dir.path().join("0Harmony.dll"), b"MZ\x90\x00").unwrap();
+        std::fs::create_dir(mods_dir.path().join("screenshots")).unwrap();
+
+        let file_manager = FileManager::new().with_mod_dirs(vec![mods_dir.path().to_path_buf()]);
+        let mut mods: Vec<(String, String)> = file_manager
+            .collect_mods(&None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(info, _)| (info.modid.unwrap(), info.version.unwrap()))
+            .collect();
+        mods.sort();
+
+        assert_eq!(
+            mods,
+            [
+                ("carryon".to_string(), "1.8.0".to_string()),
+                ("hudclock".to_string(), "1.2.0".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_folders_and_code_mods_can_be_enabled_again() {
+        let mods_dir = tempdir().unwrap();
+        let folder = mods_dir.path().join("hudclock");
+        std::fs::create_dir(&folder).unwrap();
+        std::fs::write(
+            folder.join("modinfo.json"),
+            r#"{"modid": "hudclock", "version": "1.2.0"}"#,
+        )
+        .unwrap();
+        let code_mod = mods_dir.path().join("CarryOn.cs");
+        std::fs::write(
+            &code_mod,
+            r#"[assembly: ModInfo("Carry On", "carryon", Version = "1.8.0")]"#,
+        )
+        .unwrap();
+        let file_manager = FileManager::new().with_mod_dirs(vec![mods_dir.path().to_path_buf()]);
+
+        for path in [&folder, &code_mod] {
+            file_manager.disable_mod(path).await.unwrap();
+        }
+        assert!(file_manager.collect_mods(&None).await.unwrap().is_empty());
+        let disabled = file_manager.collect_disabled_mods().await.unwrap();
+        assert_eq!(disabled.len(), 2);
+
+        for (_, path) in disabled {
+            file_manager.enable_mod(&path).await.unwrap();
+        }
+        assert!(folder.join("modinfo.json").is_file());
+        assert!(code_mod.is_file());
+        assert_eq!(file_manager.collect_mods(&None).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn collect_mods_filters_by_side() {
+        let mods_dir = tempdir().unwrap();
+        for (modid, side) in [
+            ("hud", "Client"),
+            ("admin", "Server"),
+            ("both", "Universal"),
+        ] {
+            let modinfo =
+                format!(r#"{{"modid": "{modid}", "version": "1.0.0", "side": "{side}"}}"#);
+            std::fs::write(
+                mods_dir.path().join(format!("{modid}.zip")),
+                modinfo_zip(&modinfo),
+            )
+            .unwrap();
+        }
+        std::fs::write(
+            mods_dir.path().join("noside.zip"),
+            mod_zip("noside", "1.0.0"),
+        )
+        .unwrap();
+        let file_manager = FileManager::new().with_mod_dirs(vec![mods_dir.path().to_path_buf()]);
+
+        let filters = Some(CliFlags {
+            side: Some(Side::Server),
+            ..CliFlags::default()
+        });
+        let mut modids: Vec<String> = file_manager
+            .collect_mods(&filters)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|(info, _)| info.modid)
+            .collect();
+        modids.sort();
+
+        assert_eq!(modids, vec!["admin", "both", "noside"]);
+    }
+
+    #[tokio::test]
+    async fn install_mod_file_links_into_store() {
+        let mods_dir = tempdir().unwrap();
+        let store_dir = tempdir().unwrap();
+        let destination = mods_dir.path().join("foo_v1.0.0.zip");
+
+        for mode in [InstallMode::Symlink, InstallMode::Hardlink] {
+            let file_manager = FileManager::new()
+                .with_install_mode(mode)
+                .with_store_dir(store_dir.path().to_path_buf());
+            file_manager
+                .install_mod_file(&destination, b"mod bytes")
+                .await
+                .unwrap();
+
+            assert_eq!(std::fs::read(&destination).unwrap(), b"mod bytes");
+            assert_eq!(std::fs::read_dir(store_dir.path()).unwrap().count(), 1);
+        }
+
+        #[cfg(unix)]
+        {
+            let file_manager = FileManager::new()
+                .with_install_mode(InstallMode::Symlink)
+                .with_store_dir(store_dir.path().to_path_buf());
+            file_manager
+                .install_mod_file(&destination, b"mod bytes")
+                .await
+                .unwrap();
+            assert!(
+                std::fs::symlink_metadata(&destination)
+                    .unwrap()
+                    .file_type()
+                    .is_symlink()
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn preserve_metadata_copies_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let old_file = temp_dir.path().join("foo_v1.0.0.zip");
+        let new_file = temp_dir.path().join("foo_v1.1.0.zip");
+        std::fs::write(&old_file, b"old").unwrap();
+        std::fs::write(&new_file, b"new").unwrap();
+        std::fs::set_permissions(&old_file, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        let original = std::fs::metadata(&old_file).unwrap();
+        FileManager::new()
+            .preserve_metadata(&new_file, &original)
+            .unwrap();
+
+        let mode = std::fs::metadata(&new_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    #[tokio::test]
+    async fn hash_files_matches_known_digests() {
+        let file_manager = FileManager::new();
+        let temp_dir = tempdir().unwrap();
+        let hello = temp_dir.path().join("hello.zip");
+        let empty = temp_dir.path().join("empty.zip");
+        std::fs::write(&hello, b"hello").unwrap();
+        std::fs::write(&empty, b"").unwrap();
+
+        let hashes = file_manager
+            .hash_files(vec![hello.clone(), empty.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(hashes[0].0, hello);
+        assert_eq!(
+            hashes[0].1.as_ref().unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(
+            hashes[1].1.as_ref().unwrap(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
     #[tokio::test]
     async fn file_exists_returns_false_for_non_existing_file() {
-        let file_manager = FileManager::new(false);
+        let file_manager = FileManager::new();
         let file_name = "non_existing_file.txt";
 
         let exists = file_manager.file_exists(file_name).await.unwrap();
         assert!(!exists);
     }
+
+    #[tokio::test]
+    async fn save_file_leaves_no_part_file_and_cleanup_removes_stale_ones() {
+        let dir = tempdir().unwrap();
+        let started = SystemTime::now() - std::time::Duration::from_secs(1);
+        let file_manager = FileManager::new();
+
+        let mod_path = dir.path().join("carryon.zip");
+        file_manager.save_file(&mod_path, b"zip").await.unwrap();
+        assert!(!part_path(&mod_path).exists());
+
+        std::fs::write(dir.path().join("hudclock.zip.part"), b"partial").unwrap();
+        let dirs = [dir.path().to_path_buf(), dir.path().join("missing")];
+        let future = SystemTime::now() + std::time::Duration::from_secs(3600);
+        assert!(FileManager::remove_partial_files(&dirs, future).is_empty());
+
+        let validator = dir.path().join("hudclock.zip.validator");
+        std::fs::write(&validator, "\"v1\"").unwrap();
+        let removed = FileManager::remove_partial_files(&dirs, started);
+        assert_eq!(removed, [dir.path().join("hudclock.zip.part")]);
+        assert!(!validator.exists());
+        assert!(mod_path.exists());
+    }
 }
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Current version of the lockfile format.
+const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum LockError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse lockfile: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Failed to serialize lockfile: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Unsupported lockfile version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Checksum mismatch for {modid}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        modid: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Lockfile entry for {modid} has an unsafe filename: {filename}")]
+    UnsafeFilename { modid: String, filename: String },
+}
+
+/// An exact mod release pinned by the lockfile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedMod {
+    pub modid: String,
+    pub version: String,
+    pub fileid: Option<u32>,
+    pub filename: String,
+    pub url: String,
+    /// Lowercase hex SHA-256 of the downloaded file.
+    pub sha256: String,
+}
+
+/// The `mods.lock` file, recording every mod installed by this tool exactly enough to
+/// reproduce the same mods directory on another machine.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LockFile {
+    #[serde(skip)]
+    path: PathBuf,
+    pub version: u32,
+    /// Locked mods, sorted by lowercase modid.
+    #[serde(default, rename = "mod")]
+    pub mods: Vec<LockedMod>,
+}
+
+impl LockFile {
+    /// Loads the lockfile at `path`, returning an empty lockfile if it does not exist yet.
+    pub fn load(path: &Path) -> Result<Self, LockError> {
+        let mut lock = if path.exists() {
+            toml::from_str::<LockFile>(&fs::read_to_string(path)?)?
+        } else {
+            LockFile {
+                path: PathBuf::new(),
+                version: LOCKFILE_VERSION,
+                mods: Vec::new(),
+            }
+        };
+
+        if lock.version > LOCKFILE_VERSION {
+            return Err(LockError::UnsupportedVersion(lock.version));
+        }
+
+        lock.path = path.to_path_buf();
+        Ok(lock)
+    }
+
+    /// Writes the lockfile back to the file it was loaded from.
+    pub fn save(&self) -> Result<(), LockError> {
+        fs::write(&self.path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Adds or replaces the entry for a mod, keeping entries sorted by modid.
+    pub fn upsert(&mut self, locked: LockedMod) {
+        self.remove(&locked.modid);
+        let position = self
+            .mods
+            .partition_point(|m| m.modid.to_lowercase() < locked.modid.to_lowercase());
+        self.mods.insert(position, locked);
+    }
+
+    /// Returns the entry for a mod (case-insensitive), if it is locked.
+    pub fn get(&self, modid: &str) -> Option<&LockedMod> {
+        self.mods
+            .iter()
+            .find(|m| m.modid.eq_ignore_ascii_case(modid))
+    }
+
+    /// Removes a mod (case-insensitive), returning its entry if it was locked.
+    pub fn remove(&mut self, modid: &str) -> Option<LockedMod> {
+        let index = self
+            .mods
+            .iter()
+            .position(|m| m.modid.eq_ignore_ascii_case(modid))?;
+        Some(self.mods.remove(index))
+    }
+}
+
+impl LockedMod {
+    /// Checks that a filename derived from this entry names a file directly inside the
+    /// mods directory, so a tampered lockfile can't write anywhere else.
+    pub fn check_filename(&self, filename: &str) -> Result<(), LockError> {
+        if Path::new(filename).file_name() == Some(OsStr::new(filename)) {
+            Ok(())
+        } else {
+            Err(LockError::UnsafeFilename {
+                modid: self.modid.clone(),
+                filename: filename.to_string(),
+            })
+        }
+    }
+
+    /// Checks downloaded bytes against the locked checksum.
+    pub fn verify(&self, actual_sha256: &str) -> Result<(), LockError> {
+        if self.sha256.eq_ignore_ascii_case(actual_sha256) {
+            Ok(())
+        } else {
+            Err(LockError::ChecksumMismatch {
+                modid: self.modid.clone(),
+                expected: self.sha256.clone(),
+                actual: actual_sha256.to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn locked(modid: &str, version: &str) -> LockedMod {
+        LockedMod {
+            modid: modid.to_string(),
+            version: version.to_string(),
+            fileid: Some(1),
+            filename: format!("{modid}_v{version}.zip"),
+            url: format!("https://mods.vintagestory.at/files/{modid}.zip"),
+            sha256: "ab".repeat(32),
+        }
+    }
+
+    #[test]
+    fn filenames_must_stay_in_the_mods_directory() {
+        let entry = locked("carryon", "1.8.0");
+        assert!(entry.check_filename("carryon_v1.8.0.zip").is_ok());
+        for filename in [
+            "../carryon.zip",
+            "/tmp/carryon.zip",
+            "mods/carryon.zip",
+            "..",
+            "",
+        ] {
+            assert!(matches!(
+                entry.check_filename(filename),
+                Err(LockError::UnsafeFilename { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn upsert_replaces_and_sorts() {
+        let dir = tempdir().unwrap();
+        let mut lock = LockFile::load(&dir.path().join("mods.lock")).unwrap();
+        lock.upsert(locked("zeta", "1.0.0"));
+        lock.upsert(locked("Alpha", "1.0.0"));
+        lock.upsert(locked("zeta", "2.0.0"));
+
+        let ids: Vec<_> = lock.mods.iter().map(|m| m.modid.as_str()).collect();
+        assert_eq!(ids, ["Alpha", "zeta"]);
+        assert_eq!(lock.mods[1].version, "2.0.0");
+    }
+
+    #[test]
+    fn lockfile_round_trips_through_disk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mods.lock");
+
+        let mut lock = LockFile::load(&path).unwrap();
+        lock.upsert(locked("carryon", "1.8.0"));
+        lock.save().unwrap();
+
+        let loaded = LockFile::load(&path).unwrap();
+        assert_eq!(loaded.mods, vec![locked("carryon", "1.8.0")]);
+        assert!(loaded.mods[0].verify(&"AB".repeat(32)).is_ok());
+        assert!(loaded.mods[0].verify("00").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::utils::get_log_dir;
+use crate::utils::progress::suspend_progress;
+use std::io::Write;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
+
+/// Daily log files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+/// Messages of this crate shown for a `-v` count. Messages of dependencies such as the
+/// HTTP client are only shown from `-vvv` on.
+fn console_filter(verbosity: u8) -> Targets {
+    let (ours, dependencies) = match verbosity {
+        0 => (LevelFilter::WARN, LevelFilter::WARN),
+        1 => (LevelFilter::INFO, LevelFilter::WARN),
+        2 => (LevelFilter::DEBUG, LevelFilter::WARN),
+        _ => (LevelFilter::TRACE, LevelFilter::TRACE),
+    };
+    Targets::new()
+        .with_target(env!("CARGO_CRATE_NAME"), ours)
+        .with_default(dependencies)
+}
+
+/// Writes console log messages to stderr with the download progress bars cleared, so
+/// messages are printed above the bars instead of into them.
+struct ProgressAwareStderr;
+
+impl Write for ProgressAwareStderr {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        suspend_progress(|| std::io::stderr().write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stderr().flush()
+    }
+}
+
+/// Sends log messages to stderr and to a daily log file in the data directory.
+///
+/// Stderr shows warnings, and more with every `-v`. The log file always records this
+/// crate's debug messages, so it can be attached to bug reports whatever the verbosity.
+///
+/// # Arguments
+///
+/// * `verbosity` - How often `-v` was given.
+///
+/// # Returns
+///
+/// A guard that flushes the log file when dropped, or `None` if there is no log file.
+pub fn init_logging(verbosity: u8) -> Option<WorkerGuard> {
+    let console = fmt::layer()
+        .with_writer(|| ProgressAwareStderr)
+        .with_target(false)
+        .without_time()
+        .with_filter(console_filter(verbosity));
+
+    let appender = get_log_dir().and_then(|dir| {
+        // The appender prunes old files on startup and complains if the directory is missing
+        std::fs::create_dir_all(&dir).ok()?;
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("vsmm")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .ok()
+    });
+    let (file, guard) = match appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_filter(
+                    Targets::new().with_target(env!("CARGO_CRATE_NAME"), LevelFilter::DEBUG),
+                );
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    // Fails if a subscriber is already set, e.g. by an application embedding the crate
+    let _ = tracing_subscriber::registry()
+        .with(console)
+        .with(file)
+        .try_init();
+    guard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Level;
+
+    #[test]
+    fn each_verbose_flag_shows_more() {
+        let ours = env!("CARGO_CRATE_NAME");
+        assert!(!console_filter(0).would_enable(ours, &Level::INFO));
+        assert!(console_filter(1).would_enable(ours, &Level::INFO));
+        assert!(!console_filter(1).would_enable(ours, &Level::DEBUG));
+        assert!(console_filter(2).would_enable(ours, &Level::DEBUG));
+        assert!(!console_filter(2).would_enable("reqwest", &Level::DEBUG));
+        assert!(console_filter(3).would_enable("reqwest", &Level::TRACE));
+    }
+}
@@ -1,17 +1,61 @@
+mod about;
+mod bundle;
 mod cli;
+mod code_mods;
+mod compat;
+mod completions;
 mod config_manager;
+mod deps;
 mod encoding;
 mod files;
-mod logger;
+mod lockfile;
+mod logging;
+mod mod_index;
 mod mod_manager;
+mod output;
+mod plugins;
+mod popularity;
 mod progress;
+mod prompt;
+mod remote;
+mod report;
+mod retention;
+mod semver;
+mod share;
+mod state;
 mod system;
 mod terminal;
+mod tui;
+mod unattended;
+mod webhook;
 
-pub use cli::{Cli, CliFlags, Commands, DownloadFlags};
-pub use encoding::{Encoder, EncoderData};
-pub use files::FileManager;
-pub use logger::{LogLevel, Logger};
+pub use bundle::{
+    BUNDLE_EXTENSION, Bundle, BundleManifest, BundledMod, bundled_file_path, write_bundle,
+};
+pub use cli::{
+    BundleFlags, Cli, CliFlags, Commands, DownloadFlags, examples_for, read_ids_from_stdin_if_dash,
+};
+pub use compat::{Compatibility, Readiness};
+pub use completions::{complete_if_requested, complete_mod_ids, write_completions};
+pub use deps::{DependencyGraph, DependencyProblem};
+pub use encoding::{Encoder, EncoderData, parse_server_log};
+pub use files::{Clutter, FileManager};
+pub use lockfile::{LockFile, LockedMod};
+pub use logging::init_logging;
+pub use mod_index::ModIndex;
 pub use mod_manager::{ModManager, ModManagerError};
-pub use progress::ProgressBarWrapper;
+pub use output::{OutputFormat, json_output, print_json, set_output_format};
+pub use plugins::{PluginContext, PluginError, discover_plugins, find_plugin, run_plugin};
+pub use popularity::PopularityCache;
+pub use progress::{DownloadProgress, ProgressBarWrapper};
+pub use prompt::{Prompt, default_prompt, labels, set_assume_yes};
+pub use remote::{RemoteStore, SftpClient, SyncFile, SyncPlan, local_mod_files};
+pub use report::{hint, report_error};
+pub use retention::{PruneReport, prune_dir};
+pub use semver::{ModVersion, PreRelease, compare_versions, is_newer};
+pub use share::{parse_share_link, render_qr, share_link};
+pub use state::{InstallReason, PendingUpdate, StateFile, UpdateRun};
 pub use system::*;
+pub use tui::{QueueStatus, TuiAction, TuiApp};
+pub use unattended::{AUTO_LOG_NAME, RunLock, append_actions};
+pub use webhook::{RunSummary, UpdatedMod, send_webhook, webhook_client};
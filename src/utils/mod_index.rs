@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexEntry {
+    /// Size of the zip when its modinfo.json was read.
+    size: u64,
+    /// Modification time of the zip when its modinfo.json was read.
+    modified: SystemTime,
+    /// The zip's modinfo.json. The text is kept rather than the parsed `ModInfo`, so
+    /// mods from the index are parsed exactly like mods read from their zip.
+    modinfo: String,
+}
+
+/// The `modinfo.json` of every installed mod zip, so listing mods doesn't open every
+/// archive on each run.
+///
+/// Entries are keyed by the zip's path and only used while its size and modification
+/// time are unchanged; any other change to the file reads it again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ModIndex {
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    changed: bool,
+    entries: BTreeMap<PathBuf, IndexEntry>,
+}
+
+impl ModIndex {
+    /// Loads the index at `path`. A missing or unreadable index is treated as empty.
+    pub fn load(path: &Path) -> Self {
+        let mut index = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<ModIndex>(&contents).ok())
+            .unwrap_or_default();
+        index.path = path.to_path_buf();
+        index
+    }
+
+    /// Writes the index back to the file it was loaded from, if anything changed.
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        if !self.changed {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string(self)?)
+    }
+
+    /// Returns the indexed modinfo.json of a zip, if the zip hasn't changed since.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The mod zip.
+    /// * `metadata` - The zip's current metadata.
+    pub fn get(&self, path: &Path, metadata: &fs::Metadata) -> Option<&[u8]> {
+        let entry = self.entries.get(path)?;
+        let unchanged = entry.size == metadata.len()
+            && metadata
+                .modified()
+                .is_ok_and(|modified| modified == entry.modified);
+        unchanged.then_some(entry.modinfo.as_bytes())
+    }
+
+    /// Records the modinfo.json read from a zip. Files that aren't UTF-8 are not indexed.
+    pub fn insert(&mut self, path: &Path, metadata: &fs::Metadata, modinfo: &[u8]) {
+        let (Ok(modified), Ok(modinfo)) = (metadata.modified(), std::str::from_utf8(modinfo))
+        else {
+            return;
+        };
+        let entry = IndexEntry {
+            size: metadata.len(),
+            modified,
+            modinfo: modinfo.to_string(),
+        };
+        if self.entries.get(path) != Some(&entry) {
+            self.entries.insert(path.to_path_buf(), entry);
+            self.changed = true;
+        }
+    }
+
+    /// Forgets the zips of `dirs` that are not in `present`, e.g. after they were removed.
+    pub fn retain_present(&mut self, dirs: &[PathBuf], present: &[PathBuf]) {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| {
+            present.contains(path)
+                || !path
+                    .parent()
+                    .is_some_and(|dir| dirs.iter().any(|d| d == dir))
+        });
+        self.changed |= self.entries.len() != before;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn entries_are_dropped_when_the_zip_changes() {
+        let dir = tempdir().unwrap();
+        let zip = dir.path().join("carryon.zip");
+        fs::write(&zip, b"zip v1").unwrap();
+        let index_path = dir.path().join("index").join("mod_index.json");
+
+        let mut index = ModIndex::load(&index_path);
+        let metadata = fs::metadata(&zip).unwrap();
+        assert!(index.get(&zip, &metadata).is_none());
+        index.insert(&zip, &metadata, br#"{"modid": "carryon"}"#);
+        index.save().unwrap();
+
+        let mut index = ModIndex::load(&index_path);
+        assert_eq!(
+            index.get(&zip, &metadata),
+            Some(br#"{"modid": "carryon"}"#.as_slice())
+        );
+
+        fs::write(&zip, b"zip v2, larger").unwrap();
+        assert!(index.get(&zip, &fs::metadata(&zip).unwrap()).is_none());
+
+        index.retain_present(&[dir.path().to_path_buf()], &[]);
+        assert!(index.entries.is_empty());
+    }
+}
@@ -1,19 +1,56 @@
 use crate::api::{
-    ClientError, ModApiResponse, ModInfo, ModSearchResult, OrderBy, Query, Release,
-    VintageApiHandler,
+    ClientError, ModApiResponse, ModInfo, ModPageLink, ModRepository, ModSearchResult, OrderBy,
+    Query, Release, ResponseCache, RetryPolicy, SearchThrottle, VintageApiHandler,
+};
+use crate::config::{
+    Channel, IgnoreLists, IgnoreScope, InstallMode, NetworkConfig, Profile, ProfileMod, Remote,
+    RetentionPolicy, Webhook,
+};
+use crate::say;
+use crate::utils::about::About;
+use crate::utils::bundle::BundleError;
+use crate::utils::cli::{
+    BackupCommands, CacheCommands, ConfigCommands, IgnoreCommands, IsAllNone, ListFormat, ListSort,
+    ProfileCommands, RemoteCommands,
 };
-use crate::utils::cli::{ConfigCommands, IsAllNone};
 use crate::utils::config_manager::{ConfigError, ConfigManager};
-use crate::utils::encoding::EncodingError;
-use crate::utils::files::FileError;
-use crate::utils::terminal::Terminal;
+use crate::utils::deps::GAME_MODS;
+use crate::utils::encoding::{EncodingError, ModListEntry, ModListFormat};
+use crate::utils::files::{FileError, is_mod_zip, mod_info_from_zip};
+use crate::utils::lockfile::LockError;
+use crate::utils::remote::RemoteError;
+use crate::utils::state::StateError;
+use crate::utils::terminal::{Columns, Terminal};
 use crate::utils::{
-    Cli, CliFlags, Commands, DownloadFlags, Encoder, EncoderData, FileManager, LogLevel, Logger,
-    ProgressBarWrapper, get_vintage_mods_dir,
+    AUTO_LOG_NAME, BUNDLE_EXTENSION, Bundle, BundleFlags, BundleManifest, BundledMod, Cli,
+    CliFlags, Clutter, Commands, Compatibility, DependencyGraph, DownloadFlags, Encoder,
+    EncoderData, FileManager, InstallReason, LockFile, LockedMod, PendingUpdate, PluginContext,
+    PluginError, PopularityCache, ProgressBarWrapper, Prompt, PruneReport, QueueStatus, Readiness,
+    RemoteStore, RunLock, RunSummary, SftpClient, StateFile, SyncPlan, TuiAction, TuiApp,
+    UpdateRun, UpdatedMod, append_actions, backup_dir_for, bundled_file_path, compare_versions,
+    complete_if_requested, default_prompt, default_vintage_mods_dir, discover_plugins,
+    examples_for, existing_mods_dir, find_plugin, get_cache_dir, get_config_dir, get_download_dir,
+    get_lock_path, get_log_dir, get_remote_mirror_dir, get_run_lock_path, get_state_path,
+    init_logging, is_newer, json_output, labels, local_mod_files, parse_server_log,
+    parse_share_link, print_json, prune_dir, read_ids_from_stdin_if_dash, render_qr, report_error,
+    root_owner_mismatch, run_plugin, send_webhook, set_assume_yes, set_output_format, share_link,
+    webhook_client, write_bundle, write_completions,
 };
 use clap::Parser;
+use colored::Colorize;
+use futures::StreamExt;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
+use tracing::{info, warn};
 
 #[derive(Error, Debug)]
 pub enum ModManagerError {
@@ -25,8 +62,14 @@ pub enum ModManagerError {
     Serde(#[from] serde_json::Error),
     #[error("Var Error: {0}")]
     Var(#[from] std::env::VarError),
-    #[error("No Releases Error")]
-    NoReleases,
+    #[error(
+        "No release of {name} supports game version {}",
+        .game_version.as_deref().unwrap_or("(not set)")
+    )]
+    NoReleases {
+        name: String,
+        game_version: Option<String>,
+    },
     #[error("Invalid mod path: {0}")]
     InvalidModPath(String),
     #[error("Missing modinfo")]
@@ -41,627 +84,7569 @@ pub enum ModManagerError {
     Config(#[from] ConfigError), // Add this line
     #[error("Api Error: {0}")]
     ApiError(#[from] ClientError),
+    #[error("State Error: {0}")]
+    State(#[from] StateError),
+    #[error("Lockfile Error: {0}")]
+    Lock(#[from] LockError),
+    #[error("Plugin Error: {0}")]
+    Plugin(#[from] PluginError),
+    #[error("Bundle Error: {0}")]
+    Bundle(#[from] BundleError),
+    #[error("Remote Error: {0}")]
+    Remote(#[from] RemoteError),
+    #[error("Mod {0} is not installed")]
+    NotInstalled(String),
+    #[error("No release {version} of {modid} on ModDB")]
+    ReleaseNotFound { modid: String, version: String },
+    #[error("This needs a choice --yes can't make: {0}")]
+    InteractionRequired(String),
+    #[error("Unknown key: {0}")]
+    UnknownKey(String),
+    #[error("No backup named {0}, see `restore --list`")]
+    BackupNotFound(String),
+    #[error("{0} mod(s) could not be installed")]
+    InstallFailed(usize),
+    #[error("{modid} {installed} is newer than {candidate}, use --force to replace it")]
+    NewerInstalled {
+        modid: String,
+        installed: String,
+        candidate: String,
+    },
 }
 
-pub struct ModManager {
-    api: VintageApiHandler,
-    file_manager: FileManager,
-    encoder: Encoder,
-    logger: Logger,
+/// Exit status of a command cancelled by `--timeout`, matching coreutils `timeout`.
+const TIMEOUT_EXIT_CODE: u8 = 124;
+
+/// Exit status of `check` when updates are available, matching `update` applying them.
+const UPDATES_AVAILABLE_EXIT_CODE: u8 = 2;
+
+/// Exit status of `compat-check` when some mods have no release for the target version.
+const NOT_READY_EXIT_CODE: u8 = 2;
+
+/// The `compat-check` status of mods ModDB doesn't know.
+const NOT_ON_MODDB: &str = "not on ModDB";
+
+/// Exit status of `update --auto` when another run holds the mods directory, `EX_TEMPFAIL`
+/// from sysexits.h so schedulers treat it as "try again later".
+const BUSY_EXIT_CODE: u8 = 75;
+
+/// How many ModDB requests an update check keeps in flight at once.
+const UPDATE_CHECK_CONCURRENCY: usize = 8;
+
+/// Backups taken during an update run are named after the time the run started.
+const BACKUP_NAME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// Copies a file, or a folder with everything in it, such as an unpacked mod.
+fn copy_recursively(from: &Path, to: &Path) -> Result<(), std::io::Error> {
+    if !from.is_dir() {
+        return std::fs::copy(from, to).map(|_| ());
+    }
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
 }
 
-enum SelectionResult {
-    Continue,
-    Break,
-    NoResults,
+/// Turns a ModDB HTML description into plain text for the terminal.
+fn strip_html(html: &str) -> String {
+    let breaks = Regex::new(r"(?i)<br\s*/?>|</p>|</li>|</h\d>").unwrap();
+    let tags = Regex::new(r"<[^>]+>").unwrap();
+    let blank_lines = Regex::new(r"\n{3,}").unwrap();
+
+    let text = breaks.replace_all(html, "\n");
+    let text = tags.replace_all(&text, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    let text = text.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+    blank_lines.replace_all(&text, "\n\n").trim().to_string()
 }
 
-impl ModManager {
-    pub fn new(verbose: bool) -> Self {
-        Self {
-            api: VintageApiHandler::new(verbose),
-            file_manager: FileManager::new(verbose),
-            encoder: Encoder::new(verbose),
-            logger: Logger::new("ModManager".to_string(), LogLevel::Info, None, verbose),
-        }
-    }
+/// Collects the changelogs of the releases after the installed version, up to and
+/// including `target`, newest first.
+///
+/// # Arguments
+///
+/// * `releases` - The releases of the mod.
+/// * `installed` - The installed version, or `None` to only include `target`'s changelog.
+/// * `target` - The version that would be installed.
+///
+/// # Returns
+///
+/// The plain-text changelogs, each under a `v<version>:` heading, or an empty string if
+/// none of the releases have one.
+fn changelog_between(releases: &[Release], installed: Option<&str>, target: &str) -> String {
+    let mut entries: Vec<(&str, String)> = releases
+        .iter()
+        .filter_map(|release| {
+            let version = release.modversion.as_deref()?;
+            let in_range = match installed {
+                Some(installed) => is_newer(version, installed) && !is_newer(version, target),
+                None => compare_versions(version, target).is_eq(),
+            };
+            let text = strip_html(release.changelog.as_deref()?);
+            (in_range && !text.is_empty()).then_some((version, text))
+        })
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| compare_versions(b, a));
+    entries.dedup_by(|(a, _), (b, _)| compare_versions(a, b).is_eq());
 
-    pub async fn run() -> Result<(), ModManagerError> {
-        let cli = Cli::parse();
-        let verbose = cli.verbose.unwrap_or(false);
-        let mod_manager = ModManager::new(verbose);
+    entries
+        .iter()
+        .map(|(version, text)| format!("v{}:\n{text}", version.trim_start_matches('v')))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
 
-        match cli.command {
-            Some(Commands::Download {
-                mod_string,
-                mods,
-                mod_,
-            }) => {
-                mod_manager
-                    .import_mods(Some(DownloadFlags {
-                        mod_string,
-                        mods,
-                        mod_,
-                    }))
-                    .await?;
-            }
+/// Prints a changelog indented under the line announcing the release.
+fn print_changelog(changelog: &str) {
+    for line in changelog.lines() {
+        say!("    {line}");
+    }
+}
 
-            Some(Commands::Export {
-                exclude,
-                include,
-                mod_,
-                interactive,
-            }) => {
-                let options = CliFlags {
-                    exclude,
-                    include,
-                    mod_,
-                };
+/// Prints an exported mod list, or writes it to `out`.
+fn write_mod_list(
+    format: ModListFormat, encoded: String, out: Option<&Path>,
+) -> Result<(), ModManagerError> {
+    if let Some(out) = out {
+        std::fs::write(out, format!("{encoded}\n"))?;
+        say!("Exported mod list to {}", out.display());
+    } else if json_output() {
+        print_json(&ExportOutput {
+            format: format!("{format:?}").to_lowercase(),
+            output: encoded,
+        })?;
+    } else {
+        println!("{encoded}");
+    }
+    Ok(())
+}
 
-                mod_manager.handle_export(interactive, options).await?;
-            }
+/// Builds the summary announcing updates that were found but not installed.
+fn available_summary(command: &str, updates: &[AvailableUpdate]) -> RunSummary {
+    RunSummary {
+        available: updates
+            .iter()
+            .map(|update| UpdatedMod {
+                name: update.name.clone(),
+                modid: update.modid.clone(),
+                from: update.from.clone(),
+                to: update.to.clone(),
+            })
+            .collect(),
+        ..RunSummary::new(command)
+    }
+}
 
-            Some(Commands::Update {
-                exclude,
-                include,
-                mod_,
-            }) => {
-                mod_manager
-                    .update_mods(CliFlags {
-                        exclude,
-                        include,
-                        mod_,
-                    })
-                    .await?;
-            }
+/// Shows a desktop notification listing the available updates. Failures, e.g. when no
+/// notification service is running, are only logged.
+#[cfg(feature = "desktop-notifications")]
+fn show_desktop_notification(updates: &[AvailableUpdate]) {
+    let body = updates
+        .iter()
+        .map(|update| format!("{} {} → {}", update.name, update.from, update.to))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let result = notify_rust::Notification::new()
+        .appname("VintageModManager")
+        .summary(&format!("{} mod update(s) available", updates.len()))
+        .body(&body)
+        .show();
+    if let Err(e) = result {
+        warn!("Failed to show a desktop notification: {e}");
+    }
+}
 
-            Some(Commands::Config(config_cmd)) => {
-                let mut config_manager = ConfigManager::new(verbose)?;
+#[cfg(not(feature = "desktop-notifications"))]
+fn show_desktop_notification(_updates: &[AvailableUpdate]) {
+    warn!("This build has no desktop notification support");
+}
 
-                match config_cmd {
-                    ConfigCommands::SetPath { path } => {
-                        config_manager.set_game_path(path)?;
-                    }
-                    ConfigCommands::Show => {
-                        config_manager.show();
-                    }
-                    ConfigCommands::Init { force } => {
-                        config_manager.init(force)?;
-                    }
-                    ConfigCommands::UpdateVersions { verbose } => {
-                        config_manager
-                            .update_version_mappings(verbose.unwrap_or(false))
-                            .await?;
-                    }
-                    ConfigCommands::ListVersions => {
-                        config_manager.list_versions();
-                    }
-                    ConfigCommands::Reset { yes } => {
-                        config_manager.reset(yes)?;
-                    }
-                    ConfigCommands::Validate => {
-                        config_manager.validate()?;
-                    }
-                    ConfigCommands::SetGameVersion { version } => {
-                        // Implementation needed - add to ConfigManager
-                        println!("Setting game version preference to: {version}");
-                        // You could implement this as a user preference override
-                        // For now, just show what the detected version is vs requested
-                        if let Some(detected) = config_manager.get_detected_game_version() {
-                            if detected == &version {
-                                println!("Matches detected version: {detected}");
-                            } else {
-                                println!(
-                                    "Requested version {version} differs from detected version {detected}"
-                                );
-                            }
-                        } else {
-                            println!(
-                                "No version detected. Set game path first with 'config set-path'"
-                            );
-                        }
+/// Prints the `compat-check` report as a table, followed by a verdict on upgrading.
+fn print_compat_report(target: &str, entries: &[CompatEntry]) {
+    if entries.is_empty() {
+        say!("No mods installed");
+        return;
+    }
+
+    let columns = vec![
+        Columns::new("Name", entries.iter().map(|e| e.name.clone()).collect()),
+        Columns::new("Mod ID", entries.iter().map(|e| e.modid.clone()).collect()),
+        Columns::new(
+            "Installed",
+            entries.iter().map(|e| e.installed.clone()).collect(),
+        ),
+        Columns::new(
+            &format!("Game version {target}"),
+            entries
+                .iter()
+                .map(|e| match &e.readiness {
+                    Some(readiness) => readiness.to_string(),
+                    None if e.status == NOT_ON_MODDB => {
+                        format!("- {NOT_ON_MODDB}").dimmed().to_string()
                     }
-                }
-            }
+                    None => "? unknown".dimmed().to_string(),
+                })
+                .collect(),
+        ),
+    ];
+    Terminal::new().print_table(columns);
 
-            _ => {}
+    let count = |status: &str| entries.iter().filter(|e| e.status == status).count();
+    say!(
+        "\n{} ready, {} need updates, {} untested, {} unsupported, {} unknown, {} not on ModDB",
+        count("ready"),
+        count("needs update"),
+        count("untested"),
+        count("unsupported"),
+        count("unknown"),
+        count(NOT_ON_MODDB)
+    );
+    if entries.iter().all(CompatEntry::is_supported) {
+        say!("Every mod supports {target}; run `update` after upgrading the game.");
+        if count(NOT_ON_MODDB) > 0 {
+            say!("Mods not on ModDB can't be checked, make sure they support {target} too.");
         }
+    } else {
+        say!("Not every mod supports {target} yet.");
+    }
+}
 
-        Ok(())
+/// Prints the result of `check` as one block per mod.
+fn print_available_updates(updates: &[AvailableUpdate]) {
+    if updates.is_empty() {
+        say!("\nAll mods are up to date.");
+        return;
     }
 
-    async fn import_mods(&self, options: Option<DownloadFlags>) -> Result<(), ModManagerError> {
-        let options = options.ok_or(ModManagerError::MissingModInfo)?;
+    say!("\n{} update(s) available:", updates.len());
+    for update in updates {
+        say!(
+            "  {} ({}) {} → {} {} [{}]",
+            update.name,
+            update.modid,
+            update.from,
+            update.to,
+            update.compatibility,
+            update
+                .size
+                .map_or_else(|| "unknown size".to_string(), Terminal::format_size)
+        );
+    }
+}
 
-        if let Some(mod_string) = &options.mod_string {
-            self.download_mod_string(mod_string).await?;
+/// Suffix for dry-run lines that would replace an existing file.
+fn overwrite_note(path: &Path) -> &'static str {
+    if path.exists() {
+        " (overwriting existing file)"
+    } else {
+        ""
+    }
+}
+
+/// Finds the release of a mod with the given version.
+fn release_with_version<'a>(
+    modid: &str, releases: &'a [Release], version: &str,
+) -> Result<&'a Release, ModManagerError> {
+    releases
+        .iter()
+        .find(|release| {
+            release
+                .modversion
+                .as_deref()
+                .is_some_and(|modversion| compare_versions(modversion, version).is_eq())
+        })
+        .ok_or_else(|| ModManagerError::ReleaseNotFound {
+            modid: modid.to_string(),
+            version: version.to_string(),
+        })
+}
+
+/// A row of the `list` command.
+#[derive(Serialize)]
+struct ListEntry {
+    name: String,
+    modid: String,
+    version: String,
+    side: String,
+    size_bytes: u64,
+    file: PathBuf,
+    pinned: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    contributors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    downloads: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    releaseid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release_created: Option<String>,
+    /// How the installed release matches the current game version, looked up for
+    /// `--verbose`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compatibility: Option<Compatibility>,
+}
+
+/// The overview printed by `status`.
+#[derive(Serialize)]
+struct Status {
+    game_version: Option<String>,
+    game_path: Option<PathBuf>,
+    mods_dir: Option<PathBuf>,
+    installed: usize,
+    disabled: usize,
+    /// Mods that were not installed by this tool.
+    unmanaged: Vec<String>,
+    last_update: Option<UpdateRun>,
+}
+
+impl Status {
+    /// Prints the overview followed by the suggested next steps.
+    fn print(&self) {
+        let mut next = Vec::new();
+
+        match (&self.game_version, &self.game_path) {
+            (Some(version), _) => say!("{:<17} {version}", "Game version:"),
+            (None, Some(path)) => {
+                say!("{:<17} not detected in {}", "Game version:", path.display());
+                next.push("check the game path with 'config validate'");
+            }
+            (None, None) => {
+                say!("{:<17} unknown", "Game version:");
+                next.push("point the tool at the game with 'config set-path <path>'");
+            }
         }
 
-        if let Some(mods) = &options.mods {
-            self.download_mods(mods).await?;
+        match &self.mods_dir {
+            Some(dir) => {
+                let disabled = match self.disabled {
+                    0 => String::new(),
+                    count => format!(", {count} disabled"),
+                };
+                say!(
+                    "{:<17} {}{disabled} in {}",
+                    "Mods installed:",
+                    self.installed,
+                    dir.display()
+                );
+                if self.installed == 0 {
+                    next.push("install mods with 'download <modid>'");
+                }
+            }
+            None => {
+                say!("{:<17} no mods directory found", "Mods installed:");
+                next.push("create it with 'config create-mods-dir'");
+            }
         }
 
-        if let Some(mod_) = &options.mod_ {
-            self.download_mod(mod_).await?;
+        if !self.unmanaged.is_empty() {
+            let mut names = self.unmanaged[..self.unmanaged.len().min(5)].join(", ");
+            if self.unmanaged.len() > 5 {
+                names.push_str(", ...");
+            }
+            say!(
+                "{:<17} {} ({names})",
+                "Unmanaged mods:",
+                self.unmanaged.len()
+            );
         }
 
-        if options.is_all_none() {
-            self.show_paginated_mods().await?;
+        match &self.last_update {
+            Some(run) => {
+                let finished_at = chrono::DateTime::parse_from_rfc3339(&run.finished_at)
+                    .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|_| run.finished_at.clone());
+                say!(
+                    "{:<17} {finished_at}, {} mod(s) updated",
+                    "Last update run:",
+                    run.updated
+                );
+                if run.pending.is_empty() {
+                    say!("{:<17} none", "Pending updates:");
+                } else {
+                    say!("{:<17} {}", "Pending updates:", run.pending.len());
+                    for update in &run.pending {
+                        say!("  {} {} -> {}", update.modid, update.from, update.to);
+                    }
+                    next.push("retry the pending updates with 'update'");
+                }
+            }
+            None if self.installed > 0 => {
+                say!("{:<17} never", "Last update run:");
+                next.push("check for updates with 'update --dry-run'");
+            }
+            None => {}
         }
 
-        Ok(())
+        if !next.is_empty() {
+            say!();
+            say!("Next steps:");
+            for step in next {
+                say!("  - {step}");
+            }
+        }
     }
+}
 
-    async fn handle_export(
-        &self, interactive: Option<bool>, option: CliFlags,
-    ) -> Result<(), ModManagerError> {
-        let mods: Vec<(ModInfo, PathBuf)> = self.file_manager.collect_mods(&Some(option)).await?;
-
-        let selected_mods = if interactive.unwrap_or(false) {
-            let mod_names: Vec<_> = mods
-                .iter()
-                .map(|(info, _)| info.name.as_deref().unwrap_or("Unknown"))
-                .collect();
+/// How installing one mod of a manifest went.
+#[derive(Serialize)]
+struct ManifestResult {
+    modid: String,
+    /// The installed version, if the install succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
-            let selections = Terminal::multi_select("Select mods to export", &mod_names);
-            selections
-                .into_iter()
-                .map(|idx| mods[idx].clone())
-                .collect()
-        } else {
-            mods
-        };
+/// A newer compatible release found by `check`.
+#[derive(Serialize)]
+struct AvailableUpdate {
+    name: String,
+    modid: String,
+    from: String,
+    to: String,
+    /// How the new release matches the current game version.
+    compatibility: Compatibility,
+    /// The download size reported by the server.
+    size: Option<u64>,
+    /// The changelogs of the releases since the installed version, as plain text.
+    changelog: String,
+}
 
-        let encoder_data = self.create_encoder_data(&selected_mods)?;
-        let encoded = self.encoder.encode_mod_string(&encoder_data);
+/// A row of the `compat-check` report.
+#[derive(Serialize)]
+struct CompatEntry {
+    name: String,
+    modid: String,
+    installed: String,
+    /// The readiness label, `not on ModDB` for mods ModDB doesn't know, or `unknown` if the
+    /// lookup failed.
+    status: &'static str,
+    /// The release to switch to, if the installed one doesn't support the version.
+    release: Option<String>,
+    #[serde(skip)]
+    readiness: Option<Readiness>,
+}
 
-        self.logger
-            .log_default(&format!("Exported {} mods", selected_mods.len()));
-        println!("{encoded}");
-        Ok(())
+impl CompatEntry {
+    /// Whether the mod doesn't hold back the upgrade. Mods ModDB doesn't know can't be
+    /// checked, so they are left out of the verdict instead of always failing it.
+    fn is_supported(&self) -> bool {
+        self.status == NOT_ON_MODDB || self.readiness.as_ref().is_some_and(Readiness::is_supported)
     }
+}
 
-    fn create_encoder_data(
-        &self, mods: &[(ModInfo, PathBuf)],
-    ) -> Result<Vec<EncoderData>, ModManagerError> {
-        mods.iter()
-            .map(|(mod_info, _)| {
-                self.logger
-                    .log_default(&format!("Creating encoder data for: {mod_info:?}"));
-                let mod_id = mod_info
-                    .modid
-                    .as_ref()
-                    .ok_or_else(|| ModManagerError::InvalidModPath("Missing mod ID".to_string()))?;
-                let version = mod_info.version.as_ref().ok_or_else(|| {
-                    ModManagerError::InvalidModPath("Missing mod version".to_string())
-                })?;
+/// The result of the `export` command with `--output json`.
+#[derive(Serialize)]
+struct ExportOutput {
+    format: String,
+    output: String,
+}
 
-                Ok(EncoderData {
-                    mod_id: mod_id.clone(),
-                    mod_version: version.clone(),
-                })
-            })
-            .collect()
-    }
+/// Runs the CLI commands against the mods directories and a [`ModRepository`], ModDB by
+/// default.
+pub struct ModManager<R = VintageApiHandler> {
+    api: R,
+    file_manager: FileManager,
+    encoder: Encoder,
+    prompt: Arc<dyn Prompt>,
+    /// Print planned downloads and deletions instead of touching the filesystem.
+    dry_run: bool,
+    /// Lowercase mod IDs that updates leave alone.
+    pinned: Vec<String>,
+    /// Mods hidden from commands by the config's ignore lists.
+    ignore: IgnoreLists,
+    /// Release channel for mods without an override.
+    channel: Channel,
+    /// Release channels of individual mods, by lowercase mod ID.
+    mod_channels: BTreeMap<String, Channel>,
+    /// Print the changelogs of releases found by `update`, `check` and interactive downloads.
+    show_changelog: bool,
+    /// Only pick releases marked compatible with the game version, never a fallback.
+    strict_compatibility: bool,
+    /// Let the user deselect updates before `update` downloads anything.
+    review_updates: bool,
+    /// Let the user pick the release of mods downloaded with `--mod` or from the browser.
+    pick_release: bool,
+    /// Game version from `--game-version`, used instead of the configured one.
+    game_version_override: Option<String>,
+    /// Retention policies applied to the backup and cache directories after commands that
+    /// modify the mods directory. `None` leaves the directories alone.
+    retention: Option<(RetentionPolicy, RetentionPolicy)>,
+    /// HTTP client run summaries are posted to webhooks with.
+    webhook_client: reqwest::Client,
+    /// The config file the game version is read from, or `None` for the user's.
+    config_path: Option<PathBuf>,
+}
 
-    async fn update_mods(&self, mod_options: CliFlags) -> Result<(), ModManagerError> {
-        let mods = self.file_manager.collect_mods(&Some(mod_options)).await?;
-        let vintage_mods_dir = get_vintage_mods_dir()?;
+enum SelectionResult {
+    Continue,
+    Break,
+    NoResults,
+}
 
-        println!("Checking for updates...");
-        for (mod_info, path) in mods {
-            self.process_mod_update(&mod_info, path, &vintage_mods_dir)
-                .await;
-        }
+/// What became of a mod [`ModManager::handle_mod_update`] was asked to replace.
+enum UpdateOutcome {
+    /// The new release was installed at this path, or would be in a dry run.
+    Installed(PathBuf),
+    /// The user kept the installed version because the new release has unmet dependencies.
+    Declined,
+    /// The update failed and the installed version was kept.
+    Failed,
+}
 
-        Ok(())
+/// Entries listed after the mods of a page in the interactive browser.
+#[derive(Clone, Copy)]
+enum BrowseNavigation {
+    NextPage,
+    PreviousPage,
+    Filter,
+    Exit,
+}
+
+impl BrowseNavigation {
+    fn label(self) -> &'static str {
+        match self {
+            BrowseNavigation::NextPage => "--- Next page ---",
+            BrowseNavigation::PreviousPage => "--- Previous page ---",
+            BrowseNavigation::Filter => "--- Filter mods ---",
+            BrowseNavigation::Exit => "--- Exit ---",
+        }
     }
+}
 
-    async fn process_mod_update(&self, mod_info: &ModInfo, path: PathBuf, mods_dir: &Path) {
-        let name = mod_info.name.as_deref().unwrap_or("Unknown");
-        let version = mod_info.version.as_deref().unwrap_or("Unknown");
+impl Default for ModManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        match self.check_and_get_update(mod_info, name, version).await {
-            Some(release) => {
-                self.handle_mod_update(name, version, path, mods_dir, release)
-                    .await
-            }
-            None => println!("No update available for mod: {name} - Current version: {version}"),
+impl ModManager {
+    pub fn new() -> Self {
+        Self {
+            api: VintageApiHandler::new(),
+            file_manager: FileManager::new(),
+            encoder: Encoder::new(),
+            prompt: default_prompt(),
+            dry_run: false,
+            pinned: Vec::new(),
+            ignore: IgnoreLists::default(),
+            channel: Channel::default(),
+            mod_channels: BTreeMap::new(),
+            show_changelog: false,
+            strict_compatibility: false,
+            review_updates: false,
+            pick_release: false,
+            game_version_override: None,
+            retention: None,
+            webhook_client: webhook_client(&NetworkConfig::default())
+                .expect("Failed to build HTTP client"),
+            config_path: None,
         }
     }
 
-    async fn check_and_get_update(
-        &self, mod_info: &ModInfo, name: &str, version: &str,
-    ) -> Option<Release> {
-        // Handle the main result cases first
-        match self.api.check_for_mod_update(mod_info).await {
-            Ok((false, _)) => return None,
-            Ok(result) => result,
-            Err(e) => {
-                eprintln!("Failed to check updates for {name}: {e}");
-                return None;
-            }
-        };
-
-        // Early return pattern for the rest
-        let mod_id = mod_info.modid.as_ref()?;
-        let full_mod_info = self.fetch_mod_info(mod_id).await.ok()?;
-        let compatible_release = self.find_compatible_release(&full_mod_info.mod_data.releases)?;
+    /// Applies the configured API server, mirrors, retry, timeout and proxy settings to
+    /// ModDB requests.
+    ///
+    /// # Returns
+    ///
+    /// The manager, or an error if the configured proxy is not a valid URL.
+    pub fn with_network(mut self, network: &NetworkConfig) -> Result<Self, ModManagerError> {
+        self.api = self
+            .api
+            .with_retry_policy(RetryPolicy::new(
+                network.max_attempts,
+                Duration::from_millis(network.retry_delay_ms),
+            ))
+            .with_connection(
+                Duration::from_secs(network.connect_timeout_secs),
+                Duration::from_secs(network.timeout_secs),
+                network.proxy.as_deref(),
+            )?
+            .with_mirrors(network.mirrors.clone());
+        if let Some(api_url) = &network.api_url {
+            self.api = self.api.with_api_url(api_url);
+        }
+        self.webhook_client = webhook_client(network)?;
+        Ok(self)
+    }
 
-        // Simple version check
-        let current_version = mod_info.version.as_deref().unwrap_or("Unknown");
-        let new_version = compatible_release
-            .modversion
-            .as_deref()
-            .unwrap_or("Unknown");
+    /// Caches ModDB API responses in the cache directory for `ttl`, or not at all if `ttl`
+    /// is `None` or the cache directory is unknown.
+    pub fn with_response_cache(mut self, ttl: Option<Duration>) -> Self {
+        let cache = ttl
+            .zip(get_cache_dir())
+            .map(|(ttl, dir)| ResponseCache::new(dir.join("api"), ttl));
+        self.api = self.api.with_cache(cache);
+        self
+    }
+}
 
-        if current_version == new_version {
-            println!("Mod {name} is already at the latest compatible version: {current_version}");
-            return None;
+impl<R: ModRepository> ModManager<R> {
+    /// Looks up and downloads mods from `repository` instead of ModDB.
+    pub fn with_repository<S: ModRepository>(self, repository: S) -> ModManager<S> {
+        ModManager {
+            api: repository,
+            file_manager: self.file_manager,
+            encoder: self.encoder,
+            prompt: self.prompt,
+            dry_run: self.dry_run,
+            pinned: self.pinned,
+            ignore: self.ignore,
+            channel: self.channel,
+            mod_channels: self.mod_channels,
+            show_changelog: self.show_changelog,
+            strict_compatibility: self.strict_compatibility,
+            review_updates: self.review_updates,
+            pick_release: self.pick_release,
+            game_version_override: self.game_version_override,
+            retention: self.retention,
+            webhook_client: self.webhook_client,
+            config_path: self.config_path,
         }
+    }
 
-        // Print update info and return
-        self.print_update_info(name, current_version, new_version, compatible_release);
-        Some(compatible_release.clone())
+    /// Sets where answers to interactive questions come from.
+    #[cfg(test)]
+    pub fn with_prompt(mut self, prompt: Arc<dyn Prompt>) -> Self {
+        self.prompt = prompt;
+        self
     }
 
-    fn print_update_info(&self, name: &str, current: &str, new: &str, release: &Release) {
-        println!(
-            "Update available for mod: {name} - Current version: {current} - New compatible version: {new}"
-        );
+    /// Reads the configuration from `config_path` instead of the user's config file.
+    #[cfg(test)]
+    pub fn with_config_path(mut self, config_path: PathBuf) -> Self {
+        self.config_path = Some(config_path);
+        self
+    }
 
-        // Show version compatibility info
-        if let Some(game_version) = self.get_current_game_version() {
-            if release.tags.contains(&game_version) {
-                println!("New version is compatible with game version {game_version}");
-            } else {
-                println!(
-                    "Using fallback version (no version found compatible with game version {game_version})"
-                );
-            }
+    /// Loads the configuration the manager reads the game version and profiles from.
+    fn load_config_file(&self) -> Result<ConfigManager, ConfigError> {
+        match &self.config_path {
+            Some(config_path) => ConfigManager::load(config_path.clone()),
+            None => ConfigManager::new(),
         }
     }
 
-    async fn handle_mod_update(
-        &self, name: &str, _version: &str, path: PathBuf, mods_dir: &Path, release: Release,
-    ) {
-        // Delete old mod
-        if let Err(e) = self.delete_old_mod(&path).await {
-            eprintln!("Failed to delete old mod: {e}");
-            return;
-        }
+    /// Sets the mods directories the manager operates on.
+    pub fn with_mod_dirs(mut self, mod_dirs: Vec<PathBuf>) -> Self {
+        self.file_manager = self.file_manager.with_mod_dirs(mod_dirs);
+        self
+    }
 
-        // Get new mod path
-        let new_mod_path = match self.get_new_mod_path(mods_dir, &release, name) {
-            Some(path) => path,
-            None => return,
-        };
+    /// Only reports what `download`, `update` and `remove` would download, delete or
+    /// overwrite, without changing anything.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
 
-        // Download and save new mod
-        self.download_and_save_mod(name, &new_mod_path, &release)
-            .await;
+    /// Sets the game version embedded in exported mod strings and checked on import.
+    pub fn with_game_version(mut self, game_version: Option<String>) -> Self {
+        self.encoder = self.encoder.with_game_version(game_version);
+        self
     }
 
-    async fn show_paginated_mods(&self) -> Result<(), ModManagerError> {
-        let page_size = 50;
-        let mut current_filter = String::new();
-        let mods = self.fetch_initial_mods().await?;
+    /// Prints the changelogs of the releases `update`, `check` and interactive downloads
+    /// are about to install.
+    pub fn with_changelog(mut self, show_changelog: bool) -> Self {
+        self.show_changelog = show_changelog;
+        self
+    }
 
-        while !mods.is_empty() {
+    /// Refuses updates to releases not marked compatible with the game version, instead of
+    /// falling back to releases made for other game versions. Used by `update --auto`.
+    pub fn with_strict_compatibility(mut self, strict_compatibility: bool) -> Self {
+        self.strict_compatibility = strict_compatibility;
+        self
+    }
+
+    /// Shows the updates `update` finds in a pre-checked list before anything is
+    /// downloaded, so single mods can be left at their installed version.
+    pub fn with_update_review(mut self, review_updates: bool) -> Self {
+        self.review_updates = review_updates;
+        self
+    }
+
+    /// Lists every release of a mod downloaded with `--mod` or from the interactive browser
+    /// to pick from, instead of taking the newest compatible one.
+    pub fn with_release_picker(mut self, pick_release: bool) -> Self {
+        self.pick_release = pick_release;
+        self
+    }
+
+    /// Matches releases against `game_version` instead of the configured or detected game
+    /// version, e.g. to prepare the mods of a server running another version. `None` keeps
+    /// the configured version.
+    pub fn with_game_version_override(mut self, game_version: Option<String>) -> Self {
+        if game_version.is_some() {
+            self.encoder = self.encoder.with_game_version(game_version.clone());
+            self.game_version_override = game_version;
+        }
+        self
+    }
+
+    /// Sets the release channels releases are picked from.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The channel for mods without an override.
+    /// * `mod_channels` - Channels of individual mods, by mod ID.
+    pub fn with_channels(
+        mut self, channel: Channel, mod_channels: BTreeMap<String, Channel>,
+    ) -> Self {
+        self.channel = channel;
+        self.mod_channels = mod_channels
+            .into_iter()
+            .map(|(modid, channel)| (modid.to_lowercase(), channel))
+            .collect();
+        self
+    }
+
+    /// Sets the mods `update` skips, by mod ID.
+    pub fn with_pinned(mut self, pinned: Vec<String>) -> Self {
+        self.pinned = pinned.iter().map(|modid| modid.to_lowercase()).collect();
+        self
+    }
+
+    /// Returns `true` if updates should leave the mod alone.
+    fn is_pinned(&self, modid: Option<&str>) -> bool {
+        modid.is_some_and(|modid| self.pinned.contains(&modid.to_lowercase()))
+    }
+
+    /// Sets the retention policies pruning applies to the backup and cache directories
+    /// after commands that modify the mods directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `backups` - The policy for mod backups.
+    /// * `cache` - The policy for cached API responses.
+    pub fn with_retention(mut self, backups: RetentionPolicy, cache: RetentionPolicy) -> Self {
+        self.retention = Some((backups, cache));
+        self
+    }
+
+    /// Sets the mods commands skip. Mods ignored everywhere are left out of `list`,
+    /// `export` and the terminal UI, and are never updated.
+    pub fn with_ignore_lists(mut self, ignore: IgnoreLists) -> Self {
+        self.ignore = ignore;
+        self
+    }
+
+    /// Returns `true` if the ignore lists hide the mod from commands of `scope`.
+    fn is_ignored(&self, scope: IgnoreScope, modid: Option<&str>) -> bool {
+        modid.is_some_and(|modid| self.ignore.ignores(scope, modid))
+    }
+
+    /// Drops the installed mods the ignore lists hide from every command. Commands that
+    /// manage files, like `remove` and `clean`, still see them.
+    fn hide_ignored_mods(&self, mut mods: Vec<(ModInfo, PathBuf)>) -> Vec<(ModInfo, PathBuf)> {
+        mods.retain(|(info, _)| !self.is_ignored(IgnoreScope::All, info.modid.as_deref()));
+        mods
+    }
+
+    /// Sets the template new mod files are named after instead of their ModDB filename.
+    pub fn with_filename_template(mut self, filename_template: Option<String>) -> Self {
+        self.file_manager = self.file_manager.with_filename_template(filename_template);
+        self
+    }
+
+    /// Keeps an index of installed mods in the cache directory, so unchanged zips aren't
+    /// opened on every run.
+    pub fn with_mod_index(mut self) -> Self {
+        let index = get_cache_dir().map(|dir| dir.join("mod_index.json"));
+        self.file_manager = self.file_manager.with_mod_index(index);
+        self
+    }
+
+    /// Sets how downloaded mods are placed into the mods directory.
+    pub fn with_install_mode(mut self, install_mode: InstallMode) -> Self {
+        self.file_manager = self.file_manager.with_install_mode(install_mode);
+        self
+    }
+
+    /// Warns when running as root on a mods directory owned by another user, and offers to
+    /// hand new files over to the directory owner so the game can still read them.
+    fn guard_ownership(mut self) -> Self {
+        let Ok(install_dir) = self.file_manager.install_dir() else {
+            return self;
+        };
+        let Some((uid, gid)) = root_owner_mismatch(&install_dir) else {
+            return self;
+        };
+
+        warn!(
+            "Running as root, but {} is owned by uid {uid}. Files written as root may not be readable by the game.",
+            install_dir.display()
+        );
+
+        if self.can_prompt()
+            && self
+                .prompt
+                .confirm(&format!("Change ownership of new files to {uid}:{gid}?"))
+        {
+            self.file_manager = self.file_manager.with_owner(Some((uid, gid)));
+        }
+        self
+    }
+
+    /// Creates the default mods directory when it is missing, either because the config
+    /// asks for it or after confirming with the user, and installs into it.
+    ///
+    /// # Arguments
+    ///
+    /// * `mods_dir` - The default mods directory.
+    /// * `create` - Create it without asking.
+    fn ensure_mods_dir(self, mods_dir: PathBuf, create: bool) -> Self {
+        if mods_dir.exists() {
+            return self;
+        }
+
+        let confirmed = create
+            || (self.can_prompt()
+                && self.prompt.confirm(&format!(
+                    "Mods directory {} does not exist yet. Create it?",
+                    mods_dir.display()
+                )));
+        if !confirmed {
+            return self;
+        }
+
+        if self.dry_run {
+            say!(
+                "[dry run] Would create mods directory: {}",
+                mods_dir.display()
+            );
+        } else if let Err(e) = std::fs::create_dir_all(&mods_dir) {
+            warn!(
+                "Failed to create mods directory {}: {e}",
+                mods_dir.display()
+            );
+            return self;
+        } else {
+            say!("Created mods directory: {}", mods_dir.display());
+        }
+
+        let mut mod_dirs = self.file_manager.mod_dirs().to_vec();
+        mod_dirs.insert(0, mods_dir);
+        self.with_mod_dirs(mod_dirs)
+    }
+
+    /// Whether a question can be answered: by the user on a terminal, or by `--yes`.
+    fn can_prompt(&self) -> bool {
+        !self.prompt.is_interactive() || self.prompt.can_ask()
+    }
+
+    /// Fails with a clear error instead of asking a question `--yes` can't answer.
+    ///
+    /// # Arguments
+    ///
+    /// * `hint` - How to make the choice without a prompt.
+    fn require_interactive(&self, hint: &str) -> Result<(), ModManagerError> {
+        if self.prompt.is_interactive() {
+            Ok(())
+        } else {
+            Err(ModManagerError::InteractionRequired(hint.to_string()))
+        }
+    }
+}
+
+impl ModManager {
+    /// Runs the command given on the command line, printing the error it fails with along
+    /// with a hint on how to fix it.
+    pub async fn run() -> ExitCode {
+        complete_if_requested();
+        let cli = Cli::parse();
+        // `config update-versions --verbose` predates `-v` and shows the same progress
+        let update_versions_verbose = matches!(
+            &cli.command,
+            Some(Commands::Config(ConfigCommands::UpdateVersions {
+                verbose: Some(true)
+            }))
+        );
+        let _log_guard = init_logging(cli.verbose + u8::from(update_versions_verbose));
+        let verbose = cli.verbose > 0;
+        match Self::run_cli(cli).await {
+            Ok(code) => code,
+            Err(e) => {
+                report_error(&e, verbose);
+                ExitCode::FAILURE
+            }
+        }
+    }
+
+    async fn run_cli(cli: Cli) -> Result<ExitCode, ModManagerError> {
+        // The script is usually sourced, so nothing else may be printed alongside it
+        if let Some(Commands::Completions { shell }) = cli.command {
+            write_completions(shell, &mut std::io::stdout())?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        set_assume_yes(cli.yes);
+        set_output_format(cli.output);
+        let config_manager = Self::load_config(&cli)?;
+        let Some(timeout) = cli.timeout else {
+            return Self::execute(cli, config_manager).await;
+        };
+
+        let started = SystemTime::now();
+        let mod_dirs = Self::resolve_mod_dirs(&cli, config_manager.as_ref());
+        let command = Self::execute(cli, config_manager);
+        match tokio::time::timeout(Duration::from_secs(timeout), command).await {
+            Ok(result) => result,
+            Err(_) => {
+                // The command future was dropped, which cancels in-flight downloads
+                Self::report_timeout(timeout, started, &mod_dirs);
+                Ok(ExitCode::from(TIMEOUT_EXIT_CODE))
+            }
+        }
+    }
+
+    /// Loads the config for this run, pointing the main mods directory at `--mods-dir` or
+    /// the `--instance`'s data directory, in that order of precedence, instead of the
+    /// configured `mods_dir`.
+    ///
+    /// # Returns
+    ///
+    /// The config, or `None` if it can't be loaded. Selecting an instance needs the config,
+    /// so then the error is returned instead.
+    fn load_config(cli: &Cli) -> Result<Option<ConfigManager>, ModManagerError> {
+        let config_manager = match ConfigManager::new() {
+            Ok(config_manager) => config_manager,
+            Err(e) if cli.mods_dir.is_none() && cli.instance.is_some() => return Err(e.into()),
+            Err(_) => return Ok(None),
+        };
+        Ok(Some(Self::select_mods_dir(cli, config_manager)?))
+    }
+
+    /// Points the main mods directory of `config_manager` at `--mods-dir`, or else at the
+    /// `Mods` directory of the `--instance`.
+    fn select_mods_dir(
+        cli: &Cli, config_manager: ConfigManager,
+    ) -> Result<ConfigManager, ConfigError> {
+        let mods_dir = match (&cli.mods_dir, &cli.instance) {
+            (Some(mods_dir), _) => Some(mods_dir.clone()),
+            (None, Some(instance)) => Some(
+                config_manager
+                    .get_instance(instance)?
+                    .data_path
+                    .join("Mods"),
+            ),
+            (None, None) => None,
+        };
+        Ok(config_manager.with_mods_dir(mods_dir))
+    }
+
+    /// Returns the config loaded at startup, or the error loading it again reports.
+    fn require_config(
+        config_manager: Option<ConfigManager>,
+    ) -> Result<ConfigManager, ModManagerError> {
+        Ok(config_manager.map_or_else(ConfigManager::new, Ok)?)
+    }
+
+    /// The main mods directory: `--mods-dir`, the `--instance`'s, the configured or the
+    /// default one. Does not check if the directory exists.
+    fn main_mods_dir(cli: &Cli, config_manager: Option<&ConfigManager>) -> PathBuf {
+        match config_manager {
+            Some(config_manager) => config_manager.main_mods_dir(),
+            None => cli
+                .mods_dir
+                .clone()
+                .unwrap_or_else(default_vintage_mods_dir),
+        }
+    }
+
+    /// The mods directories to operate on: `--dir` replaces the configured directories for
+    /// this invocation.
+    fn resolve_mod_dirs(cli: &Cli, config_manager: Option<&ConfigManager>) -> Vec<PathBuf> {
+        if !cli.dirs.is_empty() {
+            cli.dirs.clone()
+        } else if let Some(config_manager) = config_manager {
+            config_manager.get_mod_dirs()
+        } else {
+            existing_mods_dir(Self::main_mods_dir(cli, None))
+                .into_iter()
+                .collect()
+        }
+    }
+
+    /// Cleans up after a command that ran out of time and reports what it got done.
+    fn report_timeout(timeout: u64, started: SystemTime, mod_dirs: &[PathBuf]) {
+        eprintln!(
+            "{}",
+            format!("Timed out after {timeout}s, the command was cancelled").red()
+        );
+
+        let mut part_dirs = mod_dirs.to_vec();
+        part_dirs.push(get_download_dir());
+        let removed = FileManager::remove_partial_files(&part_dirs, started);
+        if !removed.is_empty() {
+            eprintln!("Removed {} partial file(s):", removed.len());
+            for path in &removed {
+                eprintln!("  {}", path.display());
+            }
+        }
+
+        let started: chrono::DateTime<chrono::Local> = started.into();
+        let completed: Vec<_> = mod_dirs
+            .first()
+            .and_then(|dir| StateFile::load(&get_state_path(dir)).ok())
+            .map(|state| {
+                state
+                    .mods
+                    .into_values()
+                    .filter(|record| {
+                        chrono::DateTime::parse_from_rfc3339(&record.installed_at)
+                            .is_ok_and(|at| at >= started)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if completed.is_empty() {
+            eprintln!("No mods were installed or updated before the timeout");
+        } else {
+            eprintln!("Completed before the timeout:");
+            for record in completed {
+                eprintln!("  {} {}", record.modid, record.version);
+            }
+        }
+    }
+
+    async fn execute(
+        cli: Cli, mut config_manager: Option<ConfigManager>,
+    ) -> Result<ExitCode, ModManagerError> {
+        let verbose = cli.verbose > 0;
+
+        let install_mode = config_manager
+            .as_ref()
+            .map(|config_manager| config_manager.config().install_mode)
+            .unwrap_or_default();
+        let mut network = config_manager
+            .as_ref()
+            .map(|config_manager| config_manager.config().network.clone())
+            .unwrap_or_default();
+        if cli.api_url.is_some() {
+            network.api_url = cli.api_url.clone();
+        }
+        let filename_template = config_manager
+            .as_ref()
+            .and_then(|config_manager| config_manager.config().filename_template.clone());
+        let pinned = config_manager
+            .as_ref()
+            .map(|config_manager| config_manager.config().pinned_mods.clone())
+            .unwrap_or_default();
+        let ignore = config_manager
+            .as_ref()
+            .map(|config_manager| config_manager.config().ignore.clone())
+            .unwrap_or_default();
+
+        let game_version = config_manager
+            .as_ref()
+            .and_then(|config_manager| config_manager.get_game_version().cloned());
+        // `--channel` applies to every mod for this run
+        let (channel, mod_channels) = match (cli.channel, &config_manager) {
+            (Some(channel), _) => (channel, BTreeMap::new()),
+            (None, Some(config_manager)) => (
+                config_manager.config().channel,
+                config_manager.config().mod_channels.clone(),
+            ),
+            (None, None) => (Channel::default(), BTreeMap::new()),
+        };
+
+        let mod_dirs = Self::resolve_mod_dirs(&cli, config_manager.as_ref());
+        let mut mod_manager = ModManager::new()
+            .with_mod_dirs(mod_dirs)
+            .with_game_version(game_version)
+            .with_install_mode(install_mode)
+            .with_filename_template(filename_template)
+            .with_mod_index()
+            .with_pinned(pinned)
+            .with_ignore_lists(ignore)
+            .with_channels(channel, mod_channels)
+            .with_network(&network)?
+            .with_response_cache(
+                (!cli.no_cache && network.cache_ttl_secs > 0)
+                    .then(|| Duration::from_secs(network.cache_ttl_secs)),
+            )
+            .with_dry_run(cli.dry_run);
+        if let Some(config_manager) = &config_manager {
+            mod_manager = mod_manager.with_retention(
+                config_manager.config().backup_retention.clone(),
+                config_manager.config().cache_retention.clone(),
+            );
+        }
+
+        // Commands that install mods on a fresh setup shouldn't fail just because the game
+        // hasn't created its mods directory yet
+        if cli.dirs.is_empty()
+            && matches!(
+                cli.command,
+                Some(
+                    Commands::Download {
+                        examples: false,
+                        ..
+                    } | Commands::Profile(ProfileCommands::Switch { .. })
+                )
+            )
+        {
+            let create = config_manager
+                .as_ref()
+                .is_some_and(|config_manager| config_manager.config().create_mods_dir);
+            let mods_dir = Self::main_mods_dir(&cli, config_manager.as_ref());
+            mod_manager = mod_manager.ensure_mods_dir(mods_dir, create);
+        }
+
+        // A game update only matters to commands that pick releases to install
+        if let Some(config_manager) = config_manager.as_mut()
+            && matches!(
+                cli.command,
+                Some(
+                    Commands::Download {
+                        examples: false,
+                        ..
+                    } | Commands::Update { .. }
+                        | Commands::Sync { .. }
+                        | Commands::Watch { .. }
+                )
+            )
+        {
+            mod_manager.check_game_version_change(config_manager).await;
+        }
+
+        match cli.command {
+            Some(Commands::Download { examples: true, .. }) => {
+                Self::print_examples(Some("download"));
+            }
+
+            Some(Commands::Download {
+                mod_string,
+                url,
+                bundle,
+                mods,
+                mod_,
+                from_stdin,
+                search_online,
+                from_lock,
+                manifest,
+                manifest_format,
+                exact,
+                file,
+                force,
+                version,
+                pick_release,
+                changelog,
+                game_version,
+                ..
+            }) => {
+                let game_version =
+                    Self::game_version_override(game_version, config_manager.as_ref())?;
+                let mods = read_ids_from_stdin_if_dash(mods)?;
+                let mod_string = match url {
+                    Some(link) => Some(parse_share_link(&link)?),
+                    None => mod_string,
+                };
+                // A bare `--from-lock` uses the lockfile next to the mods directory
+                let from_lock = from_lock
+                    .map(|path| match path {
+                        Some(path) => Ok(path),
+                        None => mod_manager
+                            .file_manager
+                            .install_dir()
+                            .map(|dir| get_lock_path(&dir)),
+                    })
+                    .transpose()?;
+                let mod_manager = mod_manager
+                    .guard_ownership()
+                    .with_changelog(changelog)
+                    .with_release_picker(pick_release)
+                    .with_game_version_override(game_version);
+                mod_manager
+                    .import_mods(Some(DownloadFlags {
+                        mod_string,
+                        bundle,
+                        mods,
+                        mod_,
+                        from_stdin,
+                        search_online,
+                        from_lock,
+                        manifest,
+                        manifest_format,
+                        exact,
+                        file,
+                        force,
+                        version,
+                    }))
+                    .await?;
+                mod_manager.prune_opportunistically();
+            }
+
+            Some(Commands::Export { examples: true, .. }) => {
+                Self::print_examples(Some("export"));
+            }
+
+            Some(Commands::Export {
+                exclude,
+                include,
+                mod_,
+                side,
+                interactive,
+                format,
+                raw_ids,
+                latest,
+                url,
+                qr,
+                bundle,
+                with_files,
+                name,
+                author,
+                description,
+                out,
+                ..
+            }) => {
+                let options = CliFlags {
+                    exclude,
+                    include,
+                    mod_,
+                    side,
+                };
+                if let Some(path) = bundle {
+                    let flags = BundleFlags {
+                        path,
+                        name,
+                        author,
+                        description,
+                        with_files,
+                    };
+                    mod_manager
+                        .export_bundle(interactive, options, latest, flags)
+                        .await?;
+                    return Ok(ExitCode::SUCCESS);
+                }
+                let format = if raw_ids { ModListFormat::Ids } else { format };
+
+                mod_manager
+                    .handle_export(
+                        interactive,
+                        format,
+                        options,
+                        latest,
+                        url,
+                        qr,
+                        out.as_deref(),
+                    )
+                    .await?;
+            }
+
+            Some(Commands::ImportFrom {
+                source,
+                format,
+                out,
+            }) => {
+                mod_manager
+                    .import_from(&source, format, out.as_deref())
+                    .await?;
+            }
+
+            Some(Commands::Update { examples: true, .. }) => {
+                Self::print_examples(Some("update"));
+            }
+
+            Some(Commands::Update {
+                exclude,
+                include,
+                mod_,
+                side,
+                changelog,
+                review,
+                auto,
+                log_file,
+                game_version,
+                ..
+            }) => {
+                let game_version =
+                    Self::game_version_override(game_version, config_manager.as_ref())?;
+                let include = read_ids_from_stdin_if_dash(include)?;
+                if auto {
+                    set_assume_yes(true);
+                }
+                // Held until the run ends, so overlapping scheduled runs skip instead
+                let _run_lock = if auto {
+                    let install_dir = mod_manager.file_manager.install_dir()?;
+                    let Some(lock) = RunLock::acquire(&get_run_lock_path(&install_dir))? else {
+                        eprintln!(
+                            "{} Another update is still running on {}, skipping this run.",
+                            "Warning:".yellow().bold(),
+                            install_dir.display()
+                        );
+                        return Ok(ExitCode::from(BUSY_EXIT_CODE));
+                    };
+                    Some(lock)
+                } else {
+                    None
+                };
+                let mod_manager = mod_manager
+                    .guard_ownership()
+                    .with_changelog(changelog)
+                    .with_update_review(review)
+                    .with_strict_compatibility(auto)
+                    .with_game_version_override(game_version);
+                let summary = mod_manager
+                    .update_mods(CliFlags {
+                        exclude,
+                        include,
+                        mod_,
+                        side,
+                    })
+                    .await?;
+                if json_output() {
+                    print_json(&summary)?;
+                } else {
+                    say!("\n{}", summary.table());
+                    for warning in &summary.warnings {
+                        say!("{} {warning}", "Warning:".yellow().bold());
+                    }
+                }
+                if let Some(config_manager) = &config_manager
+                    && !cli.dry_run
+                {
+                    mod_manager
+                        .notify_webhooks(&config_manager.config().webhooks, &summary)
+                        .await;
+                }
+                if auto
+                    && !cli.dry_run
+                    && let Some(log_file) =
+                        log_file.or_else(|| get_log_dir().map(|dir| dir.join(AUTO_LOG_NAME)))
+                    && let Err(e) = append_actions(&log_file, &summary)
+                {
+                    warn!("Failed to write the action log {}: {e}", log_file.display());
+                }
+                mod_manager.prune_opportunistically();
+                return Ok(ExitCode::from(summary.exit_code()));
+            }
+
+            Some(Commands::CompatCheck { target }) => {
+                let target = match &config_manager {
+                    Some(config_manager) => config_manager.check_game_version(&target)?,
+                    None => target,
+                };
+                let entries = mod_manager.compat_check(&target).await?;
+                if json_output() {
+                    print_json(&entries)?;
+                } else {
+                    print_compat_report(&target, &entries);
+                }
+                if !entries.iter().all(CompatEntry::is_supported) {
+                    return Ok(ExitCode::from(NOT_READY_EXIT_CODE));
+                }
+            }
+
+            Some(Commands::Check {
+                exclude,
+                include,
+                mod_,
+                side,
+                changelog,
+            }) => {
+                let include = read_ids_from_stdin_if_dash(include)?;
+                let mod_manager = mod_manager.with_changelog(changelog);
+                let updates = mod_manager
+                    .check_updates(CliFlags {
+                        exclude,
+                        include,
+                        mod_,
+                        side,
+                    })
+                    .await?;
+                if json_output() {
+                    print_json(&updates)?;
+                } else {
+                    print_available_updates(&updates);
+                }
+                if let Some(config_manager) = &config_manager
+                    && !cli.dry_run
+                {
+                    mod_manager
+                        .announce_available_updates(&config_manager.config().webhooks, &updates)
+                        .await;
+                }
+                if !updates.is_empty() {
+                    return Ok(ExitCode::from(UPDATES_AVAILABLE_EXIT_CODE));
+                }
+            }
+
+            Some(Commands::Watch {
+                interval,
+                auto,
+                notify,
+            }) => {
+                let mod_manager = if auto {
+                    mod_manager
+                        .guard_ownership()
+                        .with_strict_compatibility(true)
+                } else {
+                    mod_manager
+                };
+                let webhooks = match &config_manager {
+                    Some(config_manager) if !cli.dry_run => {
+                        config_manager.config().webhooks.clone()
+                    }
+                    _ => Vec::new(),
+                };
+                mod_manager.watch(interval, auto, notify, &webhooks).await?;
+            }
+
+            Some(Commands::Sync { mod_string, prune }) => {
+                let mod_manager = mod_manager.guard_ownership();
+                let summary = mod_manager.sync_mods(&mod_string, prune).await?;
+                if json_output() {
+                    print_json(&summary)?;
+                }
+                if let Some(config_manager) = &config_manager
+                    && !cli.dry_run
+                {
+                    mod_manager
+                        .notify_webhooks(&config_manager.config().webhooks, &summary)
+                        .await;
+                }
+                mod_manager.prune_opportunistically();
+            }
+
+            Some(Commands::Remove { mods, interactive }) => {
+                let mods = read_ids_from_stdin_if_dash(Some(mods))?.unwrap_or_default();
+                mod_manager.remove_mods(&mods, interactive).await?;
+                mod_manager.prune_opportunistically();
+            }
+
+            Some(Commands::Verify { repair }) => {
+                if !mod_manager.verify_mods(repair).await? {
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
+
+            Some(Commands::Clean) => {
+                mod_manager.clean_mods()?;
+            }
+
+            Some(Commands::List {
+                format,
+                json,
+                sort,
+                reverse,
+                verbose,
+                author,
+            }) => {
+                let format = if json || json_output() {
+                    ListFormat::Json
+                } else {
+                    format
+                };
+                mod_manager
+                    .list_mods(format, sort, reverse, verbose, author.as_deref())
+                    .await?;
+            }
+
+            Some(Commands::Status) | None => {
+                mod_manager.show_status(config_manager.as_ref()).await?;
+            }
+
+            Some(Commands::Browse {
+                tags,
+                tag,
+                author,
+                search_online,
+                game_version,
+            }) => {
+                let game_version =
+                    Self::game_version_override(game_version, config_manager.as_ref())?;
+                let mut filter = Query::new().with_tag_names(tag.unwrap_or_default());
+                if let Some(author) = &author {
+                    filter = filter.with_author_name(author);
+                }
+                mod_manager
+                    .guard_ownership()
+                    .with_game_version_override(game_version)
+                    .show_paginated_mods(search_online, tags, filter)
+                    .await?;
+            }
+
+            Some(Commands::Tui) => {
+                mod_manager.run_tui().await?;
+            }
+
+            Some(Commands::Snapshot { out }) => {
+                mod_manager.snapshot(out).await?;
+            }
+
+            Some(Commands::Why { mod_ }) => {
+                mod_manager.explain_why(&mod_).await?;
+            }
+
+            Some(Commands::Completions { shell }) => {
+                write_completions(shell, &mut std::io::stdout())?;
+            }
+
+            Some(Commands::Deps { dot }) => {
+                if !mod_manager.show_deps(dot).await? {
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
+
+            Some(Commands::Normalize) => {
+                mod_manager.normalize_filenames().await?;
+            }
+
+            Some(Commands::Rollback { mod_, version }) => {
+                mod_manager
+                    .guard_ownership()
+                    .rollback_mod(&mod_, version.as_deref())
+                    .await?;
+            }
+
+            Some(Commands::Pin { mod_ }) => {
+                Self::require_config(config_manager)?.pin_mod(&mod_)?;
+            }
+
+            Some(Commands::Unpin { mod_ }) => {
+                Self::require_config(config_manager)?.unpin_mod(&mod_)?;
+            }
+
+            Some(Commands::Restore { backup, list }) => {
+                if list {
+                    mod_manager.list_backups()?;
+                } else {
+                    mod_manager
+                        .guard_ownership()
+                        .restore_backup(backup.as_deref())
+                        .await?;
+                }
+            }
+
+            Some(Commands::Info { mod_, releases }) => {
+                mod_manager.show_info(&mod_, releases).await?;
+            }
+
+            Some(Commands::Get { key }) => {
+                match mod_manager.lookup(&key, config_manager.as_ref()).await? {
+                    Some(value) => println!("{value}"),
+                    None => return Ok(ExitCode::FAILURE),
+                }
+            }
+
+            Some(Commands::About { json }) => {
+                let about = About::collect(
+                    config_manager.as_ref(),
+                    mod_manager.file_manager.mod_dirs().to_vec(),
+                    mod_manager.api.api_url(),
+                );
+                if json || json_output() {
+                    print_json(&about)?;
+                } else {
+                    about.print();
+                }
+            }
+
+            Some(Commands::Examples { command }) => {
+                Self::print_examples(command.as_deref());
+            }
+
+            Some(Commands::Profile(profile_cmd)) => match profile_cmd {
+                ProfileCommands::Create { name, empty, force } => {
+                    mod_manager.create_profile(&name, empty, force).await?;
+                }
+                ProfileCommands::Switch { name } => {
+                    let mut config_manager = Self::require_config(config_manager)?;
+                    let profile = config_manager.get_profile(&name)?.clone();
+                    mod_manager.apply_profile(&profile).await?;
+                    config_manager.set_active_profile(&name)?;
+                    say!("Switched to profile '{name}'");
+                }
+                ProfileCommands::List => {
+                    Self::require_config(config_manager)?.list_profiles();
+                }
+                ProfileCommands::Delete { name } => {
+                    Self::require_config(config_manager)?.delete_profile(&name)?;
+                }
+            },
+
+            Some(Commands::Config(config_cmd)) => {
+                let mut config_manager = Self::require_config(config_manager)?;
+
+                match config_cmd {
+                    ConfigCommands::SetPath { path } => {
+                        config_manager.set_game_path(path)?;
+                    }
+                    ConfigCommands::Show => {
+                        config_manager.show();
+                    }
+                    ConfigCommands::Get { key } => {
+                        config_manager.print_settings(key.as_deref())?;
+                    }
+                    ConfigCommands::Set { key, value } => {
+                        config_manager.set_value(&key, value.as_deref())?;
+                    }
+                    ConfigCommands::Init { force } => {
+                        config_manager.init(force)?;
+                    }
+                    ConfigCommands::UpdateVersions { .. } => {
+                        config_manager.update_version_mappings().await?;
+                    }
+                    ConfigCommands::ListVersions => {
+                        config_manager.list_versions();
+                    }
+                    ConfigCommands::Reset => {
+                        config_manager.reset(cli.yes)?;
+                    }
+                    ConfigCommands::Validate { json, fix } => {
+                        let fixes = if fix {
+                            config_manager.fix()?
+                        } else {
+                            Vec::new()
+                        };
+
+                        let mut report = config_manager.validate();
+                        report.fixes_applied = fixes;
+
+                        if json || json_output() {
+                            print_json(&report)?;
+                        } else {
+                            report.print();
+                        }
+
+                        if report.has_errors() {
+                            return Ok(ExitCode::FAILURE);
+                        }
+                    }
+                    ConfigCommands::AddModPath { path } => {
+                        config_manager.add_mod_path(path)?;
+                    }
+                    ConfigCommands::CreateModsDir { always } => {
+                        config_manager.create_mods_dir(always)?;
+                    }
+                    ConfigCommands::RemoveModPath { path } => {
+                        config_manager.remove_mod_path(path)?;
+                    }
+                    ConfigCommands::SetModsPath { path } => {
+                        config_manager.set_mods_path(path)?;
+                    }
+                    ConfigCommands::AddInstance { name, path } => {
+                        config_manager.add_instance(&name, path)?;
+                    }
+                    ConfigCommands::RemoveInstance { name } => {
+                        config_manager.remove_instance(&name)?;
+                    }
+                    ConfigCommands::SetFilenameTemplate { template } => {
+                        config_manager.set_filename_template(template)?;
+                    }
+                    ConfigCommands::SetBackupRetention {
+                        max_age_days,
+                        max_size_mb,
+                        max_count_per_mod,
+                    } => {
+                        config_manager.set_backup_retention(
+                            max_age_days,
+                            max_size_mb,
+                            max_count_per_mod,
+                        )?;
+                    }
+                    ConfigCommands::SetChannel { channel, mod_ } => {
+                        config_manager.set_channel(channel, mod_.as_deref())?;
+                    }
+                    ConfigCommands::SetInstallMode { mode } => {
+                        config_manager.set_install_mode(mode)?;
+                    }
+                    ConfigCommands::AddWebhook {
+                        url,
+                        kind,
+                        available,
+                    } => {
+                        config_manager.add_webhook(url, kind, available)?;
+                    }
+                    ConfigCommands::RemoveWebhook { url } => {
+                        config_manager.remove_webhook(&url)?;
+                    }
+                    ConfigCommands::SetGameVersion { version } => {
+                        config_manager.set_game_version(version)?;
+                    }
+                    ConfigCommands::Ignore(ignore_cmd) => match ignore_cmd {
+                        IgnoreCommands::Add { mod_, scope } => {
+                            config_manager.ignore_mod(&mod_, scope)?;
+                        }
+                        IgnoreCommands::Remove { mod_, scope } => {
+                            config_manager.unignore_mod(&mod_, scope)?;
+                        }
+                        IgnoreCommands::List => {
+                            config_manager.list_ignored();
+                        }
+                    },
+                }
+            }
+
+            Some(Commands::Cache(CacheCommands::Prune { dry_run })) => {
+                let config_manager = Self::require_config(config_manager)?;
+                if let Some(cache_dir) = get_cache_dir() {
+                    let report = prune_dir(
+                        &cache_dir,
+                        &config_manager.config().cache_retention,
+                        dry_run,
+                    )?;
+                    Self::print_prune_report("cache", &report, dry_run);
+                }
+            }
+
+            Some(Commands::Backup(BackupCommands::Prune { dry_run })) => {
+                let config_manager = Self::require_config(config_manager)?;
+                let report = prune_dir(
+                    &backup_dir_for(&mod_manager.file_manager.install_dir()?),
+                    &config_manager.config().backup_retention,
+                    dry_run,
+                )?;
+                Self::print_prune_report("backup", &report, dry_run);
+            }
+
+            Some(Commands::Remote(remote_cmd)) => match remote_cmd {
+                RemoteCommands::Add {
+                    name,
+                    target,
+                    port,
+                    identity,
+                } => {
+                    let mut remote = Remote::from_target(&target)
+                        .ok_or_else(|| ConfigError::InvalidRemote(target.clone()))?;
+                    remote.port = port;
+                    remote.identity_file = identity;
+                    Self::require_config(config_manager)?.add_remote(&name, remote)?;
+                }
+                RemoteCommands::Remove { name } => {
+                    Self::require_config(config_manager)?.remove_remote(&name)?;
+                }
+                RemoteCommands::List => {
+                    Self::require_config(config_manager)?.list_remotes();
+                }
+                RemoteCommands::Push { name, prune } => {
+                    let remote = Self::require_config(config_manager)?
+                        .get_remote(&name)?
+                        .clone();
+                    mod_manager
+                        .push_remote(&SftpClient::new(&remote), prune)
+                        .await?;
+                }
+                RemoteCommands::Pull { name, prune } => {
+                    let remote = Self::require_config(config_manager)?
+                        .get_remote(&name)?
+                        .clone();
+                    mod_manager
+                        .pull_remote(&SftpClient::new(&remote), prune)
+                        .await?;
+                }
+                RemoteCommands::Update { name } => {
+                    let remote = Self::require_config(config_manager)?
+                        .get_remote(&name)?
+                        .clone();
+                    let mirror = get_remote_mirror_dir(&name);
+                    say!("Fetching the mods of '{name}'...");
+                    let summary = mod_manager
+                        .update_remote(&SftpClient::new(&remote), mirror)
+                        .await?;
+                    return Ok(ExitCode::from(summary.exit_code()));
+                }
+            },
+
+            Some(Commands::Plugins) => {
+                let plugins = discover_plugins();
+                if plugins.is_empty() {
+                    say!("No plugins found on PATH");
+                }
+                for (name, path) in plugins {
+                    say!("{name:<20} {}", path.display());
+                }
+            }
+
+            Some(Commands::Plugin(args)) => {
+                let Some((name, args)) = args.split_first() else {
+                    return Ok(ExitCode::SUCCESS);
+                };
+                let path = find_plugin(name).ok_or_else(|| PluginError::NotFound(name.clone()))?;
+
+                let context = PluginContext {
+                    version: env!("CARGO_PKG_VERSION"),
+                    mods_dirs: mod_manager.file_manager.mod_dirs().to_vec(),
+                    config_dir: get_config_dir(),
+                    game_version: config_manager
+                        .as_ref()
+                        .and_then(|config_manager| config_manager.get_game_version())
+                        .cloned(),
+                    verbose,
+                };
+                let status = run_plugin(&path, args, &context)?;
+                if !status.success() {
+                    let code = status.code().and_then(|code| u8::try_from(code).ok());
+                    return Ok(ExitCode::from(code.unwrap_or(1)));
+                }
+            }
+        }
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+impl<R: ModRepository> ModManager<R> {
+    /// Deletes installed mods after confirmation, warning about mods that depend on them.
+    async fn remove_mods(
+        &self, mod_ids: &[String], interactive: bool,
+    ) -> Result<(), ModManagerError> {
+        let installed = self.file_manager.collect_mods(&None).await?;
+
+        let selected: Vec<&(ModInfo, PathBuf)> = if interactive {
+            self.require_interactive("pass the mod ids to remove instead of --interactive")?;
+            let mod_names: Vec<_> = installed
+                .iter()
+                .map(|(info, _)| info.name.as_deref().unwrap_or("Unknown"))
+                .collect();
+
+            self.prompt
+                .multi_select("Select mods to remove", &labels(&mod_names))
+                .into_iter()
+                .map(|idx| &installed[idx])
+                .collect()
+        } else {
+            let wanted: Vec<String> = mod_ids.iter().map(|id| id.to_lowercase()).collect();
+            for id in &wanted {
+                if !installed
+                    .iter()
+                    .any(|(info, _)| info.modid.as_deref() == Some(id.as_str()))
+                {
+                    eprintln!("Mod {id} is not installed");
+                }
+            }
+
+            installed
+                .iter()
+                .filter(|(info, _)| info.modid.as_ref().is_some_and(|id| wanted.contains(id)))
+                .collect()
+        };
+
+        if selected.is_empty() {
+            say!("No mods selected for removal");
+            return Ok(());
+        }
+
+        let removed_ids: Vec<&str> = selected
+            .iter()
+            .filter_map(|(info, _)| info.modid.as_deref())
+            .collect();
+
+        for (info, _) in &installed {
+            let Some(modid) = info.modid.as_deref() else {
+                continue;
+            };
+            if removed_ids.contains(&modid) {
+                continue;
+            }
+            for dependency in info.dependencies.iter().flat_map(|deps| deps.keys()) {
+                if removed_ids.contains(&dependency.as_str()) {
+                    say!(
+                        "Warning: {} depends on {dependency}, which will be removed",
+                        info.name.as_deref().unwrap_or(modid)
+                    );
+                }
+            }
+        }
+
+        if self.dry_run {
+            for (_, path) in &selected {
+                self.plan_delete(path);
+            }
+            return Ok(());
+        }
+
+        say!("The following files will be deleted:");
+        for (_, path) in &selected {
+            say!("  {}", path.display());
+        }
+
+        if !self
+            .prompt
+            .confirm(&format!("Remove {} mods?", selected.len()))
+        {
+            say!("Removal cancelled.");
+            return Ok(());
+        }
+
+        self.delete_mods(&selected).await
+    }
+
+    /// Deletes installed mod files and forgets them in the state file and lockfile.
+    async fn delete_mods(&self, selected: &[&(ModInfo, PathBuf)]) -> Result<(), ModManagerError> {
+        let install_dir = self.file_manager.install_dir()?;
+        let mut state = StateFile::load(&get_state_path(&install_dir))?;
+        let mut lock = LockFile::load(&get_lock_path(&install_dir))?;
+        for (info, path) in selected {
+            self.file_manager.delete_file(path).await?;
+            if let Some(modid) = &info.modid {
+                state.remove(modid);
+                lock.remove(modid);
+            }
+            say!("Removed {}", info.name.as_deref().unwrap_or("Unknown"));
+        }
+        state.save()?;
+        lock.save()?;
+
+        Ok(())
+    }
+
+    /// Checks every installed mod zip: that the archive reads without errors, that its
+    /// modinfo.json parses, and that its version and checksum match the lockfile.
+    ///
+    /// Mods without a lockfile entry are compared with the version in the state file, but
+    /// their checksum cannot be checked. A version mismatch counts as a problem but is never
+    /// repaired, since the mod may have been replaced on purpose; damaged and tampered files
+    /// can be downloaded again.
+    ///
+    /// # Arguments
+    ///
+    /// * `repair` - Download damaged and tampered files again.
+    ///
+    /// # Returns
+    ///
+    /// `true` if no problems remain. With `--dry-run`, nothing is repaired, so every
+    /// problem found remains.
+    async fn verify_mods(&self, repair: bool) -> Result<bool, ModManagerError> {
+        let install_dir = self.file_manager.install_dir()?;
+        let lock = LockFile::load(&get_lock_path(&install_dir))?;
+        let state = StateFile::load(&get_state_path(&install_dir))?;
+        let archives = self.file_manager.mod_archives()?;
+
+        let mut found = 0;
+        let mut remaining = 0;
+        let mut unlocked = 0;
+        for path in &archives {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let (modid, problem) = match self.file_manager.verify_archive(path) {
+                Ok(info) => {
+                    let modid = info.modid.unwrap_or_default();
+                    let version = info.version.unwrap_or_default();
+                    let Some(locked) = lock.get(&modid) else {
+                        unlocked += 1;
+                        if let Some(installed) = state.get(&modid)
+                            && installed.version != version
+                        {
+                            say!(
+                                "{} {file_name}: version {version} differs from {} in the state file",
+                                "!".yellow(),
+                                installed.version
+                            );
+                            found += 1;
+                            remaining += 1;
+                        }
+                        continue;
+                    };
+                    if locked.version != version {
+                        say!(
+                            "{} {file_name}: version {version} differs from {} in the lockfile",
+                            "!".yellow(),
+                            locked.version
+                        );
+                        found += 1;
+                        remaining += 1;
+                        continue;
+                    }
+                    match locked.verify(&FileManager::hash_file(path)?) {
+                        Ok(()) => continue,
+                        Err(e) => (Some(modid), e.to_string()),
+                    }
+                }
+                // The file is unreadable, but the state file knows which mod it holds
+                Err(e) => (
+                    state
+                        .mods
+                        .values()
+                        .find(|installed| installed.filename == file_name)
+                        .map(|installed| installed.modid.clone()),
+                    e.to_string(),
+                ),
+            };
+
+            say!("{} {file_name}: {problem}", "x".red());
+            found += 1;
+            if !repair {
+                remaining += 1;
+                continue;
+            }
+            match self.repair_mod(path, modid.as_deref(), &lock, &state).await {
+                Ok(()) if !self.dry_run => {}
+                Ok(()) => remaining += 1,
+                Err(e) => {
+                    eprintln!("  Failed to repair {file_name}: {e}");
+                    remaining += 1;
+                }
+            }
+        }
+
+        if found == 0 {
+            say!("All {} mod files are intact", archives.len());
+        } else {
+            say!(
+                "Checked {} mod files, {found} with problems, {remaining} remaining",
+                archives.len()
+            );
+            if !repair {
+                say!("Run `verify --repair` to download damaged files again");
+            }
+        }
+        if unlocked > 0 {
+            say!("{unlocked} mods have no lockfile entry, so their checksums were not checked");
+        }
+        Ok(remaining == 0)
+    }
+
+    /// Downloads a damaged mod file again, in place: the release pinned in the lockfile,
+    /// or the release of the version recorded in the state file.
+    async fn repair_mod(
+        &self, path: &Path, modid: Option<&str>, lock: &LockFile, state: &StateFile,
+    ) -> Result<(), ModManagerError> {
+        let modid = modid.ok_or(ModManagerError::MissingModInfo)?;
+        let (url, version) = match (lock.get(modid), state.get(modid)) {
+            (Some(locked), _) => (locked.url.clone(), locked.version.clone()),
+            (None, Some(installed)) => {
+                let mod_info = self.fetch_mod_info(&modid.to_string()).await?;
+                let release =
+                    release_with_version(modid, &mod_info.mod_data.releases, &installed.version)?;
+                let url =
+                    release
+                        .mainfile
+                        .clone()
+                        .ok_or_else(|| ModManagerError::ReleaseNotFound {
+                            modid: modid.to_string(),
+                            version: installed.version.clone(),
+                        })?;
+                (url, installed.version.clone())
+            }
+            (None, None) => return Err(ModManagerError::NotInstalled(modid.to_string())),
+        };
+
+        if self.dry_run {
+            say!("[dry run] Would download {modid} {version} again from {url}");
+            return Ok(());
+        }
+        let bytes = self.api.fetch_file(url).await?;
+        if let Some(locked) = lock.get(modid) {
+            locked.verify(&FileManager::hash_bytes(&bytes))?;
+        }
+        self.file_manager
+            .install_mod_file(&path.to_path_buf(), &bytes)
+            .await?;
+        say!("  Downloaded {modid} {version} again");
+        Ok(())
+    }
+
+    /// Deletes the files [`FileManager::find_clutter`] finds after confirmation, backing
+    /// up mods and zips without a `modinfo.json` first.
+    ///
+    /// # Returns
+    ///
+    /// The number of deleted files.
+    fn clean_mods(&self) -> Result<usize, ModManagerError> {
+        let clutter = self.file_manager.find_clutter()?;
+        if clutter.is_empty() {
+            say!("Nothing to clean up in the mods directories");
+            return Ok(0);
+        }
+
+        if self.dry_run {
+            for (path, reason) in &clutter {
+                self.plan_delete(path);
+                say!("  {reason}");
+            }
+            return Ok(0);
+        }
+
+        let size = |path: &Path| std::fs::metadata(path).map_or(0, |meta| meta.len());
+        say!("The following files will be deleted:");
+        for (path, reason) in &clutter {
+            say!(
+                "  {} ({}): {reason}",
+                path.display(),
+                Terminal::format_size(size(path))
+            );
+        }
+        if !self
+            .prompt
+            .confirm(&format!("Delete {} files?", clutter.len()))
+        {
+            say!("Clean cancelled.");
+            return Ok(0);
+        }
+
+        let backup_name = chrono::Local::now().format(BACKUP_NAME_FORMAT).to_string();
+        let mut freed = 0;
+        for (path, reason) in &clutter {
+            if matches!(
+                reason,
+                Clutter::Duplicate { .. } | Clutter::DisabledDuplicate { .. } | Clutter::NotAMod
+            ) {
+                // Clutter is backed up next to the mods directory it was found in
+                let mods_dir = path.parent().unwrap_or(path);
+                self.backup_mod(path, &backup_dir_for(mods_dir).join(&backup_name))?;
+            }
+            freed += size(path);
+            if path.is_dir() {
+                std::fs::remove_dir_all(path)?;
+            } else {
+                std::fs::remove_file(path)?;
+            }
+        }
+        say!(
+            "Deleted {} files, freeing {}",
+            clutter.len(),
+            Terminal::format_size(freed)
+        );
+        Ok(clutter.len())
+    }
+
+    /// Prints the installed mods as a table, JSON or a plain list of ids.
+    async fn list_mods(
+        &self, format: ListFormat, sort: ListSort, reverse: bool, verbose: bool,
+        author: Option<&str>,
+    ) -> Result<(), ModManagerError> {
+        let mut entries = self.list_entries(author).await?;
+
+        if verbose || format == ListFormat::Json {
+            self.fill_release_details(&mut entries)?;
+        }
+        if verbose {
+            self.fill_compatibility(&mut entries).await;
+        }
+
+        if sort == ListSort::Popularity {
+            self.fill_download_counts(&mut entries).await;
+        }
+
+        entries.sort_by(|a, b| match sort {
+            ListSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            ListSort::Modid => a.modid.cmp(&b.modid),
+            ListSort::Version => compare_versions(&a.version, &b.version),
+            ListSort::Side => a.side.cmp(&b.side),
+            ListSort::Size => a.size_bytes.cmp(&b.size_bytes),
+            ListSort::Popularity => b.downloads.cmp(&a.downloads),
+        });
+        if reverse {
+            entries.reverse();
+        }
+
+        match format {
+            ListFormat::Json => print_json(&entries)?,
+            ListFormat::Ids => {
+                for entry in &entries {
+                    println!("{}", entry.modid);
+                }
+            }
+            ListFormat::Table => {
+                if entries.is_empty() {
+                    say!("No mods installed");
+                    return Ok(());
+                }
+
+                let mut columns = vec![
+                    Columns::new(
+                        "Name",
+                        entries
+                            .iter()
+                            .map(|e| {
+                                if e.pinned {
+                                    format!("{} (pinned)", e.name)
+                                } else {
+                                    e.name.clone()
+                                }
+                            })
+                            .collect(),
+                    ),
+                    Columns::new("Mod ID", entries.iter().map(|e| e.modid.clone()).collect()),
+                    Columns::new(
+                        "Version",
+                        entries.iter().map(|e| e.version.clone()).collect(),
+                    ),
+                    Columns::new("Side", entries.iter().map(|e| e.side.clone()).collect()),
+                    Columns::new(
+                        "Size",
+                        entries
+                            .iter()
+                            .map(|e| Terminal::format_size(e.size_bytes))
+                            .collect(),
+                    ),
+                ];
+                if verbose {
+                    columns.push(Columns::new(
+                        "Authors",
+                        entries.iter().map(|e| e.authors.join(", ")).collect(),
+                    ));
+                    columns.push(Columns::new(
+                        "Contributors",
+                        entries.iter().map(|e| e.contributors.join(", ")).collect(),
+                    ));
+                    columns.push(Columns::new(
+                        "Release",
+                        entries
+                            .iter()
+                            .map(|e| e.releaseid.map(|id| id.to_string()).unwrap_or_default())
+                            .collect(),
+                    ));
+                    columns.push(Columns::new(
+                        "Released",
+                        entries
+                            .iter()
+                            .map(|e| e.release_created.clone().unwrap_or_default())
+                            .collect(),
+                    ));
+                    columns.push(Columns::new(
+                        "Compatibility",
+                        entries
+                            .iter()
+                            .map(|e| {
+                                e.compatibility
+                                    .unwrap_or(Compatibility::Unknown)
+                                    .to_string()
+                            })
+                            .collect(),
+                    ));
+                }
+                if sort == ListSort::Popularity {
+                    columns.push(Columns::new(
+                        "Downloads",
+                        entries
+                            .iter()
+                            .map(|e| e.downloads.map(|d| d.to_string()).unwrap_or_default())
+                            .collect(),
+                    ));
+                }
+                Terminal::new().print_table(columns);
+                say!("\n{} mods installed", entries.len());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collects the installed mods as `list` rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `author` - Only keep mods with an author or contributor whose name contains this
+    ///   (case-insensitive).
+    async fn list_entries(&self, author: Option<&str>) -> Result<Vec<ListEntry>, ModManagerError> {
+        let mods = self.hide_ignored_mods(self.file_manager.collect_mods(&None).await?);
+        let author = author.map(str::to_lowercase);
+
+        Ok(mods
+            .into_iter()
+            .map(|(info, path)| ListEntry {
+                pinned: self.is_pinned(info.modid.as_deref()),
+                authors: info.author_names(),
+                contributors: info.contributors.unwrap_or_default(),
+                name: info.name.unwrap_or_else(|| "Unknown".to_string()),
+                modid: info.modid.unwrap_or_default(),
+                version: info.version.unwrap_or_default(),
+                side: info.side.unwrap_or_else(|| "universal".to_string()),
+                size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                file: path,
+                downloads: None,
+                releaseid: None,
+                release_created: None,
+                compatibility: None,
+            })
+            .filter(|entry| {
+                author.as_ref().is_none_or(|author| {
+                    entry
+                        .authors
+                        .iter()
+                        .chain(&entry.contributors)
+                        .any(|name| name.to_lowercase().contains(author))
+                })
+            })
+            .collect())
+    }
+
+    /// Fills in the ModDB release each file was installed from, as recorded in the state file.
+    ///
+    /// Only files still matching the recorded file name are filled in, so a mod replaced
+    /// by hand isn't attributed to the release it replaced.
+    fn fill_release_details(&self, entries: &mut [ListEntry]) -> Result<(), ModManagerError> {
+        let state = StateFile::load(&get_state_path(&self.file_manager.install_dir()?))?;
+
+        for entry in entries.iter_mut() {
+            let file_name = entry.file.file_name().and_then(|name| name.to_str());
+            if let Some(record) = state.get(&entry.modid)
+                && file_name == Some(record.filename.as_str())
+            {
+                entry.releaseid = record.releaseid;
+                entry.release_created = record.release_created.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills in how each installed release matches the current game version, judged by its
+    /// tags on ModDB. Mods that can't be looked up count as unknown.
+    async fn fill_compatibility(&self, entries: &mut [ListEntry]) {
+        let fetched = self
+            .fetch_mods(
+                entries
+                    .iter()
+                    .map(|entry| entry.modid.clone())
+                    .filter(|modid| !modid.is_empty()),
+            )
+            .await;
+
+        for entry in entries.iter_mut() {
+            entry.compatibility = Some(match fetched.get(&entry.modid.to_lowercase()) {
+                Some(Ok(response)) => {
+                    self.installed_compatibility(&response.mod_data.releases, &entry.version)
+                }
+                _ => Compatibility::Unknown,
+            });
+        }
+    }
+
+    /// Fills in ModDB download counts from the popularity cache, refreshing stale entries.
+    ///
+    /// Counts that can't be refreshed (e.g. while offline) fall back to the last cached
+    /// value, so listing never fails because ModDB is unreachable.
+    async fn fill_download_counts(&self, entries: &mut [ListEntry]) {
+        let cache_path = get_cache_dir().map(|dir| dir.join("popularity.json"));
+        let mut cache = cache_path
+            .as_deref()
+            .map(PopularityCache::load)
+            .unwrap_or_default();
+
+        let stale: Vec<String> = entries
+            .iter()
+            .map(|entry| entry.modid.clone())
+            .filter(|modid| !modid.is_empty() && !cache.is_fresh(modid))
+            .collect();
+
+        let fetched: Vec<_> = futures::stream::iter(stale)
+            .map(|modid| async move {
+                let downloads = self.api.get_mod(&modid).await;
+                (modid, downloads)
+            })
+            .buffer_unordered(8)
+            .collect()
+            .await;
+
+        for (modid, result) in fetched {
+            match result {
+                Ok(response) => cache.insert(&modid, response.mod_data.downloads),
+                Err(e) => info!("Could not refresh downloads of {modid}: {e}"),
+            }
+        }
+
+        for entry in entries.iter_mut() {
+            entry.downloads = cache.downloads(&entry.modid);
+        }
+
+        if cache_path.is_some()
+            && let Err(e) = cache.save()
+        {
+            warn!("Failed to save popularity cache: {e}");
+        }
+    }
+
+    /// Hashes every installed mod file and writes a `{file name: sha256}` JSON snapshot.
+    /// Unpacked mod folders are left out.
+    async fn snapshot(&self, out: Option<PathBuf>) -> Result<(), ModManagerError> {
+        let mods = self.file_manager.collect_mods(&None).await?;
+        let paths = mods
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| path.is_file())
+            .collect();
+
+        let mut snapshot = std::collections::BTreeMap::new();
+        for (path, hash) in self.file_manager.hash_files(paths).await? {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            match hash {
+                Ok(hash) => {
+                    snapshot.insert(file_name, hash);
+                }
+                Err(e) => eprintln!("Failed to hash {}: {e}", path.display()),
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&snapshot)?;
+        match out {
+            Some(out) => {
+                std::fs::write(&out, json)?;
+                say!(
+                    "Wrote snapshot of {} mods to {}",
+                    snapshot.len(),
+                    out.display()
+                );
+            }
+            None => println!("{json}"),
+        }
+
+        Ok(())
+    }
+
+    /// Resolves a `get` key to its raw value.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A dotted key such as `game.version` or `mod.carryon.version`.
+    /// * `config_manager` - The loaded configuration, if there is one.
+    ///
+    /// # Returns
+    ///
+    /// The value, `None` if the key is valid but has no value, or `UnknownKey`.
+    async fn lookup(
+        &self, key: &str, config_manager: Option<&ConfigManager>,
+    ) -> Result<Option<String>, ModManagerError> {
+        let config = config_manager.map(ConfigManager::config);
+
+        let value = match key {
+            "game.version" => config_manager
+                .and_then(|config_manager| config_manager.get_game_version())
+                .cloned(),
+            "game.path" => config
+                .and_then(|config| config.game_path.as_ref())
+                .map(|path| path.display().to_string()),
+            "mods.dir" => Some(self.file_manager.install_dir()?.display().to_string()),
+            "mods.count" => Some(
+                self.file_manager
+                    .collect_mods(&None)
+                    .await?
+                    .len()
+                    .to_string(),
+            ),
+            "install-mode" => {
+                config.map(|config| format!("{:?}", config.install_mode).to_lowercase())
+            }
+            "profile" => config.and_then(|config| config.active_profile.clone()),
+            _ => {
+                let Some((mod_id, field)) = key
+                    .strip_prefix("mod.")
+                    .and_then(|rest| rest.rsplit_once('.'))
+                else {
+                    return Err(ModManagerError::UnknownKey(key.to_string()));
+                };
+                if !["version", "name", "side", "file"].contains(&field) {
+                    return Err(ModManagerError::UnknownKey(key.to_string()));
+                }
+
+                let installed = self
+                    .file_manager
+                    .collect_mods(&None)
+                    .await?
+                    .into_iter()
+                    .find(|(info, _)| {
+                        info.modid
+                            .as_deref()
+                            .is_some_and(|modid| modid.eq_ignore_ascii_case(mod_id))
+                    });
+                installed.and_then(|(info, path)| match field {
+                    "version" => info.version,
+                    "name" => info.name,
+                    "side" => info.side,
+                    _ => Some(path.display().to_string()),
+                })
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Prints a detailed view of a mod on ModDB.
+    async fn show_info(&self, mod_id: &str, release_count: usize) -> Result<(), ModManagerError> {
+        let mod_data = self.api.get_mod(mod_id).await?.mod_data;
+        if json_output() {
+            print_json(&mod_data)?;
+            return Ok(());
+        }
+
+        say!("{} by {}", mod_data.name.bold(), mod_data.author);
+        say!(
+            "Side: {} | Type: {} | Downloads: {} | Follows: {}",
+            mod_data.side,
+            mod_data.type_,
+            mod_data.downloads,
+            mod_data.follows
+        );
+        if !mod_data.tags.is_empty() {
+            say!("Tags: {}", mod_data.tags.join(", "));
+        }
+
+        let description = strip_html(&mod_data.text);
+        if !description.is_empty() {
+            say!("\n{description}");
+        }
+
+        let links = [
+            (
+                "ModDB",
+                mod_data
+                    .urlalias
+                    .as_ref()
+                    .map(|alias| format!("https://mods.vintagestory.at/{alias}")),
+            ),
+            ("Homepage", mod_data.homepageurl.clone()),
+            ("Source", mod_data.sourcecodeurl.clone()),
+            ("Issues", mod_data.issuetrackerurl.clone()),
+            ("Wiki", mod_data.wikiurl.clone()),
+        ];
+        let links: Vec<_> = links
+            .into_iter()
+            .filter_map(|(label, url)| url.filter(|url| !url.is_empty()).map(|url| (label, url)))
+            .collect();
+        if !links.is_empty() {
+            say!();
+            for (label, url) in links {
+                say!("{label}: {url}");
+            }
+        }
+
+        let releases: Vec<_> = mod_data.releases.iter().take(release_count).collect();
+        if releases.is_empty() {
+            say!("\nNo releases published");
+            return Ok(());
+        }
+
+        say!();
+        Terminal::new().print_table(vec![
+            Columns::new(
+                "Version",
+                releases
+                    .iter()
+                    .map(|r| r.modversion.clone().unwrap_or_default())
+                    .collect(),
+            ),
+            Columns::new(
+                "Released",
+                releases
+                    .iter()
+                    .map(|r| r.created.clone().unwrap_or_default())
+                    .collect(),
+            ),
+            Columns::new(
+                "Downloads",
+                releases
+                    .iter()
+                    .map(|r| r.downloads.unwrap_or(0).to_string())
+                    .collect(),
+            ),
+            Columns::new(
+                "Game versions",
+                releases.iter().map(|r| r.tags.join(", ")).collect(),
+            ),
+            Columns::new(
+                "Compatibility",
+                releases
+                    .iter()
+                    .map(|r| self.release_compatibility(r).to_string())
+                    .collect(),
+            ),
+        ]);
+
+        if mod_data.releases.len() > release_count {
+            say!(
+                "\n{} older releases not shown",
+                mod_data.releases.len() - release_count
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Renames installed mod files to the filename template in one pass.
+    ///
+    /// Files whose target name is already taken are skipped. The state file and lockfile
+    /// next to each mods directory are updated to the new names.
+    async fn normalize_filenames(&self) -> Result<(), ModManagerError> {
+        let installed = self.file_manager.collect_mods(&None).await?;
+        let mut renamed = 0;
+
+        // The template names zips; folders and code mods keep their names
+        for (mod_info, path) in installed.into_iter().filter(|(_, path)| is_mod_zip(path)) {
+            let (Some(modid), Some(version)) = (&mod_info.modid, &mod_info.version) else {
+                continue;
+            };
+            let (Some(dir), Some(old_name)) = (
+                path.parent(),
+                path.file_name().and_then(|name| name.to_str()),
+            ) else {
+                continue;
+            };
+
+            let new_name = self.file_manager.mod_filename(modid, version);
+            if old_name == new_name {
+                continue;
+            }
+
+            let new_path = dir.join(&new_name);
+            if new_path.exists() {
+                eprintln!("Skipping {old_name}: {new_name} already exists");
+                continue;
+            }
+
+            if self.dry_run {
+                say!("[dry run] Would rename {old_name} -> {new_name}");
+                renamed += 1;
+                continue;
+            }
+
+            tokio::fs::rename(&path, &new_path).await?;
+            self.rename_in_index(dir, modid, old_name, &new_name);
+            say!("Renamed {old_name} -> {new_name}");
+            renamed += 1;
+        }
+
+        if renamed == 0 {
+            say!("All mod files already match the filename template");
+        } else {
+            say!("Renamed {renamed} mod file(s)");
+        }
+        Ok(())
+    }
+
+    /// Points the state file and lockfile entries of a renamed mod at its new file name.
+    ///
+    /// Failures are only logged; the file itself was renamed successfully.
+    fn rename_in_index(&self, mods_dir: &Path, modid: &str, old_name: &str, new_name: &str) {
+        let result = StateFile::load(&get_state_path(mods_dir)).and_then(|mut state| {
+            match state.mods.get_mut(&modid.to_lowercase()) {
+                Some(record) if record.filename == old_name => {
+                    record.filename = new_name.to_string();
+                    state.save()
+                }
+                _ => Ok(()),
+            }
+        });
+        if let Err(e) = result {
+            warn!("Failed to update state file for {modid}: {e}");
+        }
+
+        let result = LockFile::load(&get_lock_path(mods_dir)).and_then(|mut lock| {
+            match lock.mods.iter_mut().find(|locked| {
+                locked.modid.eq_ignore_ascii_case(modid) && locked.filename == old_name
+            }) {
+                Some(locked) => {
+                    locked.filename = new_name.to_string();
+                    lock.save()
+                }
+                None => Ok(()),
+            }
+        });
+        if let Err(e) = result {
+            warn!("Failed to update lockfile for {modid}: {e}");
+        }
+    }
+
+    /// Prints a short overview of the setup and suggests what to do next.
+    ///
+    /// Everything shown is read from disk; ModDB is not contacted.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_manager` - The loaded configuration, if there is one.
+    async fn show_status(
+        &self, config_manager: Option<&ConfigManager>,
+    ) -> Result<(), ModManagerError> {
+        let config = config_manager.map(ConfigManager::config);
+        let mods_dir = self.file_manager.install_dir().ok();
+        let installed = if mods_dir.is_some() {
+            self.file_manager.collect_mods(&None).await?
+        } else {
+            Vec::new()
+        };
+        let disabled = if mods_dir.is_some() {
+            self.file_manager.collect_disabled_mods().await?.len()
+        } else {
+            0
+        };
+        let state = mods_dir
+            .as_deref()
+            .map(|dir| StateFile::load(&get_state_path(dir)))
+            .transpose()?
+            .unwrap_or_default();
+        let unmanaged: Vec<String> = installed
+            .iter()
+            .filter_map(|(info, _)| info.modid.clone())
+            .filter(|modid| state.get(modid).is_none())
+            .collect();
+
+        let status = Status {
+            game_version: config.and_then(|config| config.get_game_version().cloned()),
+            game_path: config.and_then(|config| config.get_game_path().cloned()),
+            mods_dir,
+            installed: installed.len(),
+            disabled,
+            unmanaged,
+            last_update: state.last_update,
+        };
+        if json_output() {
+            print_json(&status)?;
+        } else {
+            status.print();
+        }
+        Ok(())
+    }
+
+    /// Copies an installed mod file into a backup directory, creating it if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The installed mod file.
+    /// * `backup` - The backup directory of the current run.
+    fn backup_mod(&self, path: &Path, backup: &Path) -> Result<(), std::io::Error> {
+        let Some(file_name) = path.file_name() else {
+            return Ok(());
+        };
+        if self.dry_run {
+            say!(
+                "[dry run] Would back up {} to {}",
+                path.display(),
+                backup.display()
+            );
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(backup)?;
+        copy_recursively(path, &backup.join(file_name))
+    }
+
+    /// Returns the backups of the install directory, newest first, with the mod files in
+    /// each of them.
+    fn backups(&self) -> Result<Vec<(String, Vec<PathBuf>)>, ModManagerError> {
+        let backup_dir = backup_dir_for(&self.file_manager.install_dir()?);
+        if !backup_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in std::fs::read_dir(&backup_dir)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if !path.is_dir() {
+                continue;
+            }
+
+            let mut files: Vec<_> = std::fs::read_dir(&path)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| file.extension().is_some_and(|ext| ext == "zip"))
+                .collect();
+            files.sort();
+            if !files.is_empty() {
+                backups.push((name.to_string(), files));
+            }
+        }
+        // Backup names are timestamps, so they sort chronologically
+        backups.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(backups)
+    }
+
+    /// Prints the available backups with the mod files in each of them.
+    fn list_backups(&self) -> Result<(), ModManagerError> {
+        let backups = self.backups()?;
+        if backups.is_empty() {
+            say!("No backups found");
+            return Ok(());
+        }
+
+        for (name, files) in backups {
+            say!("{} ({} mod(s))", name.bold(), files.len());
+            for file in files {
+                say!(
+                    "  {}",
+                    file.file_name().unwrap_or_default().to_string_lossy()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Rolls the mods folder back to a backup: every mod file in it replaces the installed
+    /// version of the same mod.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The backup to restore, or `None` to pick one from the list.
+    async fn restore_backup(&self, name: Option<&str>) -> Result<(), ModManagerError> {
+        let backups = self.backups()?;
+        let (name, files) = match name {
+            Some(name) => backups
+                .into_iter()
+                .find(|(backup, _)| backup == name)
+                .ok_or_else(|| ModManagerError::BackupNotFound(name.to_string()))?,
+            None => {
+                if backups.is_empty() {
+                    say!("No backups found");
+                    return Ok(());
+                }
+                self.require_interactive("pass the backup to restore, see `restore --list`")?;
+                let options: Vec<_> = backups
+                    .iter()
+                    .map(|(name, files)| format!("{name} ({} mod(s))", files.len()))
+                    .collect();
+                let Some(index) = self.prompt.select("Select a backup to restore", &options) else {
+                    say!("Restore cancelled.");
+                    return Ok(());
+                };
+                backups.into_iter().nth(index).unwrap_or_default()
+            }
+        };
+
+        say!("Restoring backup {name}");
+        for file in files {
+            // Backups may be older than what is installed, so downgrades are expected
+            if let Err(e) = self.install_local_file(&file, true).await {
+                eprintln!("Failed to restore {}: {e}", file.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Announces a game update noticed since the last run and offers to check installed
+    /// mods against the new version. Mods are only moved to compatible releases by an
+    /// explicit `update`, even with `--yes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config_manager` - The loaded configuration, which remembers the last-seen version.
+    async fn check_game_version_change(&self, config_manager: &mut ConfigManager) {
+        let (previous, current) = match config_manager.check_game_version_change() {
+            Ok(Some(change)) => change,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to record the game version: {e}");
+                return;
+            }
+        };
+
+        eprintln!(
+            "{}",
+            format!("Vintage Story was updated from {previous} to {current}")
+                .yellow()
+                .bold()
+        );
+        if !config_manager.config().is_detected_version_mapped() {
+            eprintln!("Run 'config update-versions' to fetch the version mapping for {current}");
+        }
+        if !self.can_prompt() || !std::io::stdout().is_terminal() {
+            eprintln!("Run 'update' to move your mods to releases compatible with {current}");
+            return;
+        }
+
+        if !self
+            .prompt
+            .confirm("Check installed mods for compatibility with the new version?")
+        {
+            return;
+        }
+        match self.compatibility_report().await {
+            Ok(0) => {}
+            Ok(upgradable) => eprintln!(
+                "Run 'update' to move {upgradable} mod(s) to releases compatible with {current}"
+            ),
+            Err(e) => eprintln!("Failed to check mod compatibility: {e}"),
+        }
+    }
+
+    /// Prints how each installed mod's release matches the current game version, with the
+    /// newer compatible release to move to where there is one.
+    ///
+    /// # Returns
+    ///
+    /// The number of mods a newer compatible release is available for.
+    async fn compatibility_report(&self) -> Result<usize, ModManagerError> {
+        let installed = self.file_manager.collect_mods(&None).await?;
+        let (mut names, mut versions, mut compatibility, mut suggested) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut upgradable = 0;
+
+        for (mod_info, _) in &installed {
+            let (Some(modid), Some(version)) = (&mod_info.modid, &mod_info.version) else {
+                continue;
+            };
+            names.push(mod_info.name.clone().unwrap_or_else(|| modid.clone()));
+            versions.push(version.clone());
+
+            let Ok(mod_data) = self.fetch_mod_info(modid).await else {
+                compatibility.push(Compatibility::Unknown.to_string());
+                suggested.push("-".to_string());
+                continue;
+            };
+            let releases = &mod_data.mod_data.releases;
+            compatibility.push(self.installed_compatibility(releases, version).to_string());
+
+            match self
+                .find_compatible_release(releases)
+                .and_then(|release| release.modversion.as_deref())
+            {
+                Some(newer) if is_newer(newer, version) => {
+                    upgradable += 1;
+                    suggested.push(newer.to_string());
+                }
+                _ => suggested.push("-".to_string()),
+            }
+        }
+
+        if names.is_empty() {
+            say!("No mods installed");
+            return Ok(0);
+        }
+        Terminal::new().print_table(vec![
+            Columns::new("Mod", names),
+            Columns::new("Installed", versions),
+            Columns::new("Compatibility", compatibility),
+            Columns::new("Compatible release", suggested),
+        ]);
+        Ok(upgradable)
+    }
+
+    /// Explains why a mod is installed, combining the state file and the dependency graph.
+    async fn explain_why(&self, mod_id: &str) -> Result<(), ModManagerError> {
+        let installed = self.file_manager.collect_mods(&None).await?;
+        let mod_id = mod_id.to_lowercase();
+
+        let Some((mod_info, path)) = installed
+            .iter()
+            .find(|(info, _)| info.modid.as_deref() == Some(mod_id.as_str()))
+        else {
+            say!("Mod {mod_id} is not installed");
+            return Ok(());
+        };
+
+        say!(
+            "{} ({mod_id}) version {} - {}",
+            mod_info.name.as_deref().unwrap_or("Unknown"),
+            mod_info.version.as_deref().unwrap_or("Unknown"),
+            path.display()
+        );
+
+        let state = StateFile::load(&get_state_path(&self.file_manager.install_dir()?))?;
+        match state.get(&mod_id) {
+            Some(record) => {
+                let installed_at = chrono::DateTime::parse_from_rfc3339(&record.installed_at)
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|_| record.installed_at.clone());
+
+                match &record.reason {
+                    InstallReason::Explicit => {
+                        say!("Installed explicitly (last changed {installed_at})")
+                    }
+                    InstallReason::Dependency { required_by } => say!(
+                        "Installed as a dependency of: {} (last changed {installed_at})",
+                        required_by.join(", ")
+                    ),
+                    InstallReason::Adopted => say!(
+                        "Adopted from an existing file and since managed by this tool (last changed {installed_at})"
+                    ),
+                }
+            }
+            None => say!("Not installed by this tool (adopted from an unmanaged file)"),
+        }
+
+        let dependents: Vec<(&str, &str)> = installed
+            .iter()
+            .filter_map(|(info, _)| {
+                let constraint = info.dependencies.as_ref()?.get(&mod_id)?;
+                Some((info.modid.as_deref()?, constraint.as_str()))
+            })
+            .collect();
+
+        if dependents.is_empty() {
+            say!("No installed mods depend on it");
+        } else {
+            say!("Required by:");
+            for (dependent, constraint) in dependents {
+                match constraint {
+                    "" | "*" => say!("  - {dependent} (any version)"),
+                    constraint => say!("  - {dependent} (>= {constraint})"),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prints the dependency graph of the installed mods and the problems in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `dot` - Print the graph in Graphviz DOT format instead of as a tree.
+    ///
+    /// # Returns
+    ///
+    /// `true` if no missing, outdated or circular dependencies were found.
+    async fn show_deps(&self, dot: bool) -> Result<bool, ModManagerError> {
+        let installed = self.file_manager.collect_mods(&None).await?;
+        let game_version = self.get_current_game_version();
+        let graph = DependencyGraph::new(
+            installed.iter().map(|(info, _)| info),
+            game_version.as_deref(),
+        );
+
+        if dot {
+            print!("{}", graph.render_dot());
+        } else {
+            print!("{}", graph.render_tree());
+        }
+
+        let problems = graph.problems();
+        if problems.is_empty() {
+            if !dot {
+                say!("\nNo dependency problems found");
+            }
+        } else {
+            eprintln!("\n{}", "Dependency problems:".yellow().bold());
+            for problem in &problems {
+                eprintln!("  - {problem}");
+            }
+        }
+        Ok(problems.is_empty())
+    }
+
+    /// Checks downloaded bytes against the checksum pinned in the lockfile when the same
+    /// release of the mod was installed before, so a file that changed on the server or in
+    /// transit is never installed silently.
+    fn verify_download(&self, release: &Release, bytes: &[u8]) -> Result<(), ModManagerError> {
+        let sha256 = FileManager::hash_bytes(bytes);
+        info!(
+            "SHA-256 of {}: {sha256}",
+            release.filename.as_deref().unwrap_or("download")
+        );
+
+        let (Some(modid), Some(version)) = (&release.modidstr, &release.modversion) else {
+            return Ok(());
+        };
+        let lock = LockFile::load(&get_lock_path(&self.file_manager.install_dir()?))?;
+        let Some(locked) = lock.get(modid) else {
+            return Ok(());
+        };
+
+        let same_file = match (locked.fileid, release.fileid) {
+            (Some(locked_id), Some(release_id)) if locked_id != 0 && release_id != 0 => {
+                locked_id == release_id
+            }
+            _ => true,
+        };
+        if locked.version == *version && same_file {
+            locked.verify(&sha256)?;
+        }
+        Ok(())
+    }
+
+    /// The filename a release is saved under: the configured filename template, or the
+    /// name the file has on ModDB.
+    fn release_filename(&self, release: &Release) -> Option<String> {
+        match (
+            self.file_manager.filename_template(),
+            &release.modidstr,
+            &release.modversion,
+        ) {
+            (Some(_), Some(modid), Some(version)) => {
+                Some(self.file_manager.mod_filename(modid, version))
+            }
+            _ => release.filename.clone(),
+        }
+    }
+
+    /// Records an installed release in the state file and pins it in the lockfile.
+    ///
+    /// Failures are only logged; the mod itself was installed successfully.
+    fn record_install(&self, release: &Release, reason: InstallReason, bytes: &[u8]) {
+        let (Some(modid), Some(version), Some(filename)) = (
+            &release.modidstr,
+            &release.modversion,
+            self.release_filename(release),
+        ) else {
+            return;
+        };
+
+        let Ok(install_dir) = self.file_manager.install_dir() else {
+            return;
+        };
+
+        let result = StateFile::load(&get_state_path(&install_dir)).and_then(|mut state| {
+            let record = state.record_install(modid, version, &filename, reason);
+            record.releaseid = release.releaseid.filter(|id| *id != 0);
+            record.release_created = release.created.clone().filter(|c| !c.is_empty());
+            state.save()
+        });
+
+        if let Err(e) = result {
+            warn!("Failed to record install of {modid}: {e}");
+        }
+
+        let Some(url) = &release.mainfile else {
+            return;
+        };
+
+        let locked = LockedMod {
+            modid: modid.clone(),
+            version: version.clone(),
+            fileid: release.fileid,
+            filename,
+            url: url.clone(),
+            sha256: FileManager::hash_bytes(bytes),
+        };
+
+        let result = LockFile::load(&get_lock_path(&install_dir)).and_then(|mut lock| {
+            lock.upsert(locked);
+            lock.save()
+        });
+
+        if let Err(e) = result {
+            warn!("Failed to update lockfile for {modid}: {e}");
+        }
+    }
+
+    /// Creates a profile from the currently enabled mods, or an empty one.
+    async fn create_profile(
+        &self, name: &str, empty: bool, force: bool,
+    ) -> Result<(), ModManagerError> {
+        let mods = if empty {
+            Vec::new()
+        } else {
+            self.file_manager
+                .collect_mods(&None)
+                .await?
+                .into_iter()
+                .filter_map(|(info, _)| {
+                    Some(ProfileMod {
+                        modid: info.modid?,
+                        version: info.version?,
+                    })
+                })
+                .collect()
+        };
+
+        self.load_config_file()?
+            .create_profile(name, Profile { mods }, force)?;
+        Ok(())
+    }
+
+    /// Makes the mods directory match a profile.
+    ///
+    /// Mods not in the profile (or at another version) are disabled by renaming them to
+    /// `*.disabled`, previously disabled mods are re-enabled, and anything still missing is
+    /// downloaded at the pinned version.
+    async fn apply_profile(&self, profile: &Profile) -> Result<(), ModManagerError> {
+        let is_wanted = |info: &ModInfo| {
+            profile.mods.iter().any(|wanted| {
+                info.modid
+                    .as_deref()
+                    .is_some_and(|modid| modid.eq_ignore_ascii_case(&wanted.modid))
+                    && info.version.as_deref() == Some(wanted.version.as_str())
+            })
+        };
+
+        let mut enabled = Vec::new();
+        for (info, path) in self.file_manager.collect_mods(&None).await? {
+            if is_wanted(&info) {
+                enabled.push(info);
+            } else {
+                self.file_manager.disable_mod(&path).await?;
+                say!("Disabled {}", info.name.as_deref().unwrap_or("Unknown"));
+            }
+        }
+
+        for (info, path) in self.file_manager.collect_disabled_mods().await? {
+            let already_enabled = enabled
+                .iter()
+                .any(|other| other.modid == info.modid && other.version == info.version);
+            if is_wanted(&info) && !already_enabled {
+                self.file_manager.enable_mod(&path).await?;
+                say!("Enabled {}", info.name.as_deref().unwrap_or("Unknown"));
+                enabled.push(info);
+            }
+        }
+
+        for wanted in &profile.mods {
+            let present = enabled.iter().any(|info| {
+                info.modid
+                    .as_deref()
+                    .is_some_and(|modid| modid.eq_ignore_ascii_case(&wanted.modid))
+            });
+            if present {
+                continue;
+            }
+
+            let mod_info = self.fetch_mod_info(&wanted.modid).await?;
+            let release = mod_info
+                .mod_data
+                .releases
+                .iter()
+                .find(|release| release.modversion.as_deref() == Some(wanted.version.as_str()))
+                .ok_or_else(|| ModManagerError::ReleaseNotFound {
+                    modid: wanted.modid.clone(),
+                    version: wanted.version.clone(),
+                })?;
+            self.save_release(&mod_info, release).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces an installed mod with another of its releases, picked by `version` or
+    /// from a menu of the published releases.
+    ///
+    /// The selected release is downloaded before the installed file is removed, so a
+    /// failed download leaves the current version in place.
+    async fn rollback_mod(
+        &self, mod_id: &str, version: Option<&str>,
+    ) -> Result<(), ModManagerError> {
+        let (installed, installed_path) = self
+            .file_manager
+            .collect_mods(&None)
+            .await?
+            .into_iter()
+            .find(|(info, _)| {
+                info.modid
+                    .as_deref()
+                    .is_some_and(|modid| modid.eq_ignore_ascii_case(mod_id))
+            })
+            .ok_or_else(|| ModManagerError::NotInstalled(mod_id.to_string()))?;
+        let current_version = installed.version.as_deref().unwrap_or_default();
+
+        let mod_info = self.fetch_mod_info(&mod_id.to_string()).await?;
+        let releases: Vec<&Release> = mod_info
+            .mod_data
+            .releases
+            .iter()
+            .filter(|release| release.modversion.as_deref() != Some(current_version))
+            .collect();
+
+        let release = match version {
+            Some(version) => releases
+                .into_iter()
+                .find(|release| release.modversion.as_deref() == Some(version))
+                .ok_or_else(|| ModManagerError::ReleaseNotFound {
+                    modid: mod_id.to_string(),
+                    version: version.to_string(),
+                })?,
+            None => {
+                if releases.is_empty() {
+                    return Err(self.no_releases(mod_id));
+                }
+                self.require_interactive("pass the release to roll back to with --version")?;
+                let items: Vec<String> = releases
+                    .iter()
+                    .map(|release| {
+                        format!(
+                            "{} ({}) [{}]",
+                            release.modversion.as_deref().unwrap_or("?"),
+                            release.created.as_deref().unwrap_or("unknown date"),
+                            self.release_compatibility(release)
+                        )
+                    })
+                    .collect();
+                let prompt = format!("Currently installed: {current_version}. Roll back to");
+                match self.prompt.select(&prompt, &items) {
+                    Some(idx) => releases[idx],
+                    None => return Ok(()),
+                }
+            }
+        };
+
+        self.save_release(&mod_info, release).await?;
+
+        let new_path = self
+            .file_manager
+            .install_dir()?
+            .join(self.release_filename(release).unwrap_or_default());
+        if installed_path != new_path {
+            self.delete_old_mod(&installed_path).await?;
+        }
+        Ok(())
+    }
+
+    /// Installs exactly the releases pinned in a lockfile.
+    ///
+    /// Files already present with the locked checksum are kept, other versions of locked
+    /// mods are replaced, and any download whose checksum differs from the lockfile aborts
+    /// the install.
+    async fn install_from_lock(&self, path: &Path) -> Result<(), ModManagerError> {
+        if !path.exists() {
+            return Err(FileError::FileNotFound(path.display().to_string()).into());
+        }
+
+        let lock = LockFile::load(path)?;
+        if lock.mods.is_empty() {
+            say!("No mods are pinned in {}", path.display());
+            return Ok(());
+        }
+
+        let install_dir = self.file_manager.install_dir()?;
+        let installed = self.file_manager.collect_mods(&None).await?;
+
+        // Check every entry up front, so a bad lockfile doesn't leave a half-applied install
+        let mut entries = Vec::with_capacity(lock.mods.len());
+        for locked in &lock.mods {
+            let release = Release {
+                mainfile: Some(locked.url.clone()),
+                filename: Some(locked.filename.clone()),
+                fileid: locked.fileid,
+                modidstr: Some(locked.modid.clone()),
+                modversion: Some(locked.version.clone()),
+                ..Release::default()
+            };
+            locked.check_filename(&locked.filename)?;
+            let filename = self
+                .release_filename(&release)
+                .unwrap_or_else(|| locked.filename.clone());
+            locked.check_filename(&filename)?;
+            entries.push((locked, release, install_dir.join(filename)));
+        }
+
+        for (locked, release, mod_path) in entries {
+            let up_to_date = mod_path.exists()
+                && FileManager::hash_file(&mod_path).is_ok_and(|hash| locked.verify(&hash).is_ok());
+
+            if up_to_date {
+                say!("{} {} is already installed", locked.modid, locked.version);
+                continue;
+            }
+
+            if self.dry_run {
+                self.plan_download(&release, &mod_path).await;
+                continue;
+            }
+
+            let bytes = self.api.fetch_file(locked.url.clone()).await?;
+            locked.verify(&FileManager::hash_bytes(&bytes))?;
+
+            // A file already at the target path is set aside until the new one is in place
+            let mut set_aside = mod_path.clone().into_os_string();
+            set_aside.push(".old");
+            let replacing = tokio::fs::symlink_metadata(&mod_path).await.is_ok();
+            if replacing {
+                tokio::fs::rename(&mod_path, &set_aside).await?;
+            }
+            if let Err(e) = self.file_manager.install_mod_file(&mod_path, &bytes).await {
+                if replacing {
+                    tokio::fs::rename(&set_aside, &mod_path).await?;
+                }
+                return Err(e.into());
+            }
+            if replacing {
+                let _ = tokio::fs::remove_file(&set_aside).await;
+            }
+
+            for (info, old_path) in &installed {
+                let same_mod = info
+                    .modid
+                    .as_deref()
+                    .is_some_and(|modid| modid.eq_ignore_ascii_case(&locked.modid));
+                if same_mod && *old_path != mod_path {
+                    self.delete_old_mod(old_path).await?;
+                }
+            }
+
+            self.record_install(&release, InstallReason::Explicit, &bytes);
+            say!("Installed {} {}", locked.modid, locked.version);
+        }
+
+        Ok(())
+    }
+
+    /// Finds every installed copy of a mod, in any mods directory.
+    async fn installed_copies(
+        &self, modid: &str,
+    ) -> Result<Vec<(ModInfo, PathBuf)>, ModManagerError> {
+        Ok(self
+            .file_manager
+            .collect_mods(&None)
+            .await?
+            .into_iter()
+            .filter(|(other, _)| {
+                other
+                    .modid
+                    .as_deref()
+                    .is_some_and(|other| other.eq_ignore_ascii_case(modid))
+            })
+            .collect())
+    }
+
+    /// Installs a mod from a local zip under its canonical filename.
+    ///
+    /// Other installed versions of the same mod are removed. An installed version newer
+    /// than the archive is only replaced with `force`.
+    async fn install_local_file(&self, path: &Path, force: bool) -> Result<(), ModManagerError> {
+        let info = self.file_manager.read_local_mod(path)?;
+        let (Some(modid), Some(version)) = (info.modid, info.version) else {
+            return Err(ModManagerError::MissingModInfo);
+        };
+
+        let install_dir = self.file_manager.install_dir()?;
+        let installed = self.installed_copies(&modid).await?;
+
+        for (other, _) in &installed {
+            let installed_version = other.version.as_deref().unwrap_or_default();
+            if !force && compare_versions(installed_version, &version).is_gt() {
+                return Err(ModManagerError::NewerInstalled {
+                    modid,
+                    installed: installed_version.to_string(),
+                    candidate: version,
+                });
+            }
+        }
+
+        let filename = self.file_manager.mod_filename(&modid, &version);
+        let mod_path = install_dir.join(&filename);
+        let bytes = std::fs::read(path)?;
+
+        for (_, old_path) in &installed {
+            if *old_path != mod_path {
+                self.delete_old_mod(old_path).await?;
+            }
+        }
+
+        if self.dry_run {
+            say!(
+                "[dry run] Would install {modid} {version} ({}) from {} -> {}{}",
+                Terminal::format_size(bytes.len() as u64),
+                path.display(),
+                mod_path.display(),
+                overwrite_note(&mod_path)
+            );
+            return Ok(());
+        }
+
+        self.file_manager
+            .install_mod_file(&mod_path, &bytes)
+            .await?;
+
+        let release = Release {
+            filename: Some(filename),
+            modidstr: Some(modid.clone()),
+            modversion: Some(version.clone()),
+            ..Release::default()
+        };
+        self.record_install(&release, InstallReason::Explicit, &bytes);
+        say!("Installed {modid} {version} from {}", path.display());
+        Ok(())
+    }
+
+    /// Applies the configured retention policies to the backup and cache directories.
+    ///
+    /// Called after commands that modify the mods directory. Failures are only logged, as
+    /// pruning is housekeeping and must never fail the command itself.
+    fn prune_opportunistically(&self) {
+        if self.dry_run {
+            return;
+        }
+        let Some((backup_retention, cache_retention)) = &self.retention else {
+            return;
+        };
+
+        let targets = [
+            (
+                self.file_manager
+                    .install_dir()
+                    .ok()
+                    .map(|dir| backup_dir_for(&dir)),
+                backup_retention,
+            ),
+            (get_cache_dir(), cache_retention),
+        ];
+
+        for (dir, policy) in targets {
+            let Some(dir) = dir else { continue };
+            match prune_dir(&dir, policy, false) {
+                Ok(report) if !report.is_empty() => info!(
+                    "Pruned {} files from {}",
+                    report.removed_files.len(),
+                    dir.display()
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to prune {}: {e}", dir.display()),
+            }
+        }
+    }
+
+    /// Prints the outcome of a `prune` command.
+    fn print_prune_report(kind: &str, report: &PruneReport, dry_run: bool) {
+        if report.is_empty() {
+            say!("Nothing to prune in the {kind} directory");
+            return;
+        }
+
+        let verb = if dry_run { "Would remove" } else { "Removed" };
+        for path in &report.removed_files {
+            say!("{verb}: {}", path.display());
+        }
+        for path in &report.removed_dirs {
+            say!("{verb} empty directory: {}", path.display());
+        }
+        say!(
+            "{verb} {} {kind} files ({:.1} MB)",
+            report.removed_files.len(),
+            report.freed_bytes as f64 / (1024.0 * 1024.0)
+        );
+    }
+
+    /// Checks a `--game-version` override against the known version mappings. Without a
+    /// config there are no mappings to check against, so the version is used as given.
+    fn game_version_override(
+        version: Option<String>, config_manager: Option<&ConfigManager>,
+    ) -> Result<Option<String>, ConfigError> {
+        match (version, config_manager) {
+            (Some(version), Some(config_manager)) => {
+                config_manager.check_game_version(&version).map(Some)
+            }
+            (version, _) => Ok(version),
+        }
+    }
+
+    /// Prints the example invocations for a command, grouped by workflow.
+    fn print_examples(command: Option<&str>) {
+        let examples = examples_for(command);
+
+        if examples.is_empty() {
+            say!("No examples available for: {}", command.unwrap_or_default());
+            return;
+        }
+
+        let mut current_workflow = "";
+        for example in examples {
+            if example.workflow != current_workflow {
+                if !current_workflow.is_empty() {
+                    say!();
+                }
+                say!("{}:", example.workflow);
+                current_workflow = example.workflow;
+            }
+            say!("  # {}", example.description);
+            say!("  {}", example.invocation);
+        }
+    }
+
+    async fn import_mods(&self, options: Option<DownloadFlags>) -> Result<(), ModManagerError> {
+        let options = options.ok_or(ModManagerError::MissingModInfo)?;
+
+        if let Some(mod_string) = &options.mod_string {
+            self.download_mod_string(mod_string, options.exact).await?;
+        }
+
+        if let Some(bundle) = &options.bundle {
+            self.install_bundle(bundle).await?;
+        }
+
+        if let Some(mods) = &options.mods {
+            self.download_mods(mods).await?;
+        }
+
+        if let Some(mod_) = &options.mod_ {
+            self.download_mod(mod_, options.version.as_deref()).await?;
+        }
+
+        if let Some(lock_path) = &options.from_lock {
+            self.install_from_lock(lock_path).await?;
+        }
+
+        if options.from_stdin {
+            let input = std::io::read_to_string(std::io::stdin())?;
+            let mods = self.encoder.parse_raw_ids(&input)?;
+            self.download_encoder_data(mods, options.exact).await?;
+        }
+
+        if let Some(file) = &options.file {
+            self.install_local_file(file, options.force).await?;
+        }
+
+        if let Some(manifest) = &options.manifest {
+            let mods = self.read_manifest(manifest, options.manifest_format)?;
+            self.install_manifest(mods, options.exact).await?;
+        }
+
+        if options.is_all_none() {
+            self.show_paginated_mods(options.search_online, false, Query::new())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a mod list file (or stdin for `-`) and decodes it with the codec for `format`,
+    /// detecting the format from the contents when none is given.
+    fn read_manifest(
+        &self, path: &Path, format: Option<ModListFormat>,
+    ) -> Result<Vec<EncoderData>, ModManagerError> {
+        let data = if path == Path::new("-") {
+            std::io::read_to_string(std::io::stdin())?
+        } else if path.exists() {
+            std::fs::read_to_string(path)?
+        } else {
+            return Err(FileError::FileNotFound(path.display().to_string()).into());
+        };
+
+        let format = format.unwrap_or_else(|| ModListFormat::detect(&data, &self.encoder));
+        info!("Reading manifest {} as {format:?}", path.display());
+        Ok(format.codec(&self.encoder).decode(&data)?)
+    }
+
+    async fn handle_export(
+        &self, interactive: Option<bool>, format: ModListFormat, option: CliFlags, latest: bool,
+        url: bool, qr: bool, out: Option<&Path>,
+    ) -> Result<(), ModManagerError> {
+        let mut encoded = self
+            .export_string(interactive, format, option, latest)
+            .await?;
+        if url {
+            encoded = share_link(&encoded);
+        }
+        if qr {
+            say!("{}", render_qr(&encoded)?);
+        }
+        write_mod_list(format, encoded, out)
+    }
+
+    /// Prints the mods of another installation as a mod list: the mod files in a `Mods`
+    /// directory, or the mods a server or client log says were loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - A `Mods` directory, e.g. copied from a server, or a log file.
+    /// * `format` - The format to print the mod list in.
+    /// * `out` - Write the mod list to this file instead of stdout.
+    async fn import_from(
+        &self, source: &Path, format: ModListFormat, out: Option<&Path>,
+    ) -> Result<(), ModManagerError> {
+        let codec = format.codec(&self.encoder);
+        let encoded = if source.is_dir() {
+            let mods = FileManager::new()
+                .with_mod_dirs(vec![source.to_path_buf()])
+                .collect_mods(&None)
+                .await?;
+            say!("Found {} mods in {}", mods.len(), source.display());
+            let entries = self
+                .create_list_entries(&mods, format.shows_details())
+                .await?;
+            codec.encode_entries(&entries)?
+        } else {
+            let log = std::fs::read_to_string(source)?;
+            let mods = parse_server_log(&log)?;
+            say!("Found {} mods in {}", mods.len(), source.display());
+            if mods.iter().any(|data| data.mod_version.is_empty()) {
+                eprintln!(
+                    "{} The log doesn't name every mod's version; those mods will install at their newest compatible release",
+                    "Warning:".yellow().bold()
+                );
+            }
+            codec.encode(&mods)?
+        };
+        write_mod_list(format, encoded, out)
+    }
+
+    /// Builds the export output, serialized with the codec for `format`.
+    ///
+    /// With `latest` only the mod IDs are recorded, so importing the list installs the
+    /// newest compatible releases rather than the installed versions.
+    async fn export_string(
+        &self, interactive: Option<bool>, format: ModListFormat, option: CliFlags, latest: bool,
+    ) -> Result<String, ModManagerError> {
+        let selected_mods = self.select_export_mods(interactive, option).await?;
+
+        let mut entries = self
+            .create_list_entries(&selected_mods, format.shows_details() && !latest)
+            .await?;
+        if latest {
+            for entry in &mut entries {
+                entry.version.clear();
+                entry.url = None;
+            }
+        }
+        let encoded = format.codec(&self.encoder).encode_entries(&entries)?;
+
+        info!("Exported {} mods", selected_mods.len());
+        Ok(encoded)
+    }
+
+    /// The installed mods matching the export filters, narrowed down through a menu with
+    /// `interactive`.
+    async fn select_export_mods(
+        &self, interactive: Option<bool>, option: CliFlags,
+    ) -> Result<Vec<(ModInfo, PathBuf)>, ModManagerError> {
+        let mods = self.hide_ignored_mods(self.file_manager.collect_mods(&Some(option)).await?);
+
+        let selected_mods = if interactive.unwrap_or(false) {
+            self.require_interactive("use --include or --exclude instead of --interactive")?;
+            let mod_names: Vec<_> = mods
+                .iter()
+                .map(|(info, _)| info.name.as_deref().unwrap_or("Unknown"))
+                .collect();
+
+            let selections = self
+                .prompt
+                .multi_select("Select mods to export", &labels(&mod_names));
+            selections
+                .into_iter()
+                .map(|idx| mods[idx].clone())
+                .collect()
+        } else {
+            mods
+        };
+        Ok(selected_mods)
+    }
+
+    /// Writes the selected mods into a `.vsmodpack` bundle, with their files if asked to.
+    ///
+    /// Unpacked mod folders are listed in the manifest but never included, so they are
+    /// downloaded when the bundle is installed.
+    async fn export_bundle(
+        &self, interactive: Option<bool>, option: CliFlags, latest: bool, flags: BundleFlags,
+    ) -> Result<(), ModManagerError> {
+        let mods = self.select_export_mods(interactive, option).await?;
+        let entries = self.create_list_entries(&mods, !latest).await?;
+
+        let mut path = flags.path;
+        if path.extension().is_none() {
+            path.set_extension(BUNDLE_EXTENSION);
+        }
+        let name = flags.name.unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let mut manifest = BundleManifest::new(&name);
+        manifest.author = flags.author;
+        manifest.description = flags.description;
+        manifest.game_version = self.get_current_game_version();
+
+        let mut files = Vec::new();
+        for ((_, mod_path), mut entry) in mods.iter().zip(entries) {
+            // Only zips can be installed from the pack; other mods are listed by ID
+            let is_zip = is_mod_zip(mod_path);
+            let sha256 = is_zip
+                .then(|| FileManager::hash_file(mod_path))
+                .transpose()?;
+            let file = match mod_path.file_name() {
+                Some(filename) if is_zip && flags.with_files => {
+                    Some(bundled_file_path(&filename.to_string_lossy()))
+                }
+                _ => None,
+            };
+            if let Some(file) = &file {
+                files.push((file.clone(), mod_path.as_path()));
+            } else if flags.with_files {
+                warn!(
+                    "{} is not a zip archive, only its mod ID is included",
+                    entry.modid
+                );
+            }
+            if latest {
+                entry.version.clear();
+                entry.url = None;
+            }
+            manifest.mods.push(BundledMod {
+                entry,
+                sha256: sha256.filter(|_| !latest),
+                file,
+            });
+        }
+
+        write_bundle(&path, &manifest, &files)?;
+        say!(
+            "Exported {} mods ({} files included) to {}",
+            manifest.mods.len(),
+            files.len(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Installs a `.vsmodpack` bundle: the mod files it includes after checking them
+    /// against the manifest, then the remaining mods from ModDB at the listed versions.
+    async fn install_bundle(&self, path: &Path) -> Result<(), ModManagerError> {
+        let mut bundle = Bundle::open(path)?;
+        let manifest = bundle.manifest.clone();
+        say!(
+            "Installing mod pack {} ({} mods)",
+            manifest.name,
+            manifest.mods.len()
+        );
+        if let Some(description) = &manifest.description {
+            say!("{description}");
+        }
+        if let (Some(pack_version), Some(game_version)) =
+            (&manifest.game_version, self.get_current_game_version())
+            && *pack_version != game_version
+        {
+            eprintln!(
+                "{} The pack was made for game version {pack_version}, you have {game_version}",
+                "Warning:".yellow().bold()
+            );
+        }
+
+        let staging = get_download_dir();
+        std::fs::create_dir_all(&staging)?;
+        let mut to_download = Vec::new();
+        for bundled in &manifest.mods {
+            let Some(file) = &bundled.file else {
+                to_download.push(EncoderData::from(bundled.entry.clone()));
+                continue;
+            };
+            let bytes = bundle.read_file(file)?;
+            let actual = FileManager::hash_bytes(&bytes);
+            if let Some(expected) = &bundled.sha256
+                && *expected != actual
+            {
+                return Err(FileError::ChecksumMismatch {
+                    path: path.join(file),
+                    expected: expected.clone(),
+                    actual,
+                }
+                .into());
+            }
+
+            let filename = Path::new(file).file_name().unwrap_or(file.as_ref());
+            let staged = staging.join(filename);
+            std::fs::write(&staged, &bytes)?;
+            let result = self.install_local_file(&staged, false).await;
+            let _ = std::fs::remove_file(&staged);
+            match result {
+                Err(ModManagerError::NewerInstalled {
+                    modid, installed, ..
+                }) => say!("Keeping {modid} {installed}, which is newer than the pack's"),
+                other => other?,
+            }
+        }
+
+        if !to_download.is_empty() {
+            self.download_encoder_data(to_download, true).await?;
+        }
+        Ok(())
+    }
+
+    fn create_encoder_data(
+        &self, mods: &[(ModInfo, PathBuf)],
+    ) -> Result<Vec<EncoderData>, ModManagerError> {
+        mods.iter()
+            .map(|(mod_info, _)| {
+                info!("Creating encoder data for: {mod_info:?}");
+                let mod_id = mod_info
+                    .modid
+                    .as_ref()
+                    .ok_or_else(|| ModManagerError::InvalidModPath("Missing mod ID".to_string()))?;
+                let version = mod_info.version.as_ref().ok_or_else(|| {
+                    ModManagerError::InvalidModPath("Missing mod version".to_string())
+                })?;
+
+                Ok(EncoderData {
+                    mod_id: mod_id.clone(),
+                    mod_version: version.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Builds mod list entries with the names and authors from the mod files.
+    ///
+    /// Download URLs come from the lockfile. With `lookup_urls`, mods missing from the
+    /// lockfile are looked up on ModDB; lookups that fail leave the URL empty.
+    async fn create_list_entries(
+        &self, mods: &[(ModInfo, PathBuf)], lookup_urls: bool,
+    ) -> Result<Vec<ModListEntry>, ModManagerError> {
+        let lock = match self.file_manager.install_dir() {
+            Ok(dir) => LockFile::load(&get_lock_path(&dir)).ok(),
+            Err(_) => None,
+        };
+
+        let mut entries = Vec::new();
+        for (data, (mod_info, _)) in self.create_encoder_data(mods)?.into_iter().zip(mods) {
+            let mut url = lock
+                .as_ref()
+                .and_then(|lock| lock.get(&data.mod_id))
+                .filter(|locked| locked.version == data.mod_version)
+                .map(|locked| locked.url.clone());
+            if url.is_none() && lookup_urls {
+                url = match self.fetch_mod_info(&data.mod_id).await {
+                    Ok(response) => response
+                        .mod_data
+                        .releases
+                        .into_iter()
+                        .find(|release| release.modversion.as_deref() == Some(&data.mod_version))
+                        .and_then(|release| release.mainfile),
+                    Err(e) => {
+                        warn!("Could not look up {} on ModDB: {e}", data.mod_id);
+                        None
+                    }
+                };
+            }
+
+            entries.push(ModListEntry {
+                name: mod_info.name.clone(),
+                authors: mod_info.author_names(),
+                url,
+                ..ModListEntry::from(&data)
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Updates the installed mods, dependencies before the mods that need them, so a mod
+    /// whose new release requires a newer dependency finds it already updated.
+    async fn update_mods(&self, mod_options: CliFlags) -> Result<RunSummary, ModManagerError> {
+        let mut mods = self.file_manager.collect_mods(&Some(mod_options)).await?;
+        // The graph covers every installed mod, so filtered runs still see all constraints
+        let installed = self.file_manager.collect_mods(&None).await?;
+        let mut graph = DependencyGraph::new(
+            installed.iter().map(|(info, _)| info),
+            self.get_current_game_version().as_deref(),
+        );
+        let order = graph.update_order();
+        mods.sort_by_key(|(info, _)| {
+            info.modid
+                .as_deref()
+                .and_then(|modid| order.iter().position(|id| id.eq_ignore_ascii_case(modid)))
+                .unwrap_or(order.len())
+        });
+        let install_dir = self.file_manager.install_dir()?;
+        let mut summary = RunSummary::new("update");
+        let mut pending = Vec::new();
+        // Files replaced during this run are backed up together, so the run can be undone
+        let backup = backup_dir_for(&install_dir)
+            .join(chrono::Local::now().format(BACKUP_NAME_FORMAT).to_string());
+
+        say!("Checking for updates...");
+        let fetched = self.fetch_installed_mods(&mods).await;
+        let declined = if self.review_updates {
+            self.review_updates(&mods, &fetched)
+        } else {
+            HashSet::new()
+        };
+        for (mod_info, path) in mods {
+            if let Some(modid) = &mod_info.modid
+                && declined.contains(&modid.to_lowercase())
+            {
+                let name = mod_info.name.as_deref().unwrap_or("Unknown");
+                say!("Skipping deselected mod: {name}");
+                summary.skipped.push(name.to_string());
+                continue;
+            }
+            if self.is_pinned(mod_info.modid.as_deref()) {
+                let name = mod_info.name.as_deref().unwrap_or("Unknown");
+                say!(
+                    "Skipping pinned mod: {name} - Current version: {}",
+                    mod_info.version.as_deref().unwrap_or("Unknown")
+                );
+                summary.skipped.push(name.to_string());
+                continue;
+            }
+            if self.is_ignored(IgnoreScope::Updates, mod_info.modid.as_deref()) {
+                let name = mod_info.name.as_deref().unwrap_or("Unknown");
+                say!("Skipping ignored mod: {name}");
+                summary.skipped.push(name.to_string());
+                continue;
+            }
+            let updated = self
+                .process_mod_update(
+                    &mod_info,
+                    &fetched,
+                    path,
+                    &backup,
+                    &graph,
+                    &mut summary,
+                    &mut pending,
+                )
+                .await;
+            if let Some(new_path) = updated
+                && !self.dry_run
+            {
+                self.check_updated_dependencies(&mut graph, &new_path, &mut summary);
+            }
+        }
+
+        if !self.dry_run {
+            self.record_update_run(&install_dir, &summary, pending);
+        }
+        Ok(summary)
+    }
+
+    /// Looks for newer compatible releases of the installed mods without touching the disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `mod_options` - Filters selecting the mods to check, as for `update`.
+    ///
+    /// # Returns
+    ///
+    /// The available updates. Pinned mods are left out.
+    async fn check_updates(
+        &self, mod_options: CliFlags,
+    ) -> Result<Vec<AvailableUpdate>, ModManagerError> {
+        let mods = self.file_manager.collect_mods(&Some(mod_options)).await?;
+        let mut updates = Vec::new();
+
+        say!("Checking for updates...");
+        let fetched = self.fetch_installed_mods(&mods).await;
+        for (mod_info, _) in mods {
+            if self.is_pinned(mod_info.modid.as_deref())
+                || self.is_ignored(IgnoreScope::Updates, mod_info.modid.as_deref())
+            {
+                continue;
+            }
+            let name = mod_info.name.as_deref().unwrap_or("Unknown");
+            let version = mod_info.version.as_deref().unwrap_or("Unknown");
+            let Some((release, changelog)) =
+                self.check_and_get_update(&mod_info, name, version, &fetched)
+            else {
+                continue;
+            };
+
+            let size = match &release.mainfile {
+                Some(url) => self.api.fetch_file_size(url).await.ok().flatten(),
+                None => None,
+            };
+            updates.push(AvailableUpdate {
+                name: name.to_string(),
+                modid: mod_info.modid.clone().unwrap_or_default(),
+                from: version.to_string(),
+                compatibility: self.release_compatibility(&release),
+                to: release.modversion.unwrap_or_default(),
+                size,
+                changelog,
+            });
+        }
+        Ok(updates)
+    }
+
+    /// Checks for updates every `interval` until interrupted with Ctrl+C.
+    ///
+    /// Each release is announced only the first time it is found, unless `auto` fails to
+    /// install it. A failed check is reported and retried at the next interval instead of
+    /// ending the watch.
+    ///
+    /// # Arguments
+    ///
+    /// * `auto` - Install the new updates as soon as they are found.
+    /// * `notify` - Also show a desktop notification for new updates.
+    /// * `webhooks` - Webhooks to post the summary of automatic update runs to.
+    async fn watch(
+        &self, interval: Duration, auto: bool, notify: bool, webhooks: &[Webhook],
+    ) -> Result<(), ModManagerError> {
+        let mut announced = HashSet::new();
+        say!(
+            "Watching for updates every {} minute(s). Press Ctrl+C to stop.",
+            interval.as_secs() / 60
+        );
+
+        loop {
+            match self.new_updates(&mut announced).await {
+                Ok(updates) if !updates.is_empty() => {
+                    say!("\n[{}]", chrono::Local::now().format("%Y-%m-%d %H:%M"));
+                    if json_output() {
+                        print_json(&updates)?;
+                    } else {
+                        print_available_updates(&updates);
+                    }
+                    if notify {
+                        show_desktop_notification(&updates);
+                    }
+                    if auto {
+                        self.apply_watched_updates(&updates, webhooks, &mut announced)
+                            .await;
+                    } else {
+                        self.notify_webhooks(webhooks, &available_summary("watch", &updates))
+                            .await;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("{} Update check failed: {e}", "Warning:".yellow().bold()),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = tokio::signal::ctrl_c() => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs an update check and keeps only the releases not announced before.
+    ///
+    /// # Arguments
+    ///
+    /// * `announced` - The mod ID and version of every release already announced; the new
+    ///   ones are added to it.
+    async fn new_updates(
+        &self, announced: &mut HashSet<(String, String)>,
+    ) -> Result<Vec<AvailableUpdate>, ModManagerError> {
+        let mut updates = self.check_updates(CliFlags::default()).await?;
+        updates.retain(|update| announced.insert((update.modid.clone(), update.to.clone())));
+        Ok(updates)
+    }
+
+    /// Installs the updates found by `watch`, reporting the run like `update` does. A
+    /// failed run is only reported, so the watch keeps going.
+    ///
+    /// # Arguments
+    ///
+    /// * `announced` - The releases announced so far. Updates that failed to install are
+    ///   taken out again, so the next check retries them.
+    async fn apply_watched_updates(
+        &self, updates: &[AvailableUpdate], webhooks: &[Webhook],
+        announced: &mut HashSet<(String, String)>,
+    ) {
+        let include = updates.iter().map(|update| update.modid.clone()).collect();
+        let flags = CliFlags {
+            include: Some(include),
+            ..CliFlags::default()
+        };
+        let failed: Vec<&AvailableUpdate> = match self.update_mods(flags).await {
+            Ok(summary) => {
+                if !json_output() {
+                    say!("\n{}", summary.table());
+                }
+                self.notify_webhooks(webhooks, &summary).await;
+                self.prune_opportunistically();
+                updates
+                    .iter()
+                    .filter(|update| summary.failed.contains(&update.name))
+                    .collect()
+            }
+            Err(e) => {
+                eprintln!(
+                    "{} Automatic update failed: {e}",
+                    "Warning:".yellow().bold()
+                );
+                updates.iter().collect()
+            }
+        };
+        for update in failed {
+            announced.remove(&(update.modid.clone(), update.to.clone()));
+        }
+    }
+
+    /// Fetches the ModDB entries of the installed, unpinned mods for an update check.
+    ///
+    /// # Returns
+    ///
+    /// The response for each mod, keyed by its lowercased mod ID.
+    async fn fetch_installed_mods(
+        &self, mods: &[(ModInfo, PathBuf)],
+    ) -> HashMap<String, Result<ModApiResponse, ClientError>> {
+        let modids = mods
+            .iter()
+            .filter_map(|(mod_info, _)| mod_info.modid.clone())
+            .filter(|modid| {
+                !self.is_pinned(Some(modid.as_str()))
+                    && !self.is_ignored(IgnoreScope::Updates, Some(modid.as_str()))
+            });
+        self.fetch_mods(modids).await
+    }
+
+    /// Fetches the ModDB entries of several mods.
+    ///
+    /// The requests run concurrently, so checking many mods takes a few round-trips
+    /// instead of one per mod.
+    ///
+    /// # Returns
+    ///
+    /// The response for each mod, keyed by its lowercased mod ID.
+    async fn fetch_mods(
+        &self, modids: impl IntoIterator<Item = String>,
+    ) -> HashMap<String, Result<ModApiResponse, ClientError>> {
+        let modids: BTreeMap<String, String> = modids
+            .into_iter()
+            .map(|modid| (modid.to_lowercase(), modid))
+            .collect();
+
+        futures::stream::iter(modids)
+            .map(|(key, modid)| async move { (key, self.api.get_mod(&modid).await) })
+            .buffer_unordered(UPDATE_CHECK_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Checks every installed mod against the game version the game is about to be
+    /// upgraded to. Pinned mods are checked too, as they have to run on the new version
+    /// all the same.
+    ///
+    /// # Returns
+    ///
+    /// One entry per installed mod, sorted by name.
+    async fn compat_check(&self, target: &str) -> Result<Vec<CompatEntry>, ModManagerError> {
+        let mods = self.file_manager.collect_mods(&None).await?;
+        say!(
+            "Checking {} mods against game version {target}...",
+            mods.len()
+        );
+        let fetched = self
+            .fetch_mods(
+                mods.iter()
+                    .filter_map(|(mod_info, _)| mod_info.modid.clone()),
+            )
+            .await;
+
+        let mut entries: Vec<CompatEntry> = mods
+            .into_iter()
+            .map(|(mod_info, _)| {
+                let modid = mod_info.modid.unwrap_or_default();
+                let installed = mod_info.version.unwrap_or_default();
+                let lookup = fetched.get(&modid.to_lowercase());
+                let readiness = match lookup {
+                    Some(Ok(response)) => Some(Readiness::of_releases(
+                        &installed,
+                        &self.channel_candidates(&response.mod_data.releases),
+                        target,
+                    )),
+                    Some(Err(ClientError::ModNotFound(_))) => None,
+                    Some(Err(e)) => {
+                        warn!("Failed to look up {modid}: {e}");
+                        None
+                    }
+                    None => None,
+                };
+                let status = match (&readiness, lookup) {
+                    (Some(readiness), _) => readiness.label(),
+                    (None, Some(Err(ClientError::ModNotFound(_)))) => NOT_ON_MODDB,
+                    (None, _) => "unknown",
+                };
+                CompatEntry {
+                    name: mod_info.name.unwrap_or_else(|| modid.clone()),
+                    modid,
+                    installed,
+                    status,
+                    release: readiness
+                        .as_ref()
+                        .and_then(Readiness::release)
+                        .map(str::to_string),
+                    readiness,
+                }
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.name.to_lowercase());
+        Ok(entries)
+    }
+
+    /// Saves the outcome of an update run to the state file, for `status`.
+    fn record_update_run(
+        &self, install_dir: &Path, summary: &RunSummary, pending: Vec<PendingUpdate>,
+    ) {
+        let result = StateFile::load(&get_state_path(install_dir)).and_then(|mut state| {
+            state.last_update = Some(UpdateRun {
+                finished_at: chrono::Local::now().to_rfc3339(),
+                updated: summary.updated.len(),
+                pending,
+            });
+            state.save()
+        });
+        if let Err(e) = result {
+            warn!("Failed to record the update run: {e}");
+        }
+    }
+
+    /// Updates one mod if a newer compatible release exists.
+    ///
+    /// # Returns
+    ///
+    /// The path of the new file if the mod was updated.
+    async fn process_mod_update(
+        &self, mod_info: &ModInfo, fetched: &HashMap<String, Result<ModApiResponse, ClientError>>,
+        path: PathBuf, backup: &Path, graph: &DependencyGraph, summary: &mut RunSummary,
+        pending: &mut Vec<PendingUpdate>,
+    ) -> Option<PathBuf> {
+        let name = mod_info.name.as_deref().unwrap_or("Unknown");
+        let version = mod_info.version.as_deref().unwrap_or("Unknown");
+        // Keep updated mods in the directory they were found in
+        let mods_dir = match path.parent() {
+            Some(dir) => dir.to_path_buf(),
+            None => self.file_manager.install_dir().ok()?,
+        };
+
+        match self.check_and_get_update(mod_info, name, version, fetched) {
+            Some((release, _)) => {
+                let new_version = release.modversion.clone().unwrap_or_default();
+                match self
+                    .handle_mod_update(name, path, &mods_dir, backup, release, Some(graph))
+                    .await
+                {
+                    UpdateOutcome::Installed(new_path) => {
+                        summary.updated.push(UpdatedMod {
+                            name: name.to_string(),
+                            modid: mod_info.modid.clone().unwrap_or_default(),
+                            from: version.to_string(),
+                            to: new_version,
+                        });
+                        Some(new_path)
+                    }
+                    UpdateOutcome::Declined => {
+                        summary.skipped.push(name.to_string());
+                        None
+                    }
+                    UpdateOutcome::Failed => {
+                        summary.failed.push(name.to_string());
+                        pending.push(PendingUpdate {
+                            modid: mod_info.modid.clone().unwrap_or_default(),
+                            from: version.to_string(),
+                            to: new_version,
+                        });
+                        None
+                    }
+                }
+            }
+            None => {
+                if let Some(refused) = self.held_back_version(mod_info, version, fetched) {
+                    warn!(
+                        "Not updating {name} to {refused}: the release is not marked compatible with game version {}",
+                        self.get_current_game_version().unwrap_or_default()
+                    );
+                    summary.held_back.push(UpdatedMod {
+                        name: name.to_string(),
+                        modid: mod_info.modid.clone().unwrap_or_default(),
+                        from: version.to_string(),
+                        to: refused,
+                    });
+                    return None;
+                }
+                say!("No update available for mod: {name} - Current version: {version}");
+                summary.up_to_date.push(name.to_string());
+                None
+            }
+        }
+    }
+
+    /// With strict compatibility, returns the version of the newest release of a mod that
+    /// was refused for not being marked compatible with the game version, if it is newer
+    /// than the installed `version`.
+    fn held_back_version(
+        &self, mod_info: &ModInfo, version: &str,
+        fetched: &HashMap<String, Result<ModApiResponse, ClientError>>,
+    ) -> Option<String> {
+        if !self.strict_compatibility {
+            return None;
+        }
+        let modid = mod_info.modid.as_ref()?.to_lowercase();
+        let Some(Ok(full_mod_info)) = fetched.get(&modid) else {
+            return None;
+        };
+        let newest = Release::highest(self.channel_candidates(&full_mod_info.mod_data.releases))?;
+        let newest_version = newest.modversion.as_deref()?;
+        (is_newer(newest_version, version) && !self.is_release_compatible(newest))
+            .then(|| newest_version.to_string())
+    }
+
+    /// Records the version of a freshly updated mod in `graph` and adds a warning to the
+    /// summary for each of its dependencies the installed mods don't satisfy, such as a
+    /// dependency that is pinned or has no compatible newer release.
+    fn check_updated_dependencies(
+        &self, graph: &mut DependencyGraph, path: &Path, summary: &mut RunSummary,
+    ) {
+        let info = match self.file_manager.read_local_mod(path) {
+            Ok(info) => info,
+            Err(e) => {
+                warn!("Failed to read the dependencies of {}: {e}", path.display());
+                return;
+            }
+        };
+        // read_local_mod only accepts mods declaring both
+        let (Some(modid), Some(version)) = (&info.modid, &info.version) else {
+            return;
+        };
+        graph.set_version(modid, version);
+        summary.warnings.extend(
+            graph
+                .unmet(modid, info.dependencies.iter().flatten())
+                .into_iter()
+                .map(|problem| problem.to_string()),
+        );
+    }
+
+    /// Posts the summary of a run to every configured webhook. Runs that changed nothing
+    /// are not announced, updates that were only found go to the webhooks that opted in,
+    /// and webhook failures are only logged.
+    async fn notify_webhooks(&self, webhooks: &[Webhook], summary: &RunSummary) {
+        if webhooks.is_empty() || (summary.is_empty() && summary.available.is_empty()) {
+            return;
+        }
+
+        for webhook in webhooks {
+            if summary.is_available_only() && !webhook.available {
+                continue;
+            }
+            if let Err(e) = send_webhook(&self.webhook_client, webhook, summary).await {
+                warn!("Failed to notify webhook {}: {e}", webhook.url);
+            }
+        }
+    }
+
+    /// Posts the updates `check` found to the webhooks that opted in. Releases announced
+    /// by an earlier run are left out, so a scheduled `check` posts each release once.
+    ///
+    /// The announced releases are remembered in the state file. Only updates that are still
+    /// available are kept there, so a release is announced again after it was installed
+    /// and rolled back.
+    async fn announce_available_updates(&self, webhooks: &[Webhook], updates: &[AvailableUpdate]) {
+        let Ok(install_dir) = self.file_manager.install_dir() else {
+            return;
+        };
+        let mut state = match StateFile::load(&get_state_path(&install_dir)) {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Failed to read the announced updates: {e}");
+                return;
+            }
+        };
+
+        let mut summary = available_summary("check", updates);
+        summary.available.retain(|update| {
+            !state
+                .announced
+                .iter()
+                .any(|announced| announced.modid == update.modid && announced.to == update.to)
+        });
+        self.notify_webhooks(webhooks, &summary).await;
+
+        state.announced = updates
+            .iter()
+            .map(|update| PendingUpdate {
+                modid: update.modid.clone(),
+                from: update.from.clone(),
+                to: update.to.clone(),
+            })
+            .collect();
+        if let Err(e) = state.save() {
+            warn!("Failed to record the announced updates: {e}");
+        }
+    }
+
+    /// Looks for a newer compatible release of an installed mod and announces it.
+    ///
+    /// # Arguments
+    ///
+    /// * `fetched` - The ModDB entries of the installed mods, from `fetch_installed_mods`.
+    ///
+    /// # Returns
+    ///
+    /// The release to update to and the changelogs since the installed version, or `None`
+    /// if the mod is up to date or the check failed.
+    fn check_and_get_update(
+        &self, mod_info: &ModInfo, name: &str, version: &str,
+        fetched: &HashMap<String, Result<ModApiResponse, ClientError>>,
+    ) -> Option<(Release, String)> {
+        let full_mod_info = match mod_info
+            .modid
+            .as_ref()
+            .and_then(|modid| fetched.get(&modid.to_lowercase()))
+        {
+            Some(Ok(full_mod_info)) => full_mod_info,
+            Some(Err(e)) => {
+                eprintln!("Failed to check updates for {name}: {e}");
+                return None;
+            }
+            None => {
+                eprintln!(
+                    "Failed to check updates for {name}: {}",
+                    ClientError::ModNotFound(name.to_string())
+                );
+                return None;
+            }
+        };
+
+        // Handle the main result cases first
+        match mod_info.check_for_update(full_mod_info) {
+            Ok((false, _)) => return None,
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("Failed to check updates for {name}: {e}");
+                return None;
+            }
+        };
+
+        let compatible_release = self.find_compatible_release(&full_mod_info.mod_data.releases)?;
+
+        // Simple version check
+        let current_version = mod_info.version.as_deref().unwrap_or("Unknown");
+        let new_version = compatible_release
+            .modversion
+            .as_deref()
+            .unwrap_or("Unknown");
+
+        if !is_newer(new_version, current_version) {
+            say!("Mod {name} is already at the latest compatible version: {current_version}");
+            return None;
+        }
+
+        // Print update info and return
+        self.print_update_info(name, current_version, new_version, compatible_release);
+        let changelog = changelog_between(
+            &full_mod_info.mod_data.releases,
+            Some(current_version),
+            new_version,
+        );
+        if self.show_changelog && !changelog.is_empty() {
+            print_changelog(&changelog);
+        }
+        Some((compatible_release.clone(), changelog))
+    }
+
+    /// Lists the updates available for `mods` with their changelogs, and lets the user
+    /// deselect the ones to skip. Every update starts out selected.
+    ///
+    /// # Returns
+    ///
+    /// The lowercase mod IDs of the deselected updates.
+    fn review_updates(
+        &self, mods: &[(ModInfo, PathBuf)],
+        fetched: &HashMap<String, Result<ModApiResponse, ClientError>>,
+    ) -> HashSet<String> {
+        let mut modids = Vec::new();
+        let mut options = Vec::new();
+        for (mod_info, _) in mods {
+            let Some(modid) = mod_info.modid.as_deref() else {
+                continue;
+            };
+            if self.is_pinned(Some(modid)) || self.is_ignored(IgnoreScope::Updates, Some(modid)) {
+                continue;
+            }
+            let Some((release, changelog)) = self.find_update(mod_info, fetched) else {
+                continue;
+            };
+
+            let name = mod_info.name.as_deref().unwrap_or(modid);
+            let option = format!(
+                "{name}: {} -> {} [{}]",
+                mod_info.version.as_deref().unwrap_or("Unknown"),
+                release.modversion.as_deref().unwrap_or("Unknown"),
+                self.release_compatibility(&release)
+            );
+            say!("\n{}", option.bold());
+            if changelog.is_empty() {
+                say!("    (no changelog)");
+            } else {
+                print_changelog(&changelog);
+            }
+            modids.push(modid.to_lowercase());
+            options.push(option);
+        }
+        if options.is_empty() {
+            return HashSet::new();
+        }
+
+        say!("");
+        let selected = self
+            .prompt
+            .multi_select_checked("Updates to apply (space toggles)", &options);
+        modids
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !selected.contains(index))
+            .map(|(_, modid)| modid)
+            .collect()
+    }
+
+    /// The release an installed mod would be updated to, with the changelogs since the
+    /// installed version. Unlike [`check_and_get_update`](Self::check_and_get_update)
+    /// nothing is printed, and mods that can't be looked up count as up to date.
+    fn find_update(
+        &self, mod_info: &ModInfo, fetched: &HashMap<String, Result<ModApiResponse, ClientError>>,
+    ) -> Option<(Release, String)> {
+        let Some(Ok(full_mod_info)) = fetched.get(&mod_info.modid.as_ref()?.to_lowercase()) else {
+            return None;
+        };
+        if !matches!(mod_info.check_for_update(full_mod_info), Ok((true, _))) {
+            return None;
+        }
+
+        let release = self.find_compatible_release(&full_mod_info.mod_data.releases)?;
+        let current_version = mod_info.version.as_deref().unwrap_or("Unknown");
+        let new_version = release.modversion.as_deref().unwrap_or("Unknown");
+        if !is_newer(new_version, current_version) {
+            return None;
+        }
+        let changelog = changelog_between(
+            &full_mod_info.mod_data.releases,
+            Some(current_version),
+            new_version,
+        );
+        Some((release.clone(), changelog))
+    }
+
+    fn print_update_info(&self, name: &str, current: &str, new: &str, release: &Release) {
+        say!(
+            "Update available for mod: {name} - Current version: {current} - New version: {new} [{}]",
+            self.release_compatibility(release)
+        );
+    }
+
+    /// Replaces an installed mod with `release`.
+    ///
+    /// The old file is copied into the `backup` directory first and kept if that fails. The
+    /// new release is downloaded and verified before the installed file is touched, and if
+    /// installing it fails the old file is left in place (or restored from the backup when
+    /// both share a filename).
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The installed mods' dependencies. When given, the dependencies of the
+    ///   downloaded release are checked against it, and if they aren't met the user is
+    ///   warned and asked whether to install it anyway.
+    #[tracing::instrument(skip_all, fields(mod = name))]
+    async fn handle_mod_update(
+        &self, name: &str, path: PathBuf, mods_dir: &Path, backup: &Path, release: Release,
+        graph: Option<&DependencyGraph>,
+    ) -> UpdateOutcome {
+        // Remember permissions and ownership so the replacement stays readable for the game
+        let original_metadata = std::fs::metadata(&path).ok().filter(|meta| meta.is_file());
+
+        if let Err(e) = self.backup_mod(&path, backup) {
+            eprintln!("Failed to back up {name}, keeping the installed version: {e}");
+            return UpdateOutcome::Failed;
+        }
+
+        let Some(new_mod_path) = self.get_new_mod_path(mods_dir, &release, name) else {
+            return UpdateOutcome::Failed;
+        };
+
+        if self.dry_run {
+            self.plan_delete(&path);
+            self.plan_download(&release, &new_mod_path).await;
+            return UpdateOutcome::Installed(new_mod_path);
+        }
+
+        let Some(mod_bytes) = self.fetch_release(name, &release).await else {
+            return UpdateOutcome::Failed;
+        };
+        if let Some(graph) = graph
+            && !self.confirm_unmet_dependencies(name, graph, &mod_bytes)
+        {
+            say!("Keeping the installed version of {name}");
+            return UpdateOutcome::Declined;
+        }
+
+        if let Err(e) = self
+            .file_manager
+            .install_mod_file(&new_mod_path, &mod_bytes)
+            .await
+        {
+            eprintln!("Failed to save new mod {name}, keeping the installed version: {e}");
+            if new_mod_path == path {
+                self.restore_from_backup(&path, backup);
+            }
+            return UpdateOutcome::Failed;
+        }
+
+        if new_mod_path != path
+            && let Err(e) = self.delete_old_mod(&path).await
+        {
+            // Don't leave two versions of the mod behind
+            eprintln!("Failed to delete old mod {name}, rolling back: {e}");
+            if let Err(e) = self.file_manager.delete_file(&new_mod_path).await {
+                eprintln!("Failed to remove {}: {e}", new_mod_path.display());
+            }
+            return UpdateOutcome::Failed;
+        }
+
+        self.record_install(&release, InstallReason::Adopted, &mod_bytes);
+
+        if let Some(original) = original_metadata
+            && let Err(e) = self
+                .file_manager
+                .preserve_metadata(&new_mod_path, &original)
+        {
+            eprintln!("Failed to preserve permissions of {name}: {e}");
+        }
+        UpdateOutcome::Installed(new_mod_path)
+    }
+
+    /// Checks the dependencies of a downloaded release against the installed mods and,
+    /// if some aren't met, warns about each and asks whether to install it anyway.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the user declined. Without a terminal to ask on, the release is
+    /// installed and the run summary warns about the unmet dependencies.
+    fn confirm_unmet_dependencies(
+        &self, name: &str, graph: &DependencyGraph, mod_bytes: &[u8],
+    ) -> bool {
+        let Some(info) = mod_info_from_zip(mod_bytes) else {
+            return true;
+        };
+        let Some(modid) = &info.modid else {
+            return true;
+        };
+        let problems = graph.unmet(modid, info.dependencies.iter().flatten());
+        if problems.is_empty() {
+            return true;
+        }
+        for problem in &problems {
+            warn!("{problem}");
+        }
+        !self.can_prompt() || self.prompt.confirm(&format!("Update {name} anyway?"))
+    }
+
+    /// Puts the backed-up copy of a mod file back after a failed replacement.
+    fn restore_from_backup(&self, path: &Path, backup: &Path) {
+        let Some(file_name) = path.file_name() else {
+            return;
+        };
+        if let Err(e) = std::fs::copy(backup.join(file_name), path) {
+            eprintln!(
+                "Failed to restore {} from {}: {e}",
+                path.display(),
+                backup.display()
+            );
+        }
+    }
+
+    /// Lets the user browse ModDB and download mods through interactive menus.
+    ///
+    /// # Arguments
+    ///
+    /// * `search_online` - Send filters to ModDB instead of filtering the fetched mods.
+    /// * `by_tags` - Pick categories first and only list mods tagged with them.
+    /// * `filter` - Tags and author every listed mod has to match, by ID or name.
+    async fn show_paginated_mods(
+        &self, search_online: bool, by_tags: bool, filter: Query,
+    ) -> Result<(), ModManagerError> {
+        self.require_interactive("pass the mods to download, see `download --help`")?;
+        let page_size = 50;
+        let mut page = 0;
+        let mut current_filter = String::new();
+        let mut filter = self.api.resolve_query(filter).await?;
+        if by_tags {
+            filter.tag_ids.extend(self.select_tags().await?);
+        }
+        let mut mods = self.fetch_initial_mods(&filter).await?;
+
+        // In online mode the filter is sent to ModDB instead of applied to the fetched page
+        let mut throttle = SearchThrottle::new(Duration::from_millis(750));
+        let mut searched = String::new();
+        throttle.store(&searched, mods.clone());
+
+        while !mods.is_empty() {
             match self
-                .handle_mod_selection(&mods, &mut current_filter, page_size)
+                .handle_mod_selection(
+                    &mods,
+                    &mut current_filter,
+                    &mut page,
+                    page_size,
+                    search_online,
+                )
                 .await?
             {
-                SelectionResult::Continue => continue,
-                SelectionResult::Break => break,
-                SelectionResult::NoResults => return Ok(()),
+                SelectionResult::Continue => {}
+                SelectionResult::Break => break,
+                SelectionResult::NoResults => return Ok(()),
+            }
+
+            if search_online && current_filter != searched {
+                let results = self
+                    .search_online(&current_filter, &filter, &mut throttle)
+                    .await?;
+                if results.is_empty() {
+                    say!("No mods found on ModDB matching: {current_filter}");
+                    current_filter = searched.clone();
+                } else {
+                    searched = current_filter.clone();
+                    mods = results;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn search_online(
+        &self, text: &str, filter: &Query, throttle: &mut SearchThrottle,
+    ) -> Result<Vec<ModSearchResult>, ModManagerError> {
+        if let Some(results) = throttle.cached(text) {
+            return Ok(results.clone());
+        }
+
+        throttle.wait_turn().await;
+        let query = self
+            .base_search_query(filter)
+            .with_text(&[text.trim().to_string()]);
+        let results = self.hide_ignored(self.api.search_mods(query.build()).await?.mods);
+        throttle.store(text, results.clone());
+        Ok(results)
+    }
+
+    /// Runs the full-screen terminal UI until the user quits, restoring the terminal
+    /// afterwards even if the UI failed.
+    async fn run_tui(&self) -> Result<(), ModManagerError> {
+        self.require_interactive("use `list`, `info` and `download` instead")?;
+        let installed = self
+            .hide_ignored_mods(self.file_manager.collect_mods(&None).await?)
+            .into_iter()
+            .map(|(mod_info, _)| mod_info)
+            .collect();
+        let mut app = TuiApp::new(installed, self.fetch_initial_mods(&Query::new()).await?);
+
+        let mut terminal = ratatui::init();
+        let result = self.tui_loop(&mut terminal, &mut app).await;
+        ratatui::restore();
+        result
+    }
+
+    async fn tui_loop(
+        &self, terminal: &mut DefaultTerminal, app: &mut TuiApp,
+    ) -> Result<(), ModManagerError> {
+        let mut throttle = SearchThrottle::new(Duration::from_millis(750));
+        loop {
+            terminal.draw(|frame| app.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match app.handle_key(key) {
+                TuiAction::None => {}
+                TuiAction::Quit => return Ok(()),
+                TuiAction::Search(text) => {
+                    let results = if text.is_empty() {
+                        self.fetch_initial_mods(&Query::new()).await
+                    } else {
+                        self.search_online(&text, &Query::new(), &mut throttle)
+                            .await
+                    };
+                    match results {
+                        Ok(results) => app.set_results(results),
+                        Err(e) => app.set_status(format!("Search failed: {e}")),
+                    }
+                }
+                TuiAction::Download => {
+                    for index in 0..app.queue().len() {
+                        if app.queue()[index].status != QueueStatus::Queued {
+                            continue;
+                        }
+                        app.set_queue_status(index, QueueStatus::Downloading);
+                        terminal.draw(|frame| app.draw(frame))?;
+
+                        let status = match self.download_queued(&app.queue()[index].modid).await {
+                            Ok(version) => QueueStatus::Done(version),
+                            Err(e) => QueueStatus::Failed(e.to_string()),
+                        };
+                        app.set_queue_status(index, status);
+                        // Downloads print progress lines, repaint over them
+                        terminal.clear()?;
+                    }
+                    app.set_status("Downloads finished");
+                }
+            }
+        }
+    }
+
+    /// Downloads the latest compatible release of a mod queued in the terminal UI.
+    ///
+    /// # Returns
+    ///
+    /// The version that was installed.
+    #[tracing::instrument(skip_all, fields(mod = %modid))]
+    async fn download_queued(&self, modid: &String) -> Result<String, ModManagerError> {
+        let mod_info = self.fetch_mod_info(modid).await?;
+        let release = self
+            .find_compatible_release(&mod_info.mod_data.releases)
+            .ok_or_else(|| self.no_releases(&mod_info.mod_data.name))?;
+        self.save_release(&mod_info, release).await?;
+        Ok(release.modversion.clone().unwrap_or_default())
+    }
+
+    /// Asks the user to pick ModDB tags to browse by.
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the selected tags.
+    async fn select_tags(&self) -> Result<Vec<u16>, ModManagerError> {
+        let tags = self.api.fetch_tags().await?;
+        let names: Vec<String> = tags.iter().map(|tag| tag.name.clone()).collect();
+        let selected = self
+            .prompt
+            .multi_select("Select categories to browse (space to toggle)", &names);
+        if selected.is_empty() {
+            say!("No categories selected, showing all mods");
+        }
+        Ok(selected
+            .into_iter()
+            .filter_map(|index| u16::try_from(tags[index].tagid).ok())
+            .collect())
+    }
+
+    async fn fetch_initial_mods(
+        &self, filter: &Query,
+    ) -> Result<Vec<ModSearchResult>, ModManagerError> {
+        let query = self.base_search_query(filter);
+        let search_results = self.api.search_mods(query.build()).await?;
+        Ok(self.hide_ignored(search_results.mods))
+    }
+
+    /// Drops the search results the ignore lists hide from browsing.
+    fn hide_ignored(&self, mut mods: Vec<ModSearchResult>) -> Vec<ModSearchResult> {
+        mods.retain(|found| {
+            !found
+                .modidstrs
+                .iter()
+                .any(|modid| self.is_ignored(IgnoreScope::Browse, Some(modid)))
+        });
+        mods
+    }
+
+    /// `filter` sorted by downloads and filtered to the current game version, if known.
+    fn base_search_query(&self, filter: &Query) -> Query {
+        let mut query = filter.clone().with_order_by(OrderBy::Downloads);
+
+        // Add game version filtering if available
+        if let Some(version_tag) = self.get_current_game_version_tag_id() {
+            // Convert i64 to u16 for the query (assuming they fit in the positive range)
+            if let Ok(tag_u16) = u16::try_from(version_tag.abs()) {
+                query = query.with_game_version(tag_u16);
+                if let Some(version) = self.get_current_game_version() {
+                    say!("Filtering results for game version: version {version}");
+                }
+            }
+        }
+
+        query
+    }
+
+    fn filter_mods<'a>(
+        &self, mods: &'a [ModSearchResult], filter: &str,
+    ) -> Vec<&'a ModSearchResult> {
+        mods.iter()
+            .filter(|m| {
+                filter.is_empty()
+                    || m.name.to_lowercase().contains(&filter.to_lowercase())
+                    || m.author.to_lowercase().contains(&filter.to_lowercase())
+            })
+            .collect()
+    }
+
+    fn create_display_options(
+        &self, mods: &[&ModSearchResult], navigation: &[BrowseNavigation],
+    ) -> Vec<String> {
+        let mut options: Vec<String> = mods
+            .iter()
+            .map(|m| {
+                format!(
+                    "{} by {} ({} downloads)",
+                    m.name,
+                    m.author,
+                    m.downloads.unwrap_or(0)
+                )
+            })
+            .collect();
+
+        options.extend(navigation.iter().map(|entry| entry.label().to_string()));
+        options
+    }
+
+    async fn handle_mod_selection(
+        &self, mods: &[ModSearchResult], current_filter: &mut String, page: &mut usize,
+        page_size: usize, search_online: bool,
+    ) -> Result<SelectionResult, ModManagerError> {
+        // Online results already match the search, and may match on more than name or author
+        let local_filter = if search_online {
+            ""
+        } else {
+            current_filter.as_str()
+        };
+        let filtered_mods = self.filter_mods(mods, local_filter);
+
+        if filtered_mods.is_empty() {
+            say!("No mods found matching filter: {current_filter}");
+            return Ok(SelectionResult::NoResults);
+        }
+
+        let page_count = filtered_mods.len().div_ceil(page_size);
+        *page = (*page).min(page_count - 1);
+        let first = *page * page_size;
+        let displayed_mods = &filtered_mods[first..filtered_mods.len().min(first + page_size)];
+
+        let mut navigation = Vec::new();
+        if *page + 1 < page_count {
+            navigation.push(BrowseNavigation::NextPage);
+        }
+        if *page > 0 {
+            navigation.push(BrowseNavigation::PreviousPage);
+        }
+        navigation.extend([BrowseNavigation::Filter, BrowseNavigation::Exit]);
+        let options = self.create_display_options(displayed_mods, &navigation);
+
+        let message = format!(
+            "Select a mod, page {} of {page_count} (use / to search, ESC to exit)",
+            *page + 1
+        );
+        match self.prompt.select(&message, &options) {
+            Some(selection) if selection >= displayed_mods.len() => {
+                match navigation[selection - displayed_mods.len()] {
+                    BrowseNavigation::NextPage => *page += 1,
+                    BrowseNavigation::PreviousPage => *page -= 1,
+                    BrowseNavigation::Filter => {
+                        self.prompt_filter(current_filter)?;
+                        *page = 0;
+                    }
+                    BrowseNavigation::Exit => return Ok(SelectionResult::Break),
+                }
+                Ok(SelectionResult::Continue)
+            }
+            Some(selection) => {
+                self.handle_mod_download(displayed_mods[selection]).await?;
+                Ok(SelectionResult::Continue)
+            }
+            None => Ok(SelectionResult::Break),
+        }
+    }
+
+    fn prompt_filter(&self, current_filter: &mut String) -> Result<(), ModManagerError> {
+        self.clear_screen()?;
+        print!("Filter for mod: ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        *current_filter = self.prompt.input("");
+        Ok(())
+    }
+
+    async fn handle_mod_download(
+        &self, selected_mod: &ModSearchResult,
+    ) -> Result<(), ModManagerError> {
+        let mod_info = self.fetch_mod_info(&selected_mod.modidstrs[0]).await?;
+        if self.show_changelog
+            && let Some(release) = self.find_compatible_release(&mod_info.mod_data.releases)
+        {
+            let changelog = changelog_between(
+                &mod_info.mod_data.releases,
+                None,
+                release.modversion.as_deref().unwrap_or_default(),
+            );
+            if !changelog.is_empty() {
+                print_changelog(&changelog);
+            }
+        }
+
+        if self
+            .prompt
+            .confirm(&self.download_question(&selected_mod.name, &mod_info, None))
+            && self.save_requested_release(&mod_info, None).await?
+        {
+            say!("Downloaded {}", selected_mod.name);
+        }
+
+        Ok(())
+    }
+
+    fn clear_screen(&self) -> Result<(), ModManagerError> {
+        print!("\x1B[2J\x1B[1;1H");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        Ok(())
+    }
+
+    /// Prints the download a dry run skips, with the size reported by the server.
+    async fn plan_download(&self, release: &Release, path: &Path) {
+        let size = match &release.mainfile {
+            Some(url) => self.api.fetch_file_size(url).await.ok().flatten(),
+            None => None,
+        };
+        say!(
+            "[dry run] Would download {} {} ({}) -> {}{}",
+            release.modidstr.as_deref().unwrap_or("Unknown"),
+            release.modversion.as_deref().unwrap_or("Unknown"),
+            size.map_or_else(|| "unknown size".to_string(), Terminal::format_size),
+            path.display(),
+            overwrite_note(path)
+        );
+    }
+
+    /// Prints the deletion a dry run skips.
+    fn plan_delete(&self, path: &Path) {
+        let size = std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+        say!(
+            "[dry run] Would delete {} ({})",
+            path.display(),
+            Terminal::format_size(size)
+        );
+    }
+
+    async fn delete_old_mod(&self, path: &PathBuf) -> Result<(), FileError> {
+        if self.dry_run {
+            self.plan_delete(path);
+            return Ok(());
+        }
+
+        say!("Deleting old mod: {}", path.display());
+        self.file_manager.delete_file(path).await
+    }
+
+    fn get_new_mod_path(&self, mods_dir: &Path, release: &Release, name: &str) -> Option<PathBuf> {
+        match self.release_filename(release) {
+            Some(filename) => Some(mods_dir.join(filename)),
+            None => {
+                eprintln!("Missing filename for mod: {name}");
+                None
+            }
+        }
+    }
+
+    /// Downloads a release and checks it against the ModDB metadata, without saving it.
+    ///
+    /// # Returns
+    ///
+    /// The verified file contents, or `None` after printing why the download failed.
+    async fn fetch_release(&self, name: &str, release: &Release) -> Option<Vec<u8>> {
+        let mod_bytes = match &release.mainfile {
+            Some(url) => match self.api.fetch_file(url.clone()).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    eprintln!("Failed to download mod {name}: {e}");
+                    return None;
+                }
+            },
+            None => {
+                eprintln!("Missing download URL for mod: {name}");
+                return None;
+            }
+        };
+
+        if let Err(e) = self.verify_download(release, &mod_bytes) {
+            eprintln!("{}", format!("Refusing to install {name}: {e}").red());
+            return None;
+        }
+        Some(mod_bytes)
+    }
+
+    async fn download_mod(
+        &self, mod_data: &str, version: Option<&str>,
+    ) -> Result<(), ModManagerError> {
+        if let Some(link) = ModPageLink::parse(mod_data) {
+            let mod_info = self.resolve_mod_page(&link).await?;
+            if self.prompt.confirm(&self.download_question(
+                &mod_info.mod_data.name,
+                &mod_info,
+                version,
+            )) {
+                self.save_requested_release(&mod_info, version).await?;
+            }
+            return Ok(());
+        }
+
+        let query = Query::new()
+            .with_text(&[mod_data.to_string()])
+            .with_order_by(OrderBy::Downloads)
+            .build();
+
+        let query_results = self.api.search_mods(query).await?;
+        info!("Found {} mods", query_results.mods.len());
+
+        if let Some(selection) = self
+            .prompt
+            .select("Select a mod to download", &labels(&query_results.mods))
+        {
+            let selected_mod = &query_results.mods[selection];
+            let mod_info = self.fetch_mod_info(&selected_mod.modidstrs[0]).await?;
+
+            if self
+                .prompt
+                .confirm(&self.download_question(&selected_mod.name, &mod_info, version))
+            {
+                self.save_requested_release(&mod_info, version).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Downloads the release of a mod given with `--version`, the one picked from the list
+    /// of releases when picking is enabled, or else the newest compatible one. Other
+    /// installed versions of the mod are removed once the download succeeded.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the user cancelled picking a release.
+    async fn save_requested_release(
+        &self, mod_info: &ModApiResponse, version: Option<&str>,
+    ) -> Result<bool, ModManagerError> {
+        let release = match version {
+            Some(version) => release_with_version(
+                &mod_info.mod_data.name,
+                &mod_info.mod_data.releases,
+                version,
+            )?,
+            None if self.pick_release => match self.choose_release(mod_info)? {
+                Some(release) => release,
+                None => return Ok(false),
+            },
+            None => self
+                .find_compatible_release(&mod_info.mod_data.releases)
+                .ok_or_else(|| self.no_releases(&mod_info.mod_data.name))?,
+        };
+        if (version.is_some() || self.pick_release) && !self.is_release_compatible(release) {
+            eprintln!(
+                "{} {} {} is marked {}",
+                "Warning:".yellow().bold(),
+                mod_info.mod_data.name,
+                release.modversion.as_deref().unwrap_or("Unknown"),
+                self.release_compatibility(release)
+            );
+        }
+        self.save_release(mod_info, release).await?;
+
+        if let (Some(modid), Some(filename)) = (&release.modidstr, self.release_filename(release)) {
+            let mod_path = self.file_manager.install_dir()?.join(filename);
+            for (_, old_path) in self.installed_copies(modid).await? {
+                if old_path != mod_path {
+                    self.delete_old_mod(&old_path).await?;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Lets the user pick any release of a mod, each labelled with the game versions it
+    /// was made for. The newest compatible release is marked as recommended.
+    ///
+    /// # Returns
+    ///
+    /// The picked release, or `None` if the user cancelled.
+    fn choose_release<'a>(
+        &self, mod_info: &'a ModApiResponse,
+    ) -> Result<Option<&'a Release>, ModManagerError> {
+        let releases = &mod_info.mod_data.releases;
+        if releases.is_empty() {
+            return Err(self.no_releases(&mod_info.mod_data.name));
+        }
+        self.require_interactive("pass the release to install with --version")?;
+
+        let recommended = self.find_compatible_release(releases);
+        let items: Vec<String> = releases
+            .iter()
+            .map(|release| {
+                let marker =
+                    if recommended.is_some_and(|recommended| std::ptr::eq(recommended, release)) {
+                        " (recommended)"
+                    } else {
+                        ""
+                    };
+                format!(
+                    "{} ({}) [{}] {}{marker}",
+                    release.modversion.as_deref().unwrap_or("?"),
+                    release.created.as_deref().unwrap_or("unknown date"),
+                    self.release_compatibility(release),
+                    release.tags.join(", ")
+                )
+            })
+            .collect();
+        let prompt = format!("Release of {} to download", mod_info.mod_data.name);
+        Ok(self
+            .prompt
+            .select(&prompt, &items)
+            .map(|index| &releases[index]))
+    }
+
+    async fn download_mods(&self, mods: &Vec<String>) -> Result<(), ModManagerError> {
+        let query = Query::new()
+            .with_text(mods)
+            .with_order_by(OrderBy::Downloads)
+            .build();
+        info!("Searching for mods: {mods:?}");
+
+        let query_results = self.api.search_mods(query).await?;
+        info!("Found {} mods", query_results.mods.len());
+
+        if query_results.mods.is_empty() {
+            say!("No mods found, try again with different search terms");
+            return Ok(());
+        }
+
+        self.require_interactive(
+            "pass exact mod ids with --mod-string, --from-stdin or --manifest",
+        )?;
+        let selections = self
+            .prompt
+            .multi_select("Select mods to download", &labels(&query_results.mods));
+        if !selections.is_empty() {
+            let progress_bar = ProgressBarWrapper::new(selections.len() as u64);
+
+            for selection in selections {
+                let selected_mod = &query_results.mods[selection];
+                let mod_info = self.fetch_mod_info(&selected_mod.modidstrs[0]).await?;
+                self.save_mod_file(&mod_info).await?;
+                progress_bar.println(format!("Downloaded mod: {}", selected_mod.name));
+                progress_bar.inc(1);
+            }
+
+            progress_bar.finish_with_message("Finished downloading mods");
+        }
+
+        Ok(())
+    }
+
+    async fn download_mod_string(
+        &self, mod_string: &str, exact: bool,
+    ) -> Result<(), ModManagerError> {
+        let decoded: Vec<EncoderData> = self.encoder.decode_mod_string(mod_string.to_owned())?;
+        self.download_encoder_data(decoded, exact).await
+    }
+
+    /// Installs a list of mods, such as a decoded mod string.
+    ///
+    /// # Arguments
+    ///
+    /// * `decoded` - The mods to install.
+    /// * `exact` - Install the listed versions instead of the latest compatible releases.
+    ///   Entries without a version always get the latest compatible release.
+    async fn download_encoder_data(
+        &self, decoded: Vec<EncoderData>, exact: bool,
+    ) -> Result<(), ModManagerError> {
+        let progress_bar = ProgressBarWrapper::new(decoded.len() as u64);
+
+        for mod_data in decoded {
+            let mod_info = self.fetch_mod_info(&mod_data.mod_id).await?;
+            progress_bar.set_message(format!("Downloading mod: {}", mod_info.mod_data.name));
+            if exact && !mod_data.mod_version.is_empty() {
+                let release = release_with_version(
+                    &mod_data.mod_id,
+                    &mod_info.mod_data.releases,
+                    &mod_data.mod_version,
+                )?;
+                self.save_release(&mod_info, release).await?;
+            } else {
+                self.save_mod_file(&mod_info).await?;
+            }
+            progress_bar.inc(1);
+        }
+
+        progress_bar.finish_with_message("Finished downloading mods");
+        Ok(())
+    }
+
+    /// Installs the mods of a manifest one by one and reports how each of them went.
+    ///
+    /// # Arguments
+    ///
+    /// * `mods` - The manifest entries.
+    /// * `exact` - Install the listed versions instead of the latest compatible releases.
+    ///   Entries without a version always get the latest compatible release.
+    ///
+    /// # Returns
+    ///
+    /// `ModManagerError::InstallFailed` if any mod could not be installed.
+    async fn install_manifest(
+        &self, mods: Vec<EncoderData>, exact: bool,
+    ) -> Result<(), ModManagerError> {
+        let mut results = Vec::new();
+        for entry in mods {
+            let result = self.install_manifest_entry(&entry, exact).await;
+            results.push(ManifestResult {
+                modid: entry.mod_id,
+                version: result.as_ref().ok().cloned(),
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+
+        let failed = results
+            .iter()
+            .filter(|result| result.error.is_some())
+            .count();
+        if json_output() {
+            print_json(&results)?;
+        } else {
+            say!();
+            for result in &results {
+                match (&result.version, &result.error) {
+                    (Some(version), _) => {
+                        say!("  {} {} {version}", "✓".green(), result.modid)
+                    }
+                    (_, Some(error)) => say!("  {} {}: {error}", "✗".red(), result.modid),
+                    _ => {}
+                }
+            }
+            say!(
+                "Installed {} of {} mod(s)",
+                results.len() - failed,
+                results.len()
+            );
+        }
+
+        if failed > 0 {
+            return Err(ModManagerError::InstallFailed(failed));
+        }
+        Ok(())
+    }
+
+    /// Resolves a manifest entry to a release and installs it, returning the version.
+    async fn install_manifest_entry(
+        &self, entry: &EncoderData, exact: bool,
+    ) -> Result<String, ModManagerError> {
+        let mod_info = self.fetch_mod_info(&entry.mod_id).await?;
+        let releases = &mod_info.mod_data.releases;
+        let release = if exact && !entry.mod_version.is_empty() {
+            release_with_version(&entry.mod_id, releases, &entry.mod_version)?
+        } else {
+            self.find_compatible_release(releases)
+                .ok_or_else(|| self.no_releases(&mod_info.mod_data.name))?
+        };
+
+        self.save_release(&mod_info, release).await?;
+        Ok(release.modversion.clone().unwrap_or_default())
+    }
+
+    /// Makes the installed mods match a mod string.
+    ///
+    /// Mods the string lists but that aren't installed are installed, installed mods at a
+    /// different version are replaced by the listed release (backing up the old file like
+    /// `update` does), and with `prune` installed mods the string doesn't list are removed.
+    /// Pinned mods and mods without a mod ID, which a mod string can't list, are never
+    /// removed. The changes are shown and confirmed before anything is touched.
+    ///
+    /// # Arguments
+    ///
+    /// * `mod_string` - The mod string to match, as produced by `export`.
+    /// * `prune` - Also remove installed mods that are not in the mod string.
+    ///
+    /// # Returns
+    ///
+    /// A summary of the installed, replaced, removed and failed mods.
+    async fn sync_mods(
+        &self, mod_string: &str, prune: bool,
+    ) -> Result<RunSummary, ModManagerError> {
+        let wanted = self.encoder.decode_mod_string(mod_string.to_owned())?;
+        let installed = self.file_manager.collect_mods(&None).await?;
+        let mut summary = RunSummary::new("sync");
+
+        let find_installed = |modid: &str| {
+            installed.iter().find(|(info, _)| {
+                info.modid
+                    .as_deref()
+                    .is_some_and(|id| id.eq_ignore_ascii_case(modid))
+            })
+        };
+
+        let mut missing = Vec::new();
+        let mut changed = Vec::new();
+        for entry in &wanted {
+            match find_installed(&entry.mod_id) {
+                None => missing.push(entry),
+                Some((info, path))
+                    if !entry.mod_version.is_empty()
+                        && info.version.as_deref().is_none_or(|version| {
+                            compare_versions(version, &entry.mod_version).is_ne()
+                        }) =>
+                {
+                    if self.is_pinned(info.modid.as_deref()) {
+                        say!("Skipping pinned mod: {}", entry.mod_id);
+                    } else {
+                        changed.push((entry, info, path));
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut extras: Vec<&(ModInfo, PathBuf)> = Vec::new();
+        for installed_mod in installed.iter().filter(|_| prune) {
+            let (info, path) = installed_mod;
+            let Some(modid) = info.modid.as_deref() else {
+                say!("Keeping mod without a mod ID: {}", path.display());
+                continue;
+            };
+            if wanted
+                .iter()
+                .any(|entry| entry.mod_id.eq_ignore_ascii_case(modid))
+            {
+                continue;
+            }
+            if self.is_pinned(Some(modid)) {
+                say!("Keeping pinned mod: {modid}");
+                continue;
+            }
+            extras.push(installed_mod);
+        }
+
+        if missing.is_empty() && changed.is_empty() && extras.is_empty() {
+            say!("Installed mods already match the mod string");
+            return Ok(summary);
+        }
+
+        say!("Sync plan:");
+        for entry in &missing {
+            say!("  {} {} {}", "+".green(), entry.mod_id, entry.mod_version);
+        }
+        for (entry, info, _) in &changed {
+            say!(
+                "  {} {} {} -> {}",
+                "~".yellow(),
+                entry.mod_id,
+                info.version.as_deref().unwrap_or("Unknown"),
+                entry.mod_version
+            );
+        }
+        for (info, _) in &extras {
+            say!(
+                "  {} {}",
+                "-".red(),
+                info.modid
+                    .as_deref()
+                    .or(info.name.as_deref())
+                    .unwrap_or("Unknown")
+            );
+        }
+
+        if !self.dry_run && !self.prompt.confirm("Apply these changes?") {
+            say!("Sync cancelled.");
+            return Ok(summary);
+        }
+
+        for entry in missing {
+            match self.install_manifest_entry(entry, true).await {
+                Ok(_) => summary.installed.push(entry.mod_id.clone()),
+                Err(e) => {
+                    eprintln!("Failed to install {}: {e}", entry.mod_id);
+                    summary.failed.push(entry.mod_id.clone());
+                }
+            }
+        }
+
+        let install_dir = self.file_manager.install_dir()?;
+        let backup = backup_dir_for(&install_dir)
+            .join(chrono::Local::now().format(BACKUP_NAME_FORMAT).to_string());
+        for (entry, info, path) in changed {
+            let name = info.name.as_deref().unwrap_or(&entry.mod_id);
+            let release = match self.fetch_mod_info(&entry.mod_id).await {
+                Ok(mod_info) => release_with_version(
+                    &entry.mod_id,
+                    &mod_info.mod_data.releases,
+                    &entry.mod_version,
+                )
+                .cloned(),
+                Err(e) => Err(e),
+            };
+            let release = match release {
+                Ok(release) => release,
+                Err(e) => {
+                    eprintln!("Failed to sync {name}: {e}");
+                    summary.failed.push(name.to_string());
+                    continue;
+                }
+            };
+
+            let mods_dir = path.parent().unwrap_or(&install_dir);
+            if let UpdateOutcome::Installed(_) = self
+                .handle_mod_update(name, path.clone(), mods_dir, &backup, release, None)
+                .await
+            {
+                summary.updated.push(UpdatedMod {
+                    name: name.to_string(),
+                    modid: entry.mod_id.clone(),
+                    from: info.version.clone().unwrap_or_default(),
+                    to: entry.mod_version.clone(),
+                });
+            } else {
+                summary.failed.push(name.to_string());
+            }
+        }
+
+        if !extras.is_empty() {
+            if self.dry_run {
+                for (_, path) in &extras {
+                    self.plan_delete(path);
+                }
+            } else {
+                for (_, path) in &extras {
+                    self.backup_mod(path, &backup)?;
+                }
+                self.delete_mods(&extras).await?;
+                summary.removed = extras
+                    .iter()
+                    .filter_map(|(info, _)| info.modid.clone())
+                    .collect();
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Uploads the mod archives of the mods directory to a game server. Archives the server
+    /// lacks or has in a different size are uploaded, and with `prune` the server's
+    /// archives that aren't installed here are deleted.
+    async fn push_remote(
+        &self, remote: &impl RemoteStore, prune: bool,
+    ) -> Result<(), ModManagerError> {
+        let install_dir = self.file_manager.install_dir()?;
+        let plan = SyncPlan::new(&local_mod_files(&install_dir)?, &remote.list()?, prune);
+        if plan.is_empty() {
+            say!("The server's mods already match {}", install_dir.display());
+            return Ok(());
+        }
+
+        Self::print_remote_plan("Upload plan:", &plan);
+        if self.dry_run || !self.prompt.confirm("Apply these changes on the server?") {
+            return Ok(());
+        }
+        remote.upload(&install_dir, &plan.copy)?;
+        remote.remove(&plan.delete)?;
+        say!(
+            "Uploaded {} and deleted {} mod file(s)",
+            plan.copy.len(),
+            plan.delete.len()
+        );
+        Ok(())
+    }
+
+    /// Downloads a game server's mod archives into the mods directory, the reverse of
+    /// [`push_remote`](Self::push_remote).
+    async fn pull_remote(
+        &self, remote: &impl RemoteStore, prune: bool,
+    ) -> Result<(), ModManagerError> {
+        let install_dir = self.file_manager.install_dir()?;
+        let plan = SyncPlan::new(&remote.list()?, &local_mod_files(&install_dir)?, prune);
+        if plan.is_empty() {
+            say!(
+                "{} already matches the server's mods",
+                install_dir.display()
+            );
+            return Ok(());
+        }
+
+        Self::print_remote_plan("Download plan:", &plan);
+        if self.dry_run || !self.prompt.confirm("Apply these changes?") {
+            return Ok(());
+        }
+        remote.download(&plan.copy, &install_dir)?;
+        for name in &plan.delete {
+            self.delete_old_mod(&install_dir.join(name)).await?;
+        }
+        say!(
+            "Downloaded {} and deleted {} mod file(s)",
+            plan.copy.len(),
+            plan.delete.len()
+        );
+        Ok(())
+    }
+
+    /// Updates the mods of a game server. They are updated in a local mirror and uploaded
+    /// afterwards, so the server only sees the finished result.
+    ///
+    /// The server's archives the mirror no longer has, such as the old releases of updated
+    /// mods, are deleted unless an update failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `mirror` - The local copy of the server's mods directory.
+    async fn update_remote(
+        self, remote: &impl RemoteStore, mirror: PathBuf,
+    ) -> Result<RunSummary, ModManagerError> {
+        std::fs::create_dir_all(&mirror)?;
+        remote.mirror_into(&mirror)?;
+
+        let mod_manager = self.with_mod_dirs(vec![mirror]);
+        let summary = mod_manager.update_mods(CliFlags::default()).await?;
+        if json_output() {
+            print_json(&summary)?;
+        } else {
+            say!("\n{}", summary.table());
+        }
+
+        let prune = summary.failed.is_empty();
+        if !prune {
+            say!(
+                "Not deleting files from the server because {} update(s) failed",
+                summary.failed.len()
+            );
+        }
+        mod_manager.push_remote(remote, prune).await?;
+        Ok(summary)
+    }
+
+    fn print_remote_plan(title: &str, plan: &SyncPlan) {
+        say!("{title}");
+        for name in &plan.copy {
+            say!("  {} {name}", "+".green());
+        }
+        for name in &plan.delete {
+            say!("  {} {name}", "-".red());
+        }
+    }
+
+    /// Looks up the mod a ModDB page link points at.
+    ///
+    /// Page aliases usually match the mod ID. When one doesn't, the mod is found among
+    /// the search results for the alias instead.
+    async fn resolve_mod_page(
+        &self, link: &ModPageLink,
+    ) -> Result<ModApiResponse, ModManagerError> {
+        info!("Resolving ModDB page: {link:?}");
+        let result = self.api.get_mod(link.identifier()).await;
+        let (ModPageLink::Alias(alias), Err(ClientError::ModNotFound(_))) = (link, &result) else {
+            return Ok(result?);
+        };
+
+        let query = Query::new().with_text(std::slice::from_ref(alias)).build();
+        let results = self.api.search_mods(query).await?;
+        let found = results
+            .mods
+            .iter()
+            .find(|found| {
+                found
+                    .urlalias
+                    .as_deref()
+                    .is_some_and(|urlalias| urlalias.eq_ignore_ascii_case(alias))
+            })
+            .ok_or_else(|| ClientError::ModNotFound(alias.clone()))?;
+        Ok(self.api.get_mod(&found.modid.to_string()).await?)
+    }
+
+    async fn fetch_mod_info(&self, mod_id: &String) -> Result<ModApiResponse, ModManagerError> {
+        info!("Fetching mod info: {mod_id}");
+        let mod_info = self.api.get_mod(mod_id).await?;
+        Ok(mod_info)
+    }
+
+    async fn save_mod_file(&self, mod_info: &ModApiResponse) -> Result<(), ModManagerError> {
+        // Find the best compatible release instead of just using the first one
+        let release = self
+            .find_compatible_release(&mod_info.mod_data.releases)
+            .ok_or_else(|| self.no_releases(&mod_info.mod_data.name))?;
+
+        self.save_release(mod_info, release).await
+    }
+
+    /// Downloads a specific release into the install directory and records it, then
+    /// installs the dependencies it declares that are missing.
+    async fn save_release(
+        &self, mod_info: &ModApiResponse, release: &Release,
+    ) -> Result<(), ModManagerError> {
+        self.save_release_as(mod_info, release, InstallReason::Explicit)
+            .await
+    }
+
+    /// Like [`Self::save_release`], recording the release as installed for `reason`.
+    async fn save_release_as(
+        &self, mod_info: &ModApiResponse, release: &Release, reason: InstallReason,
+    ) -> Result<(), ModManagerError> {
+        let vintage_mods_dir = self.file_manager.install_dir()?;
+
+        let mod_path = vintage_mods_dir.join(self.release_filename(release).unwrap());
+        if self.dry_run {
+            self.plan_download(release, &mod_path).await;
+            return Ok(());
+        }
+
+        let mod_bytes = self
+            .api
+            .fetch_file(release.mainfile.clone().unwrap())
+            .await?;
+        self.verify_download(release, &mod_bytes)?;
+
+        self.file_manager
+            .install_mod_file(&mod_path, &mod_bytes)
+            .await?;
+        self.record_install(release, reason, &mod_bytes);
+
+        // Log which version was downloaded
+        if let Some(version) = &release.modversion {
+            say!(
+                "Downloaded {} version {} [{}]",
+                mod_info.mod_data.name,
+                version,
+                self.release_compatibility(release)
+            );
+        }
+
+        if let Some(info) = mod_info_from_zip(&mod_bytes) {
+            self.install_missing_dependencies(&info).await;
+        }
+        Ok(())
+    }
+
+    /// Downloads the dependencies of a just-installed mod that aren't installed yet, and
+    /// records them as installed for it. Their own dependencies follow the same way.
+    /// Failures are only warned about, as the mod itself is installed either way.
+    async fn install_missing_dependencies(&self, info: &ModInfo) {
+        let (Some(required_by), Some(dependencies)) = (&info.modid, &info.dependencies) else {
+            return;
+        };
+        let mut modids: Vec<&String> = dependencies
+            .keys()
+            .filter(|modid| !GAME_MODS.contains(&modid.to_lowercase().as_str()))
+            .collect();
+        modids.sort();
+
+        for modid in modids {
+            match self.installed_copies(modid).await {
+                Ok(copies) if copies.is_empty() => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!("Failed to check whether {modid} is installed: {e}");
+                    continue;
+                }
+            }
+
+            say!("Installing {modid}, which {required_by} depends on");
+            let reason = InstallReason::Dependency {
+                required_by: vec![required_by.clone()],
+            };
+            let installed = async {
+                let mod_info = self.fetch_mod_info(modid).await?;
+                let release = self
+                    .find_compatible_release(&mod_info.mod_data.releases)
+                    .ok_or_else(|| self.no_releases(&mod_info.mod_data.name))?;
+                Box::pin(self.save_release_as(&mod_info, release, reason)).await
+            };
+            if let Err(e) = installed.await {
+                warn!("Failed to install {modid}, which {required_by} depends on: {e}");
+            }
+        }
+    }
+
+    /// The error for a mod without a release compatible with the current game version.
+    fn no_releases(&self, name: &str) -> ModManagerError {
+        ModManagerError::NoReleases {
+            name: name.to_string(),
+            game_version: self.get_current_game_version(),
+        }
+    }
+
+    /// Get the current game version tag ID from config
+    fn get_current_game_version_tag_id(&self) -> Option<i64> {
+        let config_manager = self.load_config_file().ok()?;
+        match &self.game_version_override {
+            Some(version) => config_manager.config().get_tag_from_version(version),
+            None => config_manager.get_game_version_tag_id(),
+        }
+    }
+
+    /// Get the current game version string: the `--game-version` override, or else the
+    /// one from config
+    fn get_current_game_version(&self) -> Option<String> {
+        if let Some(version) = &self.game_version_override {
+            return Some(version.clone());
+        }
+        self.load_config_file()
+            .ok()
+            .and_then(|config_manager| config_manager.get_game_version().cloned())
+    }
+
+    /// Classify a release against the current game version
+    fn release_compatibility(&self, release: &Release) -> Compatibility {
+        Compatibility::of_tags(&release.tags, self.get_current_game_version().as_deref())
+    }
+
+    /// How the installed `version` of a mod matches the current game version, or unknown if
+    /// it isn't one of `releases`.
+    fn installed_compatibility(&self, releases: &[Release], version: &str) -> Compatibility {
+        releases
+            .iter()
+            .find(|release| {
+                release
+                    .modversion
+                    .as_deref()
+                    .is_some_and(|v| compare_versions(v, version).is_eq())
+            })
+            .map_or(Compatibility::Unknown, |release| {
+                self.release_compatibility(release)
+            })
+    }
+
+    /// The question asked before downloading a mod, naming the release that would be
+    /// installed and its compatibility badge.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the mod.
+    /// * `mod_info` - The mod's ModDB entry, with its releases.
+    /// * `version` - The version requested with `--version`, if any.
+    fn download_question(
+        &self, name: &str, mod_info: &ModApiResponse, version: Option<&str>,
+    ) -> String {
+        let releases = &mod_info.mod_data.releases;
+        let release = match version {
+            Some(version) => release_with_version(name, releases, version).ok(),
+            None => self.find_compatible_release(releases),
+        };
+        match release {
+            Some(release) => format!(
+                "Download mod: {name} {} [{}]?",
+                release.modversion.as_deref().unwrap_or("Unknown"),
+                self.release_compatibility(release)
+            ),
+            None => format!("Download mod: {name}?"),
+        }
+    }
+
+    /// Check if a release is compatible with the current game version
+    fn is_release_compatible(&self, release: &Release) -> bool {
+        match self.release_compatibility(release) {
+            Compatibility::Compatible => true,
+            // If no version filtering is configured, allow all releases
+            Compatibility::Unknown => self.get_current_game_version().is_none(),
+            Compatibility::SameMinor | Compatibility::Incompatible => false,
+        }
+    }
+
+    /// Returns `true` if the release channel of its mod allows picking `release`.
+    fn is_release_on_channel(&self, release: &Release) -> bool {
+        let channel = release
+            .modidstr
+            .as_ref()
+            .and_then(|modid| self.mod_channels.get(&modid.to_lowercase()))
+            .copied()
+            .unwrap_or(self.channel);
+        channel.allows(release.modversion.as_deref().unwrap_or_default())
+    }
+
+    /// The releases on the mod's release channel, or all of them if the mod has no such
+    /// release at all.
+    fn channel_candidates<'a>(&self, releases: &'a [Release]) -> Vec<&'a Release> {
+        let candidates: Vec<&Release> = releases
+            .iter()
+            .filter(|release| self.is_release_on_channel(release))
+            .collect();
+        if candidates.is_empty() {
+            releases.iter().collect()
+        } else {
+            candidates
+        }
+    }
+
+    /// Find the best compatible release for the current game version
+    ///
+    /// Only releases on the mod's release channel are considered, unless the mod has no
+    /// such release at all. With strict compatibility, releases made for other game
+    /// versions are never picked.
+    fn find_compatible_release<'a>(&self, releases: &'a [Release]) -> Option<&'a Release> {
+        let candidates = self.channel_candidates(releases);
+
+        // Prefer the highest version compatible with the current game version
+        let compatible = Release::highest(
+            candidates
+                .iter()
+                .copied()
+                .filter(|release| self.is_release_compatible(release)),
+        );
+        if self.strict_compatibility {
+            return compatible;
+        }
+        compatible
+            // Then one made for another patch of the same major.minor game version
+            .or_else(|| {
+                Release::highest(candidates.iter().copied().filter(|release| {
+                    self.release_compatibility(release) == Compatibility::SameMinor
+                }))
+            })
+            // Fallback to the highest version if no compatible version found
+            .or_else(|| Release::highest(candidates.iter().copied()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mock_moddb::{
+        MemoryRepository, MockModDb, mod_zip, modinfo_zip, search_json, zip_of,
+    };
+    use crate::config::WebhookKind;
+    use crate::utils::lockfile::LockError;
+    use crate::utils::prompt::{AssumeYesPrompt, ScriptedAnswer, ScriptedPrompt};
+    use crate::utils::remote::MemoryRemote;
+    use tempfile::{TempDir, tempdir};
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn list_filters_by_author_or_contributor() {
+        let mods_dir = tempdir().unwrap();
+        std::fs::write(
+            mods_dir.path().join("carryon.zip"),
+            modinfo_zip(r#"{"modid": "carryon", "authors": ["copygirl", "Nikeyu"]}"#),
+        )
+        .unwrap();
+        std::fs::write(
+            mods_dir.path().join("hudclock.zip"),
+            modinfo_zip(
+                r#"{"modid": "hudclock", "author": "Melchior", "contributors": ["copygirl"]}"#,
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            mods_dir.path().join("primitivesurvival.zip"),
+            modinfo_zip(r#"{"modid": "primitivesurvival", "author": "Spear and Fang, Tyron"}"#),
+        )
+        .unwrap();
+        let mod_manager = ModManager::new().with_mod_dirs(vec![mods_dir.path().to_path_buf()]);
+
+        let mut modids: Vec<_> = mod_manager
+            .list_entries(Some("CopyGirl"))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.modid)
+            .collect();
+        modids.sort();
+        assert_eq!(modids, ["carryon", "hudclock"]);
+
+        let entries = mod_manager.list_entries(Some("tyron")).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].authors, ["Spear and Fang", "Tyron"]);
+    }
+
+    #[test]
+    fn strip_html_keeps_text_and_line_breaks() {
+        let html = "<p>Carry <b>chests</b> &amp; baskets.</p><p>Works<br/>on servers.</p>";
+        assert_eq!(
+            strip_html(html),
+            "Carry chests & baskets.\nWorks\non servers."
+        );
+    }
+
+    #[test]
+    fn changelog_between_covers_releases_since_the_installed_version() {
+        let release = |version: &str, changelog: &str| Release {
+            modversion: Some(version.to_string()),
+            changelog: Some(changelog.to_string()),
+            ..Release::default()
+        };
+        let releases = [
+            release("1.9.0", "<p>Next</p>"),
+            release("1.8.1", "Hotfix"),
+            release("1.8.0", "<p>Big <b>update</b></p>"),
+            release("1.7.0", "Old"),
+        ];
+
+        assert_eq!(
+            changelog_between(&releases, Some("1.7.0"), "1.8.1"),
+            "v1.8.1:\nHotfix\n\nv1.8.0:\nBig update"
+        );
+        assert_eq!(changelog_between(&releases, None, "1.9.0"), "v1.9.0:\nNext");
+    }
+
+    #[tokio::test]
+    async fn interactive_export_uses_selected_mods() {
+        let mods_dir = tempdir().unwrap();
+        std::fs::write(mods_dir.path().join("alpha.zip"), mod_zip("alpha", "1.0.0")).unwrap();
+        std::fs::write(mods_dir.path().join("beta.zip"), mod_zip("beta", "1.0.0")).unwrap();
+
+        let prompt = Arc::new(ScriptedPrompt::new([ScriptedAnswer::MultiSelect(vec![1])]));
+        let mod_manager = ModManager::new()
+            .with_mod_dirs(vec![mods_dir.path().to_path_buf()])
+            .with_prompt(prompt.clone());
+
+        let exported = mod_manager
+            .export_string(Some(true), ModListFormat::Ids, CliFlags::default(), false)
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.asked(), ["Select mods to export"]);
+        assert_eq!(exported.lines().count(), 1);
+        assert!(exported.ends_with("@1.0.0"));
+    }
+
+    /// A mods directory inside a temp dir, so the state file and lockfile stay inside it too.
+    fn mods_dir() -> (TempDir, PathBuf) {
+        let data_dir = tempdir().unwrap();
+        let mods_dir = data_dir.path().join("Mods");
+        std::fs::create_dir(&mods_dir).unwrap();
+        (data_dir, mods_dir)
+    }
+
+    /// A manager installing into `mods_dir` from the mock ModDB. Its config file is kept
+    /// next to `mods_dir`, so the user's config doesn't change the results.
+    fn manager(moddb: &MockModDb, mods_dir: &Path) -> ModManager {
+        ModManager::new()
+            .with_mod_dirs(vec![mods_dir.to_path_buf()])
+            .with_config_path(mods_dir.with_file_name("config.toml"))
+            .with_repository(moddb.api())
+    }
+
+    fn encoder_data(mod_id: &str, mod_version: &str) -> EncoderData {
+        EncoderData {
+            mod_id: mod_id.to_string(),
+            mod_version: mod_version.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn download_accepts_moddb_page_links() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+
+        manager(&moddb, &mods_dir)
+            .with_prompt(Arc::new(AssumeYesPrompt))
+            .download_mod("https://mods.vintagestory.at/carryon#tab-files", None)
+            .await
+            .unwrap();
+
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
+        assert_eq!(moddb.requests_to("/api/mod/carryon").await, 1);
+    }
+
+    #[tokio::test]
+    async fn download_installs_the_requested_or_picked_release() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0", "1.6.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let link = "https://mods.vintagestory.at/carryon";
+
+        let mod_manager = manager(&moddb, &mods_dir).with_prompt(Arc::new(AssumeYesPrompt));
+        mod_manager.download_mod(link, Some("1.7.0")).await.unwrap();
+        assert!(mods_dir.join("carryon_v1.7.0.zip").exists());
+        assert!(matches!(
+            mod_manager.download_mod(link, Some("0.1.0")).await,
+            Err(ModManagerError::ReleaseNotFound { .. })
+        ));
+
+        let prompt = Arc::new(ScriptedPrompt::new([
+            ScriptedAnswer::Confirm(true),
+            ScriptedAnswer::Select(Some(2)),
+        ]));
+        manager(&moddb, &mods_dir)
+            .with_prompt(prompt.clone())
+            .with_release_picker(true)
+            .download_mod(link, None)
+            .await
+            .unwrap();
+        assert_eq!(prompt.remaining(), 0);
+        assert!(mods_dir.join("carryon_v1.6.0.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.7.0.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+        assert_eq!(std::fs::read_dir(&mods_dir).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn bundles_install_included_files_and_download_the_rest() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.add_mod("hudclock", &["2.0.0"]).await;
+        moddb.add_mod("clock", &["1.0.0"]).await;
+        let (_source_data, source) = mods_dir();
+        std::fs::write(
+            source.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+        let folder = source.join("hudclock");
+        std::fs::create_dir(&folder).unwrap();
+        std::fs::write(
+            folder.join("modinfo.json"),
+            r#"{"modid": "hudclock", "name": "HUD Clock", "version": "2.0.0"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            source.join("Clock.cs"),
+            r#"[assembly: ModInfo("Clock", "clock", Version = "1.0.0")]"#,
+        )
+        .unwrap();
+        let bundle = source.parent().unwrap().join("friends");
+        let flags = BundleFlags {
+            path: bundle.clone(),
+            name: None,
+            author: Some("Alex".to_string()),
+            description: None,
+            with_files: true,
+        };
+        manager(&moddb, &source)
+            .export_bundle(None, CliFlags::default(), false, flags)
+            .await
+            .unwrap();
+
+        let bundle = bundle.with_extension(BUNDLE_EXTENSION);
+        let manifest = Bundle::open(&bundle).unwrap().manifest;
+        assert_eq!(manifest.name, "friends");
+        assert_eq!(manifest.mods.len(), 3);
+
+        let (_target_data, target) = mods_dir();
+        manager(&moddb, &target)
+            .install_bundle(&bundle)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read(target.join("carryon_v1.7.0.zip")).unwrap(),
+            mod_zip("carryon", "1.7.0")
+        );
+        assert!(target.join("hudclock_v2.0.0.zip").exists());
+        assert!(target.join("clock_v1.0.0.zip").exists());
+        assert_eq!(moddb.requests_to("/files/carryon_v1.7.0.zip").await, 0);
+    }
+
+    #[tokio::test]
+    async fn download_installs_and_records_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+
+        manager(&moddb, &mods_dir)
+            .download_encoder_data(vec![encoder_data("carryon", "1.8.0")], false)
+            .await
+            .unwrap();
+
+        let installed = mods_dir.join("carryon_v1.8.0.zip");
+        assert_eq!(
+            std::fs::read(&installed).unwrap(),
+            mod_zip("carryon", "1.8.0")
+        );
+
+        let state = StateFile::load(&get_state_path(&mods_dir)).unwrap();
+        let record = state.get("carryon").unwrap();
+        assert_eq!(record.reason, InstallReason::Explicit);
+        assert_eq!(record.releaseid, Some(100));
+        assert_eq!(
+            record.release_created.as_deref(),
+            Some("2025-01-01 12:00:00")
+        );
+
+        let lock = LockFile::load(&get_lock_path(&mods_dir)).unwrap();
+        assert_eq!(lock.mods[0].url, moddb.file_url("carryon", "1.8.0"));
+        assert_eq!(
+            lock.mods[0].sha256,
+            FileManager::hash_file(&installed).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn download_installs_missing_dependencies() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("addon", &["1.0.0"]).await;
+        moddb.add_mod("lib", &["2.0.0"]).await;
+        moddb
+            .serve_file(
+                "addon",
+                "1.0.0",
+                modinfo_zip(
+                    r#"{"modid": "addon", "version": "1.0.0",
+                        "dependencies": {"game": "1.20.0", "lib": "2.0.0"}}"#,
+                ),
+            )
+            .await;
+        let (_data_dir, mods_dir) = mods_dir();
+
+        manager(&moddb, &mods_dir)
+            .download_encoder_data(vec![encoder_data("addon", "1.0.0")], false)
+            .await
+            .unwrap();
+
+        assert!(mods_dir.join("lib_v2.0.0.zip").exists());
+        let state = StateFile::load(&get_state_path(&mods_dir)).unwrap();
+        assert_eq!(state.get("addon").unwrap().reason, InstallReason::Explicit);
+        assert_eq!(
+            state.get("lib").unwrap().reason,
+            InstallReason::Dependency {
+                required_by: vec!["addon".to_string()]
             }
+        );
+    }
+
+    #[tokio::test]
+    async fn local_file_install_replaces_older_and_refuses_downgrade() {
+        let moddb = MockModDb::start().await;
+        let (data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("carryon.zip"), mod_zip("carryon", "1.7.0")).unwrap();
+        let archive = data_dir.path().join("download.zip");
+        std::fs::write(&archive, mod_zip("carryon", "1.8.0")).unwrap();
+
+        let mod_manager = manager(&moddb, &mods_dir);
+        mod_manager
+            .install_local_file(&archive, false)
+            .await
+            .unwrap();
+        assert!(!mods_dir.join("carryon.zip").exists());
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
+
+        std::fs::write(&archive, mod_zip("carryon", "1.7.10")).unwrap();
+        assert!(matches!(
+            mod_manager.install_local_file(&archive, false).await,
+            Err(ModManagerError::NewerInstalled { .. })
+        ));
+        mod_manager
+            .install_local_file(&archive, true)
+            .await
+            .unwrap();
+        assert!(mods_dir.join("carryon_v1.7.10.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn browse_pages_through_results() {
+        let mods: Vec<ModSearchResult> = ["alpha", "beta", "gamma"]
+            .iter()
+            .map(|name| {
+                serde_json::from_value(serde_json::json!({
+                    "modid": 1, "assetid": 1, "downloads": 10, "follows": 0,
+                    "trendingpoints": 0, "comments": 0, "name": name, "summary": null,
+                    "modidstrs": [name], "author": "tester", "urlalias": null,
+                    "side": "both", "type": "mod", "logo": null, "tags": [],
+                    "lastreleased": null,
+                }))
+                .unwrap()
+            })
+            .collect();
+        // Page 1 lists two mods, then "next page"; page 2 lists one, then "previous page"
+        let prompt = Arc::new(ScriptedPrompt::new([
+            ScriptedAnswer::Select(Some(2)),
+            ScriptedAnswer::Select(Some(1)),
+        ]));
+        let mod_manager = ModManager::new().with_prompt(prompt.clone());
+        let mut filter = String::new();
+        let mut page = 0;
+
+        for _ in 0..2 {
+            mod_manager
+                .handle_mod_selection(&mods, &mut filter, &mut page, 2, false)
+                .await
+                .unwrap();
         }
 
-        Ok(())
+        assert_eq!(page, 0);
+        let asked = prompt.asked();
+        assert!(asked[0].contains("page 1 of 2"));
+        assert!(asked[1].contains("page 2 of 2"));
+    }
+
+    #[tokio::test]
+    async fn browse_by_tags_lists_tagged_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_tags(&["Storage", "Quality of Life"]).await;
+        moddb.add_search_results(&["hudclock"]).await;
+        moddb.add_tagged_search_results(2, &["carryon"]).await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let prompt = Arc::new(ScriptedPrompt::new([
+            ScriptedAnswer::MultiSelect(vec![1]),
+            ScriptedAnswer::Select(Some(0)),
+            ScriptedAnswer::Confirm(true),
+            ScriptedAnswer::Select(None),
+        ]));
+
+        manager(&moddb, &mods_dir)
+            .with_prompt(prompt.clone())
+            .show_paginated_mods(false, true, Query::new())
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.remaining(), 0);
+        assert_eq!(
+            prompt.asked()[2],
+            "Download mod: carryon 1.8.0 [? unknown]?"
+        );
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn rollback_installs_picked_release_and_removes_current() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0", "1.6.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.8.0.zip"),
+            mod_zip("carryon", "1.8.0"),
+        )
+        .unwrap();
+
+        let prompt = Arc::new(ScriptedPrompt::new([ScriptedAnswer::Select(Some(1))]));
+        let mod_manager = manager(&moddb, &mods_dir).with_prompt(prompt.clone());
+        mod_manager.rollback_mod("carryon", None).await.unwrap();
+
+        assert_eq!(prompt.remaining(), 0);
+        assert!(mods_dir.join("carryon_v1.6.0.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+
+        assert!(matches!(
+            mod_manager.rollback_mod("carryon", Some("0.1.0")).await,
+            Err(ModManagerError::ReleaseNotFound { .. })
+        ));
+        mod_manager
+            .rollback_mod("carryon", Some("1.7.0"))
+            .await
+            .unwrap();
+        assert!(mods_dir.join("carryon_v1.7.0.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.6.0.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn lookup_prints_raw_values() {
+        let moddb = MockModDb::start().await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("carryon.zip"), mod_zip("carryon", "1.8.0")).unwrap();
+        let mod_manager = manager(&moddb, &mods_dir);
+
+        let lookup = |key: &'static str| mod_manager.lookup(key, None);
+        assert_eq!(lookup("mods.count").await.unwrap().as_deref(), Some("1"));
+        assert_eq!(
+            lookup("mod.carryon.version").await.unwrap().as_deref(),
+            Some("1.8.0")
+        );
+        assert_eq!(lookup("mod.missing.version").await.unwrap(), None);
+        assert_eq!(lookup("game.version").await.unwrap(), None);
+        assert!(matches!(
+            lookup("mod.carryon.author").await,
+            Err(ModManagerError::UnknownKey(_))
+        ));
+        assert!(matches!(
+            lookup("nonsense").await,
+            Err(ModManagerError::UnknownKey(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn download_by_search_installs_selected_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_search_results(&["carryon", "hudclock"]).await;
+        moddb.add_mod("hudclock", &["3.1.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+
+        let prompt = Arc::new(ScriptedPrompt::new([ScriptedAnswer::MultiSelect(vec![1])]));
+        manager(&moddb, &mods_dir)
+            .with_prompt(prompt.clone())
+            .download_mods(&vec!["clock".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.remaining(), 0);
+        assert!(mods_dir.join("hudclock_v3.1.0.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn list_fills_compatibility_of_installed_releases() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("carryon.zip"), mod_zip("carryon", "1.8.0")).unwrap();
+        std::fs::write(mods_dir.join("hudclock.zip"), mod_zip("hudclock", "1.0.0")).unwrap();
+        let manager = manager(&moddb, &mods_dir).with_game_version_override(Some("1.20.0".into()));
+
+        let mut entries = manager.list_entries(None).await.unwrap();
+        manager.fill_compatibility(&mut entries).await;
+
+        let compatibility = |modid: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.modid == modid)
+                .and_then(|entry| entry.compatibility)
+        };
+        assert_eq!(compatibility("carryon"), Some(Compatibility::Compatible));
+        assert_eq!(compatibility("hudclock"), Some(Compatibility::Unknown));
+    }
+
+    #[tokio::test]
+    async fn compatibility_report_counts_mods_with_newer_releases() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.0.0"]).await;
+        moddb.add_mod("hudclock", &["1.0.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("carryon.zip"), mod_zip("carryon", "1.0.0")).unwrap();
+        std::fs::write(mods_dir.join("hudclock.zip"), mod_zip("hudclock", "1.0.0")).unwrap();
+
+        let upgradable = manager(&moddb, &mods_dir)
+            .compatibility_report()
+            .await
+            .unwrap();
+
+        assert_eq!(upgradable, 1);
+    }
+
+    #[tokio::test]
+    async fn download_of_unknown_mod_fails() {
+        let moddb = MockModDb::start().await;
+        moddb.add_missing_mod("nosuchmod").await;
+        let (_data_dir, mods_dir) = mods_dir();
+
+        let result = manager(&moddb, &mods_dir)
+            .download_encoder_data(vec![encoder_data("nosuchmod", "1.0.0")], false)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ModManagerError::ApiError(ClientError::ModNotFound(_)))
+        ));
+        assert_eq!(std::fs::read_dir(&mods_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn missing_mods_dir_is_created_and_used_for_installs() {
+        let data_dir = tempdir().unwrap();
+        let mods_dir = data_dir.path().join("Mods");
+        let extra_dir = data_dir.path().join("Extra");
+
+        let mod_manager = ModManager::new()
+            .with_mod_dirs(vec![extra_dir.clone()])
+            .ensure_mods_dir(mods_dir.clone(), true);
+
+        assert!(mods_dir.is_dir());
+        assert_eq!(mod_manager.file_manager.mod_dirs(), [mods_dir, extra_dir]);
+    }
+
+    #[tokio::test]
+    async fn normalize_renames_files_and_index_entries() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let mod_manager =
+            manager(&moddb, &mods_dir).with_filename_template(Some("{modid}-{version}.zip".into()));
+        mod_manager
+            .download_encoder_data(vec![encoder_data("carryon", "1.8.0")], false)
+            .await
+            .unwrap();
+        std::fs::rename(
+            mods_dir.join("carryon-1.8.0.zip"),
+            mods_dir.join("CarryOn (1).zip"),
+        )
+        .unwrap();
+        let mut state = StateFile::load(&get_state_path(&mods_dir)).unwrap();
+        state.mods.get_mut("carryon").unwrap().filename = "CarryOn (1).zip".to_string();
+        state.save().unwrap();
+        std::fs::write(mods_dir.join("hudclock.zip"), mod_zip("hudclock", "2.0.0")).unwrap();
+        let folder = mods_dir.join("betterruins");
+        std::fs::create_dir(&folder).unwrap();
+        std::fs::write(
+            folder.join("modinfo.json"),
+            r#"{"modid": "betterruins", "version": "1.0.0"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            mods_dir.join("Clock.cs"),
+            r#"[assembly: ModInfo("Clock", "clock", Version = "1.0.0")]"#,
+        )
+        .unwrap();
+
+        mod_manager.normalize_filenames().await.unwrap();
+
+        let mut files: Vec<_> = std::fs::read_dir(&mods_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                "Clock.cs",
+                "betterruins",
+                "carryon-1.8.0.zip",
+                "hudclock-2.0.0.zip"
+            ]
+        );
+        assert!(folder.join("modinfo.json").is_file());
+        let state = StateFile::load(&get_state_path(&mods_dir)).unwrap();
+        assert_eq!(state.get("carryon").unwrap().filename, "carryon-1.8.0.zip");
+    }
+
+    #[tokio::test]
+    async fn assume_yes_refuses_choices_without_a_default() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.8.0.zip"),
+            mod_zip("carryon", "1.8.0"),
+        )
+        .unwrap();
+        let mod_manager = manager(&moddb, &mods_dir).with_prompt(Arc::new(AssumeYesPrompt));
+
+        let result = mod_manager.rollback_mod("carryon", None).await;
+        assert!(matches!(
+            result,
+            Err(ModManagerError::InteractionRequired(_))
+        ));
+
+        mod_manager
+            .remove_mods(&["carryon".to_string()], false)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_dir(&mods_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn clean_backs_up_zips_without_modinfo() {
+        let (_data_dir, mods_dir) = mods_dir();
+        let textures = mods_dir.join("textures.zip");
+        std::fs::write(&textures, zip_of(&[("block.png", b"")])).unwrap();
+
+        let mod_manager = ModManager::new()
+            .with_mod_dirs(vec![mods_dir.clone()])
+            .with_prompt(Arc::new(AssumeYesPrompt));
+        assert_eq!(mod_manager.clean_mods().unwrap(), 1);
+
+        assert!(!textures.exists());
+        let backups: Vec<_> = std::fs::read_dir(backup_dir_for(&mods_dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path().join("textures.zip"))
+            .collect();
+        assert!(backups[0].exists());
     }
 
-    async fn fetch_initial_mods(&self) -> Result<Vec<ModSearchResult>, ModManagerError> {
-        let mut query = Query::new().with_order_by(OrderBy::Downloads);
+    #[tokio::test]
+    async fn sync_matches_the_mod_string() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.add_mod("hudclock", &["2.0.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let newer_file = mods_dir.join("carryon_v1.8.0.zip");
+        std::fs::write(&newer_file, mod_zip("carryon", "1.8.0")).unwrap();
+        let extra_file = mods_dir.join("extra_v1.0.0.zip");
+        std::fs::write(&extra_file, mod_zip("extra", "1.0.0")).unwrap();
+        let pinned_file = mods_dir.join("pinned_v1.0.0.zip");
+        std::fs::write(&pinned_file, mod_zip("pinned", "1.0.0")).unwrap();
+        std::fs::write(
+            mods_dir.join("nameless.zip"),
+            modinfo_zip(r#"{"name": "Nameless", "version": "1.0.0"}"#),
+        )
+        .unwrap();
+        let mod_manager = manager(&moddb, &mods_dir)
+            .with_prompt(Arc::new(AssumeYesPrompt))
+            .with_pinned(vec!["pinned".to_string()]);
+        let mod_string = mod_manager.encoder.encode_mod_string(&[
+            encoder_data("carryon", "1.7.0"),
+            encoder_data("hudclock", "2.0.0"),
+        ]);
 
-        // Add game version filtering if available
-        if let Some(version_tag) = self.get_current_game_version_tag_id() {
-            // Convert i64 to u16 for the query (assuming they fit in the positive range)
-            if let Ok(tag_u16) = u16::try_from(version_tag.abs()) {
-                query = query.with_game_version(tag_u16);
-                if let Some(version) = self.get_current_game_version() {
-                    println!("Filtering results for game version: version {version}");
-                }
-            }
-        }
+        let summary = mod_manager.sync_mods(&mod_string, true).await.unwrap();
 
-        let search_results = self.api.search_mods(query.build()).await?;
-        Ok(search_results.mods)
+        assert!(!newer_file.exists());
+        assert!(mods_dir.join("carryon_v1.7.0.zip").exists());
+        assert!(mods_dir.join("hudclock_v2.0.0.zip").exists());
+        assert!(!extra_file.exists());
+        assert!(pinned_file.exists());
+        assert!(mods_dir.join("nameless.zip").exists());
+        assert_eq!(summary.installed, ["hudclock"]);
+        assert_eq!(summary.updated[0].to, "1.7.0");
+        assert_eq!(summary.removed, ["extra"]);
     }
 
-    fn filter_mods<'a>(
-        &self, mods: &'a [ModSearchResult], filter: &str, page_size: usize,
-    ) -> Vec<&'a ModSearchResult> {
-        mods.iter()
-            .filter(|m| {
-                filter.is_empty()
-                    || m.name.to_lowercase().contains(&filter.to_lowercase())
-                    || m.author.to_lowercase().contains(&filter.to_lowercase())
-            })
-            .take(page_size)
-            .collect()
+    #[tokio::test]
+    async fn update_replaces_outdated_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let old_file = mods_dir.join("carryon_v1.7.0.zip");
+        std::fs::write(&old_file, mod_zip("carryon", "1.7.0")).unwrap();
+
+        let summary = manager(&moddb, &mods_dir)
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+
+        assert!(!old_file.exists());
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
+        assert_eq!(summary.updated.len(), 1);
+        assert_eq!(summary.updated[0].from, "1.7.0");
+        assert_eq!(summary.updated[0].to, "1.8.0");
+
+        let state = StateFile::load(&get_state_path(&mods_dir)).unwrap();
+        let last_update = state.last_update.unwrap();
+        assert_eq!(last_update.updated, 1);
+        assert!(last_update.pending.is_empty());
     }
 
-    fn create_display_options(&self, mods: &[&ModSearchResult]) -> Vec<String> {
-        let mut options: Vec<String> = mods
-            .iter()
-            .map(|m| {
-                format!(
-                    "{} by {} ({} downloads)",
-                    m.name,
-                    m.author,
-                    m.downloads.unwrap_or(0)
-                )
-            })
+    #[tokio::test]
+    async fn update_installs_dependencies_before_their_dependents() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("addon", &["1.1.0", "1.0.0"]).await;
+        moddb.add_mod("lib", &["2.0.0", "1.0.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("addon.zip"),
+            modinfo_zip(
+                r#"{"modid": "addon", "version": "1.0.0", "dependencies": {"lib": "1.0.0"}}"#,
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            mods_dir.join("lib.zip"),
+            modinfo_zip(r#"{"modid": "lib", "version": "1.0.0"}"#),
+        )
+        .unwrap();
+
+        let summary = manager(&moddb, &mods_dir)
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+
+        let updated: Vec<&str> = summary.updated.iter().map(|m| m.modid.as_str()).collect();
+        assert_eq!(updated, ["lib", "addon"]);
+        assert!(summary.warnings.is_empty());
+        let downloads: Vec<String> = moddb
+            .requested_paths()
+            .await
+            .into_iter()
+            .filter(|path| path.starts_with("/files/"))
             .collect();
+        assert_eq!(
+            downloads,
+            ["/files/lib_v2.0.0.zip", "/files/addon_v1.1.0.zip"]
+        );
+    }
 
-        options.push("--- Filter mods ---".into());
-        options.push("--- Exit ---".into());
-        options
+    #[tokio::test]
+    async fn update_asks_before_installing_a_release_with_unmet_dependencies() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("addon", &["1.1.0", "1.0.0"]).await;
+        moddb.add_mod("lib", &["1.0.0"]).await;
+        moddb
+            .serve_file(
+                "addon",
+                "1.1.0",
+                modinfo_zip(
+                    r#"{"modid": "addon", "version": "1.1.0", "dependencies": {"lib": "2.0.0"}}"#,
+                ),
+            )
+            .await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("addon.zip"), modinfo_zip(r#"{"modid": "addon", "name": "Addon", "version": "1.0.0", "dependencies": {"lib": "1.0.0"}}"#)).unwrap();
+        std::fs::write(
+            mods_dir.join("lib.zip"),
+            modinfo_zip(r#"{"modid": "lib", "version": "1.0.0"}"#),
+        )
+        .unwrap();
+
+        let prompt = Arc::new(ScriptedPrompt::new([ScriptedAnswer::Confirm(false)]));
+        let summary = manager(&moddb, &mods_dir)
+            .with_prompt(prompt.clone())
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+        assert_eq!(prompt.remaining(), 0);
+        assert!(summary.updated.is_empty());
+        assert_eq!(prompt.asked(), ["Update Addon anyway?"]);
+        assert_eq!(summary.skipped, ["Addon"]);
+        assert!(summary.failed.is_empty());
+        assert!(mods_dir.join("addon.zip").exists());
+
+        let summary = manager(&moddb, &mods_dir)
+            .with_prompt(Arc::new(AssumeYesPrompt))
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+        assert_eq!(summary.updated.len(), 1);
+        assert_eq!(
+            summary.warnings,
+            ["addon needs lib >= 2.0.0, but 1.0.0 is installed"]
+        );
     }
 
-    async fn handle_mod_selection(
-        &self, mods: &[ModSearchResult], current_filter: &mut String, page_size: usize,
-    ) -> Result<SelectionResult, ModManagerError> {
-        let displayed_mods = self.filter_mods(mods, current_filter, page_size);
+    #[tokio::test]
+    async fn update_works_against_any_repository() {
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
 
-        if displayed_mods.is_empty() {
-            println!("No mods found matching filter: {current_filter}");
-            return Ok(SelectionResult::NoResults);
-        }
+        let summary = ModManager::new()
+            .with_mod_dirs(vec![mods_dir.clone()])
+            .with_config_path(mods_dir.with_file_name("config.toml"))
+            .with_repository(MemoryRepository::default().with_mod("carryon", &["1.8.0", "1.7.0"]))
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
 
-        let options = self.create_display_options(&displayed_mods);
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
+        assert_eq!(summary.updated.len(), 1);
+    }
 
-        match Terminal::select("Select a mod (use / to search, ESC to exit)", &options) {
-            Some(selection) if selection >= displayed_mods.len() => {
-                match selection - displayed_mods.len() {
-                    0 => {
-                        self.handle_navigation_selection(0, current_filter)?;
-                        Ok(SelectionResult::Continue)
-                    }
-                    1 => Ok(SelectionResult::Break), // Exit option
-                    _ => Ok(SelectionResult::Continue),
-                }
-            }
-            Some(selection) => {
-                self.handle_mod_download(displayed_mods[selection]).await?;
-                Ok(SelectionResult::Continue)
-            }
-            None => Ok(SelectionResult::Break),
-        }
+    #[tokio::test]
+    async fn update_skips_pinned_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let old_file = mods_dir.join("carryon_v1.7.0.zip");
+        std::fs::write(&old_file, mod_zip("carryon", "1.7.0")).unwrap();
+        let manager = manager(&moddb, &mods_dir).with_pinned(vec!["CarryOn".to_string()]);
+
+        let summary = manager.update_mods(CliFlags::default()).await.unwrap();
+
+        assert!(old_file.exists());
+        assert!(summary.updated.is_empty());
+        assert!(manager.list_entries(None).await.unwrap()[0].pinned);
     }
 
-    fn handle_navigation_selection(
-        &self, nav_index: usize, current_filter: &mut String,
-    ) -> Result<(), ModManagerError> {
-        match nav_index {
-            0 => {
-                self.clear_screen()?;
-                print!("Filter for mod: ");
-                std::io::Write::flush(&mut std::io::stdout())?;
-                *current_filter = Terminal::input("");
-                Ok(())
-            }
-            1 => {
-                // Exit option - this will be handled by the caller
-                Ok(())
-            }
-            _ => Ok(()),
-        }
+    #[tokio::test]
+    async fn update_review_skips_deselected_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.add_mod("hudclock", &["2.1.0", "2.0.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+        std::fs::write(
+            mods_dir.join("hudclock_v2.0.0.zip"),
+            mod_zip("hudclock", "2.0.0"),
+        )
+        .unwrap();
+        // Updates are listed in update order; only carryon stays selected
+        let prompt = Arc::new(ScriptedPrompt::new([ScriptedAnswer::MultiSelect(vec![0])]));
+        let manager = manager(&moddb, &mods_dir)
+            .with_prompt(prompt.clone())
+            .with_update_review(true);
+
+        let summary = manager.update_mods(CliFlags::default()).await.unwrap();
+
+        assert_eq!(prompt.remaining(), 0);
+        assert_eq!(summary.updated.len(), 1);
+        assert_eq!(summary.updated[0].modid, "carryon");
+        assert_eq!(summary.skipped.len(), 1);
+        assert!(mods_dir.join("hudclock_v2.0.0.zip").exists());
     }
 
-    async fn handle_mod_download(
-        &self, selected_mod: &ModSearchResult,
-    ) -> Result<(), ModManagerError> {
-        let mod_info = self.fetch_mod_info(&selected_mod.modidstrs[0]).await?;
+    #[tokio::test]
+    async fn ignore_lists_hide_mods_from_their_commands() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let old_file = mods_dir.join("carryon_v1.7.0.zip");
+        std::fs::write(&old_file, mod_zip("carryon", "1.7.0")).unwrap();
+        std::fs::write(mods_dir.join("hudclock.zip"), mod_zip("hudclock", "2.0.0")).unwrap();
+        let mut ignore = IgnoreLists::default();
+        ignore.add(IgnoreScope::Updates, "CarryOn");
+        ignore.add(IgnoreScope::All, "hudclock");
+        ignore.add(IgnoreScope::Browse, "beta");
+        let manager = manager(&moddb, &mods_dir).with_ignore_lists(ignore);
 
-        if Terminal::confirm(format!("Download mod: {}?", selected_mod.name)) {
-            self.save_mod_file(&mod_info).await?;
-            println!("Downloaded {}", selected_mod.name);
-        }
+        let summary = manager.update_mods(CliFlags::default()).await.unwrap();
+        assert!(old_file.exists());
+        assert_eq!(summary.skipped, ["carryon", "hudclock"]);
 
-        Ok(())
+        let listed = manager.list_entries(None).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].modid, "carryon");
+        let exported = manager
+            .select_export_mods(None, CliFlags::default())
+            .await
+            .unwrap();
+        assert_eq!(exported.len(), 1);
+        // Commands that manage files still see ignored mods
+        assert_eq!(
+            manager
+                .file_manager
+                .collect_mods(&None)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let results: Vec<ModSearchResult> =
+            serde_json::from_value(search_json(&["alpha", "beta", "hudclock"])["mods"].clone())
+                .unwrap();
+        let shown: Vec<String> = manager
+            .hide_ignored(results)
+            .into_iter()
+            .map(|found| found.name)
+            .collect();
+        assert_eq!(shown, ["alpha"]);
     }
 
-    fn clear_screen(&self) -> Result<(), ModManagerError> {
-        print!("\x1B[2J\x1B[1;1H");
-        std::io::Write::flush(&mut std::io::stdout())?;
-        Ok(())
+    #[tokio::test]
+    async fn game_version_override_decides_compatibility() {
+        let moddb = MockModDb::start().await;
+        // Every mock release is tagged for game version 1.20.0
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+
+        let held_back = manager(&moddb, &mods_dir)
+            .with_strict_compatibility(true)
+            .with_game_version_override(Some("1.21.0".to_string()))
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+        assert_eq!(held_back.held_back.len(), 1);
+        assert!(held_back.updated.is_empty());
+
+        let updated = manager(&moddb, &mods_dir)
+            .with_strict_compatibility(true)
+            .with_game_version_override(Some("1.20.0".to_string()))
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+        assert_eq!(updated.updated.len(), 1);
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
     }
 
-    async fn delete_old_mod(&self, path: &PathBuf) -> Result<(), FileError> {
-        println!("Deleting old mod: {}", path.display());
-        self.file_manager.delete_file(path).await
+    #[tokio::test]
+    async fn compat_check_reports_readiness_for_the_target_version() {
+        let moddb = MockModDb::start().await;
+        // Every mock release is tagged for game version 1.20.0
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+        let mod_manager = manager(&moddb, &mods_dir);
+
+        let entries = mod_manager.compat_check("1.20.0").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].is_supported());
+
+        let entries = mod_manager.compat_check("1.22.0").await.unwrap();
+        assert_eq!(entries[0].status, "unsupported");
+        assert!(!entries[0].is_supported());
+        assert!(entries[0].release.is_none());
     }
 
-    fn get_new_mod_path(&self, mods_dir: &Path, release: &Release, name: &str) -> Option<PathBuf> {
-        match &release.filename {
-            Some(filename) => Some(mods_dir.join(filename)),
-            None => {
-                eprintln!("Missing filename for mod: {name}");
-                None
-            }
-        }
+    #[tokio::test]
+    async fn compat_check_leaves_mods_unknown_to_moddb_out_of_the_verdict() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        moddb.add_missing_mod("mybuild").await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("carryon.zip"), mod_zip("carryon", "1.8.0")).unwrap();
+        std::fs::write(mods_dir.join("mybuild.zip"), mod_zip("mybuild", "0.1.0")).unwrap();
+
+        let entries = manager(&moddb, &mods_dir)
+            .compat_check("1.20.0")
+            .await
+            .unwrap();
+
+        let local = entries.iter().find(|e| e.modid == "mybuild").unwrap();
+        assert_eq!(local.status, NOT_ON_MODDB);
+        assert!(entries.iter().all(CompatEntry::is_supported));
     }
 
-    async fn download_and_save_mod(&self, name: &str, new_mod_path: &PathBuf, release: &Release) {
-        let mod_bytes = match &release.mainfile {
-            Some(url) => match self.api.fetch_file_stream_from_url(url.clone()).await {
-                Ok(bytes) => bytes,
-                Err(e) => {
-                    eprintln!("Failed to download mod {name}: {e}");
-                    return;
-                }
-            },
-            None => {
-                eprintln!("Missing download URL for mod: {name}");
-                return;
-            }
+    #[tokio::test]
+    async fn failed_download_keeps_the_installed_mod() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.fail_file("carryon", "1.8.0").await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let old_file = mods_dir.join("carryon_v1.7.0.zip");
+        std::fs::write(&old_file, mod_zip("carryon", "1.7.0")).unwrap();
+
+        let summary = manager(&moddb, &mods_dir)
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+
+        assert!(old_file.exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+        assert_eq!(summary.failed, ["carryon"]);
+
+        let state = StateFile::load(&get_state_path(&mods_dir)).unwrap();
+        let pending = state.last_update.unwrap().pending;
+        assert_eq!(pending[0].to, "1.8.0");
+    }
+
+    #[test]
+    fn instance_selects_the_mods_directory_of_its_data_directory() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let data_path = dir.path().join("Server");
+        std::fs::create_dir_all(data_path.join("Mods")).unwrap();
+        ConfigManager::load(config_path.clone())
+            .unwrap()
+            .add_instance("server", data_path.clone())
+            .unwrap();
+        let select = |args: &[&str]| {
+            let cli = Cli::parse_from([&["VintageModManager"], args].concat());
+            ModManager::select_mods_dir(&cli, ConfigManager::load(config_path.clone()).unwrap())
         };
 
-        if let Err(e) = self.file_manager.save_file(new_mod_path, &mod_bytes).await {
-            eprintln!("Failed to save new mod {name}: {e}");
-        }
+        let config_manager = select(&["--instance", "server", "list"]).unwrap();
+        assert_eq!(config_manager.get_mod_dirs(), vec![data_path.join("Mods")]);
+
+        let mods_dir = dir.path().join("Mods");
+        let config_manager = select(&[
+            "--instance",
+            "server",
+            "--mods-dir",
+            mods_dir.to_str().unwrap(),
+            "list",
+        ])
+        .unwrap();
+        assert_eq!(config_manager.main_mods_dir(), mods_dir);
+
+        assert!(matches!(
+            select(&["--instance", "client", "list"]),
+            Err(ConfigError::InstanceNotFound(_))
+        ));
     }
 
-    async fn download_mod(&self, mod_data: &str) -> Result<(), ModManagerError> {
-        let query = Query::new()
-            .with_text(&[mod_data.to_string()])
-            .with_order_by(OrderBy::Downloads)
-            .build();
+    #[tokio::test]
+    async fn push_remote_uploads_missing_mods_and_prunes_extra_ones() {
+        let moddb = MockModDb::start().await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.8.0.zip"),
+            mod_zip("carryon", "1.8.0"),
+        )
+        .unwrap();
+        let remote =
+            MemoryRemote::default().with_file("carryon_v1.7.0.zip", mod_zip("carryon", "1.7.0"));
+        let manager = manager(&moddb, &mods_dir).with_prompt(Arc::new(AssumeYesPrompt));
 
-        let query_results = self.api.search_mods(query).await?;
-        self.logger
-            .log_default(&format!("Found {} mods", query_results.mods.len()));
+        manager.push_remote(&remote, false).await.unwrap();
+        assert_eq!(remote.names(), ["carryon_v1.7.0.zip", "carryon_v1.8.0.zip"]);
 
-        if let Some(selection) = Terminal::select("Select a mod to download", &query_results.mods) {
-            let selected_mod = &query_results.mods[selection];
-            let mod_info = self.fetch_mod_info(&selected_mod.modidstrs[0]).await?;
+        manager.push_remote(&remote, true).await.unwrap();
+        assert_eq!(remote.names(), ["carryon_v1.8.0.zip"]);
+    }
 
-            if Terminal::confirm(format!("Download mod: {}?", selected_mod.name)) {
-                self.save_mod_file(&mod_info).await?;
-            }
-        }
+    #[tokio::test]
+    async fn pull_remote_downloads_the_servers_mods() {
+        let moddb = MockModDb::start().await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("hudclock.zip"), mod_zip("hudclock", "1.0.0")).unwrap();
+        let remote =
+            MemoryRemote::default().with_file("carryon_v1.8.0.zip", mod_zip("carryon", "1.8.0"));
 
-        Ok(())
+        manager(&moddb, &mods_dir)
+            .with_prompt(Arc::new(AssumeYesPrompt))
+            .pull_remote(&remote, true)
+            .await
+            .unwrap();
+
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
+        assert!(!mods_dir.join("hudclock.zip").exists());
     }
 
-    async fn download_mods(&self, mods: &Vec<String>) -> Result<(), ModManagerError> {
-        let query = Query::new()
-            .with_text(mods)
-            .with_order_by(OrderBy::Downloads)
-            .build();
-        self.logger
-            .log_default(&format!("Searching for mods: {mods:?}"));
+    #[tokio::test]
+    async fn remote_update_replaces_old_releases_on_the_server() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let remote =
+            MemoryRemote::default().with_file("carryon_v1.7.0.zip", mod_zip("carryon", "1.7.0"));
 
-        let query_results = self.api.search_mods(query).await?;
-        self.logger
-            .log_default(&format!("Found {} mods", query_results.mods.len()));
+        let summary = manager(&moddb, &mods_dir)
+            .with_prompt(Arc::new(AssumeYesPrompt))
+            .update_remote(&remote, mods_dir.with_file_name("mirror"))
+            .await
+            .unwrap();
 
-        if query_results.mods.is_empty() {
-            println!("No mods found, try again with different search terms");
-            return Ok(());
-        }
+        assert!(summary.failed.is_empty());
+        assert_eq!(remote.names(), ["carryon_v1.8.0.zip"]);
+    }
 
-        let selections = Terminal::multi_select("Select mods to download", &query_results.mods);
-        if !selections.is_empty() {
-            let progress_bar = ProgressBarWrapper::new(selections.len() as u64);
+    #[tokio::test]
+    async fn remote_update_keeps_server_files_when_an_update_failed() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.add_mod("hudclock", &["1.1.0", "1.0.0"]).await;
+        moddb.fail_file("hudclock", "1.1.0").await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let remote = MemoryRemote::default()
+            .with_file("carryon_v1.7.0.zip", mod_zip("carryon", "1.7.0"))
+            .with_file("hudclock_v1.0.0.zip", mod_zip("hudclock", "1.0.0"));
 
-            for selection in selections {
-                let selected_mod = &query_results.mods[selection];
-                let mod_info = self.fetch_mod_info(&selected_mod.modidstrs[0]).await?;
-                self.save_mod_file(&mod_info).await?;
-                progress_bar.println(format!("Downloaded mod: {}", selected_mod.name));
-                progress_bar.inc(1);
-            }
+        let summary = manager(&moddb, &mods_dir)
+            .with_prompt(Arc::new(AssumeYesPrompt))
+            .update_remote(&remote, mods_dir.with_file_name("mirror"))
+            .await
+            .unwrap();
 
-            progress_bar.finish_with_message("Finished downloading mods");
+        assert_eq!(summary.failed, ["hudclock"]);
+        // The successful update is uploaded, but nothing is deleted
+        assert_eq!(
+            remote.names(),
+            [
+                "carryon_v1.7.0.zip",
+                "carryon_v1.8.0.zip",
+                "hudclock_v1.0.0.zip"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn manifest_install_reports_each_mod() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.add_missing_mod("gone").await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let entry = |mod_id: &str, mod_version: &str| EncoderData {
+            mod_id: mod_id.to_string(),
+            mod_version: mod_version.to_string(),
+        };
+
+        let result = manager(&moddb, &mods_dir)
+            .install_manifest(
+                vec![entry("carryon", "1.7.0"), entry("gone", "1.0.0")],
+                true,
+            )
+            .await;
+
+        assert!(matches!(result, Err(ModManagerError::InstallFailed(1))));
+        assert!(mods_dir.join("carryon_v1.7.0.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn restore_rolls_back_an_update_from_its_backup() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+        let manager = manager(&moddb, &mods_dir);
+
+        manager.update_mods(CliFlags::default()).await.unwrap();
+        let backups = manager.backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].1[0].ends_with("carryon_v1.7.0.zip"));
+
+        manager.restore_backup(Some(&backups[0].0)).await.unwrap();
+
+        assert!(mods_dir.join("carryon_v1.7.0.zip").exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+        assert!(matches!(
+            manager.restore_backup(Some("nonsense")).await,
+            Err(ModManagerError::BackupNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn update_picks_highest_version_and_skips_downgrades() {
+        let moddb = MockModDb::start().await;
+        // Listed by upload date: a hotfix for the old line was uploaded after 2.0.0-rc.1
+        moddb
+            .add_mod("carryon", &["1.9.1", "2.0.0-rc.1", "1.9.0"])
+            .await;
+        moddb.add_mod("hudclock", &["1.2.0", "1.1.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.9.0.zip"),
+            mod_zip("carryon", "1.9.0"),
+        )
+        .unwrap();
+        let newer = mods_dir.join("hudclock_v1.3.0-dev.1.zip");
+        std::fs::write(&newer, mod_zip("hudclock", "1.3.0-dev.1")).unwrap();
+
+        let summary = manager(&moddb, &mods_dir)
+            .with_channels(Channel::Rc, BTreeMap::new())
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+
+        assert!(mods_dir.join("carryon_v2.0.0-rc.1.zip").exists());
+        assert!(newer.exists());
+        assert_eq!(summary.updated.len(), 1);
+        assert_eq!(summary.updated[0].to, "2.0.0-rc.1");
+    }
+
+    #[tokio::test]
+    async fn update_fetches_each_mod_once() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.9.0", "1.8.0"]).await;
+        moddb.add_mod("hudclock", &["1.2.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        for (modid, version) in [("carryon", "1.8.0"), ("hudclock", "1.2.0")] {
+            std::fs::write(
+                mods_dir.join(MockModDb::filename(modid, version)),
+                mod_zip(modid, version),
+            )
+            .unwrap();
         }
 
-        Ok(())
+        let summary = manager(&moddb, &mods_dir)
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.updated.len(), 1);
+        assert_eq!(summary.up_to_date, ["hudclock"]);
+        assert_eq!(moddb.requests_to("/api/mod/carryon").await, 1);
+        assert_eq!(moddb.requests_to("/api/mod/hudclock").await, 1);
     }
 
-    async fn download_mod_string(&self, mod_string: &str) -> Result<(), ModManagerError> {
-        let decoded: Vec<EncoderData> = self.encoder.decode_mod_string(mod_string.to_owned())?;
-        let progress_bar = ProgressBarWrapper::new(decoded.len() as u64);
+    #[tokio::test]
+    async fn update_skips_prereleases_unless_the_channel_allows_them() {
+        let moddb = MockModDb::start().await;
+        moddb
+            .add_mod("carryon", &["2.0.0-rc.1", "1.9.0", "1.8.0"])
+            .await;
+        moddb
+            .add_mod("hudclock", &["1.3.0-dev.1", "1.2.0", "1.1.0"])
+            .await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.8.0.zip"),
+            mod_zip("carryon", "1.8.0"),
+        )
+        .unwrap();
+        std::fs::write(
+            mods_dir.join("hudclock_v1.1.0.zip"),
+            mod_zip("hudclock", "1.1.0"),
+        )
+        .unwrap();
 
-        for mod_data in decoded {
-            let mod_info = self.fetch_mod_info(&mod_data.mod_id).await?;
-            progress_bar.set_message(format!("Downloading mod: {}", mod_info.mod_data.name));
-            self.save_mod_file(&mod_info).await?;
-            progress_bar.inc(1);
+        manager(&moddb, &mods_dir)
+            .with_channels(
+                Channel::Stable,
+                BTreeMap::from([("CarryOn".to_string(), Channel::Rc)]),
+            )
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
+
+        assert!(mods_dir.join("carryon_v2.0.0-rc.1.zip").exists());
+        assert!(mods_dir.join("hudclock_v1.2.0.zip").exists());
+        assert!(Channel::Dev.allows("1.3.0-dev.1"));
+        assert!(!Channel::Rc.allows("1.3.0-dev.1"));
+    }
+
+    #[tokio::test]
+    async fn check_reports_updates_without_installing() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.add_mod("hudclock", &["2.0.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let old_file = mods_dir.join("carryon_v1.7.0.zip");
+        std::fs::write(&old_file, mod_zip("carryon", "1.7.0")).unwrap();
+        std::fs::write(
+            mods_dir.join("hudclock_v2.0.0.zip"),
+            mod_zip("hudclock", "2.0.0"),
+        )
+        .unwrap();
+
+        let updates = manager(&moddb, &mods_dir)
+            .check_updates(CliFlags::default())
+            .await
+            .unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].modid, "carryon");
+        assert_eq!(updates[0].to, "1.8.0");
+        assert_eq!(updates[0].changelog, "v1.8.0:\nChanges in 1.8.0");
+        assert!(old_file.exists());
+        assert!(!mods_dir.join("carryon_v1.8.0.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn check_posts_each_available_update_to_webhooks_once() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+        let hook = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&hook)
+            .await;
+        let webhooks = [Webhook {
+            url: hook.uri(),
+            kind: WebhookKind::Json,
+            available: true,
+        }];
+
+        let mod_manager = manager(&moddb, &mods_dir);
+        for _ in 0..2 {
+            let updates = mod_manager
+                .check_updates(CliFlags::default())
+                .await
+                .unwrap();
+            assert_eq!(updates.len(), 1);
+            mod_manager
+                .announce_available_updates(&webhooks, &updates)
+                .await;
         }
+        let state = StateFile::load(&get_state_path(&mods_dir)).unwrap();
+        assert_eq!(state.announced.len(), 1);
+        assert_eq!(state.announced[0].to, "1.8.0");
+    }
 
-        progress_bar.finish_with_message("Finished downloading mods");
-        Ok(())
+    #[tokio::test]
+    async fn watch_announces_each_release_once() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+        let mod_manager = manager(&moddb, &mods_dir);
+        let mut announced = HashSet::new();
+
+        let first = mod_manager.new_updates(&mut announced).await.unwrap();
+        let second = mod_manager.new_updates(&mut announced).await.unwrap();
+
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].to, "1.8.0");
+        assert!(second.is_empty());
     }
 
-    async fn fetch_mod_info(&self, mod_id: &String) -> Result<ModApiResponse, ModManagerError> {
-        self.logger
-            .log_default(&format!("Fetching mod info: {mod_id}"));
-        let mod_info = self.api.get_mod(mod_id).await?;
-        Ok(mod_info)
+    #[tokio::test]
+    async fn watch_retries_updates_that_failed_to_install() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.fail_file("carryon", "1.8.0").await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(
+            mods_dir.join("carryon_v1.7.0.zip"),
+            mod_zip("carryon", "1.7.0"),
+        )
+        .unwrap();
+        let mod_manager = manager(&moddb, &mods_dir);
+        let mut announced = HashSet::new();
+
+        let updates = mod_manager.new_updates(&mut announced).await.unwrap();
+        mod_manager
+            .apply_watched_updates(&updates, &[], &mut announced)
+            .await;
+
+        assert!(announced.is_empty());
+        assert_eq!(
+            mod_manager.new_updates(&mut announced).await.unwrap().len(),
+            1
+        );
     }
 
-    async fn save_mod_file(&self, mod_info: &ModApiResponse) -> Result<(), ModManagerError> {
-        let vintage_mods_dir = get_vintage_mods_dir()?;
+    #[tokio::test]
+    async fn dry_run_leaves_mods_dir_untouched() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        moddb.add_mod("hudclock", &["2.0.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let old_file = mods_dir.join("carryon_v1.7.0.zip");
+        std::fs::write(&old_file, mod_zip("carryon", "1.7.0")).unwrap();
+        let mod_manager = manager(&moddb, &mods_dir).with_dry_run(true);
 
-        // Find the best compatible release instead of just using the first one
-        let release = self
-            .find_compatible_release(&mod_info.mod_data.releases)
-            .ok_or_else(|| ModManagerError::NoReleases)?;
+        let summary = mod_manager.update_mods(CliFlags::default()).await.unwrap();
+        mod_manager
+            .download_encoder_data(vec![encoder_data("hudclock", "2.0.0")], false)
+            .await
+            .unwrap();
 
-        let mod_path = vintage_mods_dir.join(release.filename.clone().unwrap());
-        let mod_bytes = self
-            .api
-            .fetch_file_stream_from_url(release.mainfile.clone().unwrap())
-            .await?;
+        assert_eq!(summary.updated.len(), 1);
+        assert_eq!(std::fs::read_dir(&mods_dir).unwrap().count(), 1);
+        assert!(old_file.exists());
+        assert!(!get_state_path(&mods_dir).exists());
+        assert!(!get_lock_path(&mods_dir).exists());
+    }
 
-        self.file_manager.save_file(&mod_path, &mod_bytes).await?;
+    #[tokio::test]
+    async fn update_keeps_mods_when_moddb_fails() {
+        let moddb = MockModDb::start().await;
+        moddb.add_failing_mod("carryon").await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let old_file = mods_dir.join("carryon_v1.7.0.zip");
+        std::fs::write(&old_file, mod_zip("carryon", "1.7.0")).unwrap();
 
-        // Log which version was downloaded
-        if let Some(version) = &release.modversion {
-            println!("Downloaded {} version {}", mod_info.mod_data.name, version);
+        manager(&moddb, &mods_dir)
+            .update_mods(CliFlags::default())
+            .await
+            .unwrap();
 
-            if let Some(current_version) = self.get_current_game_version() {
-                if !release.tags.contains(&current_version) {
-                    println!(
-                        "Note: This mod version may not be fully compatible with your game version {current_version}"
-                    );
-                }
-            }
-        }
+        assert!(old_file.exists());
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn export_round_trips_installed_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let mod_manager = manager(&moddb, &mods_dir);
+
+        mod_manager
+            .download_encoder_data(vec![encoder_data("carryon", "1.8.0")], false)
+            .await
+            .unwrap();
+        let exported = mod_manager
+            .export_string(None, ModListFormat::String, CliFlags::default(), false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            mod_manager.encoder.decode_mod_string(exported).unwrap(),
+            vec![encoder_data("carryon", "1.8.0")]
+        );
     }
 
-    /// Get the current game version tag ID from config
-    fn get_current_game_version_tag_id(&self) -> Option<i64> {
-        ConfigManager::new(false)
-            .ok()
-            .and_then(|config_manager| config_manager.get_detected_version_tag_id())
+    #[tokio::test]
+    async fn exports_choose_between_pinned_and_latest_versions() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.9.0", "1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let mod_manager = manager(&moddb, &mods_dir);
+
+        mod_manager
+            .download_encoder_data(vec![encoder_data("carryon", "1.8.0")], true)
+            .await
+            .unwrap();
+        assert!(mods_dir.join("carryon_v1.8.0.zip").exists());
+
+        let exported = mod_manager
+            .export_string(None, ModListFormat::String, CliFlags::default(), true)
+            .await
+            .unwrap();
+        assert_eq!(
+            mod_manager.encoder.decode_mod_string(exported).unwrap(),
+            vec![encoder_data("carryon", "")]
+        );
+        let raw_ids = mod_manager
+            .export_string(None, ModListFormat::Ids, CliFlags::default(), true)
+            .await
+            .unwrap();
+        assert_eq!(raw_ids, "carryon");
     }
 
-    /// Get the current game version string from config
-    fn get_current_game_version(&self) -> Option<String> {
-        ConfigManager::new(false)
-            .ok()
-            .and_then(|config_manager| config_manager.get_detected_game_version().cloned())
+    #[tokio::test]
+    async fn verify_finds_and_repairs_damaged_mods() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let mod_manager = manager(&moddb, &mods_dir);
+        mod_manager
+            .download_encoder_data(vec![encoder_data("carryon", "1.8.0")], false)
+            .await
+            .unwrap();
+        assert!(mod_manager.verify_mods(false).await.unwrap());
+
+        let path = mods_dir.join(MockModDb::filename("carryon", "1.8.0"));
+        let original = std::fs::read(&path).unwrap();
+        std::fs::write(&path, b"not a zip").unwrap();
+        assert!(!mod_manager.verify_mods(false).await.unwrap());
+
+        let dry_run = manager(&moddb, &mods_dir).with_dry_run(true);
+        assert!(!dry_run.verify_mods(true).await.unwrap());
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a zip");
+
+        assert!(mod_manager.verify_mods(true).await.unwrap());
+        assert_eq!(std::fs::read(&path).unwrap(), original);
     }
 
-    /// Check if a release is compatible with the current game version
-    fn is_release_compatible(&self, release: &Release) -> bool {
-        // Get the current game version string
-        let Some(current_version) = self.get_current_game_version() else {
-            // If no version filtering is configured, allow all releases
-            return true;
-        };
+    #[tokio::test]
+    async fn verify_compares_mods_without_lockfile_entry_with_the_state_file() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.7.0", "1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        let mod_manager = manager(&moddb, &mods_dir);
+        mod_manager
+            .download_encoder_data(vec![encoder_data("carryon", "1.8.0")], false)
+            .await
+            .unwrap();
+        let lock_path = get_lock_path(&mods_dir);
+        let mut lock = LockFile::load(&lock_path).unwrap();
+        lock.mods.clear();
+        lock.save().unwrap();
+        assert!(mod_manager.verify_mods(false).await.unwrap());
 
-        // Check if the release tags contain the current game version
-        release.tags.iter().any(|tag| tag == &current_version)
+        let path = mods_dir.join(MockModDb::filename("carryon", "1.8.0"));
+        std::fs::write(&path, mod_zip("carryon", "1.7.0")).unwrap();
+        assert!(!mod_manager.verify_mods(true).await.unwrap());
     }
 
-    /// Find the best compatible release for the current game version
-    fn find_compatible_release<'a>(&self, releases: &'a [Release]) -> Option<&'a Release> {
-        // First try to find a release compatible with current version
-        if let Some(compatible_release) = releases
-            .iter()
-            .find(|release| self.is_release_compatible(release))
-        {
-            return Some(compatible_release);
-        }
+    #[tokio::test]
+    async fn install_from_lock_rejects_checksum_mismatch() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+
+        let lock_path = get_lock_path(&mods_dir);
+        let mut lock = LockFile::load(&lock_path).unwrap();
+        lock.upsert(LockedMod {
+            modid: "carryon".to_string(),
+            version: "1.8.0".to_string(),
+            fileid: None,
+            filename: MockModDb::filename("carryon", "1.8.0"),
+            url: moddb.file_url("carryon", "1.8.0"),
+            sha256: "0".repeat(64),
+        });
+        lock.save().unwrap();
+
+        let mod_manager = manager(&moddb, &mods_dir);
+        let result = mod_manager.install_from_lock(&lock_path).await;
+        assert!(matches!(
+            result,
+            Err(ModManagerError::Lock(LockError::ChecksumMismatch { .. }))
+        ));
+
+        // Re-downloading the locked release outside of `--from-lock` is verified too
+        let result = mod_manager
+            .download_encoder_data(vec![encoder_data("carryon", "1.8.0")], false)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ModManagerError::Lock(LockError::ChecksumMismatch { .. }))
+        ));
+        assert_eq!(std::fs::read_dir(&mods_dir).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn lockfile_filenames_cannot_leave_the_mods_directory() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+
+        let lock_path = get_lock_path(&mods_dir);
+        let mut lock = LockFile::load(&lock_path).unwrap();
+        lock.upsert(LockedMod {
+            modid: "carryon".to_string(),
+            version: "1.8.0".to_string(),
+            fileid: None,
+            filename: "../carryon.zip".to_string(),
+            url: moddb.file_url("carryon", "1.8.0"),
+            sha256: "0".repeat(64),
+        });
+        lock.save().unwrap();
+
+        let result = manager(&moddb, &mods_dir)
+            .install_from_lock(&lock_path)
+            .await;
+        assert!(matches!(
+            result,
+            Err(ModManagerError::Lock(LockError::UnsafeFilename { .. }))
+        ));
+        assert!(!mods_dir.parent().unwrap().join("carryon.zip").exists());
+    }
+
+    #[tokio::test]
+    async fn apply_profile_disables_enables_and_installs() {
+        let moddb = MockModDb::start().await;
+        moddb.add_mod("carryon", &["1.8.0", "1.7.0"]).await;
+        let (_data_dir, mods_dir) = mods_dir();
+        std::fs::write(mods_dir.join("alpha.zip"), mod_zip("alpha", "1.0.0")).unwrap();
+        std::fs::write(mods_dir.join("beta.zip"), mod_zip("beta", "1.0.0")).unwrap();
+        std::fs::write(
+            mods_dir.join("gamma.zip.disabled"),
+            mod_zip("gamma", "1.0.0"),
+        )
+        .unwrap();
+
+        let profile = Profile {
+            mods: ["beta@1.0.0", "gamma@1.0.0", "carryon@1.7.0"]
+                .iter()
+                .map(|entry| {
+                    let (modid, version) = entry.split_once('@').unwrap();
+                    ProfileMod {
+                        modid: modid.to_string(),
+                        version: version.to_string(),
+                    }
+                })
+                .collect(),
+        };
+
+        manager(&moddb, &mods_dir)
+            .apply_profile(&profile)
+            .await
+            .unwrap();
 
-        // Fallback to the first release if no compatible version found
-        releases.first()
+        assert!(mods_dir.join("alpha.zip.disabled").exists());
+        assert!(mods_dir.join("beta.zip").exists());
+        assert!(mods_dir.join("gamma.zip").exists());
+        assert!(mods_dir.join("carryon_v1.7.0.zip").exists());
     }
 }
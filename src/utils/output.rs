@@ -0,0 +1,49 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `--output json`: results go to stdout as JSON, everything else to stderr.
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// How command results are written to stdout, chosen with the global `--output` flag.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A single JSON document per command, with progress and messages on stderr
+    Json,
+}
+
+/// Selects the output format for the rest of the process.
+pub fn set_output_format(format: OutputFormat) {
+    JSON_OUTPUT.store(format == OutputFormat::Json, Ordering::Relaxed);
+}
+
+/// Returns `true` when running with `--output json`.
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Prints a command's result to stdout as pretty-printed JSON.
+///
+/// # Arguments
+///
+/// * `value` - The result to print.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), serde_json::Error> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Prints a line of human-readable text: to stdout normally, to stderr with
+/// `--output json` so stdout only carries the JSON result.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::utils::json_output() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
@@ -0,0 +1,170 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use thiserror::Error;
+
+/// Executables named `vsmm-<name>` on `PATH` are exposed as the subcommand `<name>`.
+pub const PLUGIN_PREFIX: &str = "vsmm-";
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("Unknown command '{0}' (no `{PLUGIN_PREFIX}{0}` plugin found on PATH)")]
+    NotFound(String),
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to serialize plugin context: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+/// Context handed to plugins as JSON on stdin, so they don't have to rediscover the
+/// user's setup.
+#[derive(Debug, Serialize)]
+pub struct PluginContext {
+    /// Version of the mod manager invoking the plugin.
+    pub version: &'static str,
+    /// Mods directories in use, the first one receives new installs.
+    pub mods_dirs: Vec<PathBuf>,
+    /// Directory holding the mod manager's configuration.
+    pub config_dir: PathBuf,
+    /// Game version detected from the configured game path, if any.
+    pub game_version: Option<String>,
+    /// Whether `--verbose` was passed.
+    pub verbose: bool,
+}
+
+/// Finds the executable implementing the plugin `name` on `PATH`.
+///
+/// # Arguments
+///
+/// * `name` - The subcommand name, without the `vsmm-` prefix.
+///
+/// # Returns
+///
+/// The path of the first matching executable, or `None` if there is none.
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{PLUGIN_PREFIX}{name}{}", std::env::consts::EXE_SUFFIX);
+    search_path()
+        .into_iter()
+        .map(|dir| dir.join(&file_name))
+        .find(|path| is_executable(path))
+}
+
+/// Lists every plugin on `PATH` by name. Earlier `PATH` entries shadow later ones.
+pub fn discover_plugins() -> BTreeMap<String, PathBuf> {
+    let mut plugins = BTreeMap::new();
+
+    for dir in search_path() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(PLUGIN_PREFIX))
+                .and_then(|name| name.strip_suffix(std::env::consts::EXE_SUFFIX))
+            else {
+                continue;
+            };
+
+            if !name.is_empty() && is_executable(&path) {
+                plugins.entry(name.to_string()).or_insert(path);
+            }
+        }
+    }
+
+    plugins
+}
+
+/// Runs a plugin with the remaining command line arguments and the JSON context on stdin.
+///
+/// # Arguments
+///
+/// * `path` - The plugin executable.
+/// * `args` - Arguments following the subcommand name.
+/// * `context` - Context serialized to the plugin's stdin.
+///
+/// # Returns
+///
+/// The plugin's exit status.
+pub fn run_plugin(
+    path: &Path, args: &[String], context: &PluginContext,
+) -> Result<ExitStatus, PluginError> {
+    let payload = serde_json::to_vec(context)?;
+    let mut child = Command::new(path)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Plugins that don't care about the context may exit without reading it
+        match stdin.write_all(&payload) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => {}
+        }
+    }
+
+    Ok(child.wait()?)
+}
+
+fn search_path() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn plugin_receives_args_and_context() {
+        let dir = tempdir().unwrap();
+        let plugin = dir.path().join("vsmm-echo");
+        let out = dir.path().join("out.txt");
+        std::fs::write(
+            &plugin,
+            format!("#!/bin/sh\necho \"$1\" > {0}\ncat >> {0}\n", out.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&plugin, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(dir.path().join("vsmm-not-executable"), "").unwrap();
+
+        let context = PluginContext {
+            version: "1.0.0",
+            mods_dirs: vec![PathBuf::from("/mods")],
+            config_dir: PathBuf::from("/config"),
+            game_version: None,
+            verbose: false,
+        };
+        let status = run_plugin(&plugin, &["hello".to_string()], &context).unwrap();
+        assert!(status.success());
+
+        let output = std::fs::read_to_string(&out).unwrap();
+        let (arg, json) = output.split_once('\n').unwrap();
+        assert_eq!(arg, "hello");
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(json["mods_dirs"][0], "/mods");
+
+        assert!(is_executable(&plugin));
+        assert!(!is_executable(&dir.path().join("vsmm-not-executable")));
+    }
+}
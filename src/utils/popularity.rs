@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How long a cached download count is used before it is fetched again.
+const POPULARITY_TTL_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PopularityEntry {
+    downloads: u32,
+    /// RFC 3339 timestamp of when the count was fetched.
+    fetched_at: String,
+}
+
+/// Cached ModDB download counts, used to sort local listings by popularity.
+///
+/// Stale counts are still returned by [`PopularityCache::downloads`], so listings keep
+/// working offline; callers refresh entries that aren't [`PopularityCache::is_fresh`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PopularityCache {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Download counts keyed by lowercase modid.
+    entries: BTreeMap<String, PopularityEntry>,
+}
+
+impl PopularityCache {
+    /// Loads the cache at `path`. A missing or unreadable cache is treated as empty.
+    pub fn load(path: &Path) -> Self {
+        let mut cache = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<PopularityCache>(&contents).ok())
+            .unwrap_or_default();
+        cache.path = path.to_path_buf();
+        cache
+    }
+
+    /// Writes the cache back to the file it was loaded from.
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// The last known download count of a mod, however old.
+    pub fn downloads(&self, modid: &str) -> Option<u32> {
+        self.entries
+            .get(&modid.to_lowercase())
+            .map(|entry| entry.downloads)
+    }
+
+    /// Whether the cached count of a mod is recent enough to skip refreshing it.
+    pub fn is_fresh(&self, modid: &str) -> bool {
+        self.entries
+            .get(&modid.to_lowercase())
+            .and_then(|entry| chrono::DateTime::parse_from_rfc3339(&entry.fetched_at).ok())
+            .is_some_and(|fetched_at| {
+                chrono::Local::now().signed_duration_since(fetched_at)
+                    < chrono::Duration::hours(POPULARITY_TTL_HOURS)
+            })
+    }
+
+    /// Records a freshly fetched download count.
+    pub fn insert(&mut self, modid: &str, downloads: u32) {
+        self.entries.insert(
+            modid.to_lowercase(),
+            PopularityEntry {
+                downloads,
+                fetched_at: chrono::Local::now().to_rfc3339(),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn stale_counts_are_kept_but_not_fresh() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("popularity.json");
+        fs::write(
+            &path,
+            r#"{"entries": {"carryon": {"downloads": 42, "fetched_at": "2020-01-01T00:00:00+00:00"}}}"#,
+        )
+        .unwrap();
+
+        let mut cache = PopularityCache::load(&path);
+        assert_eq!(cache.downloads("CarryOn"), Some(42));
+        assert!(!cache.is_fresh("carryon"));
+
+        cache.insert("carryon", 50);
+        cache.save().unwrap();
+
+        let reloaded = PopularityCache::load(&path);
+        assert_eq!(reloaded.downloads("carryon"), Some(50));
+        assert!(reloaded.is_fresh("carryon"));
+    }
+}
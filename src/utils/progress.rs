@@ -1,4 +1,18 @@
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::sync::OnceLock;
+
+/// All progress bars of the process, drawn together so concurrent downloads don't
+/// overwrite each other's lines.
+fn progress_group() -> &'static MultiProgress {
+    static GROUP: OnceLock<MultiProgress> = OnceLock::new();
+    GROUP.get_or_init(MultiProgress::new)
+}
+
+/// Runs `f` with the progress bars cleared from the terminal and draws them again after,
+/// so output printed meanwhile doesn't end up inside a bar.
+pub(crate) fn suspend_progress<R>(f: impl FnOnce() -> R) -> R {
+    progress_group().suspend(f)
+}
 
 /// Wrapper for the ProgressBar struct
 pub struct ProgressBarWrapper {
@@ -18,7 +32,7 @@ impl ProgressBarWrapper {
     /// # Examples
     ///
     /// ```
-    /// use utils::progress::ProgressBarWrapper;
+    /// use vintage_mod_manager::utils::ProgressBarWrapper;
     ///
     /// let progress_bar = ProgressBarWrapper::new(100);
     /// for _ in 0..100 {
@@ -26,8 +40,8 @@ impl ProgressBarWrapper {
     /// }
     /// progress_bar.finish();
     /// ```
-    pub(crate) fn new(len: u64) -> Self {
-        let progress_bar = ProgressBar::new(len);
+    pub fn new(len: u64) -> Self {
+        let progress_bar = progress_group().add(ProgressBar::new(len));
         let progress_style = ProgressStyle::default_bar()
             .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos:>7}/{len:7} ({msg})")
             .expect("Failed to create progress style")
@@ -42,12 +56,12 @@ impl ProgressBarWrapper {
     }
 
     /// increments the progress bar by `n`
-    pub(crate) fn inc(&self, n: u64) {
+    pub fn inc(&self, n: u64) {
         self.progress_bar.inc(n);
     }
 
     /// finishes the progress bar
-    fn finish(&self) {
+    pub fn finish(&self) {
         self.progress_bar.finish();
     }
 
@@ -86,3 +100,58 @@ impl ProgressBarWrapper {
         self.progress_bar.set_prefix(prefix.to_string());
     }
 }
+
+/// Byte progress of a single download, with transfer speed and the remaining time.
+///
+/// The bar is removed once the download is dropped, whether it completed or failed.
+pub struct DownloadProgress {
+    progress_bar: ProgressBar,
+}
+
+impl DownloadProgress {
+    /// Shows a bar for a download.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The file being downloaded.
+    /// * `received` - Bytes already on disk from an earlier attempt.
+    /// * `total` - The size of the whole file, if the server reported one. Without it a
+    ///   spinner shows the bytes received so far.
+    pub(crate) fn new(name: &str, received: u64, total: Option<u64>) -> Self {
+        let (progress_bar, template) = match total {
+            Some(total) => (
+                ProgressBar::new(total),
+                "{msg:24!} [{bar:30.cyan/blue}] {bytes}/{total_bytes} {binary_bytes_per_sec} ETA {eta}",
+            ),
+            None => (
+                ProgressBar::no_length(),
+                "{spinner:.green} {msg:24!} {bytes} {binary_bytes_per_sec}",
+            ),
+        };
+        progress_bar.set_style(
+            ProgressStyle::default_bar()
+                .template(template)
+                .expect("Failed to create progress style")
+                .progress_chars("#>-"),
+        );
+        progress_bar.set_message(name.to_string());
+        progress_bar.set_position(received);
+        // Speed and ETA only count what this attempt transfers
+        progress_bar.reset_eta();
+
+        Self {
+            progress_bar: progress_group().add(progress_bar),
+        }
+    }
+
+    /// Records `n` more bytes received.
+    pub(crate) fn inc(&self, n: u64) {
+        self.progress_bar.inc(n);
+    }
+}
+
+impl Drop for DownloadProgress {
+    fn drop(&mut self) {
+        self.progress_bar.finish_and_clear();
+    }
+}
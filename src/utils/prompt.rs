@@ -0,0 +1,285 @@
+use crate::say;
+use dialoguer::Confirm;
+use dialoguer::theme::ColorfulTheme;
+#[cfg(test)]
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::Arc;
+#[cfg(test)]
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `--yes`: confirmations are accepted without asking.
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+
+/// Source of answers for interactive questions.
+///
+/// The default implementation asks the user through dialoguer; tests swap in a
+/// [`ScriptedPrompt`] so interactive flows run without a TTY.
+pub trait Prompt: Send + Sync {
+    /// Asks a yes/no question.
+    fn confirm(&self, message: &str) -> bool;
+
+    /// Lets the user pick one of `options`, or `None` if they cancel.
+    fn select(&self, message: &str, options: &[String]) -> Option<usize>;
+
+    /// Asks for a line of text.
+    fn input(&self, message: &str) -> String;
+
+    /// Lets the user pick any number of `options`.
+    fn multi_select(&self, message: &str, options: &[String]) -> Vec<usize>;
+
+    /// Lets the user deselect any of `options`, which start out picked.
+    fn multi_select_checked(&self, message: &str, options: &[String]) -> Vec<usize>;
+
+    /// Returns `false` if nobody is there to answer, so choices that have no sensible
+    /// default should fail instead of being asked.
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    /// Returns `false` if an interactive prompt has nobody to ask right now, e.g. because
+    /// stdin is not a terminal.
+    fn can_ask(&self) -> bool {
+        std::io::stdin().is_terminal()
+    }
+}
+
+/// Makes [`default_prompt`] accept every confirmation without asking, for `--yes`.
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// The prompt questions are asked through: the terminal, or [`AssumeYesPrompt`] when
+/// running with `--yes`.
+pub fn default_prompt() -> Arc<dyn Prompt> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        Arc::new(AssumeYesPrompt)
+    } else {
+        Arc::new(DialoguerPrompt)
+    }
+}
+
+/// Converts displayable options into the labels shown by a [`Prompt`].
+pub fn labels<T: Display>(options: &[T]) -> Vec<String> {
+    options.iter().map(ToString::to_string).collect()
+}
+
+/// Interactive prompts on the terminal.
+pub struct DialoguerPrompt;
+
+impl Prompt for DialoguerPrompt {
+    fn confirm(&self, message: &str) -> bool {
+        Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(message)
+            .interact()
+            .unwrap()
+    }
+
+    fn select(&self, message: &str, options: &[String]) -> Option<usize> {
+        dialoguer::FuzzySelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(message)
+            .items(options)
+            .default(0)
+            .interact_opt()
+            .unwrap_or(None)
+    }
+
+    fn input(&self, message: &str) -> String {
+        dialoguer::Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(message)
+            .interact()
+            .unwrap()
+    }
+
+    fn multi_select(&self, message: &str, options: &[String]) -> Vec<usize> {
+        dialoguer::MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(message)
+            .items(options)
+            .interact()
+            .unwrap()
+    }
+
+    fn multi_select_checked(&self, message: &str, options: &[String]) -> Vec<usize> {
+        dialoguer::MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt(message)
+            .items(options)
+            .defaults(&vec![true; options.len()])
+            .interact()
+            .unwrap()
+    }
+}
+
+/// Answers questions without a user, for CI and cron jobs.
+///
+/// Confirmations are accepted and selections pick the first option, which is the
+/// default the terminal prompt highlights. Pre-checked multi-selections keep every option.
+/// Free-form input and other multi-selections have no default and get empty answers;
+/// callers check [`Prompt::is_interactive`] first.
+pub struct AssumeYesPrompt;
+
+impl Prompt for AssumeYesPrompt {
+    fn confirm(&self, message: &str) -> bool {
+        say!("{message} yes (--yes)");
+        true
+    }
+
+    fn select(&self, message: &str, options: &[String]) -> Option<usize> {
+        let first = options.first()?;
+        say!("{message}: {first} (--yes)");
+        Some(0)
+    }
+
+    fn input(&self, _message: &str) -> String {
+        String::new()
+    }
+
+    fn multi_select(&self, _message: &str, _options: &[String]) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn multi_select_checked(&self, message: &str, options: &[String]) -> Vec<usize> {
+        say!("{message}: all {} (--yes)", options.len());
+        (0..options.len()).collect()
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// A pre-recorded answer for a [`ScriptedPrompt`].
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptedAnswer {
+    Confirm(bool),
+    Select(Option<usize>),
+    Input(String),
+    MultiSelect(Vec<usize>),
+}
+
+/// A [`Prompt`] that replays scripted answers in order and records the questions asked.
+///
+/// Panics if a question is asked that doesn't match the next scripted answer, so tests fail
+/// loudly when an interactive flow changes.
+#[cfg(test)]
+#[derive(Default)]
+pub struct ScriptedPrompt {
+    answers: Mutex<VecDeque<ScriptedAnswer>>,
+    asked: Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+impl ScriptedPrompt {
+    pub fn new(answers: impl IntoIterator<Item = ScriptedAnswer>) -> Self {
+        Self {
+            answers: Mutex::new(answers.into_iter().collect()),
+            asked: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The messages of all questions asked so far, in order.
+    pub fn asked(&self) -> Vec<String> {
+        self.asked.lock().unwrap().clone()
+    }
+
+    /// Number of scripted answers that haven't been used yet.
+    pub fn remaining(&self) -> usize {
+        self.answers.lock().unwrap().len()
+    }
+
+    fn next(&self, message: &str) -> ScriptedAnswer {
+        self.asked.lock().unwrap().push(message.to_string());
+        self.answers
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| panic!("No scripted answer left for prompt: {message}"))
+    }
+}
+
+#[cfg(test)]
+impl Prompt for ScriptedPrompt {
+    fn confirm(&self, message: &str) -> bool {
+        match self.next(message) {
+            ScriptedAnswer::Confirm(answer) => answer,
+            other => panic!("Expected a confirm answer for '{message}', got {other:?}"),
+        }
+    }
+
+    fn select(&self, message: &str, options: &[String]) -> Option<usize> {
+        match self.next(message) {
+            ScriptedAnswer::Select(answer) => {
+                assert!(answer.is_none_or(|index| index < options.len()));
+                answer
+            }
+            other => panic!("Expected a select answer for '{message}', got {other:?}"),
+        }
+    }
+
+    fn input(&self, message: &str) -> String {
+        match self.next(message) {
+            ScriptedAnswer::Input(answer) => answer,
+            other => panic!("Expected an input answer for '{message}', got {other:?}"),
+        }
+    }
+
+    fn multi_select(&self, message: &str, options: &[String]) -> Vec<usize> {
+        match self.next(message) {
+            ScriptedAnswer::MultiSelect(answer) => {
+                assert!(answer.iter().all(|index| *index < options.len()));
+                answer
+            }
+            other => panic!("Expected a multi-select answer for '{message}', got {other:?}"),
+        }
+    }
+
+    fn can_ask(&self) -> bool {
+        true
+    }
+
+    fn multi_select_checked(&self, message: &str, options: &[String]) -> Vec<usize> {
+        self.multi_select(message, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_prompt_replays_answers_in_order() {
+        let prompt = ScriptedPrompt::new([
+            ScriptedAnswer::Confirm(true),
+            ScriptedAnswer::Input("carry".to_string()),
+            ScriptedAnswer::Select(None),
+            ScriptedAnswer::MultiSelect(vec![1]),
+        ]);
+        let options = labels(&["a", "b"]);
+
+        assert!(prompt.confirm("Continue?"));
+        assert_eq!(prompt.input("Filter"), "carry");
+        assert_eq!(prompt.select("Choose", &options), None);
+        assert_eq!(prompt.multi_select("Pick", &options), vec![1]);
+        assert_eq!(prompt.asked(), ["Continue?", "Filter", "Choose", "Pick"]);
+        assert_eq!(prompt.remaining(), 0);
+    }
+
+    #[test]
+    fn assume_yes_prompt_accepts_and_picks_the_default() {
+        let options = labels(&["a", "b"]);
+
+        assert!(AssumeYesPrompt.confirm("Continue?"));
+        assert_eq!(AssumeYesPrompt.select("Choose", &options), Some(0));
+        assert_eq!(AssumeYesPrompt.select("Choose", &[]), None);
+        assert!(!AssumeYesPrompt.is_interactive());
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected a confirm answer")]
+    fn scripted_prompt_rejects_unexpected_questions() {
+        let prompt = ScriptedPrompt::new([ScriptedAnswer::Input("x".to_string())]);
+        prompt.confirm("Continue?");
+    }
+}
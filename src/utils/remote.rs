@@ -0,0 +1,364 @@
+use crate::config::Remote;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    #[error("Failed to run sftp: {0}")]
+    Io(#[from] io::Error),
+    #[error("sftp failed: {0}")]
+    Sftp(String),
+}
+
+/// A mod archive compared by name and size when synchronizing mods directories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncFile {
+    pub name: String,
+    pub size: u64,
+}
+
+/// The files to copy and delete to make one mods directory match another.
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncPlan {
+    /// Files missing from the target, or of a different size there.
+    pub copy: Vec<String>,
+    /// Files only the target has, deleted when pruning.
+    pub delete: Vec<String>,
+}
+
+impl SyncPlan {
+    /// Plans making `target` match `source`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prune` - Also delete the files of `target` that `source` doesn't have.
+    pub fn new(source: &[SyncFile], target: &[SyncFile], prune: bool) -> Self {
+        let copy = source
+            .iter()
+            .filter(|file| !target.contains(file))
+            .map(|file| file.name.clone())
+            .collect();
+        let delete = target
+            .iter()
+            .filter(|file| prune && !source.iter().any(|other| other.name == file.name))
+            .map(|file| file.name.clone())
+            .collect();
+        Self { copy, delete }
+    }
+
+    /// Returns `true` if the directories already match.
+    pub fn is_empty(&self) -> bool {
+        self.copy.is_empty() && self.delete.is_empty()
+    }
+}
+
+fn is_mod_archive(name: &str) -> bool {
+    name.to_lowercase().ends_with(".zip")
+}
+
+/// Lists the mod archives in a local mods directory. Unpacked mod folders are not
+/// synchronized.
+pub fn local_mod_files(dir: &Path) -> Result<Vec<SyncFile>, io::Error> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Follows symlinks, which linked install modes leave in the mods directory
+        let metadata = std::fs::metadata(entry.path())?;
+        if metadata.is_file() && is_mod_archive(&name) {
+            files.push(SyncFile {
+                name,
+                size: metadata.len(),
+            });
+        }
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(files)
+}
+
+/// The mods directory of a game server, which mod archives are synchronized with.
+///
+/// [`SftpClient`] reaches servers over SSH. Other implementations can keep the files
+/// elsewhere, such as in memory for tests.
+pub trait RemoteStore {
+    /// Lists the mod archives in the remote mods directory.
+    fn list(&self) -> Result<Vec<SyncFile>, RemoteError>;
+
+    /// Downloads remote mod archives into `dir`.
+    fn download(&self, names: &[String], dir: &Path) -> Result<(), RemoteError>;
+
+    /// Uploads mod archives from `dir` into the remote mods directory.
+    fn upload(&self, dir: &Path, names: &[String]) -> Result<(), RemoteError>;
+
+    /// Deletes mod archives from the remote mods directory.
+    fn remove(&self, names: &[String]) -> Result<(), RemoteError>;
+
+    /// Makes `dir` an exact copy of the mod archives in the remote mods directory.
+    ///
+    /// # Returns
+    ///
+    /// The files that were downloaded and deleted.
+    fn mirror_into(&self, dir: &Path) -> Result<SyncPlan, RemoteError> {
+        let plan = SyncPlan::new(&self.list()?, &local_mod_files(dir)?, true);
+        self.download(&plan.copy, dir)?;
+        for name in &plan.delete {
+            std::fs::remove_file(dir.join(name))?;
+        }
+        Ok(plan)
+    }
+}
+
+/// Transfers mod archives to and from a remote through OpenSSH's `sftp` in batch mode, so
+/// logins use the same keys, agent and `~/.ssh/config` as `ssh`.
+pub struct SftpClient<'a> {
+    remote: &'a Remote,
+}
+
+impl<'a> SftpClient<'a> {
+    pub fn new(remote: &'a Remote) -> Self {
+        Self { remote }
+    }
+
+    fn remote_path(&self, name: &str) -> String {
+        format!("{}/{name}", self.remote.path.trim_end_matches('/'))
+    }
+
+    /// Runs `sftp` with `commands` as its batch file. Batch mode stops at the first
+    /// failing command.
+    ///
+    /// # Returns
+    ///
+    /// What `sftp` printed to stdout.
+    fn run(&self, commands: &[String]) -> Result<String, RemoteError> {
+        if commands.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut command = Command::new("sftp");
+        command.args(["-q", "-b", "-"]);
+        if let Some(port) = self.remote.port {
+            command.args(["-P", &port.to_string()]);
+        }
+        if let Some(identity_file) = &self.remote.identity_file {
+            command.arg("-i").arg(identity_file);
+        }
+        let mut child = command
+            .arg(self.remote.destination())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(commands.join("\n").as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(RemoteError::Sftp(if stderr.is_empty() {
+                output.status.to_string()
+            } else {
+                stderr
+            }));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl RemoteStore for SftpClient<'_> {
+    fn list(&self) -> Result<Vec<SyncFile>, RemoteError> {
+        let output = self.run(&[format!("ls -ln {}", quote(&self.remote.path))])?;
+        Ok(parse_listing(&output))
+    }
+
+    fn download(&self, names: &[String], dir: &Path) -> Result<(), RemoteError> {
+        let commands: Vec<String> = names
+            .iter()
+            .map(|name| {
+                let local = dir.join(name);
+                format!(
+                    "get {} {}",
+                    quote(&self.remote_path(name)),
+                    quote(&local.to_string_lossy())
+                )
+            })
+            .collect();
+        self.run(&commands).map(drop)
+    }
+
+    fn upload(&self, dir: &Path, names: &[String]) -> Result<(), RemoteError> {
+        let commands: Vec<String> = names
+            .iter()
+            .map(|name| {
+                let local = dir.join(name);
+                format!(
+                    "put {} {}",
+                    quote(&local.to_string_lossy()),
+                    quote(&self.remote_path(name))
+                )
+            })
+            .collect();
+        self.run(&commands).map(drop)
+    }
+
+    fn remove(&self, names: &[String]) -> Result<(), RemoteError> {
+        let commands: Vec<String> = names
+            .iter()
+            .map(|name| format!("rm {}", quote(&self.remote_path(name))))
+            .collect();
+        self.run(&commands).map(drop)
+    }
+}
+
+/// A [`RemoteStore`] keeping the server's mod archives in memory, for tests.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct MemoryRemote {
+    files: std::sync::Mutex<std::collections::BTreeMap<String, Vec<u8>>>,
+}
+
+#[cfg(test)]
+impl MemoryRemote {
+    /// Puts a mod archive on the server.
+    pub(crate) fn with_file(self, name: &str, bytes: Vec<u8>) -> Self {
+        self.files.lock().unwrap().insert(name.to_string(), bytes);
+        self
+    }
+
+    /// The names of the archives on the server, sorted.
+    pub(crate) fn names(&self) -> Vec<String> {
+        self.files.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+impl RemoteStore for MemoryRemote {
+    fn list(&self) -> Result<Vec<SyncFile>, RemoteError> {
+        let files = self.files.lock().unwrap();
+        Ok(files
+            .iter()
+            .map(|(name, bytes)| SyncFile {
+                name: name.clone(),
+                size: bytes.len() as u64,
+            })
+            .collect())
+    }
+
+    fn download(&self, names: &[String], dir: &Path) -> Result<(), RemoteError> {
+        let files = self.files.lock().unwrap();
+        for name in names {
+            let bytes = files
+                .get(name)
+                .ok_or_else(|| RemoteError::Sftp(format!("{name} not found")))?;
+            std::fs::write(dir.join(name), bytes)?;
+        }
+        Ok(())
+    }
+
+    fn upload(&self, dir: &Path, names: &[String]) -> Result<(), RemoteError> {
+        let mut files = self.files.lock().unwrap();
+        for name in names {
+            files.insert(name.clone(), std::fs::read(dir.join(name))?);
+        }
+        Ok(())
+    }
+
+    fn remove(&self, names: &[String]) -> Result<(), RemoteError> {
+        let mut files = self.files.lock().unwrap();
+        for name in names {
+            files.remove(name);
+        }
+        Ok(())
+    }
+}
+
+/// Quotes an argument of an sftp batch command. Glob characters are escaped too, as
+/// `get` and `rm` expand them.
+fn quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if matches!(c, '"' | '\\' | '*' | '?' | '[' | ']') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Reads the mod archives out of the output of `ls -ln`, skipping the commands `sftp`
+/// echoes in batch mode.
+fn parse_listing(output: &str) -> Vec<SyncFile> {
+    let mut files: Vec<SyncFile> = output
+        .lines()
+        .filter(|line| line.starts_with('-'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let size = fields.get(4)?.parse().ok()?;
+            let path = fields.get(8..)?.join(" ");
+            let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+            is_mod_archive(&name).then_some(SyncFile { name, size })
+        })
+        .collect();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64) -> SyncFile {
+        SyncFile {
+            name: name.to_string(),
+            size,
+        }
+    }
+
+    #[test]
+    fn parses_sftp_listings() {
+        let output = "\
+sftp> ls -ln \"/srv/vs/Mods\"
+-rw-r--r--    1 1000     1000        52311 Jan  3 12:00 /srv/vs/Mods/carryon_v1.8.0.zip
+drwxr-xr-x    2 1000     1000         4096 Jan  3 12:00 /srv/vs/Mods/unpacked
+-rw-r--r--    1 1000     1000          120 Jan  3 12:00 /srv/vs/Mods/notes.txt
+-rw-r--r--    1 1000     1000         9000 Feb 10  2024 /srv/vs/Mods/HUD Clock v2.zip";
+
+        assert_eq!(
+            parse_listing(output),
+            vec![
+                file("HUD Clock v2.zip", 9000),
+                file("carryon_v1.8.0.zip", 52311)
+            ]
+        );
+    }
+
+    #[test]
+    fn plans_copy_changed_files_and_prune_extra_ones() {
+        let source = [
+            file("carryon_v1.8.0.zip", 10),
+            file("hudclock_v2.0.0.zip", 5),
+        ];
+        let target = [
+            file("carryon_v1.7.0.zip", 9),
+            file("hudclock_v2.0.0.zip", 4),
+        ];
+
+        let plan = SyncPlan::new(&source, &target, true);
+        assert_eq!(plan.copy, ["carryon_v1.8.0.zip", "hudclock_v2.0.0.zip"]);
+        assert_eq!(plan.delete, ["carryon_v1.7.0.zip"]);
+
+        assert!(SyncPlan::new(&source, &target, false).delete.is_empty());
+        assert!(SyncPlan::new(&source, &source, true).is_empty());
+    }
+
+    #[test]
+    fn quotes_batch_arguments() {
+        assert_eq!(
+            quote("/srv/my mods/a\"b*.zip"),
+            r#""/srv/my mods/a\"b\*.zip""#
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crate::api::ClientError;
+use crate::utils::ModManagerError;
+use crate::utils::config_manager::ConfigError;
+use crate::utils::encoding::EncodingError;
+use crate::utils::files::FileError;
+use crate::utils::remote::RemoteError;
+use colored::Colorize;
+use std::error::Error;
+use std::io::ErrorKind;
+
+/// Suggests what to do about an error, for errors whose message alone doesn't say.
+///
+/// # Returns
+///
+/// A sentence naming the command or setting that fixes the problem, or `None` if there
+/// is nothing useful to suggest.
+pub fn hint(error: &ModManagerError) -> Option<String> {
+    let hint = match error {
+        ModManagerError::NoReleases { name, game_version } => {
+            let game_version = game_version.as_deref().unwrap_or("your game version");
+            return Some(format!(
+                "No release of {name} is marked compatible with {game_version}. Try another \
+                 version for this run with `--game-version <version>`, check yours with \
+                 `config show`, change it with `config set-game-version`, or pick a release \
+                 with `rollback`."
+            ));
+        }
+        ModManagerError::InvalidModPath(_) | ModManagerError::MissingModInfo => {
+            "Mods must be .zip archives with a modinfo.json; reinstall the mod with \
+             `download` if the file is damaged."
+        }
+        ModManagerError::Request(_) => return Some(network_hint().to_string()),
+        ModManagerError::ApiError(error) | ModManagerError::Config(ConfigError::Client(error)) => {
+            return client_hint(error);
+        }
+        ModManagerError::File(FileError::InvalidMod(_)) => {
+            "Remove or replace the file; `list` shows which mods could be read."
+        }
+        ModManagerError::File(FileError::ChecksumMismatch { .. }) => {
+            "The file differs from the one recorded when it was installed or locked; \
+             download it again with `download`."
+        }
+        ModManagerError::Config(ConfigError::InvalidGamePath(_)) => {
+            "Point it at your Vintage Story installation with `config set-path <dir>`."
+        }
+        ModManagerError::Config(ConfigError::NotFound(_)) => {
+            "Create a config file with `config init`."
+        }
+        ModManagerError::Config(ConfigError::Toml(_)) => {
+            "Fix the config file by hand, or start over with `config reset`."
+        }
+        ModManagerError::Encoding(EncodingError::UnsupportedVersion { .. }) => return None,
+        ModManagerError::Encoding(EncodingError::ShareLink(_)) => {
+            "Share links start with `vintagemods://import?mods=` and are made by `export --url`."
+        }
+        ModManagerError::Encoding(EncodingError::QrCode(_)) => {
+            "Export fewer mods with --include or --exclude, or share the text without --qr."
+        }
+        ModManagerError::Encoding(_) => {
+            "Make sure the whole mod string was copied, without line breaks or spaces."
+        }
+        ModManagerError::NotInstalled(_) => "See the installed mods with `list`.",
+        ModManagerError::Remote(RemoteError::Io(_)) => {
+            "Remote commands run OpenSSH's `sftp`; install the OpenSSH client."
+        }
+        ModManagerError::Remote(RemoteError::Sftp(_)) => {
+            "Check that `sftp` reaches the server without asking for a password, using a key \
+             (`remote add --identity`) or ssh-agent, and that the remote path exists."
+        }
+        ModManagerError::Io(error) if error.kind() == ErrorKind::PermissionDenied => {
+            "Check that you can write to the mods directory, or choose another one with \
+             `config set-mods-path`."
+        }
+        _ => return None,
+    };
+    Some(hint.to_string())
+}
+
+fn client_hint(error: &ClientError) -> Option<String> {
+    let hint = match error {
+        ClientError::Request(_) | ClientError::IncompleteDownload { .. } => network_hint(),
+        ClientError::ApiError { status: 429 } => {
+            "ModDB is rate limiting requests; wait a minute and try again."
+        }
+        ClientError::ApiError { status } if *status >= 500 => {
+            "ModDB is having trouble; try again later, or add a mirror under [network] in \
+             the config file."
+        }
+        ClientError::ModNotFound(_) => {
+            "Check the mod ID on the mod's ModDB page, or find the mod with `browse`."
+        }
+        ClientError::UnknownName { kind: "tag", .. } => {
+            "See the available tags with `browse --tags`."
+        }
+        _ => return None,
+    };
+    Some(hint.to_string())
+}
+
+fn network_hint() -> &'static str {
+    "Check your internet connection. Behind a proxy, set `proxy` under [network] in the \
+     config file or the HTTPS_PROXY environment variable."
+}
+
+/// Prints an error that ended a command, with a hint on how to fix it.
+///
+/// # Arguments
+///
+/// * `error` - The error the command failed with.
+/// * `verbose` - Also print the chain of underlying errors.
+pub fn report_error(error: &ModManagerError, verbose: bool) {
+    eprintln!("{} {error}", "Error:".red().bold());
+    if let Some(hint) = hint(error) {
+        eprintln!("{} {hint}", "Hint:".yellow().bold());
+    }
+    if !verbose {
+        return;
+    }
+
+    let mut source = error.source();
+    while let Some(cause) = source {
+        eprintln!("  caused by: {cause}");
+        source = cause.source();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_point_to_the_fixing_command() {
+        let no_releases = hint(&ModManagerError::NoReleases {
+            name: "Carry On".to_string(),
+            game_version: Some("1.21.0".to_string()),
+        })
+        .unwrap();
+        assert!(no_releases.contains("Carry On"));
+        assert!(no_releases.contains("1.21.0"));
+        assert!(no_releases.contains("--game-version"));
+        assert!(no_releases.contains("config set-game-version"));
+
+        let game_path = hint(&ModManagerError::Config(ConfigError::InvalidGamePath(
+            "/nowhere".into(),
+        )))
+        .unwrap();
+        assert!(game_path.contains("config set-path"));
+
+        let rate_limited = hint(&ModManagerError::ApiError(ClientError::ApiError {
+            status: 429,
+        }))
+        .unwrap();
+        assert!(rate_limited.contains("rate limiting"));
+
+        assert!(hint(&ModManagerError::InstallFailed(2)).is_none());
+    }
+}
@@ -0,0 +1,282 @@
+use crate::config::RetentionPolicy;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Summary of what a prune run removed (or would remove in a dry run).
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    /// Files that were removed.
+    pub removed_files: Vec<PathBuf>,
+    /// Directories that were left empty and removed.
+    pub removed_dirs: Vec<PathBuf>,
+    /// Total size of the removed files in bytes.
+    pub freed_bytes: u64,
+}
+
+impl PruneReport {
+    /// Returns `true` if nothing was removed.
+    pub fn is_empty(&self) -> bool {
+        self.removed_files.is_empty() && self.removed_dirs.is_empty()
+    }
+}
+
+/// A file considered for pruning.
+struct Entry {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Applies a `RetentionPolicy` to every file below `dir`.
+///
+/// Rules are applied in order: entries older than `max_age_days` are removed first, then
+/// all but the newest `max_count_per_mod` entries of each mod, then the oldest remaining
+/// entries until the directory is below `max_size_mb`. Directories left empty afterwards
+/// are removed as well.
+///
+/// # Arguments
+///
+/// * `dir` - The directory to prune. A missing directory is not an error.
+/// * `policy` - The limits to enforce.
+/// * `dry_run` - Only report what would be removed.
+///
+/// # Returns
+///
+/// A `PruneReport` describing the removed files and directories.
+pub fn prune_dir(
+    dir: &Path, policy: &RetentionPolicy, dry_run: bool,
+) -> Result<PruneReport, std::io::Error> {
+    let mut report = PruneReport::default();
+    if !dir.is_dir() {
+        return Ok(report);
+    }
+
+    let mut entries = Vec::new();
+    collect_files(dir, &mut entries)?;
+    // Newest first, so the entries we keep are always at the front.
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.modified));
+
+    let mut doomed = vec![false; entries.len()];
+
+    if let Some(max_age_days) = policy.max_age_days {
+        let max_age = Duration::from_secs(max_age_days * 24 * 60 * 60);
+        let now = SystemTime::now();
+        for (idx, entry) in entries.iter().enumerate() {
+            if now.duration_since(entry.modified).unwrap_or_default() > max_age {
+                doomed[idx] = true;
+            }
+        }
+    }
+
+    if let Some(max_count) = policy.max_count_per_mod {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for (idx, entry) in entries.iter().enumerate() {
+            if doomed[idx] {
+                continue;
+            }
+            let count = seen.entry(mod_key(&entry.path)).or_default();
+            *count += 1;
+            if *count > max_count {
+                doomed[idx] = true;
+            }
+        }
+    }
+
+    if let Some(max_size_mb) = policy.max_size_mb {
+        let max_size = max_size_mb * 1024 * 1024;
+        let mut total: u64 = entries
+            .iter()
+            .zip(&doomed)
+            .filter(|(_, doomed)| !**doomed)
+            .map(|(entry, _)| entry.size)
+            .sum();
+        for (idx, entry) in entries.iter().enumerate().rev() {
+            if total <= max_size {
+                break;
+            }
+            if !doomed[idx] {
+                doomed[idx] = true;
+                total -= entry.size;
+            }
+        }
+    }
+
+    for (entry, doomed) in entries.into_iter().zip(doomed) {
+        if !doomed {
+            continue;
+        }
+        if !dry_run {
+            fs::remove_file(&entry.path)?;
+        }
+        report.freed_bytes += entry.size;
+        report.removed_files.push(entry.path);
+    }
+
+    if !dry_run {
+        remove_empty_dirs(dir, dir, &mut report.removed_dirs)?;
+    }
+
+    Ok(report)
+}
+
+/// Recursively collects all files below `dir`.
+fn collect_files(dir: &Path, entries: &mut Vec<Entry>) -> Result<(), std::io::Error> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            collect_files(&entry.path(), entries)?;
+        } else {
+            entries.push(Entry {
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Removes empty directories below `root`, leaving `root` itself in place.
+fn remove_empty_dirs(
+    root: &Path, dir: &Path, removed: &mut Vec<PathBuf>,
+) -> Result<bool, std::io::Error> {
+    let mut is_empty = true;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && remove_empty_dirs(root, &path, removed)? {
+            continue;
+        }
+        is_empty = false;
+    }
+
+    if is_empty && dir != root {
+        fs::remove_dir(dir)?;
+        removed.push(dir.to_path_buf());
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Derives the mod a file belongs to from its name by stripping the version suffix.
+///
+/// `carryon_v1.8.0.zip`, `CarryOn-1.9.0.zip` and `carryon 2.0.zip` all map to `carryon`.
+fn mod_key(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let bytes = stem.as_bytes();
+    for (idx, window) in bytes.windows(2).enumerate() {
+        let is_separator = matches!(window[0], b'_' | b'-' | b' ');
+        let starts_version = window[1].is_ascii_digit()
+            || (window[1] == b'v' && bytes.get(idx + 2).is_some_and(u8::is_ascii_digit));
+        if is_separator && starts_version {
+            return stem[..idx].to_string();
+        }
+    }
+    stem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    fn write_file(path: &Path, size: usize, age_days: u64) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![0u8; size]).unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn mod_key_strips_versions() {
+        assert_eq!(mod_key(Path::new("carryon_v1.8.0.zip")), "carryon");
+        assert_eq!(mod_key(Path::new("CarryOn-1.9.0.zip")), "carryon");
+        assert_eq!(mod_key(Path::new("vanilla-plus.zip")), "vanilla-plus");
+    }
+
+    #[test]
+    fn prune_removes_old_files_and_empty_dirs() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("2020-01-01").join("foo_v1.0.0.zip");
+        let new = dir.path().join("2099-01-01").join("foo_v1.1.0.zip");
+        write_file(&old, 10, 40);
+        write_file(&new, 10, 1);
+
+        let policy = RetentionPolicy {
+            max_age_days: Some(30),
+            ..Default::default()
+        };
+        let report = prune_dir(dir.path(), &policy, false).unwrap();
+
+        assert_eq!(report.removed_files, vec![old.clone()]);
+        assert_eq!(
+            report.removed_dirs,
+            vec![old.parent().unwrap().to_path_buf()]
+        );
+        assert!(new.exists());
+    }
+
+    #[test]
+    fn prune_keeps_newest_per_mod() {
+        let dir = tempdir().unwrap();
+        for (idx, name) in ["foo_v1.0.0.zip", "foo_v1.1.0.zip", "foo_v1.2.0.zip"]
+            .iter()
+            .enumerate()
+        {
+            write_file(&dir.path().join(name), 10, 3 - idx as u64);
+        }
+        write_file(&dir.path().join("bar_v1.0.0.zip"), 10, 10);
+
+        let policy = RetentionPolicy {
+            max_count_per_mod: Some(1),
+            ..Default::default()
+        };
+        let report = prune_dir(dir.path(), &policy, false).unwrap();
+
+        assert_eq!(report.removed_files.len(), 2);
+        assert!(dir.path().join("foo_v1.2.0.zip").exists());
+        assert!(dir.path().join("bar_v1.0.0.zip").exists());
+    }
+
+    #[test]
+    fn prune_enforces_max_size_oldest_first() {
+        let dir = tempdir().unwrap();
+        write_file(&dir.path().join("a.json"), 600 * 1024, 3);
+        write_file(&dir.path().join("b.json"), 600 * 1024, 2);
+        write_file(&dir.path().join("c.json"), 600 * 1024, 1);
+
+        let policy = RetentionPolicy {
+            max_size_mb: Some(1),
+            ..Default::default()
+        };
+        let report = prune_dir(dir.path(), &policy, true).unwrap();
+
+        assert_eq!(report.removed_files.len(), 2);
+        assert_eq!(report.freed_bytes, 1200 * 1024);
+        // Dry run leaves everything in place.
+        assert!(dir.path().join("a.json").exists());
+    }
+
+    #[test]
+    fn prune_missing_dir_is_empty_report() {
+        let report = prune_dir(
+            Path::new("/definitely/not/here"),
+            &RetentionPolicy::backups(),
+            false,
+        )
+        .unwrap();
+        assert!(report.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Pre-release channels used by Vintage Story mods, from least to most stable.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreRelease {
+    /// Any label not listed below, e.g. `-alpha` or `-beta`, ordered by name.
+    Other(String),
+    /// `-dev.N`
+    Dev,
+    /// `-pre.N`
+    Pre,
+    /// `-rc.N`
+    Rc,
+}
+
+/// A parsed mod or game version such as `1.20.3`, `v2.0.0-rc.2` or `1.8.0-dev.4`.
+///
+/// Numeric parts are compared as numbers (`1.10.0` > `1.9.0`), missing parts count as
+/// zero (`1.2` == `1.2.0`), and a pre-release sorts before the release it precedes
+/// (`1.2.0-rc.1` < `1.2.0`). Build metadata after `+` is ignored.
+#[derive(Debug, Clone)]
+pub struct ModVersion {
+    parts: Vec<u64>,
+    pre: Option<(PreRelease, u64)>,
+    raw: String,
+}
+
+impl ModVersion {
+    /// Parses a version string, tolerating a leading `v`.
+    ///
+    /// # Returns
+    ///
+    /// The parsed version, or `None` if the numeric part is missing or malformed.
+    pub fn parse(version: &str) -> Option<Self> {
+        let raw = version.trim();
+        let version = raw.trim_start_matches(['v', 'V']);
+        let version = version.split('+').next().unwrap_or_default();
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+
+        let parts = core
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        let pre = pre.map(|pre| {
+            let label: String = pre
+                .chars()
+                .take_while(|c| c.is_ascii_alphabetic())
+                .collect();
+            let number = pre[label.len()..]
+                .trim_start_matches(['.', '-'])
+                .parse()
+                .unwrap_or(0);
+            let kind = match label.to_lowercase().as_str() {
+                "dev" => PreRelease::Dev,
+                "pre" => PreRelease::Pre,
+                "rc" => PreRelease::Rc,
+                _ => PreRelease::Other(pre.to_lowercase()),
+            };
+            (kind, number)
+        });
+
+        Some(Self {
+            parts,
+            pre,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// Returns the pre-release channel, or `None` for a stable release.
+    pub fn pre_release(&self) -> Option<&PreRelease> {
+        self.pre.as_ref().map(|(kind, _)| kind)
+    }
+
+    fn part(&self, index: usize) -> u64 {
+        self.parts.get(index).copied().unwrap_or(0)
+    }
+}
+
+impl Ord for ModVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.parts.len().max(other.parts.len());
+        (0..len)
+            .map(|index| self.part(index).cmp(&other.part(index)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialOrd for ModVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ModVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for ModVersion {}
+
+impl fmt::Display for ModVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+/// Compares two version strings, falling back to a plain string comparison when either
+/// one can't be parsed.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (ModVersion::parse(a), ModVersion::parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Returns `true` if `candidate` is a newer version than `current`.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    compare_versions(candidate, current).is_gt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_numeric_parts_and_prereleases() {
+        let ordered = [
+            "0.9",
+            "1.2.0-alpha",
+            "1.2.0-dev.2",
+            "1.2.0-dev.10",
+            "1.2.0-pre.1",
+            "v1.2.0-rc.1",
+            "1.2.0-rc.3",
+            "1.2",
+            "1.2.1",
+            "1.10.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(
+                compare_versions(pair[0], pair[1]),
+                Ordering::Less,
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+        assert_eq!(compare_versions("1.2", "1.2.0+build5"), Ordering::Equal);
+    }
+
+    #[test]
+    fn detects_downgrades_and_prereleases() {
+        assert!(is_newer("1.8.0", "1.7.10"));
+        assert!(!is_newer("1.7.0", "1.8.0"));
+        assert!(!is_newer("1.8.0-rc.1", "1.8.0"));
+        assert!(ModVersion::parse("latest").is_none());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::utils::encoding::EncodingError;
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
+use reqwest::Url;
+
+/// Scheme and path of the links made by `export --url`.
+const SHARE_LINK_BASE: &str = "vintagemods://import";
+
+/// Query parameter holding the mod string in a share link.
+const SHARE_LINK_PARAM: &str = "mods";
+
+/// Wraps a mod string in a `vintagemods://import?mods=...` link.
+///
+/// Base85 uses characters such as `?`, `&` and `#` that would end the link, so the mod
+/// string is percent-encoded.
+pub fn share_link(mod_string: &str) -> String {
+    Url::parse_with_params(SHARE_LINK_BASE, [(SHARE_LINK_PARAM, mod_string)])
+        .expect("the share link base is a valid URL")
+        .to_string()
+}
+
+/// Extracts the mod string from a link made by [`share_link`].
+///
+/// # Returns
+///
+/// The mod string, or an error if `link` is not a share link.
+pub fn parse_share_link(link: &str) -> Result<String, EncodingError> {
+    let not_a_link = || EncodingError::ShareLink(link.to_string());
+    let url = Url::parse(link.trim()).map_err(|_| not_a_link())?;
+    if url.scheme() != "vintagemods" || url.host_str() != Some("import") {
+        return Err(not_a_link());
+    }
+    url.query_pairs()
+        .find(|(key, _)| key == SHARE_LINK_PARAM)
+        .map(|(_, mods)| mods.into_owned())
+        .filter(|mods| !mods.is_empty())
+        .ok_or_else(not_a_link)
+}
+
+/// Renders `data` as a QR code out of half-block characters, two modules per character
+/// row, so it can be scanned off the terminal.
+///
+/// # Returns
+///
+/// The QR code as lines of text, or an error if `data` doesn't fit in a QR code.
+pub fn render_qr(data: &str) -> Result<String, EncodingError> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| EncodingError::QrCode(e.to_string()))?;
+    // Drawn for dark terminals: light modules are the filled blocks
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_links_round_trip_mod_strings() {
+        let mod_string = "VMM3:a?b&c#d+e=f%g";
+        let link = share_link(mod_string);
+
+        assert!(link.starts_with("vintagemods://import?mods="));
+        assert!(!link.contains('#'));
+        assert_eq!(parse_share_link(&link).unwrap(), mod_string);
+        assert!(matches!(
+            parse_share_link("https://mods.vintagestory.at/carryon"),
+            Err(EncodingError::ShareLink(_))
+        ));
+        assert!(parse_share_link("vintagemods://import").is_err());
+    }
+
+    #[test]
+    fn qr_codes_reject_oversized_data() {
+        assert!(render_qr("VMM3:short").unwrap().lines().count() > 10);
+        assert!(matches!(
+            render_qr(&"x".repeat(5000)),
+            Err(EncodingError::QrCode(_))
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StateError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Why a mod ended up in the mods directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InstallReason {
+    /// The user asked for the mod directly.
+    Explicit,
+    /// The mod was pulled in because other mods depend on it.
+    Dependency { required_by: Vec<String> },
+    /// The mod was already in the mods directory when the tool first managed it.
+    Adopted,
+}
+
+/// A mod installed or adopted by the mod manager.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledMod {
+    pub modid: String,
+    pub version: String,
+    pub filename: String,
+    pub reason: InstallReason,
+    /// RFC 3339 timestamp of when the mod was installed or last updated.
+    pub installed_at: String,
+    /// ModDB id of the installed release, if it was downloaded from ModDB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub releaseid: Option<u32>,
+    /// When the installed release was published on ModDB, as reported by the API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_created: Option<String>,
+}
+
+/// An update that was found but not installed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingUpdate {
+    pub modid: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// The outcome of the last `update` run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRun {
+    /// RFC 3339 timestamp of when the run finished.
+    pub finished_at: String,
+    /// Number of mods that were updated.
+    pub updated: usize,
+    /// Updates that were found but failed to install.
+    pub pending: Vec<PendingUpdate>,
+}
+
+/// Persistent record of the mods managed by this tool, stored next to the mods directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StateFile {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Installed mods keyed by lowercase modid.
+    pub mods: BTreeMap<String, InstalledMod>,
+    /// The last `update` run, shown by `status`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_update: Option<UpdateRun>,
+    /// Updates `check` already posted to webhooks, so each release is announced once.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub announced: Vec<PendingUpdate>,
+}
+
+impl StateFile {
+    /// Loads the state file at `path`, returning an empty state if it does not exist yet.
+    pub fn load(path: &Path) -> Result<Self, StateError> {
+        let mut state = if path.exists() {
+            serde_json::from_str::<StateFile>(&fs::read_to_string(path)?)?
+        } else {
+            StateFile::default()
+        };
+        state.path = path.to_path_buf();
+        Ok(state)
+    }
+
+    /// Writes the state back to the file it was loaded from.
+    pub fn save(&self) -> Result<(), StateError> {
+        fs::write(&self.path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Looks up an installed mod by modid (case-insensitive).
+    pub fn get(&self, modid: &str) -> Option<&InstalledMod> {
+        self.mods.get(&modid.to_lowercase())
+    }
+
+    /// Records a newly installed or updated mod.
+    ///
+    /// If the mod is already known its original install reason is kept unless `reason`
+    /// is `Explicit`, so updating a dependency does not turn it into an explicit install.
+    ///
+    /// Returns the new record so callers can attach release details.
+    pub fn record_install(
+        &mut self, modid: &str, version: &str, filename: &str, reason: InstallReason,
+    ) -> &mut InstalledMod {
+        let key = modid.to_lowercase();
+        let reason = match (self.mods.get(&key), reason) {
+            (Some(_), InstallReason::Explicit) => InstallReason::Explicit,
+            (Some(existing), _) => existing.reason.clone(),
+            (None, reason) => reason,
+        };
+
+        self.mods.insert(
+            key.clone(),
+            InstalledMod {
+                modid: modid.to_string(),
+                version: version.to_string(),
+                filename: filename.to_string(),
+                reason,
+                installed_at: chrono::Local::now().to_rfc3339(),
+                releaseid: None,
+                release_created: None,
+            },
+        );
+        self.mods.get_mut(&key).expect("record was just inserted")
+    }
+
+    /// Forgets a mod, returning its record if it was known.
+    pub fn remove(&mut self, modid: &str) -> Option<InstalledMod> {
+        self.mods.remove(&modid.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn record_install_keeps_reason_on_update() {
+        let mut state = StateFile::default();
+        state.record_install(
+            "Foo",
+            "1.0.0",
+            "foo_v1.0.0.zip",
+            InstallReason::Dependency {
+                required_by: vec!["bar".to_string()],
+            },
+        );
+        state.record_install("foo", "1.1.0", "foo_v1.1.0.zip", InstallReason::Adopted);
+
+        let foo = state.get("FOO").unwrap();
+        assert_eq!(foo.version, "1.1.0");
+        assert!(matches!(foo.reason, InstallReason::Dependency { .. }));
+
+        state.record_install("foo", "1.1.0", "foo_v1.1.0.zip", InstallReason::Explicit);
+        assert_eq!(state.get("foo").unwrap().reason, InstallReason::Explicit);
+    }
+
+    #[test]
+    fn state_round_trips_through_disk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ModManagerState.json");
+
+        let mut state = StateFile::load(&path).unwrap();
+        state.record_install("foo", "1.0.0", "foo.zip", InstallReason::Explicit);
+        state.save().unwrap();
+
+        let loaded = StateFile::load(&path).unwrap();
+        assert_eq!(loaded.get("foo").unwrap().filename, "foo.zip");
+    }
+}
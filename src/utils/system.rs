@@ -1,5 +1,5 @@
-use directories::BaseDirs;
-use std::path::PathBuf;
+use directories::{BaseDirs, ProjectDirs};
+use std::path::{Path, PathBuf};
 
 const UNIX_PATH: &str = "/VintagestoryData/Mods/";
 
@@ -17,14 +17,14 @@ pub fn get_config_dir() -> PathBuf {
     base_dirs.config_dir().to_path_buf()
 }
 
-/// Get the directory where Vintage Story mods are stored.
+/// Get the default location of the Vintage Story mods directory.
 ///
 /// Does not check if the directory exists.
 ///
 /// # Returns
 ///
-/// A `String` representing the path to the Vintage Story mods directory.
-pub fn get_vintage_mods_dir() -> Result<PathBuf, std::io::Error> {
+/// The `VintagestoryData/Mods` directory inside the user's configuration directory.
+pub fn default_vintage_mods_dir() -> PathBuf {
     let config_dir = get_config_dir();
 
     let sys_path = if cfg!(unix) || cfg!(target_os = "macos") {
@@ -35,19 +35,143 @@ pub fn get_vintage_mods_dir() -> Result<PathBuf, std::io::Error> {
         panic!("Unsupported operating system");
     };
 
-    let mods_dir = config_dir.join(sys_path);
+    config_dir.join(sys_path)
+}
 
+/// Get the directory where Vintage Story mods are stored.
+///
+/// # Returns
+///
+/// The Vintage Story mods directory, or a `NotFound` error naming the expected path if
+/// the game hasn't created it yet.
+pub fn get_vintage_mods_dir() -> Result<PathBuf, std::io::Error> {
+    existing_mods_dir(default_vintage_mods_dir())
+}
+
+/// Checks that a mods directory exists.
+///
+/// # Returns
+///
+/// `mods_dir`, or a `NotFound` error naming it if it doesn't exist.
+pub fn existing_mods_dir(mods_dir: PathBuf) -> Result<PathBuf, std::io::Error> {
     if !mods_dir.exists() {
-        // as the mods dir is created by the game we just want to panic out if it doesn't exist
+        // The mods dir is normally created by the game, see `ModManager::ensure_mods_dir`
         return Err(std::io::Error::new(
             std::io::ErrorKind::NotFound,
-            "Mods directory not found",
+            format!("Mods directory not found: {}", mods_dir.display()),
         ));
     }
 
     Ok(mods_dir)
 }
 
+/// Get the directory backups of the mods in `mods_dir` are stored in, `ModBackups` next to it.
+pub fn backup_dir_for(mods_dir: &Path) -> PathBuf {
+    mods_dir.parent().unwrap_or(mods_dir).join("ModBackups")
+}
+
+/// Get the path of the file recording which mods this tool installed into `mods_dir` and why.
+///
+/// The state file lives next to the mods directory, e.g. `VintagestoryData/ModManagerState.json`.
+pub fn get_state_path(mods_dir: &Path) -> PathBuf {
+    mods_dir
+        .parent()
+        .unwrap_or(mods_dir)
+        .join("ModManagerState.json")
+}
+
+/// Get the path of the lockfile pinning the exact releases installed into `mods_dir`.
+///
+/// The lockfile lives next to the mods directory, e.g. `VintagestoryData/mods.lock`.
+pub fn get_lock_path(mods_dir: &Path) -> PathBuf {
+    mods_dir.parent().unwrap_or(mods_dir).join("mods.lock")
+}
+
+/// Get the path of the file locked while `update --auto` runs on `mods_dir`.
+///
+/// The file lives next to the mods directory, e.g. `VintagestoryData/vsmm-update.lock`.
+pub fn get_run_lock_path(mods_dir: &Path) -> PathBuf {
+    mods_dir
+        .parent()
+        .unwrap_or(mods_dir)
+        .join("vsmm-update.lock")
+}
+
+/// Get the cache directory used by the mod manager.
+///
+/// Does not check if the directory exists.
+///
+/// # Returns
+///
+/// The cache directory, or `None` if no home directory could be determined.
+pub fn get_cache_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "mikkelmh", "vintage-story-mod-manager")
+        .map(|proj_dirs| proj_dirs.cache_dir().to_path_buf())
+}
+
+/// Get the directory daily log files are written to.
+///
+/// Does not check if the directory exists.
+pub fn get_log_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "mikkelmh", "vintage-story-mod-manager")
+        .map(|proj_dirs| proj_dirs.data_local_dir().join("logs"))
+}
+
+/// Get the directory holding partially downloaded files (`*.part`) until they complete.
+///
+/// Does not check if the directory exists.
+pub fn get_download_dir() -> PathBuf {
+    get_cache_dir()
+        .unwrap_or_else(|| std::env::temp_dir().join("vintage-story-mod-manager"))
+        .join("downloads")
+}
+
+/// Get the content-addressed store that linked installs point into.
+///
+/// Unlike the cache directory the store is never pruned automatically, as installed
+/// symlinks depend on it. Does not check if the directory exists.
+pub fn get_store_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "mikkelmh", "vintage-story-mod-manager")
+        .map(|proj_dirs| proj_dirs.data_local_dir().join("store"))
+}
+
+/// Get the local copy of a remote's mods directory that `remote update` updates before
+/// pushing the changes back.
+///
+/// Does not check if the directory exists.
+pub fn get_remote_mirror_dir(name: &str) -> PathBuf {
+    ProjectDirs::from("com", "mikkelmh", "vintage-story-mod-manager")
+        .map(|proj_dirs| proj_dirs.data_local_dir().to_path_buf())
+        .unwrap_or_else(|| std::env::temp_dir().join("vintage-story-mod-manager"))
+        .join("remotes")
+        .join(name)
+        .join("Mods")
+}
+
+/// Detect the tool running as root on a mods directory owned by another user.
+///
+/// Files written by root are unreadable to a game server running under its own account,
+/// which is a common misconfiguration on Linux servers.
+///
+/// # Returns
+///
+/// The `(uid, gid)` owning `dir` when the current user is root and the directory belongs
+/// to someone else, otherwise `None`.
+#[cfg(unix)]
+pub fn root_owner_mismatch(dir: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    let euid = unsafe { libc::geteuid() };
+    let metadata = std::fs::metadata(dir).ok()?;
+    (euid == 0 && metadata.uid() != 0).then(|| (metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+pub fn root_owner_mismatch(_dir: &Path) -> Option<(u32, u32)> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,6 +183,12 @@ mod tests {
         assert!(!config_dir.to_str().unwrap().is_empty());
     }
 
+    #[test]
+    fn root_owner_mismatch_ignores_own_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(root_owner_mismatch(dir.path()), None);
+    }
+
     #[test]
     fn test_get_vintage_mods_dir() {
         let mods_dir = get_vintage_mods_dir();
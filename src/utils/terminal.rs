@@ -1,9 +1,9 @@
 // Wrapper for pretty-printing messages to the Terminal
 
 use crate::api::ModSearchResult;
+use crate::say;
+use crate::utils::prompt::{default_prompt, labels};
 use colored::Colorize;
-use dialoguer::Confirm;
-use dialoguer::theme::ColorfulTheme;
 use std::env;
 use std::fmt::Display;
 
@@ -23,12 +23,12 @@ impl Terminal {
     }
 
     pub fn print<T: ToString>(message: T) {
-        println!("{}", message.to_string());
+        say!("{}", message.to_string());
     }
 
     /// Prints a message to the terminal with a newline
     pub fn println<T: ToString>(message: T) {
-        println!("{}", message.to_string());
+        say!("{}", message.to_string());
     }
 
     /// Prints a message to the terminal with a newline
@@ -43,34 +43,28 @@ impl Terminal {
     }
 
     pub fn confirm<T: ToString>(message: T) -> bool {
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(message.to_string())
-            .interact()
-            .unwrap()
+        default_prompt().confirm(&message.to_string())
     }
 
     pub fn select<T: Display>(message: &str, options: &[T]) -> Option<usize> {
-        dialoguer::FuzzySelect::with_theme(&ColorfulTheme::default())
-            .with_prompt(message)
-            .items(options)
-            .default(0)
-            .interact_opt()
-            .unwrap_or(None)
+        default_prompt().select(message, &labels(options))
     }
 
-    pub fn input(message: &str) -> String {
-        dialoguer::Input::with_theme(&ColorfulTheme::default())
-            .with_prompt(message)
-            .interact()
-            .unwrap()
-    }
+    /// Formats a byte count as a human-readable size (e.g. `1.5 MB`).
+    pub fn format_size(bytes: u64) -> String {
+        const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+        let mut size = bytes as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
 
-    pub fn multi_select<T: Display>(message: &str, options: &[T]) -> Vec<usize> {
-        dialoguer::MultiSelect::with_theme(&ColorfulTheme::default())
-            .with_prompt(message)
-            .items(options)
-            .interact()
-            .unwrap()
+        if unit == 0 {
+            format!("{bytes} B")
+        } else {
+            format!("{size:.1} {}", UNITS[unit])
+        }
     }
 
     fn format_mod_options(options: &[ModSearchResult]) -> Vec<String> {
@@ -109,11 +103,11 @@ impl Terminal {
             .join("-+-");
 
         if self.colors_enabled {
-            println!("{}", header_row.bold());
-            println!("{}", separator.dimmed());
+            say!("{}", header_row.bold());
+            say!("{}", separator.dimmed());
         } else {
-            println!("{header_row}");
-            println!("{separator}");
+            say!("{header_row}");
+            say!("{separator}");
         }
 
         // Print data rows
@@ -123,18 +117,18 @@ impl Terminal {
                 .iter()
                 .zip(&column_widths)
                 .map(|(col, width)| {
-                    format!(
-                        "{:<width$}",
-                        col.data
-                            .get(row_idx)
-                            .map(|val| val.to_string())
-                            .unwrap_or_default(),
-                        width = width
-                    )
+                    let value = col
+                        .data
+                        .get(row_idx)
+                        .map(|val| val.to_string())
+                        .unwrap_or_default();
+                    // Pad by hand, `format!` would count color codes and multi-byte symbols
+                    let padding = width.saturating_sub(visible_width(&value));
+                    format!("{value}{}", " ".repeat(padding))
                 })
                 .collect::<Vec<_>>()
                 .join(" | ");
-            println!("{row}");
+            say!("{row}");
         }
     }
 }
@@ -157,9 +151,36 @@ impl<T: ToString> Columns<T> {
         let data_width = self
             .data
             .iter()
-            .map(|val| val.to_string().len())
+            .map(|val| visible_width(&val.to_string()))
             .max()
             .unwrap_or(0);
         header_width.max(data_width)
     }
 }
+
+/// The number of columns `text` takes up in the terminal, leaving out ANSI color codes.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the escape sequence up to and including its final letter
+            chars.by_ref().find(char::is_ascii_alphabetic);
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visible_width_skips_color_codes() {
+        assert_eq!(visible_width("✓ compatible"), 12);
+        assert_eq!(visible_width("\x1b[32m✓ compatible\x1b[0m"), 12);
+        assert_eq!(visible_width("\x1b[1;31m✗\x1b[0m no"), 4);
+    }
+}
@@ -0,0 +1,396 @@
+use crate::api::{ModInfo, ModSearchResult};
+use ratatui::Frame;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+
+/// The list pane that receives arrow keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Installed,
+    Search,
+}
+
+/// The state of a mod in the download queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueStatus {
+    Queued,
+    Downloading,
+    Done(String),
+    Failed(String),
+}
+
+/// A mod waiting in, or finished by, the download queue.
+#[derive(Debug, Clone)]
+pub struct QueuedMod {
+    pub modid: String,
+    pub name: String,
+    pub status: QueueStatus,
+}
+
+/// What the caller of [`TuiApp::handle_key`] has to do next.
+#[derive(Debug, PartialEq, Eq)]
+pub enum TuiAction {
+    None,
+    /// Search ModDB for the text and pass the results to [`TuiApp::set_results`].
+    Search(String),
+    /// Download every queued mod.
+    Download,
+    Quit,
+}
+
+/// State of the full-screen terminal UI started by the `tui` command.
+///
+/// The app only tracks what is shown and turns key presses into [`TuiAction`]s, the
+/// network and filesystem work is left to the caller.
+pub struct TuiApp {
+    installed: Vec<ModInfo>,
+    results: Vec<ModSearchResult>,
+    queue: Vec<QueuedMod>,
+    focus: Pane,
+    installed_state: ListState,
+    results_state: ListState,
+    /// The search being typed, or `None` when not in search mode.
+    input: Option<String>,
+    status: String,
+}
+
+impl TuiApp {
+    /// Creates the app with the installed mods and an initial page of search results.
+    pub fn new(installed: Vec<ModInfo>, results: Vec<ModSearchResult>) -> Self {
+        let mut app = Self {
+            installed,
+            results: Vec::new(),
+            queue: Vec::new(),
+            focus: Pane::Search,
+            installed_state: ListState::default(),
+            results_state: ListState::default(),
+            input: None,
+            status: String::new(),
+        };
+        if !app.installed.is_empty() {
+            app.installed_state.select(Some(0));
+        }
+        app.set_results(results);
+        app.status = "/ search  enter queue  d download queue  tab switch pane  q quit".to_string();
+        app
+    }
+
+    /// Replaces the search results, selecting the first one.
+    pub fn set_results(&mut self, results: Vec<ModSearchResult>) {
+        self.status = format!("{} mod(s) found", results.len());
+        self.results = results;
+        self.results_state
+            .select((!self.results.is_empty()).then_some(0));
+    }
+
+    /// Sets the message shown in the bottom line.
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = status.into();
+    }
+
+    /// Returns the download queue.
+    pub fn queue(&self) -> &[QueuedMod] {
+        &self.queue
+    }
+
+    /// Updates the status of the queued mod at `index`.
+    pub fn set_queue_status(&mut self, index: usize, status: QueueStatus) {
+        if let Some(queued) = self.queue.get_mut(index) {
+            queued.status = status;
+        }
+    }
+
+    /// Handles a key press.
+    ///
+    /// # Returns
+    ///
+    /// The work the caller has to do in response.
+    pub fn handle_key(&mut self, key: KeyEvent) -> TuiAction {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return TuiAction::Quit;
+        }
+
+        if let Some(input) = &mut self.input {
+            match key.code {
+                KeyCode::Enter => {
+                    let text = input.trim().to_string();
+                    self.input = None;
+                    self.focus = Pane::Search;
+                    return TuiAction::Search(text);
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return TuiAction::None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return TuiAction::Quit,
+            KeyCode::Char('/') => self.input = Some(String::new()),
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Pane::Installed => Pane::Search,
+                    Pane::Search => Pane::Installed,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Enter if self.focus == Pane::Search => self.enqueue_selected(),
+            KeyCode::Char('d') if !self.queue.is_empty() => return TuiAction::Download,
+            _ => {}
+        }
+        TuiAction::None
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (state, len) = match self.focus {
+            Pane::Installed => (&mut self.installed_state, self.installed.len()),
+            Pane::Search => (&mut self.results_state, self.results.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as isize;
+        state.select(Some((current + delta).clamp(0, len as isize - 1) as usize));
+    }
+
+    /// Adds the selected search result to the download queue, once.
+    fn enqueue_selected(&mut self) {
+        let Some(selected) = self
+            .results_state
+            .selected()
+            .and_then(|i| self.results.get(i))
+        else {
+            return;
+        };
+        let Some(modid) = selected.modidstrs.first() else {
+            self.status = format!("{} has no mod ID to download", selected.name);
+            return;
+        };
+        if self.queue.iter().any(|queued| &queued.modid == modid) {
+            self.status = format!("{} is already queued", selected.name);
+            return;
+        }
+
+        self.status = format!("Queued {}", selected.name);
+        self.queue.push(QueuedMod {
+            modid: modid.clone(),
+            name: selected.name.clone(),
+            status: QueueStatus::Queued,
+        });
+    }
+
+    /// Renders the panes: installed mods and search results on the left, details of the
+    /// selected mod and the download queue on the right.
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(50); 2]).areas(main);
+        let [installed, results] =
+            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(left);
+        let [details, queue] =
+            Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(right);
+
+        self.draw_installed(frame, installed);
+        self.draw_results(frame, results);
+        frame.render_widget(
+            Paragraph::new(self.details())
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).title("Details")),
+            details,
+        );
+        self.draw_queue(frame, queue);
+
+        let status_line = match &self.input {
+            Some(input) => Line::from(vec![Span::raw("Search: "), Span::raw(input.as_str())]),
+            None => Line::from(Span::styled(
+                self.status.as_str(),
+                Style::default().fg(Color::DarkGray),
+            )),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+    }
+
+    fn pane_block(&self, title: &'static str, pane: Pane) -> Block<'static> {
+        let style = if self.focus == pane {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style)
+            .title(title)
+    }
+
+    fn draw_installed(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .installed
+            .iter()
+            .map(|mod_info| {
+                ListItem::new(format!(
+                    "{} {}",
+                    mod_info.name.as_deref().unwrap_or("Unknown"),
+                    mod_info.version.as_deref().unwrap_or("")
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.pane_block("Installed", Pane::Installed))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.installed_state);
+    }
+
+    fn draw_results(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .map(|result| ListItem::new(result.to_string()))
+            .collect();
+        let list = List::new(items)
+            .block(self.pane_block("Search results", Pane::Search))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.results_state);
+    }
+
+    fn draw_queue(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .queue
+            .iter()
+            .map(|queued| {
+                let (label, color) = match &queued.status {
+                    QueueStatus::Queued => ("queued".to_string(), Color::DarkGray),
+                    QueueStatus::Downloading => ("downloading".to_string(), Color::Yellow),
+                    QueueStatus::Done(version) => (format!("✓ {version}"), Color::Green),
+                    QueueStatus::Failed(error) => (format!("✗ {error}"), Color::Red),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{} ", queued.name)),
+                    Span::styled(label, Style::default().fg(color)),
+                ]))
+            })
+            .collect();
+        frame.render_widget(
+            List::new(items).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Download queue"),
+            ),
+            area,
+        );
+    }
+
+    /// Describes the selected mod of the focused pane.
+    fn details(&self) -> Vec<Line<'_>> {
+        match self.focus {
+            Pane::Installed => {
+                let Some(mod_info) = self
+                    .installed_state
+                    .selected()
+                    .and_then(|i| self.installed.get(i))
+                else {
+                    return vec![Line::from("No mods installed")];
+                };
+                vec![
+                    Line::from(mod_info.name.clone().unwrap_or_default())
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Line::from(format!(
+                        "ID: {}",
+                        mod_info.modid.as_deref().unwrap_or("unknown")
+                    )),
+                    Line::from(format!(
+                        "Version: {}",
+                        mod_info.version.as_deref().unwrap_or("unknown")
+                    )),
+                    Line::from(format!(
+                        "Side: {}",
+                        mod_info.side.as_deref().unwrap_or("unknown")
+                    )),
+                    Line::from(""),
+                    Line::from(mod_info.description.clone().unwrap_or_default()),
+                ]
+            }
+            Pane::Search => {
+                let Some(result) = self
+                    .results_state
+                    .selected()
+                    .and_then(|i| self.results.get(i))
+                else {
+                    return vec![Line::from("No search results")];
+                };
+                vec![
+                    Line::from(result.name.clone())
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Line::from(format!("Author: {}", result.author)),
+                    Line::from(format!("ID: {}", result.modidstrs.join(", "))),
+                    Line::from(format!("Side: {}", result.side)),
+                    Line::from(format!("Downloads: {}", result.downloads.unwrap_or(0))),
+                    Line::from(format!("Tags: {}", result.tags.join(", "))),
+                    Line::from(""),
+                    Line::from(result.summary.clone().unwrap_or_default()),
+                ]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(modid: &str) -> ModSearchResult {
+        serde_json::from_value(serde_json::json!({
+            "modid": 1,
+            "assetid": 1,
+            "downloads": 10,
+            "follows": 0,
+            "trendingpoints": 0,
+            "comments": 0,
+            "name": modid,
+            "summary": null,
+            "modidstrs": [modid],
+            "author": "tester",
+            "urlalias": null,
+            "side": "both",
+            "type": "mod",
+            "logo": null,
+            "tags": [],
+            "lastreleased": null,
+        }))
+        .unwrap()
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn keys_search_queue_and_download() {
+        let mut app = TuiApp::new(Vec::new(), vec![result("carryon"), result("hudclock")]);
+
+        assert_eq!(app.handle_key(key(KeyCode::Char('d'))), TuiAction::None);
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Enter));
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.queue().len(), 1);
+        assert_eq!(app.queue()[0].modid, "hudclock");
+        assert_eq!(app.handle_key(key(KeyCode::Char('d'))), TuiAction::Download);
+
+        app.handle_key(key(KeyCode::Char('/')));
+        for c in "prospect".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            TuiAction::Search("prospect".to_string())
+        );
+        assert_eq!(app.handle_key(key(KeyCode::Char('q'))), TuiAction::Quit);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::utils::RunSummary;
+use serde::Serialize;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::Path;
+
+/// Name of the file in the log directory `update --auto` logs its actions to by default.
+pub const AUTO_LOG_NAME: &str = "auto-update.jsonl";
+
+/// An exclusive lock on a mods directory, held while `update --auto` runs so a cron job
+/// starting before the previous run finished leaves the mods alone.
+///
+/// The operating system releases the lock when the process exits, so a run that crashed
+/// never leaves a stale lock behind.
+pub struct RunLock {
+    _file: File,
+}
+
+impl RunLock {
+    /// Takes the lock on the file at `path`, creating the file if needed.
+    ///
+    /// # Returns
+    ///
+    /// The lock, or `None` if another process holds it.
+    pub fn acquire(path: &Path) -> Result<Option<Self>, std::io::Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+}
+
+/// One line of the action log.
+#[derive(Serialize)]
+struct LoggedAction<'a> {
+    time: &'a str,
+    command: &'a str,
+    /// `updated`, `held_back`, `failed`, `installed`, `removed` or `finished`.
+    action: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modid: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<&'a str>,
+    /// The exit status of the run, on the `finished` line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u8>,
+}
+
+impl<'a> LoggedAction<'a> {
+    fn new(time: &'a str, command: &'a str, action: &'a str) -> Self {
+        Self {
+            time,
+            command,
+            action,
+            name: None,
+            modid: None,
+            from: None,
+            to: None,
+            status: None,
+        }
+    }
+}
+
+/// Appends what a run did to a log file, one JSON object per line, ending with a
+/// `finished` line holding the run's exit status.
+///
+/// # Arguments
+///
+/// * `path` - The log file, created along with its directory if missing.
+/// * `summary` - The summary of the finished run.
+pub fn append_actions(path: &Path, summary: &RunSummary) -> Result<(), std::io::Error> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let time = chrono::Local::now().to_rfc3339();
+    let command = summary.command.as_str();
+
+    let mut actions = Vec::new();
+    for (action, mods) in [
+        ("updated", &summary.updated),
+        ("held_back", &summary.held_back),
+    ] {
+        actions.extend(mods.iter().map(|updated| LoggedAction {
+            name: Some(&updated.name),
+            modid: Some(&updated.modid),
+            from: Some(&updated.from),
+            to: Some(&updated.to),
+            ..LoggedAction::new(&time, command, action)
+        }));
+    }
+    for (action, names) in [
+        ("failed", &summary.failed),
+        ("installed", &summary.installed),
+        ("removed", &summary.removed),
+    ] {
+        actions.extend(names.iter().map(|name| LoggedAction {
+            name: Some(name),
+            ..LoggedAction::new(&time, command, action)
+        }));
+    }
+    actions.push(LoggedAction {
+        status: Some(summary.exit_code()),
+        ..LoggedAction::new(&time, command, "finished")
+    });
+
+    let mut lines = String::new();
+    for action in actions {
+        lines.push_str(&serde_json::to_string(&action)?);
+        lines.push('\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::UpdatedMod;
+    use tempfile::tempdir;
+
+    #[test]
+    fn run_lock_is_exclusive() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("vsmm-update.lock");
+
+        let lock = RunLock::acquire(&path).unwrap();
+        assert!(lock.is_some());
+        assert!(RunLock::acquire(&path).unwrap().is_none());
+
+        drop(lock);
+        assert!(RunLock::acquire(&path).unwrap().is_some());
+    }
+
+    #[test]
+    fn actions_are_appended_as_json_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("logs").join(AUTO_LOG_NAME);
+        let summary = RunSummary {
+            updated: vec![UpdatedMod {
+                name: "Carry On".to_string(),
+                modid: "carryon".to_string(),
+                from: "1.7.0".to_string(),
+                to: "1.8.0".to_string(),
+            }],
+            ..RunSummary::new("update")
+        };
+
+        append_actions(&path, &summary).unwrap();
+        append_actions(&path, &RunSummary::new("update")).unwrap();
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["action"], "updated");
+        assert_eq!(lines[0]["to"], "1.8.0");
+        assert_eq!(lines[1]["action"], "finished");
+        assert_eq!(lines[1]["status"], 2);
+        assert_eq!(lines[2]["status"], 0);
+    }
+}
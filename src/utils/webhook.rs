@@ -0,0 +1,316 @@
+use crate::config::{NetworkConfig, Webhook, WebhookKind};
+use serde::Serialize;
+use serde_json::json;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Chat services reject long messages (Discord allows 2000 characters), so the mod list is
+/// cut off after this many entries.
+const MAX_LISTED_MODS: usize = 25;
+
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Webhook responded with status {0}")]
+    Status(reqwest::StatusCode),
+}
+
+/// A mod replaced by a newer release during a run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpdatedMod {
+    pub name: String,
+    pub modid: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// What a run changed, posted to the configured webhooks when it is done.
+#[derive(Debug, Default, Serialize)]
+pub struct RunSummary {
+    /// The command that produced the summary, e.g. `update`.
+    pub command: String,
+    pub updated: Vec<UpdatedMod>,
+    /// Newer releases `update --auto` refused because they are not marked compatible with
+    /// the game version.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub held_back: Vec<UpdatedMod>,
+    /// IDs of mods installed by a `sync`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub installed: Vec<String>,
+    /// IDs of mods removed by a `sync --prune`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+    /// Names of mods that could not be updated.
+    pub failed: Vec<String>,
+    /// Names of mods that were already at their latest compatible release.
+    pub up_to_date: Vec<String>,
+    /// Names of pinned mods that were not checked.
+    pub skipped: Vec<String>,
+    /// Newer releases found by `check` or `watch` that were not installed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub available: Vec<UpdatedMod>,
+    /// Problems the run left behind, such as dependencies of updated mods that the
+    /// installed mods don't satisfy.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl RunSummary {
+    /// Creates an empty summary for `command`.
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns `true` if the run neither changed, held back nor failed anything.
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty()
+            && self.held_back.is_empty()
+            && self.installed.is_empty()
+            && self.removed.is_empty()
+            && self.failed.is_empty()
+    }
+
+    /// Returns the process exit status for the run: 1 if anything failed, 3 if updates
+    /// were held back, 2 if mods were changed and 0 if there was nothing to do.
+    pub fn exit_code(&self) -> u8 {
+        if !self.failed.is_empty() {
+            1
+        } else if !self.held_back.is_empty() {
+            3
+        } else if self.is_empty() {
+            0
+        } else {
+            2
+        }
+    }
+
+    /// Formats the per-category counts as an aligned table for the end of a run. Held back
+    /// updates are only listed when there are any.
+    pub fn table(&self) -> String {
+        [
+            ("Updated", self.updated.len()),
+            ("Held back", self.held_back.len()),
+            ("Up to date", self.up_to_date.len()),
+            ("Skipped", self.skipped.len()),
+            ("Failed", self.failed.len()),
+        ]
+        .iter()
+        .filter(|(label, count)| *label != "Held back" || *count > 0)
+        .map(|(label, count)| format!("{label:<11} {count:>4}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    /// Returns `true` if the summary only announces updates that were not installed.
+    pub fn is_available_only(&self) -> bool {
+        self.is_empty() && !self.available.is_empty()
+    }
+
+    /// Formats the summary as a short chat message.
+    pub fn message(&self) -> String {
+        let mut lines = Vec::new();
+
+        push_mod_list(&mut lines, "Server mods updated", &self.updated);
+        push_mod_list(
+            &mut lines,
+            "Updates held back, not compatible with the game version",
+            &self.held_back,
+        );
+        push_mod_list(&mut lines, "Mod updates available", &self.available);
+
+        if !self.installed.is_empty() {
+            lines.push(format!("Mods installed: {}", self.installed.join(", ")));
+        }
+
+        if !self.removed.is_empty() {
+            lines.push(format!("Mods removed: {}", self.removed.join(", ")));
+        }
+
+        if !self.failed.is_empty() {
+            lines.push(format!("Failed to update: {}", self.failed.join(", ")));
+        }
+
+        for warning in &self.warnings {
+            lines.push(format!("Warning: {warning}"));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Appends a heading and one line per mod to a chat message, if there are any mods.
+fn push_mod_list(lines: &mut Vec<String>, heading: &str, mods: &[UpdatedMod]) {
+    if mods.is_empty() {
+        return;
+    }
+
+    lines.push(format!("{heading} ({}):", mods.len()));
+    for updated in mods.iter().take(MAX_LISTED_MODS) {
+        lines.push(format!(
+            "- {} {} → {}",
+            updated.name, updated.from, updated.to
+        ));
+    }
+    if mods.len() > MAX_LISTED_MODS {
+        lines.push(format!("- ... and {} more", mods.len() - MAX_LISTED_MODS));
+    }
+}
+
+/// Builds the request body for a webhook.
+fn payload(kind: WebhookKind, summary: &RunSummary) -> serde_json::Value {
+    match kind {
+        WebhookKind::Discord => json!({ "content": summary.message() }),
+        WebhookKind::Slack => json!({ "text": summary.message() }),
+        WebhookKind::Json => json!(summary),
+    }
+}
+
+/// Builds the HTTP client webhooks are posted with, using the configured timeouts and
+/// proxy. The read timeout bounds the whole request, so an endpoint that never answers
+/// can't stall `update --auto` or `watch`.
+///
+/// # Returns
+///
+/// The client, or an error if the configured proxy is not a valid URL.
+pub fn webhook_client(network: &NetworkConfig) -> Result<reqwest::Client, reqwest::Error> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(network.connect_timeout_secs))
+        .timeout(Duration::from_secs(network.timeout_secs));
+    if let Some(proxy) = &network.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    builder.build()
+}
+
+/// Posts a run summary to a webhook.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client to send the request with.
+/// * `webhook` - The webhook to notify.
+/// * `summary` - The summary of the finished run.
+///
+/// # Returns
+///
+/// A `Result` indicating whether the webhook accepted the payload.
+pub async fn send_webhook(
+    client: &reqwest::Client, webhook: &Webhook, summary: &RunSummary,
+) -> Result<(), WebhookError> {
+    let response = client
+        .post(&webhook.url)
+        .json(&payload(webhook.kind, summary))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(WebhookError::Status(response.status()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn summary() -> RunSummary {
+        RunSummary {
+            command: "update".to_string(),
+            updated: vec![UpdatedMod {
+                name: "Carry On".to_string(),
+                modid: "carryon".to_string(),
+                from: "1.7.0".to_string(),
+                to: "1.8.0".to_string(),
+            }],
+            failed: vec!["hudclock".to_string()],
+            ..RunSummary::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_discord_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(body_json(json!({
+                "content": "Server mods updated (1):\n- Carry On 1.7.0 → 1.8.0\nFailed to update: hudclock"
+            })))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let webhook = Webhook {
+            url: format!("{}/hook", server.uri()),
+            kind: WebhookKind::Discord,
+            available: false,
+        };
+        send_webhook(&reqwest::Client::new(), &webhook, &summary())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_rejected_payloads() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let webhook = Webhook {
+            url: server.uri(),
+            kind: WebhookKind::Json,
+            available: false,
+        };
+        let result = send_webhook(&reqwest::Client::new(), &webhook, &summary()).await;
+        assert!(matches!(result, Err(WebhookError::Status(status)) if status == 400));
+    }
+
+    #[test]
+    fn lists_available_updates() {
+        let summary = RunSummary {
+            available: summary().updated,
+            ..RunSummary::new("watch")
+        };
+
+        assert!(summary.is_available_only());
+        assert_eq!(
+            summary.message(),
+            "Mod updates available (1):\n- Carry On 1.7.0 → 1.8.0"
+        );
+        assert_eq!(summary.exit_code(), 0);
+    }
+
+    #[test]
+    fn exit_code_reflects_the_outcome() {
+        assert_eq!(summary().exit_code(), 1);
+        assert_eq!(RunSummary::new("update").exit_code(), 0);
+        let updated = RunSummary {
+            failed: Vec::new(),
+            up_to_date: vec!["hudclock".to_string()],
+            ..summary()
+        };
+        assert_eq!(updated.exit_code(), 2);
+        assert_eq!(
+            updated.table(),
+            "Updated        1\nUp to date     1\nSkipped        0\nFailed         0"
+        );
+
+        let held_back = RunSummary {
+            held_back: summary().updated,
+            ..RunSummary::new("update")
+        };
+        assert_eq!(held_back.exit_code(), 3);
+        assert!(
+            held_back
+                .table()
+                .starts_with("Updated        0\nHeld back      1\n")
+        );
+    }
+}